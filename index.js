@@ -1,4 +1,14 @@
 // For more comments about what's going on here, check out the `hello_world`
 // example.
 import('./pkg')
+  .then(({ set_pdf_worker_factory, install_offline }) => {
+    set_pdf_worker_factory(() => new Worker(new URL('./pdf_worker.js', import.meta.url)));
+    // emitted as they are, next to index.html, so the service worker's scope
+    // covers the whole app and the manifest can find the icon
+    install_offline(
+      new URL('./service_worker.js', import.meta.url).href,
+      new URL('./manifest.webmanifest', import.meta.url).href,
+      new URL('./icon.svg', import.meta.url).href,
+    );
+  })
   .catch(console.error);
@@ -0,0 +1,20 @@
+// Builds export PDFs off the main thread so the page doesn't freeze while
+// large, high-DPI images are decoded and compressed.
+const pkg = import('./pkg');
+
+self.onmessage = (event) => {
+  pkg
+    .then(({ build_pdf }) => {
+      const pdf = build_pdf(
+        event.data.png,
+        event.data.pngLengths,
+        event.data.marginInches,
+        event.data.title,
+        event.data.keywords,
+        event.data.createdAt,
+      );
+      self.postMessage(pdf, [pdf.buffer]);
+    })
+    // the page is waiting on an answer either way, so failures are sent back
+    .catch((err) => self.postMessage({ error: String(err) }));
+};
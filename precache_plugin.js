@@ -0,0 +1,39 @@
+// Fills in the service worker with every file the build emits and a cache
+// name unique to the build, so installing it caches the whole app up front
+// and a new build's worker replaces the old cache instead of mixing with it.
+const { Compilation, sources } = require('webpack');
+
+const SERVICE_WORKER = 'service_worker.js';
+
+class PrecachePlugin {
+  apply(compiler) {
+    compiler.hooks.thisCompilation.tap('PrecachePlugin', (compilation) => {
+      compilation.hooks.processAssets.tap(
+        {
+          name: 'PrecachePlugin',
+          // after index.html and the rest are emitted
+          stage: Compilation.PROCESS_ASSETS_STAGE_SUMMARIZE,
+        },
+        (assets) => {
+          const worker = compilation.getAsset(SERVICE_WORKER);
+          if (!worker) {
+            return;
+          }
+          const urls = Object.keys(assets)
+            .filter((name) => name !== SERVICE_WORKER)
+            .map((name) => `./${name}`);
+          // the worker may have been minified by now, which can copy the
+          // cache name into more than one place
+          const source = worker.source
+            .source()
+            .toString()
+            .replaceAll('__PRECACHE_URLS__', JSON.stringify(urls))
+            .replaceAll('__BUILD_ID__', compilation.hash);
+          compilation.updateAsset(SERVICE_WORKER, new sources.RawSource(source));
+        },
+      );
+    });
+  }
+}
+
+module.exports = PrecachePlugin;
@@ -0,0 +1,51 @@
+// Keeps a copy of the whole app so it opens and works with no connection.
+// Requests go to the network first, so an online visit always gets the
+// latest build, and fall back to the copy when that fails.
+
+// filled in by precache_plugin.js: every file the build emits, and a cache
+// name that changes with each build
+const PRECACHE_URLS = __PRECACHE_URLS__;
+const CACHE = 'geargen-__BUILD_ID__';
+
+// everything is cached at install, not as it's first used, so exports and
+// the pdf worker work offline even if they were never opened online
+self.addEventListener('install', (event) => {
+  event.waitUntil(
+    caches
+      .open(CACHE)
+      .then((cache) => cache.addAll(['./', ...PRECACHE_URLS]))
+      .then(() => self.skipWaiting()),
+  );
+});
+
+self.addEventListener('activate', (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) => Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))))
+      .then(() => self.clients.claim()),
+  );
+});
+
+self.addEventListener('fetch', (event) => {
+  const request = event.request;
+  if (request.method !== 'GET' || new URL(request.url).origin !== self.location.origin) {
+    return;
+  }
+  event.respondWith(
+    fetch(request)
+      .then((response) => {
+        if (response.ok) {
+          const copy = response.clone();
+          caches.open(CACHE).then((cache) => cache.put(request, copy));
+        }
+        return response;
+      })
+      .catch(() =>
+        caches
+          .match(request, { ignoreSearch: request.mode === 'navigate' })
+          .then((cached) => cached || (request.mode === 'navigate' ? caches.match('./') : undefined))
+          .then((cached) => cached || Response.error()),
+      ),
+  );
+});
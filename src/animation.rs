@@ -0,0 +1,140 @@
+use crate::geometry::GearGeometry;
+use std::f64;
+
+// frames drawn for each turn of the first gear, and how long each is shown
+// in hundredths of a second, so a turn takes three seconds
+pub const FRAMES_PER_TURN: u32 = 60;
+pub const FRAME_DELAY: u16 = 5;
+
+// what a recording of the gears turning is saved as
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Format {
+    #[default]
+    Gif,
+    WebM,
+}
+
+impl Format {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "gif" => Some(Format::Gif),
+            "webm" => Some(Format::WebM),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            Format::Gif => "gif",
+            Format::WebM => "webm",
+        }
+    }
+}
+
+// one gear of the train as far as turning it goes
+pub struct Turning {
+    pub teeth: f64,
+    // turns with the previous gear instead of meshing with it
+    pub same_arbor: bool,
+    // where the first tooth's centerline points, in radians
+    pub tooth_center: f64,
+}
+
+// the angle of the first tooth's centerline, in radians, found from the
+// middle of the points furthest out on it
+pub fn tooth_center(geometry: &GearGeometry) -> f64 {
+    let points: Vec<_> = geometry
+        .tooth()
+        .iter()
+        .flat_map(|segment| segment.points().to_vec())
+        .collect();
+    let furthest = points.iter().map(|pt| pt.x.hypot(pt.y)).fold(0.0, f64::max);
+    let (x, y) = points
+        .iter()
+        .filter(|pt| pt.x.hypot(pt.y) >= furthest * (1.0 - 1e-6))
+        .fold((0.0, 0.0), |(x, y), pt| (x + pt.x, y + pt.y));
+    y.atan2(x)
+}
+
+// how far to turn each gear, in radians, with the first gear turned `turn`
+// from its tooth pointing at the second gear. every mesh is along the x
+// axis, the next gear to the right, so a gear's tooth at the mesh sits in
+// a space of the next gear, which turns back by the ratio of their teeth.
+pub fn angles(gears: &[Turning], turn: f64) -> Vec<f64> {
+    let mut angles: Vec<f64> = Vec::with_capacity(gears.len());
+    for (index, gear) in gears.iter().enumerate() {
+        let angle = match index.checked_sub(1) {
+            None => turn - gear.tooth_center,
+            Some(previous) if gear.same_arbor => angles[previous],
+            Some(previous) => {
+                let driver = &gears[previous];
+                let driver_pitch = 2.0 * f64::consts::PI / driver.teeth;
+                let pitch = 2.0 * f64::consts::PI / gear.teeth;
+                // how many teeth of the driver have gone past the mesh
+                let passed = (-angles[previous] - driver.tooth_center) / driver_pitch;
+                f64::consts::PI - gear.tooth_center - pitch * (0.5 - passed)
+            }
+        };
+        angles.push(angle);
+    }
+    angles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{GearSpecs, Thinning, ToothProfile};
+
+    fn spec(teeth: f64) -> GearSpecs {
+        GearSpecs {
+            teeth,
+            diametric_pitch: 12.0,
+            tooth_angle: 20.0,
+            clearance_mult: 0.167,
+            backlash_mult: 0.05,
+            involute_steps: 20,
+            profile: ToothProfile::Involute,
+            helix_angle: 0.0,
+            thinning: Thinning::Half,
+        }
+    }
+
+    #[test]
+    fn teeth_land_in_spaces_as_the_train_turns() {
+        let turning: Vec<Turning> = [(20.0, false), (30.0, false), (12.0, true), (40.0, false)]
+            .iter()
+            .map(|&(teeth, same_arbor)| Turning {
+                teeth,
+                same_arbor,
+                tooth_center: tooth_center(&GearGeometry::new(&spec(teeth))),
+            })
+            .collect();
+        // where a gear's teeth are at `direction`, counted in teeth from its
+        // first tooth's centerline
+        let teeth_at = |index: usize, angles: &[f64], direction: f64| {
+            let gear = &turning[index];
+            (direction - angles[index] - gear.tooth_center) * gear.teeth / (2.0 * f64::consts::PI)
+        };
+        let whole = |teeth: f64| (teeth - teeth.round()).abs() < 1e-9;
+        for turn in [0.0, 0.1, 1.0, 4.0] {
+            let angles = angles(&turning, turn);
+            assert_eq!(angles[2], angles[1]);
+            // at the start the first gear's tooth points at the mesh and the
+            // second gear has a space there
+            if turn == 0.0 {
+                assert!(whole(teeth_at(0, &angles, 0.0)));
+                assert!(whole(teeth_at(1, &angles, f64::consts::PI) + 0.5));
+            }
+            // as it turns the teeth leaving one gear at the mesh arrive on
+            // the other, half a tooth apart
+            for (driver, driven) in [(0, 1), (2, 3)] {
+                let leaving = teeth_at(driver, &angles, 0.0);
+                let arriving = teeth_at(driven, &angles, f64::consts::PI);
+                assert!(whole(leaving + arriving + 0.5));
+            }
+        }
+        // the second gear turns back by the ratio
+        let (start, later) = (angles(&turning, 0.0), angles(&turning, 0.3));
+        assert!((later[1] - start[1] + 0.3 * 20.0 / 30.0).abs() < 1e-12);
+    }
+}
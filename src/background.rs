@@ -0,0 +1,215 @@
+use crate::geometry::Point;
+
+// what the workspace is drawn on. it's only for looking at, exports are
+// always printed on the half inch grid.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum BackgroundStyle {
+    // white with a half inch grid
+    #[default]
+    Grid,
+    White,
+    Transparent,
+    // tenth inch squares with heavier inch lines
+    GraphPaper,
+    // triangles for sketching in isometric
+    Isometric,
+}
+
+impl BackgroundStyle {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "grid" => Some(BackgroundStyle::Grid),
+            "white" => Some(BackgroundStyle::White),
+            "transparent" => Some(BackgroundStyle::Transparent),
+            "graph_paper" => Some(BackgroundStyle::GraphPaper),
+            "isometric" => Some(BackgroundStyle::Isometric),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            BackgroundStyle::Grid => "grid",
+            BackgroundStyle::White => "white",
+            BackgroundStyle::Transparent => "transparent",
+            BackgroundStyle::GraphPaper => "graph_paper",
+            BackgroundStyle::Isometric => "isometric",
+        }
+    }
+
+    // what's under the lines, `None` to leave it see-through
+    pub fn fill(&self) -> Option<&'static str> {
+        match self {
+            BackgroundStyle::Transparent => None,
+            _ => Some("white"),
+        }
+    }
+}
+
+// lines drawn in one color, in pixels
+pub struct Rule {
+    pub color: &'static str,
+    pub lines: Vec<(Point, Point)>,
+}
+
+// the style's lines across a `width` by `height` pixel view, lined up on its
+// center so they stay put around the gears as the window is resized
+pub fn rules(style: BackgroundStyle, width: u32, height: u32, ppi: u32) -> Vec<Rule> {
+    let (width, height, ppi) = (width as f64, height as f64, ppi as f64);
+    match style {
+        BackgroundStyle::White | BackgroundStyle::Transparent => Vec::new(),
+        BackgroundStyle::Grid => vec![Rule {
+            color: "lightblue",
+            lines: square_grid(width, height, ppi / 2.0, 1),
+        }],
+        BackgroundStyle::GraphPaper => vec![
+            Rule {
+                color: "#e2f1f8",
+                lines: square_grid(width, height, ppi / 10.0, 10),
+            },
+            Rule {
+                color: "lightblue",
+                lines: square_grid(width, height, ppi, 1),
+            },
+        ],
+        BackgroundStyle::Isometric => vec![Rule {
+            color: "lightblue",
+            lines: isometric_grid(width, height, ppi / 4.0),
+        }],
+    }
+}
+
+// lines every `spacing` pixels both ways through the center, leaving out
+// every `skip`th one when a heavier grid is drawn over them
+fn square_grid(width: f64, height: f64, spacing: f64, skip: i64) -> Vec<(Point, Point)> {
+    let center = Point {
+        x: width / 2.0,
+        y: height / 2.0,
+    };
+    let kept = |i: &i64| skip == 1 || i % skip != 0;
+    let across = (-(center.y / spacing).floor() as i64..=(center.y / spacing).floor() as i64)
+        .filter(kept)
+        .map(|i| {
+            let y = center.y + i as f64 * spacing;
+            (Point { x: 0.0, y }, Point { x: width, y })
+        });
+    let down = (-(center.x / spacing).floor() as i64..=(center.x / spacing).floor() as i64)
+        .filter(kept)
+        .map(|i| {
+            let x = center.x + i as f64 * spacing;
+            (Point { x, y: 0.0 }, Point { x, y: height })
+        });
+    across.chain(down).collect()
+}
+
+// equilateral triangles with `side` pixel sides: upright lines, and lines
+// climbing and falling at 30 degrees, all through the center
+fn isometric_grid(width: f64, height: f64, side: f64) -> Vec<(Point, Point)> {
+    let center = Point {
+        x: width / 2.0,
+        y: height / 2.0,
+    };
+    let column = side * 3f64.sqrt() / 2.0;
+    let columns = (center.x / column).floor() as i64;
+    let upright = (-columns..=columns).map(|i| {
+        let x = center.x + i as f64 * column;
+        (Point { x, y: 0.0 }, Point { x, y: height })
+    });
+    // sloped lines are `side` apart measured upright, and can cross the view
+    // from well above or below it
+    let slope = (30f64).to_radians().tan();
+    let reach = ((center.y + slope * center.x) / side).ceil() as i64;
+    let sloped = [slope, -slope].into_iter().flat_map(move |slope| {
+        (-reach..=reach).map(move |i| {
+            let y = |x: f64| center.y + i as f64 * side + slope * (x - center.x);
+            (
+                Point { x: 0.0, y: y(0.0) },
+                Point {
+                    x: width,
+                    y: y(width),
+                },
+            )
+        })
+    });
+    upright.chain(sloped).collect()
+}
+
+// clears the view and draws the style onto it, with a small red crosshair
+// marking the center
+pub fn draw(
+    context: &web_sys::CanvasRenderingContext2d,
+    style: BackgroundStyle,
+    width: u32,
+    height: u32,
+    ppi: u32,
+) {
+    context.clear_rect(0.0, 0.0, width as f64, height as f64);
+    if let Some(fill) = style.fill() {
+        context.set_fill_style_str(fill);
+        context.fill_rect(0.0, 0.0, width as f64, height as f64);
+    }
+
+    context.set_line_width(1.0);
+    for rule in rules(style, width, height, ppi) {
+        context.set_stroke_style_str(rule.color);
+        context.begin_path();
+        for (from, to) in &rule.lines {
+            context.move_to(from.x, from.y);
+            context.line_to(to.x, to.y);
+        }
+        context.stroke();
+    }
+
+    // Draw tiny crosshair in the middle for debugging
+    context.set_stroke_style_str("red");
+    context.begin_path();
+    let offset = 5.0;
+    let (x, y) = (width as f64 / 2.0, height as f64 / 2.0);
+    context.move_to(x, y - offset);
+    context.line_to(x, y + offset);
+    context.move_to(x - offset, y);
+    context.line_to(x + offset, y);
+    context.stroke();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grids_line_up_on_the_center() {
+        let (width, height, ppi) = (800, 600, 96);
+        let crosses_center = |lines: &[(Point, Point)]| {
+            lines
+                .iter()
+                .any(|(from, to)| (from.x - 400.0).abs() < 1e-9 && (to.x - 400.0).abs() < 1e-9)
+                && lines
+                    .iter()
+                    .any(|(from, to)| (from.y - 300.0).abs() < 1e-9 && (to.y - 300.0).abs() < 1e-9)
+        };
+        let grid = rules(BackgroundStyle::Grid, width, height, ppi);
+        assert!(crosses_center(&grid[0].lines));
+
+        // the graph paper's inch lines aren't drawn twice
+        let paper = rules(BackgroundStyle::GraphPaper, width, height, ppi);
+        assert!(!crosses_center(&paper[0].lines));
+        assert!(crosses_center(&paper[1].lines));
+
+        // every sloped isometric line crosses the upright through the
+        // center a whole number of sides away from it
+        let iso = rules(BackgroundStyle::Isometric, width, height, ppi);
+        let side = 96.0 / 4.0;
+        for (from, to) in &iso[0].lines {
+            if from.x == to.x {
+                continue;
+            }
+            let slope = (to.y - from.y) / (to.x - from.x);
+            assert!((slope.abs() - (30f64).to_radians().tan()).abs() < 1e-9);
+            let at_center = from.y + slope * (400.0 - from.x) - 300.0;
+            assert!((at_center / side - (at_center / side).round()).abs() < 1e-9);
+        }
+
+        assert!(rules(BackgroundStyle::White, width, height, ppi).is_empty());
+        assert_eq!(BackgroundStyle::Transparent.fill(), None);
+    }
+}
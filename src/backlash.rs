@@ -0,0 +1,93 @@
+use crate::geometry::{GearGeometry, GearSpecs};
+
+const ARCMINUTES_PER_RADIAN: f64 = 60.0 * 180.0 / std::f64::consts::PI;
+
+// how far each gear of a train can rock, in radians, with the first gear
+// held still. the last gear's is the train's backlash at its output.
+// `gears` pairs each gear's specs with whether it shares the previous gear's
+// arbor instead of meshing with it.
+pub fn angular(gears: &[(&GearSpecs, bool)]) -> Vec<f64> {
+    let geometries: Vec<GearGeometry> = gears
+        .iter()
+        .map(|(spec, _)| GearGeometry::new(spec))
+        .collect();
+    let mut play = Vec::with_capacity(gears.len());
+    for (index, (_, same_arbor)) in gears.iter().enumerate() {
+        let Some(previous) = index.checked_sub(1) else {
+            play.push(0.0);
+            continue;
+        };
+        if *same_arbor {
+            play.push(play[previous]);
+            continue;
+        }
+        // both gears' teeth are thinned by their allowance, which opens a
+        // gap along the pitch circle. the play already upstream turns this
+        // gear by the mesh ratio on top of that.
+        let (driver, driven) = (&geometries[previous], &geometries[index]);
+        let mesh = (driver.backlash_allowance + driven.backlash_allowance) / driven.pitch_radius;
+        play.push(play[previous] * driver.pitch_radius / driven.pitch_radius + mesh);
+    }
+    play
+}
+
+pub fn arcminutes(radians: f64) -> f64 {
+    radians * ARCMINUTES_PER_RADIAN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Thinning, ToothProfile};
+
+    fn spec(teeth: f64) -> GearSpecs {
+        GearSpecs {
+            teeth,
+            diametric_pitch: 10.0,
+            tooth_angle: 20.0,
+            clearance_mult: 0.25,
+            backlash_mult: 0.05,
+            involute_steps: 10,
+            profile: ToothProfile::Involute,
+            helix_angle: 0.0,
+            thinning: Thinning::Half,
+        }
+    }
+
+    #[test]
+    fn play_builds_up_along_the_train() {
+        let (small, large) = (spec(10.0), spec(40.0));
+        // 0.01 in of play across the mesh on a 2 in pitch radius
+        let pair = angular(&[(&small, false), (&large, false)]);
+        assert_eq!(pair[0], 0.0);
+        assert!((pair[1] - 0.005).abs() < 1e-12);
+
+        // a compound stage: the second pinion rocks with its arbor, and its
+        // play shrinks by the 4:1 mesh on the way to the output
+        let train = angular(&[
+            (&small, false),
+            (&large, false),
+            (&small, true),
+            (&large, false),
+        ]);
+        assert_eq!(train[2], train[1]);
+        assert!((train[3] - (0.005 / 4.0 + 0.005)).abs() < 1e-12);
+        assert!((arcminutes(std::f64::consts::PI / 180.0) - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn one_sided_thinning_keeps_the_mesh_backlash() {
+        let pinion = GearSpecs {
+            thinning: Thinning::None,
+            ..spec(10.0)
+        };
+        let wheel = GearSpecs {
+            thinning: Thinning::All,
+            ..spec(40.0)
+        };
+        let split = angular(&[(&spec(10.0), false), (&spec(40.0), false)]);
+        let one_sided = angular(&[(&pinion, false), (&wheel, false)]);
+        assert!((split[1] - one_sided[1]).abs() < 1e-12);
+        assert_eq!(GearGeometry::new(&pinion).backlash_allowance, 0.0);
+    }
+}
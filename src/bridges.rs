@@ -0,0 +1,137 @@
+use crate::geometry::Point;
+
+// holding tabs left uncut around every outline, so parts and web cutouts
+// stay in the stock until the last pass on a router. `width` is the length
+// of outline each one spans, in inches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bridges {
+    pub count: usize,
+    pub width: f64,
+}
+
+impl Default for Bridges {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            width: 0.125,
+        }
+    }
+}
+
+// a closed loop broken up by its tabs: the stretches to cut, and the
+// stretches left standing under each tab
+pub struct Split {
+    pub cuts: Vec<Vec<Point>>,
+    pub tabs: Vec<Vec<Point>>,
+}
+
+// spaces `bridges` evenly along the closed loop `points` by length. a loop
+// too short for its tabs to fit with room between them is left whole.
+pub fn split(points: &[Point], bridges: Bridges) -> Split {
+    let mut lengths = vec![0.0];
+    for pair in points.windows(2) {
+        let step = (pair[1].x - pair[0].x).hypot(pair[1].y - pair[0].y);
+        lengths.push(lengths.last().unwrap() + step);
+    }
+    let total = *lengths.last().unwrap();
+    let count = bridges.count;
+    if count == 0 || bridges.width <= 0.0 || bridges.width * count as f64 >= total {
+        return Split {
+            cuts: vec![points.to_vec()],
+            tabs: Vec::new(),
+        };
+    }
+
+    let spacing = total / count as f64;
+    let spans: Vec<(f64, f64)> = (0..count)
+        .map(|i| {
+            let middle = (i as f64 + 0.5) * spacing;
+            (middle - bridges.width / 2.0, middle + bridges.width / 2.0)
+        })
+        .collect();
+    let tabs = spans
+        .iter()
+        .map(|&(from, to)| stretch(points, &lengths, from, to))
+        .collect();
+    let mut cuts: Vec<Vec<Point>> = spans
+        .windows(2)
+        .map(|pair| stretch(points, &lengths, pair[0].1, pair[1].0))
+        .collect();
+    // the cut past the last tab carries on through the loop's start
+    let mut wrap = stretch(points, &lengths, spans[count - 1].1, total);
+    wrap.extend(
+        stretch(points, &lengths, 0.0, spans[0].0)
+            .into_iter()
+            .skip(1),
+    );
+    cuts.push(wrap);
+    Split { cuts, tabs }
+}
+
+// the part of the loop between two distances along it
+fn stretch(points: &[Point], lengths: &[f64], from: f64, to: f64) -> Vec<Point> {
+    let at = |distance: f64| {
+        let index = lengths
+            .partition_point(|length| *length <= distance)
+            .clamp(1, points.len() - 1);
+        let (a, b) = (points[index - 1], points[index]);
+        let span = lengths[index] - lengths[index - 1];
+        let t = if span > 0.0 {
+            ((distance - lengths[index - 1]) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        Point {
+            x: a.x + (b.x - a.x) * t,
+            y: a.y + (b.y - a.y) * t,
+        }
+    };
+    let mut stretch = vec![at(from)];
+    stretch.extend(
+        points
+            .iter()
+            .zip(lengths)
+            .filter(|(_, length)| **length > from && **length < to)
+            .map(|(pt, _)| *pt),
+    );
+    stretch.push(at(to));
+    stretch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tabs_sit_evenly_between_the_cuts() {
+        // a square 4 inches around, one tab in the middle of each side
+        let square = vec![
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 1.0, y: 0.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 0.0, y: 1.0 },
+            Point { x: 0.0, y: 0.0 },
+        ];
+        let bridges = Bridges {
+            count: 4,
+            width: 0.2,
+        };
+        let split = split(&square, bridges);
+        assert_eq!(split.tabs.len(), 4);
+        assert_eq!(split.cuts.len(), 4);
+        assert_eq!(
+            split.tabs[0],
+            vec![Point { x: 0.4, y: 0.0 }, Point { x: 0.6, y: 0.0 }]
+        );
+        // each cut turns a corner, the last one the loop's start
+        assert_eq!(split.cuts[0].len(), 3);
+        assert_eq!(split.cuts[3][1], Point { x: 0.0, y: 0.0 });
+        assert_eq!(split.cuts[3].len(), 3);
+
+        let too_many = Bridges {
+            count: 20,
+            width: 0.2,
+        };
+        assert!(self::split(&square, too_many).tabs.is_empty());
+    }
+}
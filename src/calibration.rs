@@ -0,0 +1,188 @@
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlInputElement;
+use web_sys::HtmlSelectElement;
+
+// the range of screens the slider covers, in pixels per inch
+const MIN_PPI: f64 = 50.0;
+const MAX_PPI: f64 = 400.0;
+const MM_PER_INCH: f64 = 25.4;
+
+// something of a known length to hold against the screen
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Reference {
+    // any bank or id card, 85.6 mm wide
+    #[default]
+    CreditCard,
+    // the 0 to 4 marks on an inch ruler
+    InchRuler,
+    // the 0 to 10 marks on a centimeter ruler
+    CentimeterRuler,
+}
+
+impl Reference {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "credit_card" => Some(Reference::CreditCard),
+            "inch_ruler" => Some(Reference::InchRuler),
+            "centimeter_ruler" => Some(Reference::CentimeterRuler),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            Reference::CreditCard => "credit_card",
+            Reference::InchRuler => "inch_ruler",
+            Reference::CentimeterRuler => "centimeter_ruler",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Reference::CreditCard => "Credit card (long edge)",
+            Reference::InchRuler => "Ruler, 0 to 4 in",
+            Reference::CentimeterRuler => "Ruler, 0 to 10 cm",
+        }
+    }
+
+    pub fn inches(&self) -> f64 {
+        match self {
+            Reference::CreditCard => 85.6 / MM_PER_INCH,
+            Reference::InchRuler => 4.0,
+            Reference::CentimeterRuler => 100.0 / MM_PER_INCH,
+        }
+    }
+}
+
+// a modal where a bar on screen is stretched until it's as long as a card
+// or ruler held up against it. `on_save` gets the pixels per inch that
+// gives, nothing happens if it's cancelled.
+pub fn show(initial_ppi: f64, on_save: impl FnOnce(f64) + 'static) -> Result<(), JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let overlay = document.create_element("div")?;
+    overlay.set_attribute("style", "position: fixed; left: 0; top: 0; width: 100%; height: 100%; background-color: rgba(0, 0, 0, 0.3); z-index: 10;")?;
+
+    let dialog = document.create_element("div")?;
+    dialog.set_attribute("style", "position: absolute; left: 50%; top: 40%; transform: translate(-50%, -50%); width: 90%; max-width: 900px; padding: 20px; background-color: #f0f0f0; text-align: center;")?;
+    overlay.append_child(&dialog)?;
+
+    let title = document.create_element("h3")?;
+    title.set_text_content(Some("Calibrate Screen"));
+    dialog.append_child(&title)?;
+
+    let instructions = document.create_element("p")?;
+    instructions.set_text_content(Some(
+        "Hold the reference against the screen and drag the slider until the bar is exactly as long.",
+    ));
+    dialog.append_child(&instructions)?;
+
+    let reference_select = document
+        .create_element("select")?
+        .dyn_into::<HtmlSelectElement>()?;
+    for reference in [
+        Reference::CreditCard,
+        Reference::InchRuler,
+        Reference::CentimeterRuler,
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", reference.value())?;
+        option.set_text_content(Some(reference.label()));
+        reference_select.append_child(&option)?;
+    }
+    dialog.append_child(&reference_select)?;
+
+    // the bar starts at the left edge so the reference can be lined up on it
+    let bar = document.create_element("div")?;
+    dialog.append_child(&bar)?;
+
+    let slider = document
+        .create_element("input")?
+        .dyn_into::<HtmlInputElement>()?;
+    slider.set_attribute("type", "range")?;
+    slider.set_attribute("min", &MIN_PPI.to_string())?;
+    slider.set_attribute("max", &MAX_PPI.to_string())?;
+    slider.set_attribute("step", "0.1")?;
+    slider.set_attribute("style", "width: 100%;")?;
+    slider.set_value(&initial_ppi.clamp(MIN_PPI, MAX_PPI).to_string());
+    dialog.append_child(&slider)?;
+
+    let readout = document.create_element("div")?;
+    dialog.append_child(&readout)?;
+
+    // the bar is sized from the slider's pixels per inch, so switching
+    // references keeps the calibration so far
+    let update = {
+        let reference_select = reference_select.clone();
+        let bar = bar.clone();
+        let slider = slider.clone();
+        let readout = readout.clone();
+        move || {
+            let reference = Reference::from_value(&reference_select.value()).unwrap_or_default();
+            let ppi = slider.value_as_number();
+            bar.set_attribute(
+                "style",
+                &format!(
+                    "width: {}px; height: 54px; margin: 10px 0; background-color: #4a90d9; \
+                     border-radius: {};",
+                    ppi * reference.inches(),
+                    if reference == Reference::CreditCard {
+                        "6px"
+                    } else {
+                        "0"
+                    }
+                ),
+            )
+            .unwrap();
+            readout.set_text_content(Some(&format!("{:.1} pixels per inch", ppi)));
+        }
+    };
+    update();
+    let update_closure = Closure::wrap(Box::new(update) as Box<dyn Fn()>);
+    slider.add_event_listener_with_callback("input", update_closure.as_ref().unchecked_ref())?;
+    reference_select
+        .add_event_listener_with_callback("change", update_closure.as_ref().unchecked_ref())?;
+    update_closure.forget();
+
+    let save_button = document.create_element("button")?;
+    save_button.set_text_content(Some("Save"));
+    save_button.set_attribute("style", "margin: 10px;")?;
+    dialog.append_child(&save_button)?;
+    let cancel_button = document.create_element("button")?;
+    cancel_button.set_text_content(Some("Cancel"));
+    cancel_button.set_attribute("style", "margin: 10px;")?;
+    dialog.append_child(&cancel_button)?;
+
+    let save_overlay = overlay.clone();
+    let save_closure = Closure::once_into_js(move || {
+        save_overlay.remove();
+        on_save(slider.value_as_number());
+    });
+    save_button.add_event_listener_with_callback("click", save_closure.unchecked_ref())?;
+    let cancel_overlay = overlay.clone();
+    let cancel_closure = Closure::once_into_js(move || {
+        cancel_overlay.remove();
+    });
+    cancel_button.add_event_listener_with_callback("click", cancel_closure.unchecked_ref())?;
+
+    document.body().unwrap().append_child(&overlay)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_are_known_lengths() {
+        // a card is a bit under 3 3/8 inches, 323.5 pixels at 96 ppi
+        assert!((Reference::CreditCard.inches() * 96.0 - 323.5).abs() < 0.1);
+        assert!((Reference::CentimeterRuler.inches() - 3.937).abs() < 1e-3);
+        for reference in [
+            Reference::CreditCard,
+            Reference::InchRuler,
+            Reference::CentimeterRuler,
+        ] {
+            assert_eq!(Reference::from_value(reference.value()), Some(reference));
+        }
+    }
+}
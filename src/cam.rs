@@ -0,0 +1,540 @@
+use crate::geometry::Point;
+use std::f64;
+
+// points around the cam's outline
+const OUTLINE_STEPS: u32 = 720;
+// steeper than this and the follower binds in its guide
+const MAX_PRESSURE_ANGLE: f64 = 30.0;
+
+// how the follower moves through a rise or fall, as the share of the lift
+// covered over the share of the segment `u`, with its first and second
+// derivatives
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MotionLaw {
+    // constant velocity, jerks at either end
+    Uniform,
+    SimpleHarmonic,
+    // no jumps in acceleration, the gentlest on the follower
+    Cycloidal,
+}
+
+impl MotionLaw {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "uniform" => Some(MotionLaw::Uniform),
+            "harmonic" => Some(MotionLaw::SimpleHarmonic),
+            "cycloidal" => Some(MotionLaw::Cycloidal),
+            _ => None,
+        }
+    }
+
+    fn at(&self, u: f64) -> (f64, f64, f64) {
+        let pi = f64::consts::PI;
+        match self {
+            MotionLaw::Uniform => (u, 1.0, 0.0),
+            MotionLaw::SimpleHarmonic => (
+                (1.0 - (pi * u).cos()) / 2.0,
+                pi / 2.0 * (pi * u).sin(),
+                pi * pi / 2.0 * (pi * u).cos(),
+            ),
+            MotionLaw::Cycloidal => (
+                u - (2.0 * pi * u).sin() / (2.0 * pi),
+                1.0 - (2.0 * pi * u).cos(),
+                2.0 * pi * (2.0 * pi * u).sin(),
+            ),
+        }
+    }
+}
+
+// one stretch of the displacement diagram, `duration` in degrees of cam
+// rotation and `lift` in inches
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    Dwell {
+        duration: f64,
+    },
+    Rise {
+        duration: f64,
+        lift: f64,
+        law: MotionLaw,
+    },
+    Fall {
+        duration: f64,
+        lift: f64,
+        law: MotionLaw,
+    },
+}
+
+impl Segment {
+    fn duration(&self) -> f64 {
+        match self {
+            Segment::Dwell { duration }
+            | Segment::Rise { duration, .. }
+            | Segment::Fall { duration, .. } => *duration,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Follower {
+    KnifeEdge,
+    // `radius` in inches
+    Roller { radius: f64 },
+    // a flat face square to the follower's travel
+    FlatFaced,
+}
+
+// a lift program and the follower riding it. the base circle is where the
+// follower sits at zero lift, measured to the knife edge, the flat face or
+// the roller's nearest point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cam {
+    pub base_radius: f64,
+    pub follower: Follower,
+    pub segments: Vec<Segment>,
+}
+
+// reads a lift program like "rise 120 0.5 cycloidal, dwell 60, fall 120 0.5
+// harmonic, dwell 60": segments separated by commas, each a kind and its
+// duration in degrees, with the lift in inches and a motion law for rises
+// and falls. the law defaults to cycloidal. the segments have to cover a
+// full turn and end back at zero lift.
+pub fn parse_program(text: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    for part in text
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let words: Vec<&str> = part.split_whitespace().collect();
+        let number = |index: usize, what: &str| {
+            words
+                .get(index)
+                .and_then(|word| crate::locale::parse::<f64>(word))
+                .filter(|value| *value >= 0.0)
+                .ok_or_else(|| format!("\"{}\" needs a {}", part, what))
+        };
+        let law = || match words.get(3) {
+            Some(name) => {
+                MotionLaw::from_name(name).ok_or_else(|| format!("\"{}\" isn't a motion law", name))
+            }
+            None => Ok(MotionLaw::Cycloidal),
+        };
+        let segment = match words[0] {
+            "dwell" if words.len() == 2 => Segment::Dwell {
+                duration: number(1, "duration")?,
+            },
+            "rise" if (3..=4).contains(&words.len()) => Segment::Rise {
+                duration: number(1, "duration")?,
+                lift: number(2, "lift")?,
+                law: law()?,
+            },
+            "fall" if (3..=4).contains(&words.len()) => Segment::Fall {
+                duration: number(1, "duration")?,
+                lift: number(2, "lift")?,
+                law: law()?,
+            },
+            _ => return Err(format!("\"{}\" isn't a dwell, rise or fall", part)),
+        };
+        if segment.duration() <= 0.0 {
+            return Err(format!("\"{}\" takes no time", part));
+        }
+        segments.push(segment);
+    }
+
+    let total: f64 = segments.iter().map(Segment::duration).sum();
+    if (total - 360.0).abs() > 1e-6 {
+        return Err(format!("the segments cover {}\u{b0}, not 360\u{b0}", total));
+    }
+    let mut lift = 0.0;
+    for segment in &segments {
+        match segment {
+            Segment::Rise { lift: rise, .. } => lift += rise,
+            Segment::Fall { lift: fall, .. } => lift -= fall,
+            Segment::Dwell { .. } => {}
+        }
+        if lift < -1e-9 {
+            return Err("the follower falls below the base circle".to_string());
+        }
+    }
+    if lift.abs() > 1e-9 {
+        return Err(format!(
+            "the follower ends {:.3} in off the base circle",
+            lift
+        ));
+    }
+    Ok(segments)
+}
+
+impl Cam {
+    // the follower's lift at `angle` radians of cam rotation, with its first
+    // and second derivatives with respect to the angle
+    pub fn displacement(&self, angle: f64) -> (f64, f64, f64) {
+        let angle = angle.rem_euclid(2.0 * f64::consts::PI).to_degrees();
+        let (mut start, mut lift) = (0.0, 0.0);
+        for segment in &self.segments {
+            let duration = segment.duration();
+            if angle < start + duration {
+                let u = (angle - start) / duration;
+                // the law's derivatives are per share of the segment
+                let span = duration.to_radians();
+                return match segment {
+                    Segment::Dwell { .. } => (lift, 0.0, 0.0),
+                    Segment::Rise {
+                        lift: rise, law, ..
+                    } => {
+                        let (f, df, ddf) = law.at(u);
+                        (lift + rise * f, rise * df / span, rise * ddf / span.powi(2))
+                    }
+                    Segment::Fall {
+                        lift: fall, law, ..
+                    } => {
+                        let (f, df, ddf) = law.at(u);
+                        (
+                            lift - fall * f,
+                            -fall * df / span,
+                            -fall * ddf / span.powi(2),
+                        )
+                    }
+                };
+            }
+            start += duration;
+            match segment {
+                Segment::Rise { lift: rise, .. } => lift += rise,
+                Segment::Fall { lift: fall, .. } => lift -= fall,
+                Segment::Dwell { .. } => {}
+            }
+        }
+        (lift, 0.0, 0.0)
+    }
+
+    // the follower's reference point, the knife edge, roller center or flat
+    // face, at zero lift
+    fn prime_radius(&self) -> f64 {
+        match self.follower {
+            Follower::Roller { radius } => self.base_radius + radius,
+            _ => self.base_radius,
+        }
+    }
+
+    // the cam's closed outline centered on its axis, the last point
+    // repeating the first. the follower rides along the x axis, and the cam
+    // turns counterclockwise under it.
+    pub fn outline(&self) -> Vec<Point> {
+        let prime = self.prime_radius();
+        let mut points: Vec<Point> = (0..OUTLINE_STEPS)
+            .map(|i| {
+                let angle = 2.0 * f64::consts::PI * i as f64 / OUTLINE_STEPS as f64;
+                let (s, ds, _) = self.displacement(angle);
+                // the follower's line at this angle, measured back on the cam
+                let radial = Point {
+                    x: angle.cos(),
+                    y: angle.sin(),
+                };
+                let tangent = Point {
+                    x: -angle.sin(),
+                    y: angle.cos(),
+                };
+                let rho = prime + s;
+                match self.follower {
+                    Follower::KnifeEdge => Point {
+                        x: rho * radial.x,
+                        y: rho * radial.y,
+                    },
+                    // the roller center's path, moved in by the roller radius
+                    // square to it
+                    Follower::Roller { radius } => {
+                        let length = (rho * rho + ds * ds).sqrt();
+                        let normal = Point {
+                            x: (rho * radial.x - ds * tangent.x) / length,
+                            y: (rho * radial.y - ds * tangent.y) / length,
+                        };
+                        Point {
+                            x: rho * radial.x - radius * normal.x,
+                            y: rho * radial.y - radius * normal.y,
+                        }
+                    }
+                    // the envelope of the face's positions touches it off
+                    // the follower's line by the lift's rate of change
+                    Follower::FlatFaced => Point {
+                        x: rho * radial.x + ds * tangent.x,
+                        y: rho * radial.y + ds * tangent.y,
+                    },
+                }
+            })
+            .collect();
+        points.push(points[0]);
+        points
+    }
+
+    // what would keep the cam from working, in the order they turn up
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.base_radius <= 0.0 {
+            problems.push("The cam needs a base circle".to_string());
+            return problems;
+        }
+        let prime = self.prime_radius();
+        let samples = (0..OUTLINE_STEPS).map(|i| {
+            let angle = 2.0 * f64::consts::PI * i as f64 / OUTLINE_STEPS as f64;
+            let (s, ds, dds) = self.displacement(angle);
+            (prime + s, ds, dds)
+        });
+        let mut pressure_angle: f64 = 0.0;
+        let mut cusped = false;
+        let mut undercut = false;
+        for (rho, ds, dds) in samples {
+            pressure_angle = pressure_angle.max((ds / rho).atan().abs().to_degrees());
+            match self.follower {
+                // the face can only touch where the cam is convex
+                Follower::FlatFaced => cusped |= rho + dds <= 0.0,
+                // and the roller can't fit into anything sharper than itself
+                Follower::Roller { radius } => {
+                    let curvature =
+                        (rho * rho + 2.0 * ds * ds - rho * dds) / (rho * rho + ds * ds).powf(1.5);
+                    undercut |= curvature > 0.0 && 1.0 / curvature < radius;
+                }
+                Follower::KnifeEdge => {}
+            }
+        }
+        // a flat face doesn't push at an angle
+        if self.follower != Follower::FlatFaced && pressure_angle > MAX_PRESSURE_ANGLE {
+            problems.push(format!(
+                "The cam's pressure angle reaches {:.1}\u{b0}, over {}\u{b0}; try a larger base circle or longer rises and falls",
+                pressure_angle, MAX_PRESSURE_ANGLE
+            ));
+        }
+        if cusped {
+            problems.push(
+                "The flat follower can't follow the cam without a larger base circle".to_string(),
+            );
+        }
+        if undercut {
+            problems.push("The roller is too big for the cam's sharpest curves".to_string());
+        }
+        problems
+    }
+}
+
+// a closed outline in inches, stroked around `center` in pixels
+pub fn draw_outline(
+    context: &web_sys::CanvasRenderingContext2d,
+    outline: &[Point],
+    center: Point,
+    scale: f64,
+) {
+    context.begin_path();
+    outline.iter().enumerate().for_each(|(i, pt)| {
+        let (x, y) = (center.x + pt.x * scale, center.y + pt.y * scale);
+        if i == 0 {
+            context.move_to(x, y);
+        } else {
+            context.line_to(x, y);
+        }
+    });
+    context.stroke();
+}
+
+// the cam around `center` in pixels with its follower at the start of the
+// program, and the displacement diagram to its right
+pub fn draw(context: &web_sys::CanvasRenderingContext2d, cam: &Cam, center: Point, scale: f64) {
+    let outline = cam.outline();
+    context.set_line_dash(&js_sys::Array::new()).unwrap();
+    context.set_line_width(1.0);
+    context.set_stroke_style_str("black");
+    draw_outline(context, &outline, center, scale);
+
+    // base circle and axis
+    context.set_stroke_style_str("gray");
+    context
+        .set_line_dash(&js_sys::Array::of2(&4.0.into(), &4.0.into()))
+        .unwrap();
+    context.begin_path();
+    context
+        .arc(
+            center.x,
+            center.y,
+            cam.base_radius * scale,
+            0.0,
+            2.0 * f64::consts::PI,
+        )
+        .unwrap();
+    context.stroke();
+    context.set_line_dash(&js_sys::Array::new()).unwrap();
+    context.begin_path();
+    context.move_to(center.x - 6.0, center.y);
+    context.line_to(center.x + 6.0, center.y);
+    context.move_to(center.x, center.y - 6.0);
+    context.line_to(center.x, center.y + 6.0);
+    context.stroke();
+
+    // the follower riding the cam along the x axis
+    let (lift, _, _) = cam.displacement(0.0);
+    let contact = center.x + (cam.prime_radius() + lift) * scale;
+    let stem = 0.75 * scale;
+    context.set_stroke_style_str("rgb(0, 90, 200)");
+    context.set_line_width(2.0);
+    context.begin_path();
+    match cam.follower {
+        Follower::KnifeEdge => {
+            context.move_to(contact, center.y);
+            context.line_to(contact + stem, center.y);
+        }
+        Follower::Roller { radius } => {
+            context
+                .arc(
+                    contact,
+                    center.y,
+                    radius * scale,
+                    0.0,
+                    2.0 * f64::consts::PI,
+                )
+                .unwrap();
+            context.move_to(contact + radius * scale, center.y);
+            context.line_to(contact + stem, center.y);
+        }
+        Follower::FlatFaced => {
+            context.move_to(contact, center.y - 0.4 * scale);
+            context.line_to(contact, center.y + 0.4 * scale);
+            context.move_to(contact, center.y);
+            context.line_to(contact + stem, center.y);
+        }
+    }
+    context.stroke();
+    context.set_line_width(1.0);
+
+    // lift over a turn, clear of the cam and follower
+    let max_lift = (0..OUTLINE_STEPS)
+        .map(|i| {
+            cam.displacement(2.0 * f64::consts::PI * i as f64 / OUTLINE_STEPS as f64)
+                .0
+        })
+        .fold(0.0, f64::max);
+    let left = contact + stem + 0.5 * scale;
+    let (width, height) = (3.0 * scale, 1.5 * scale);
+    let baseline = center.y + height / 2.0;
+    context.set_stroke_style_str("gray");
+    context.begin_path();
+    context.move_to(left, baseline - height);
+    context.line_to(left, baseline);
+    context.line_to(left + width, baseline);
+    context.stroke();
+    if max_lift > 0.0 {
+        context.set_stroke_style_str("rgb(0, 90, 200)");
+        context.begin_path();
+        (0..=OUTLINE_STEPS).for_each(|i| {
+            let share = i as f64 / OUTLINE_STEPS as f64;
+            let (lift, _, _) = cam.displacement(2.0 * f64::consts::PI * share);
+            let (x, y) = (left + width * share, baseline - height * lift / max_lift);
+            if i == 0 {
+                context.move_to(x, y);
+            } else {
+                context.line_to(x, y);
+            }
+        });
+        context.stroke();
+    }
+    context.set_font("12px sans-serif");
+    context.set_fill_style_str("black");
+    context.set_text_align("left");
+    context.set_text_baseline("top");
+    context
+        .fill_text(
+            &format!("lift over one turn, {:.3} in at most", max_lift),
+            left,
+            baseline + 6.0,
+        )
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cam(follower: Follower) -> Cam {
+        Cam {
+            base_radius: 1.0,
+            follower,
+            segments: parse_program("rise 120 0.5, dwell 60, fall 120 0.5 harmonic, dwell 60")
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn programs_cover_a_turn_and_return_to_the_base_circle() {
+        assert_eq!(
+            parse_program("dwell 180, rise 90 0.25 uniform, fall 90 0.25"),
+            Ok(vec![
+                Segment::Dwell { duration: 180.0 },
+                Segment::Rise {
+                    duration: 90.0,
+                    lift: 0.25,
+                    law: MotionLaw::Uniform
+                },
+                Segment::Fall {
+                    duration: 90.0,
+                    lift: 0.25,
+                    law: MotionLaw::Cycloidal
+                },
+            ])
+        );
+        assert!(parse_program("dwell 180, rise 90 0.25").is_err());
+        assert!(parse_program("rise 180 0.25, fall 180 0.5").is_err());
+        assert!(parse_program("fall 180 0.25, rise 180 0.25").is_err());
+        assert!(parse_program("dwell 180, rise 90 0.25 bouncy, fall 90 0.25").is_err());
+        assert!(parse_program("spin 360").is_err());
+    }
+
+    #[test]
+    fn displacement_follows_the_program() {
+        let cam = cam(Follower::KnifeEdge);
+        let at = |degrees: f64| cam.displacement(degrees.to_radians());
+        assert!(at(0.0).0.abs() < 1e-12);
+        // cycloidal rises are halfway up halfway through, at their fastest
+        assert!((at(60.0).0 - 0.25).abs() < 1e-12);
+        assert!((at(60.0).1 - 0.5 * 2.0 / 120f64.to_radians()).abs() < 1e-9);
+        assert!((at(150.0).0 - 0.5).abs() < 1e-12);
+        assert!((at(240.0).0 - 0.25).abs() < 1e-12);
+        assert!(at(330.0).0.abs() < 1e-12);
+    }
+
+    #[test]
+    fn outlines_sit_on_the_base_circle_through_the_dwell() {
+        let radius = |pt: &Point| (pt.x * pt.x + pt.y * pt.y).sqrt();
+        for follower in [
+            Follower::KnifeEdge,
+            Follower::Roller { radius: 0.25 },
+            Follower::FlatFaced,
+        ] {
+            let outline = cam(follower).outline();
+            assert_eq!(outline.first(), outline.last());
+            // the last dwell, from 300 to 360 degrees, at zero lift
+            let dwell = &outline[OUTLINE_STEPS as usize * 310 / 360..OUTLINE_STEPS as usize];
+            assert!(dwell.iter().all(|pt| (radius(pt) - 1.0).abs() < 1e-9));
+            // the top of the lift
+            let top = outline[OUTLINE_STEPS as usize * 150 / 360];
+            assert!((radius(&top) - 1.5).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn steep_cams_are_flagged() {
+        assert!(cam(Follower::KnifeEdge).problems().is_empty());
+        let steep = Cam {
+            base_radius: 0.25,
+            ..cam(Follower::Roller { radius: 0.2 })
+        };
+        let problems = steep.problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("pressure angle"));
+        // a flat face doesn't mind the pressure angle, but can't reach into
+        // the hollow a small base circle leaves
+        let cusped = Cam {
+            base_radius: 0.25,
+            ..cam(Follower::FlatFaced)
+        };
+        let problems = cusped.problems();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("flat follower"));
+    }
+}
@@ -0,0 +1,122 @@
+use std::ops::RangeInclusive;
+
+// which part of a clock to design
+#[derive(Clone, Copy, PartialEq)]
+pub enum ClockTrain {
+    // center wheel (once an hour) down to the escape wheel
+    Going,
+    // cannon pinion (once an hour) down to the hour wheel
+    MotionWork,
+}
+
+impl ClockTrain {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "going" => Some(ClockTrain::Going),
+            "motion_work" => Some(ClockTrain::MotionWork),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            ClockTrain::Going => "going",
+            ClockTrain::MotionWork => "motion_work",
+        }
+    }
+
+    // tooth counts clockmakers would recognize, pinions are only a few leaves
+    fn wheel_teeth(&self) -> RangeInclusive<u32> {
+        match self {
+            ClockTrain::Going => 40..=128,
+            ClockTrain::MotionWork => 24..=72,
+        }
+    }
+
+    fn pinion_teeth(&self) -> RangeInclusive<u32> {
+        match self {
+            ClockTrain::Going => 6..=12,
+            ClockTrain::MotionWork => 6..=14,
+        }
+    }
+}
+
+// the motion work turns the hour hand once for every 12 turns of the minute hand
+pub const MOTION_WORK_RATIO: f64 = 12.0;
+
+// turns of the escape wheel per turn of the center wheel. the escape wheel
+// moves one tooth per full swing of the pendulum, and the center wheel
+// carries the minute hand round once an hour.
+pub fn going_train_ratio(pendulum_period: f64, escape_teeth: u32) -> f64 {
+    3600.0 / (pendulum_period * escape_teeth as f64)
+}
+
+// one wheel driving a pinion, or a pinion driving a wheel in the motion work
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClockStage {
+    pub wheel: u32,
+    pub pinion: u32,
+}
+
+impl ClockStage {
+    pub fn ratio(&self) -> f64 {
+        self.wheel as f64 / self.pinion as f64
+    }
+}
+
+// two wheel / pinion stages whose ratios multiply to as close to `ratio` as
+// the train's tooth counts allow. ties go to the stages closest to sharing the
+// reduction evenly, then to the fewest teeth.
+pub fn two_stage_train(train: ClockTrain, ratio: f64) -> Option<[ClockStage; 2]> {
+    if !ratio.is_finite() || ratio <= 0.0 {
+        return None;
+    }
+    let stages: Vec<ClockStage> = train
+        .pinion_teeth()
+        .flat_map(|pinion| {
+            train
+                .wheel_teeth()
+                .map(move |wheel| ClockStage { wheel, pinion })
+        })
+        .collect();
+    let score = |[first, second]: &[ClockStage; 2]| {
+        (
+            (first.ratio() * second.ratio() - ratio).abs(),
+            (first.ratio() - second.ratio()).abs(),
+            first.wheel + first.pinion + second.wheel + second.pinion,
+        )
+    };
+    stages
+        .iter()
+        .flat_map(|&first| stages.iter().map(move |&second| [first, second]))
+        .min_by(|a, b| score(a).partial_cmp(&score(b)).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seconds_pendulum_train_turns_the_escape_wheel_once_a_minute() {
+        let ratio = going_train_ratio(2.0, 30);
+        assert_eq!(ratio, 60.0);
+        let [first, second] = two_stage_train(ClockTrain::Going, ratio).unwrap();
+        assert!((first.ratio() * second.ratio() - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn motion_work_is_twelve_to_one() {
+        let [first, second] = two_stage_train(ClockTrain::MotionWork, MOTION_WORK_RATIO).unwrap();
+        assert!((first.ratio() * second.ratio() - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stages_stay_within_clock_tooth_counts() {
+        let train = ClockTrain::Going;
+        let stages = two_stage_train(train, going_train_ratio(1.5, 40)).unwrap();
+        stages.iter().for_each(|stage| {
+            assert!(train.wheel_teeth().contains(&stage.wheel));
+            assert!(train.pinion_teeth().contains(&stage.pinion));
+        });
+    }
+}
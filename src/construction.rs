@@ -0,0 +1,129 @@
+use crate::geometry::{involute, GearGeometry, Point, Rotation};
+use std::f64;
+
+// one pass of the string unwinding, then a pause on the finished flank
+const SWEEP_MS: f64 = 4000.0;
+const HOLD_MS: f64 = 1500.0;
+
+// construction points marked along the way, like the steps of a hand drawing
+const CONSTRUCTION_STEPS: u32 = 8;
+
+// how far through the unwinding to draw at `ms`, from 0 to 1
+pub fn progress_at(ms: f64) -> f64 {
+    f64::min(ms % (SWEEP_MS + HOLD_MS) / SWEEP_MS, 1.0)
+}
+
+// the first tooth's rising flank being traced by the end of a taut string as
+// it unwinds from the base circle. `center` is in pixels, `scale` in pixels
+// per inch.
+pub fn draw_construction(
+    context: &web_sys::CanvasRenderingContext2d,
+    geometry: &GearGeometry,
+    center: Point,
+    scale: f64,
+    progress: f64,
+) {
+    let base_radius = geometry.base_radius;
+    // nothing to unwind from, and canvas throws on a negative radius
+    if base_radius <= 0.0 {
+        return;
+    }
+    let (_, theta_max) = geometry.flank_theta_range();
+    let theta = theta_max * progress;
+    let rotation = Rotation::new(-geometry.pitch_correction());
+    let to_screen = |pt: Point| {
+        let pt = rotation.apply(&pt);
+        Point {
+            x: center.x + pt.x * scale,
+            y: center.y + pt.y * scale,
+        }
+    };
+    // where the string leaves the base circle, and its free end
+    let tangent_point = |theta: f64| {
+        to_screen(Point {
+            x: base_radius * theta.cos(),
+            y: base_radius * theta.sin(),
+        })
+    };
+    let string_end = |theta: f64| to_screen(involute(base_radius, theta));
+
+    // base circle the string is wound around
+    context.set_stroke_style_str("gray");
+    context.set_line_width(1.0);
+    context
+        .set_line_dash(&js_sys::Array::of2(&4.0.into(), &4.0.into()))
+        .unwrap();
+    context.begin_path();
+    context
+        .arc(
+            center.x,
+            center.y,
+            base_radius * scale,
+            0.0,
+            2.0 * f64::consts::PI,
+        )
+        .unwrap();
+    context.stroke();
+    context.set_line_dash(&js_sys::Array::new()).unwrap();
+
+    // earlier positions of the string, each one tangent to the base circle
+    // and as long as the arc it has unwound
+    context.set_stroke_style_str("rgba(255, 140, 0, 0.35)");
+    (1..=CONSTRUCTION_STEPS)
+        .map(|step| theta_max * step as f64 / CONSTRUCTION_STEPS as f64)
+        .filter(|&step_theta| step_theta < theta)
+        .for_each(|step_theta| {
+            let (from, to) = (tangent_point(step_theta), string_end(step_theta));
+            context.begin_path();
+            context.move_to(from.x, from.y);
+            context.line_to(to.x, to.y);
+            context.stroke();
+            draw_dot(context, to, 3.0, "rgba(0, 90, 200, 0.8)");
+        });
+
+    // the flank traced so far
+    let samples = geometry.involute_steps.max(2);
+    context.set_stroke_style_str("rgb(0, 90, 200)");
+    context.set_line_width(2.0);
+    context.begin_path();
+    (0..=samples).for_each(|i| {
+        let pt = string_end(theta * i as f64 / samples as f64);
+        if i == 0 {
+            context.move_to(pt.x, pt.y);
+        } else {
+            context.line_to(pt.x, pt.y);
+        }
+    });
+    context.stroke();
+
+    // the string as it is now
+    let (from, to) = (tangent_point(theta), string_end(theta));
+    context.set_stroke_style_str("darkorange");
+    context.begin_path();
+    context.move_to(from.x, from.y);
+    context.line_to(to.x, to.y);
+    context.stroke();
+    draw_dot(context, from, 3.0, "darkorange");
+    draw_dot(context, to, 4.0, "rgb(0, 90, 200)");
+    context.set_line_width(1.0);
+
+    let caption = format!(
+        "unwound {:.1}\u{b0}, string = base radius x angle = {:.3} in",
+        theta.to_degrees(),
+        base_radius * theta
+    );
+    context.set_font("12px sans-serif");
+    context.set_text_align("left");
+    context.set_text_baseline("top");
+    context.set_fill_style_str("black");
+    context.fill_text(&caption, 10.0, 10.0).unwrap();
+}
+
+fn draw_dot(context: &web_sys::CanvasRenderingContext2d, at: Point, radius: f64, color: &str) {
+    context.set_fill_style_str(color);
+    context.begin_path();
+    context
+        .arc(at.x, at.y, radius, 0.0, 2.0 * f64::consts::PI)
+        .unwrap();
+    context.fill();
+}
@@ -0,0 +1,289 @@
+use crate::geometry::Point;
+use crate::reference;
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+// points round each rounded corner
+const CORNER_STEPS: u32 = 12;
+
+// a shape from an svg cut out of a gear's blank, like a logo or a window.
+// `paths` are closed loops in inches, centered on the origin. the shape is
+// scaled by `scale`, turned `rotation` degrees and moved `offset` inches
+// from the gear's center.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportedCutout {
+    pub name: String,
+    pub paths: Vec<Vec<Point>>,
+    pub offset: Point,
+    pub rotation: f64,
+    pub scale: f64,
+}
+
+impl ImportedCutout {
+    // the svg's shapes as loops, open paths closed back to their start.
+    // lines and other shapes with nothing inside them are left out.
+    pub fn from_svg(name: &str, text: &str) -> Result<ImportedCutout, String> {
+        let mut paths: Vec<Vec<Point>> = reference::parse_svg(text)?
+            .into_iter()
+            .filter(|path| path.len() >= 3)
+            .map(|mut path| {
+                if path.first() != path.last() {
+                    path.push(path[0]);
+                }
+                path
+            })
+            .filter(|path| path.len() > 3)
+            .collect();
+        if paths.is_empty() {
+            return Err(format!("{} has no closed shapes in it", name));
+        }
+        let center = reference::Reference {
+            name: name.to_string(),
+            paths: paths.clone(),
+        }
+        .center();
+        for pt in paths.iter_mut().flatten() {
+            *pt = Point {
+                x: pt.x - center.x,
+                y: pt.y - center.y,
+            };
+        }
+        Ok(ImportedCutout {
+            name: name.to_string(),
+            paths,
+            offset: Point { x: 0.0, y: 0.0 },
+            rotation: 0.0,
+            scale: 1.0,
+        })
+    }
+
+    // the loops where they're cut, in inches from the gear's center
+    pub fn placed(&self) -> Vec<Vec<Point>> {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        self.paths
+            .iter()
+            .map(|path| {
+                path.iter()
+                    .map(|pt| {
+                        let (x, y) = (pt.x * self.scale, pt.y * self.scale);
+                        Point {
+                            x: x * cos - y * sin + self.offset.x,
+                            y: x * sin + y * cos + self.offset.y,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// the outline of a parametric cutout
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ShapeKind {
+    // `width` across
+    #[default]
+    Circle,
+    // `width` long and `height` wide, with round ends
+    Slot,
+    RoundedRectangle,
+}
+
+impl ShapeKind {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "circle" => Some(ShapeKind::Circle),
+            "slot" => Some(ShapeKind::Slot),
+            "rounded_rectangle" => Some(ShapeKind::RoundedRectangle),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            ShapeKind::Circle => "circle",
+            ShapeKind::Slot => "slot",
+            ShapeKind::RoundedRectangle => "rounded_rectangle",
+        }
+    }
+}
+
+// a cutout drawn from a few numbers, in inches and degrees. the shape is
+// turned `rotation` about its own center and moved `offset` from the gear's
+// center, then repeated `copies` times evenly round the gear.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShapeCutout {
+    pub kind: ShapeKind,
+    pub width: f64,
+    pub height: f64,
+    pub corner_radius: f64,
+    pub offset: Point,
+    pub rotation: f64,
+    pub copies: u32,
+}
+
+impl Default for ShapeCutout {
+    fn default() -> Self {
+        Self {
+            kind: ShapeKind::Circle,
+            width: 0.25,
+            height: 0.125,
+            corner_radius: 0.05,
+            offset: Point { x: 0.5, y: 0.0 },
+            rotation: 0.0,
+            copies: 1,
+        }
+    }
+}
+
+impl ShapeCutout {
+    // every copy as a closed loop, in inches from the gear's center. a
+    // shape with no size has nothing to cut.
+    pub fn loops(&self) -> Vec<Vec<Point>> {
+        let (width, height, radius) = match self.kind {
+            ShapeKind::Circle => (self.width, self.width, self.width / 2.0),
+            ShapeKind::Slot => (self.width, self.height, self.width.min(self.height) / 2.0),
+            ShapeKind::RoundedRectangle => (
+                self.width,
+                self.height,
+                self.corner_radius
+                    .clamp(0.0, self.width.min(self.height) / 2.0),
+            ),
+        };
+        if width <= 0.0 || height <= 0.0 {
+            return Vec::new();
+        }
+        let shape = rounded_rectangle(width, height, radius);
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let placed: Vec<Point> = shape
+            .iter()
+            .map(|pt| Point {
+                x: pt.x * cos - pt.y * sin + self.offset.x,
+                y: pt.x * sin + pt.y * cos + self.offset.y,
+            })
+            .collect();
+        let copies = self.copies.max(1);
+        (0..copies)
+            .map(|copy| {
+                let angle = 2.0 * f64::consts::PI * copy as f64 / copies as f64;
+                let (sin, cos) = angle.sin_cos();
+                placed
+                    .iter()
+                    .map(|pt| Point {
+                        x: pt.x * cos - pt.y * sin,
+                        y: pt.x * sin + pt.y * cos,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// a closed loop round a rectangle centered on the origin, its corners
+// rounded to `radius`. the straight sides between corners can be nothing,
+// like a circle's, so points landing on the last one are skipped.
+fn rounded_rectangle(width: f64, height: f64, radius: f64) -> Vec<Point> {
+    let (half_x, half_y) = (width / 2.0 - radius, height / 2.0 - radius);
+    let steps = if radius > 0.0 { CORNER_STEPS } else { 0 };
+    let mut points: Vec<Point> = Vec::new();
+    for (corner, (x, y)) in [
+        (half_x, half_y),
+        (-half_x, half_y),
+        (-half_x, -half_y),
+        (half_x, -half_y),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let start = corner as f64 * f64::consts::FRAC_PI_2;
+        for step in 0..=steps {
+            let angle = start + f64::consts::FRAC_PI_2 * step as f64 / steps.max(1) as f64;
+            let pt = Point {
+                x: x + radius * angle.cos(),
+                y: y + radius * angle.sin(),
+            };
+            let repeated = points
+                .last()
+                .is_some_and(|last| (last.x - pt.x).hypot(last.y - pt.y) < 1e-12);
+            if !repeated {
+                points.push(pt);
+            }
+        }
+    }
+    if (points[0].x - points[points.len() - 1].x).hypot(points[0].y - points[points.len() - 1].y)
+        < 1e-12
+    {
+        points.pop();
+    }
+    points.push(points[0]);
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imported_shapes_are_centered_and_closed() {
+        let svg = r#"<svg width="2in" height="2in" viewBox="0 0 200 200">
+            <polyline points="0,0 100,0 100,50"/>
+            <line x1="0" y1="0" x2="100" y2="100"/>
+        </svg>"#;
+        let mut cutout = ImportedCutout::from_svg("logo.svg", svg).unwrap();
+        assert_eq!(cutout.paths.len(), 1);
+        let triangle = &cutout.paths[0];
+        assert_eq!(triangle.len(), 4);
+        assert_eq!(triangle.first(), triangle.last());
+        assert_eq!(triangle[0], Point { x: -0.5, y: -0.25 });
+
+        cutout.scale = 2.0;
+        cutout.rotation = 90.0;
+        cutout.offset = Point { x: 1.0, y: 0.0 };
+        let corner = cutout.placed()[0][1];
+        assert!((corner.x - 1.5).abs() < 1e-9 && (corner.y - 1.0).abs() < 1e-9);
+        assert!(
+            ImportedCutout::from_svg("line.svg", svg.replace("polyline", "x").as_str()).is_err()
+        );
+    }
+
+    #[test]
+    fn shapes_repeat_round_the_gear() {
+        let circle = ShapeCutout {
+            copies: 4,
+            ..ShapeCutout::default()
+        };
+        let loops = circle.loops();
+        assert_eq!(loops.len(), 4);
+        for (copy, angle) in loops.iter().zip([0.0f64, 90.0, 180.0, 270.0]) {
+            assert_eq!(copy.first(), copy.last());
+            // every point is on a 0.25 inch circle half an inch out
+            let (sin, cos) = angle.to_radians().sin_cos();
+            assert!(copy
+                .iter()
+                .all(|pt| ((pt.x - 0.5 * cos).hypot(pt.y - 0.5 * sin) - 0.125).abs() < 1e-9));
+        }
+
+        // a square has just its four corners
+        let square = ShapeCutout {
+            kind: ShapeKind::RoundedRectangle,
+            width: 1.0,
+            height: 0.5,
+            corner_radius: 0.0,
+            offset: Point { x: 0.0, y: 0.0 },
+            ..ShapeCutout::default()
+        };
+        let square = &square.loops()[0];
+        assert_eq!(square.len(), 5);
+        assert_eq!(square[0], Point { x: 0.5, y: 0.25 });
+        let slot = ShapeCutout {
+            kind: ShapeKind::Slot,
+            ..ShapeCutout::default()
+        };
+        assert!(slot.loops()[0]
+            .iter()
+            .all(|pt| (pt.y).abs() <= 0.0625 + 1e-12));
+        assert_eq!(
+            ShapeKind::from_value(ShapeKind::Slot.value()),
+            Some(ShapeKind::Slot)
+        );
+    }
+}
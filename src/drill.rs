@@ -0,0 +1,198 @@
+use crate::geometry::{GearOutline, Point};
+use crate::spacers::Nest;
+use wasm_bindgen::prelude::*;
+
+// each arm of a center punch crosshair, in inches
+const CROSS_ARM: f64 = 0.15;
+// the pilot drill the circles are drawn for, in inches
+const PILOT_DIAMETER: f64 = 0.125;
+// marks closer than this are the same hole, in inches
+const SAME_HOLE: f64 = 1e-6;
+// how far a hole's points can stray from its radius and still be round
+const ROUND_TOLERANCE: f64 = 1e-6;
+// marking is drawn in a color of its own, apart from the cut lines
+pub const MARKING_COLOR: &str = "#0070c0";
+
+// somewhere to center punch and drill, in inches. `diameter` is the
+// finished hole's, or zero when it isn't known, like a gear's bore.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mark {
+    pub center: Point,
+    pub diameter: f64,
+}
+
+impl Mark {
+    // the pilot circle, never bigger than the hole itself
+    pub fn pilot_radius(&self) -> f64 {
+        if self.diameter > 0.0 {
+            PILOT_DIAMETER.min(self.diameter) / 2.0
+        } else {
+            PILOT_DIAMETER / 2.0
+        }
+    }
+
+    // the crosshair's two strokes, each from one end to the other
+    pub fn cross(&self) -> [(Point, Point); 2] {
+        let Point { x, y } = self.center;
+        [
+            (
+                Point {
+                    x: x - CROSS_ARM,
+                    y,
+                },
+                Point {
+                    x: x + CROSS_ARM,
+                    y,
+                },
+            ),
+            (
+                Point {
+                    x,
+                    y: y - CROSS_ARM,
+                },
+                Point {
+                    x,
+                    y: y + CROSS_ARM,
+                },
+            ),
+        ]
+    }
+}
+
+// every hole to drill: each gear's bore, once per arbor, its round web
+// holes and the bores of any spacers. cut-out web windows aren't drilled.
+pub fn marks(gears: &[GearOutline], spacers: Option<&Nest>) -> Vec<Mark> {
+    let mut marks: Vec<Mark> = Vec::new();
+    let mut add = |mark: Mark| {
+        let duplicate = marks.iter().any(|other| {
+            (other.center.x - mark.center.x).abs() < SAME_HOLE
+                && (other.center.y - mark.center.y).abs() < SAME_HOLE
+        });
+        if !duplicate {
+            marks.push(mark);
+        }
+    };
+    for gear in gears {
+        add(Mark {
+            center: gear.center,
+            diameter: 0.0,
+        });
+        for hole in &gear.holes {
+            if let Some((center, radius)) = round_hole(hole) {
+                add(Mark {
+                    center: center + gear.center,
+                    diameter: 2.0 * radius,
+                });
+            }
+        }
+    }
+    for ring in spacers.iter().flat_map(|nest| &nest.rings) {
+        add(Mark {
+            center: ring.center,
+            diameter: 2.0 * ring.inner_radius,
+        });
+    }
+    marks
+}
+
+// the center and radius of a closed outline that's a circle, `None` for
+// any other shape
+fn round_hole(hole: &[Point]) -> Option<(Point, f64)> {
+    // the last point repeats the first
+    let points = &hole[..hole.len().saturating_sub(1)];
+    if points.len() < 3 {
+        return None;
+    }
+    let count = points.len() as f64;
+    let center = Point {
+        x: points.iter().map(|pt| pt.x).sum::<f64>() / count,
+        y: points.iter().map(|pt| pt.y).sum::<f64>() / count,
+    };
+    let distance = |pt: &Point| ((pt.x - center.x).powi(2) + (pt.y - center.y).powi(2)).sqrt();
+    let radius = distance(&points[0]);
+    points
+        .iter()
+        .all(|pt| (distance(pt) - radius).abs() < ROUND_TOLERANCE)
+        .then_some((center, radius))
+}
+
+// draws the marks in the marking color, on a context already centered on
+// the page
+pub fn draw(context: &web_sys::CanvasRenderingContext2d, marks: &[Mark], ppi: f64) {
+    context.set_stroke_style_str(MARKING_COLOR);
+    context
+        .set_line_dash(&JsValue::from(Vec::<f64>::new()))
+        .unwrap();
+    for mark in marks {
+        context.begin_path();
+        for (from, to) in mark.cross() {
+            context.move_to(from.x * ppi, from.y * ppi);
+            context.line_to(to.x * ppi, to.y * ppi);
+        }
+        context.stroke();
+        context.begin_path();
+        context
+            .arc(
+                mark.center.x * ppi,
+                mark.center.y * ppi,
+                mark.pilot_radius() * ppi,
+                0.0,
+                2.0 * std::f64::consts::PI,
+            )
+            .unwrap();
+        context.stroke();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{GearGeometry, GearSpecs, Thinning, ToothProfile};
+
+    fn gear(center: Point, holes: Vec<Vec<Point>>) -> GearOutline {
+        GearOutline {
+            center,
+            segments: Vec::new(),
+            holes,
+            geometry: GearGeometry::new(&GearSpecs {
+                teeth: 30.0,
+                diametric_pitch: 12.0,
+                tooth_angle: 20.0,
+                clearance_mult: 0.167,
+                backlash_mult: 0.05,
+                involute_steps: 10,
+                profile: ToothProfile::Involute,
+                helix_angle: 0.0,
+                thinning: Thinning::Half,
+            }),
+        }
+    }
+
+    #[test]
+    fn every_bore_and_round_hole_is_marked_once() {
+        let circle: Vec<Point> = (0..=24)
+            .map(|i| {
+                let angle = 2.0 * std::f64::consts::PI * (i % 24) as f64 / 24.0;
+                Point {
+                    x: 0.5 + 0.1 * angle.cos(),
+                    y: 0.1 * angle.sin(),
+                }
+            })
+            .collect();
+        let window = vec![
+            Point { x: 0.2, y: 0.0 },
+            Point { x: 0.6, y: 0.0 },
+            Point { x: 0.6, y: 0.3 },
+            Point { x: 0.2, y: 0.0 },
+        ];
+        let center = Point { x: 1.0, y: 0.0 };
+        // two gears on one arbor share a bore
+        let gears = [gear(center, vec![circle, window]), gear(center, Vec::new())];
+        let marks = marks(&gears, None);
+        assert_eq!(marks.len(), 2);
+        assert_eq!(marks[0].center, center);
+        assert_eq!(marks[0].pilot_radius(), PILOT_DIAMETER / 2.0);
+        assert!((marks[1].center.x - 1.5).abs() < 1e-12 && marks[1].center.y.abs() < 1e-12);
+        assert!((marks[1].diameter - 0.2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,582 @@
+use crate::bridges::{self, Bridges};
+use crate::drill::Mark;
+use crate::geometry::{GearOutline, OutlineSegment, Point};
+use crate::kerf_test::KerfTest;
+use crate::mold::Blank;
+use crate::roughing::Paths;
+use crate::spacers::Nest;
+use crate::tolerance;
+use std::fmt::Write;
+
+// how flank curves are written into the dxf
+#[derive(Clone, Copy, PartialEq)]
+pub enum DxfCurveMode {
+    Polyline,
+    Spline,
+}
+
+impl DxfCurveMode {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "polyline" => Some(DxfCurveMode::Polyline),
+            "spline" => Some(DxfCurveMode::Spline),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            DxfCurveMode::Polyline => "polyline",
+            DxfCurveMode::Spline => "spline",
+        }
+    }
+}
+
+// cut lines go on the default layer, drill marks on one of their own so
+// they can be engraved, or left off
+const CUT_LAYER: &str = "0";
+const MARKING_LAYER: &str = "MARKING";
+// roughing passes, one layer for the tooth spaces and one for the web
+// cutouts so they can get different depths
+const ROUGH_SPACES_LAYER: &str = "ROUGH_SPACES";
+const ROUGH_HOLES_LAYER: &str = "ROUGH_HOLES";
+// the stretches of outline left standing as holding tabs
+const TABS_LAYER: &str = "TABS";
+
+// build a dxf document (in inches) from tessellated gears, with any spacer
+// rings nested alongside them, drill marks on the marking layer and
+// roughing passes on layers of their own. with holding tabs, each gear's
+// outlines are broken up around them.
+pub fn export_gears(
+    gears: &[GearOutline],
+    spacers: Option<&Nest>,
+    marks: &[Mark],
+    roughing: Option<&Paths>,
+    tabs: Bridges,
+    mode: DxfCurveMode,
+) -> String {
+    let mut out = write_start();
+    for gear in gears {
+        if tabs.count > 0 {
+            write_tabbed_gear(&mut out, gear, tabs);
+        } else {
+            write_gear(&mut out, gear, mode);
+        }
+    }
+    if let Some(spacers) = spacers {
+        for ring in &spacers.rings {
+            write_circle(&mut out, CUT_LAYER, ring.center, ring.outer_radius);
+            if ring.inner_radius > 0.0 {
+                write_circle(&mut out, CUT_LAYER, ring.center, ring.inner_radius);
+            }
+        }
+        let label = &spacers.label;
+        write_text(&mut out, label.position, label.height, &label.text);
+    }
+    for mark in marks {
+        for (from, to) in mark.cross() {
+            write_line(&mut out, MARKING_LAYER, from, to);
+        }
+        write_circle(&mut out, MARKING_LAYER, mark.center, mark.pilot_radius());
+    }
+    if let Some(roughing) = roughing {
+        for path in &roughing.spaces {
+            write_path(&mut out, ROUGH_SPACES_LAYER, path);
+        }
+        for path in &roughing.holes {
+            write_path(&mut out, ROUGH_HOLES_LAYER, path);
+        }
+    }
+    write_end(out)
+}
+
+// build a dxf document (in inches) of mold cavities: each gear's outline
+// and cutouts inside its blank, together bounding the negative of the gear
+pub fn export_mold(gears: &[GearOutline], blanks: &[Blank], mode: DxfCurveMode) -> String {
+    let mut out = write_start();
+    for (gear, blank) in gears.iter().zip(blanks) {
+        match blank.corners() {
+            Some(corners) => write_closed_polyline(&mut out, &[OutlineSegment::Line(corners)]),
+            None => write_circle(&mut out, CUT_LAYER, blank.center, blank.radius),
+        }
+        write_gear(&mut out, gear, mode);
+    }
+    write_end(out)
+}
+
+// build a dxf document (in inches) with a single closed outline, like a
+// cutter profile
+pub fn export_outline(outline: &[OutlineSegment]) -> String {
+    let mut out = write_start();
+    write_closed_polyline(&mut out, outline);
+    write_end(out)
+}
+
+// build a dxf document (in inches) of a kerf test, its outlines to cut and
+// its labels as text to engrave
+pub fn export_kerf_test(test: &KerfTest) -> String {
+    let mut out = write_start();
+    for outline in &test.outlines {
+        write_closed_polyline(&mut out, &[OutlineSegment::Line(outline.clone())]);
+    }
+    for label in &test.labels {
+        write_text(&mut out, label.position, label.height, &label.text);
+    }
+    write_end(out)
+}
+
+// an AC1015 (autocad 2000) document as it's written. everything after the
+// header goes into `body`, handing out handles as it goes, so the header
+// and its handle seed are only written once the rest is done.
+struct Dxf {
+    body: String,
+    next_handle: u32,
+    // the model space block record, the owner of every entity
+    model_space: String,
+}
+
+impl Dxf {
+    fn handle(&mut self) -> String {
+        let handle = format!("{:X}", self.next_handle);
+        self.next_handle += 1;
+        handle
+    }
+}
+
+// the tables and blocks every document needs, and the start of the
+// entities section
+fn write_start() -> Dxf {
+    let mut out = Dxf {
+        body: String::new(),
+        next_handle: 1,
+        model_space: String::new(),
+    };
+    let paper_space = write_tables(&mut out);
+    write_blocks(&mut out, &paper_space);
+    write_pair(&mut out, 0, "SECTION");
+    write_pair(&mut out, 2, "ENTITIES");
+    out
+}
+
+// the end of the entities section and the objects after it, behind the
+// header
+fn write_end(mut out: Dxf) -> String {
+    write_pair(&mut out, 0, "ENDSEC");
+    write_objects(&mut out);
+    write_pair(&mut out, 0, "EOF");
+
+    let mut header = Dxf {
+        body: String::new(),
+        next_handle: out.next_handle,
+        model_space: String::new(),
+    };
+    write_pair(&mut header, 0, "SECTION");
+    write_pair(&mut header, 2, "HEADER");
+    write_pair(&mut header, 9, "$ACADVER");
+    write_pair(&mut header, 1, "AC1015");
+    write_pair(&mut header, 9, "$HANDSEED");
+    write_pair(&mut header, 5, &format!("{:X}", out.next_handle));
+    write_pair(&mut header, 9, "$INSUNITS");
+    write_pair(&mut header, 70, "1"); // inches
+    write_pair(&mut header, 0, "ENDSEC");
+    header.body + &out.body
+}
+
+// the symbol tables, returning the paper space block record's handle
+fn write_tables(out: &mut Dxf) -> String {
+    write_pair(out, 0, "SECTION");
+    write_pair(out, 2, "TABLES");
+
+    // viewports, views and coordinate systems are left to the reader
+    for name in ["VPORT", "VIEW", "UCS"] {
+        write_table(out, name, 0);
+        write_pair(out, 0, "ENDTAB");
+    }
+
+    let table = write_table(out, "LTYPE", 3);
+    for (name, description) in [
+        ("ByBlock", ""),
+        ("ByLayer", ""),
+        ("Continuous", "Solid line"),
+    ] {
+        write_record(out, "LTYPE", &table, "AcDbLinetypeTableRecord");
+        write_pair(out, 2, name);
+        write_pair(out, 70, "0");
+        write_pair(out, 3, description);
+        write_pair(out, 72, "65");
+        write_pair(out, 73, "0");
+        write_number(out, 40, 0.0);
+    }
+    write_pair(out, 0, "ENDTAB");
+
+    let layers = [
+        CUT_LAYER,
+        MARKING_LAYER,
+        ROUGH_SPACES_LAYER,
+        ROUGH_HOLES_LAYER,
+        TABS_LAYER,
+    ];
+    let table = write_table(out, "LAYER", layers.len());
+    for name in layers {
+        write_record(out, "LAYER", &table, "AcDbLayerTableRecord");
+        write_pair(out, 2, name);
+        write_pair(out, 70, "0");
+        write_pair(out, 62, "7"); // white
+        write_pair(out, 6, "Continuous");
+    }
+    write_pair(out, 0, "ENDTAB");
+
+    let table = write_table(out, "STYLE", 1);
+    write_record(out, "STYLE", &table, "AcDbTextStyleTableRecord");
+    write_pair(out, 2, "Standard");
+    write_pair(out, 70, "0");
+    write_number(out, 40, 0.0);
+    write_number(out, 41, 1.0);
+    write_number(out, 50, 0.0);
+    write_pair(out, 71, "0");
+    write_number(out, 42, 0.2);
+    write_pair(out, 3, "txt");
+    write_pair(out, 4, "");
+    write_pair(out, 0, "ENDTAB");
+
+    let table = write_table(out, "APPID", 1);
+    write_record(out, "APPID", &table, "AcDbRegAppTableRecord");
+    write_pair(out, 2, "ACAD");
+    write_pair(out, 70, "0");
+    write_pair(out, 0, "ENDTAB");
+
+    write_table(out, "DIMSTYLE", 0);
+    write_pair(out, 100, "AcDbDimStyleTable");
+    write_pair(out, 0, "ENDTAB");
+
+    let table = write_table(out, "BLOCK_RECORD", 2);
+    out.model_space = write_record(out, "BLOCK_RECORD", &table, "AcDbBlockTableRecord");
+    write_pair(out, 2, "*Model_Space");
+    let paper_space = write_record(out, "BLOCK_RECORD", &table, "AcDbBlockTableRecord");
+    write_pair(out, 2, "*Paper_Space");
+    write_pair(out, 0, "ENDTAB");
+
+    write_pair(out, 0, "ENDSEC");
+    paper_space
+}
+
+// a table's start, returning its handle for its records to point back to
+fn write_table(out: &mut Dxf, name: &str, count: usize) -> String {
+    let handle = out.handle();
+    write_pair(out, 0, "TABLE");
+    write_pair(out, 2, name);
+    write_pair(out, 5, &handle);
+    write_pair(out, 330, "0");
+    write_pair(out, 100, "AcDbSymbolTable");
+    write_pair(out, 70, &count.to_string());
+    handle
+}
+
+// the start of a table's record, returning its handle
+fn write_record(out: &mut Dxf, kind: &str, table: &str, subclass: &str) -> String {
+    let handle = out.handle();
+    write_pair(out, 0, kind);
+    write_pair(out, 5, &handle);
+    write_pair(out, 330, table);
+    write_pair(out, 100, "AcDbSymbolTableRecord");
+    write_pair(out, 100, subclass);
+    handle
+}
+
+// the model and paper space blocks, both empty, their entities go in the
+// entities section
+fn write_blocks(out: &mut Dxf, paper_space: &str) {
+    write_pair(out, 0, "SECTION");
+    write_pair(out, 2, "BLOCKS");
+    let model_space = out.model_space.clone();
+    for (name, record) in [
+        ("*Model_Space", model_space.as_str()),
+        ("*Paper_Space", paper_space),
+    ] {
+        let handle = out.handle();
+        write_pair(out, 0, "BLOCK");
+        write_pair(out, 5, &handle);
+        write_pair(out, 330, record);
+        write_pair(out, 100, "AcDbEntity");
+        write_pair(out, 8, CUT_LAYER);
+        write_pair(out, 100, "AcDbBlockBegin");
+        write_pair(out, 2, name);
+        write_pair(out, 70, "0");
+        write_number(out, 10, 0.0);
+        write_number(out, 20, 0.0);
+        write_number(out, 30, 0.0);
+        write_pair(out, 3, name);
+        write_pair(out, 1, "");
+
+        let handle = out.handle();
+        write_pair(out, 0, "ENDBLK");
+        write_pair(out, 5, &handle);
+        write_pair(out, 330, record);
+        write_pair(out, 100, "AcDbEntity");
+        write_pair(out, 8, CUT_LAYER);
+        write_pair(out, 100, "AcDbBlockEnd");
+    }
+    write_pair(out, 0, "ENDSEC");
+}
+
+// the root dictionary, holding only the (empty) group dictionary
+fn write_objects(out: &mut Dxf) {
+    write_pair(out, 0, "SECTION");
+    write_pair(out, 2, "OBJECTS");
+    let root = out.handle();
+    let groups = out.handle();
+    write_pair(out, 0, "DICTIONARY");
+    write_pair(out, 5, &root);
+    write_pair(out, 330, "0");
+    write_pair(out, 100, "AcDbDictionary");
+    write_pair(out, 281, "1");
+    write_pair(out, 3, "ACAD_GROUP");
+    write_pair(out, 350, &groups);
+    write_pair(out, 0, "DICTIONARY");
+    write_pair(out, 5, &groups);
+    write_pair(out, 330, &root);
+    write_pair(out, 100, "AcDbDictionary");
+    write_pair(out, 281, "1");
+    write_pair(out, 0, "ENDSEC");
+}
+
+// the start of an entity in model space, up to its own subclass
+fn write_entity(out: &mut Dxf, kind: &str, layer: &str, subclass: &str) {
+    let handle = out.handle();
+    let owner = out.model_space.clone();
+    write_pair(out, 0, kind);
+    write_pair(out, 5, &handle);
+    write_pair(out, 330, &owner);
+    write_pair(out, 100, "AcDbEntity");
+    write_pair(out, 8, layer);
+    write_pair(out, 100, subclass);
+}
+
+fn write_gear(out: &mut Dxf, gear: &GearOutline, mode: DxfCurveMode) {
+    let outline: Vec<OutlineSegment> = gear
+        .segments
+        .iter()
+        .map(|segment| translate_segment(segment, gear.center))
+        .collect();
+    match mode {
+        DxfCurveMode::Polyline => write_closed_polyline(out, &outline),
+        DxfCurveMode::Spline => outline.iter().for_each(|segment| match segment {
+            OutlineSegment::Line(points) => write_lines(out, points),
+            OutlineSegment::Flank(points) => write_spline(out, points),
+        }),
+    }
+    // web cutouts go on the same layer, they're cut too
+    for hole in &gear.holes {
+        let hole: Vec<Point> = hole.iter().map(|pt| *pt + gear.center).collect();
+        write_closed_polyline(out, &[OutlineSegment::Line(hole)]);
+    }
+}
+
+// a gear's outline and cutouts as open polylines between their tabs, and
+// the tabs on a layer of their own. flanks are always polylines here, the
+// breaks don't fall on segment ends.
+fn write_tabbed_gear(out: &mut Dxf, gear: &GearOutline, tabs: Bridges) {
+    let outline: Vec<Point> = tolerance::outline_points(&gear.segments)
+        .iter()
+        .map(|pt| *pt + gear.center)
+        .collect();
+    let holes = gear.holes.iter().map(|hole| {
+        hole.iter()
+            .map(|pt| *pt + gear.center)
+            .collect::<Vec<Point>>()
+    });
+    for points in std::iter::once(outline).chain(holes) {
+        let split = bridges::split(&points, tabs);
+        for cut in &split.cuts {
+            write_path(out, CUT_LAYER, cut);
+        }
+        for tab in &split.tabs {
+            write_path(out, TABS_LAYER, tab);
+        }
+    }
+}
+
+fn translate_segment(segment: &OutlineSegment, center: Point) -> OutlineSegment {
+    let shift = |points: &[Point]| points.iter().map(|pt| *pt + center).collect();
+    match segment {
+        OutlineSegment::Line(points) => OutlineSegment::Line(shift(points)),
+        OutlineSegment::Flank(points) => OutlineSegment::Flank(shift(points)),
+    }
+}
+
+fn write_pair(out: &mut Dxf, code: u32, value: &str) {
+    writeln!(out.body, "{}\n{}", code, value).unwrap();
+}
+
+fn write_number(out: &mut Dxf, code: u32, value: f64) {
+    writeln!(out.body, "{}\n{:.6}", code, value).unwrap();
+}
+
+fn write_closed_polyline(out: &mut Dxf, outline: &[OutlineSegment]) {
+    // segments share end points, so only keep the first point once
+    let mut points: Vec<Point> = Vec::new();
+    outline.iter().for_each(|segment| {
+        let skip = if points.is_empty() { 0 } else { 1 };
+        points.extend(segment.points().iter().skip(skip));
+    });
+    // the last point closes back onto the first
+    points.pop();
+
+    write_entity(out, "LWPOLYLINE", CUT_LAYER, "AcDbPolyline");
+    write_pair(out, 90, &points.len().to_string());
+    write_pair(out, 70, "1"); // closed
+    points.iter().for_each(|pt| {
+        write_number(out, 10, pt.x);
+        write_number(out, 20, pt.y);
+    });
+}
+
+// a polyline through `points`, closed if it ends where it starts
+fn write_path(out: &mut Dxf, layer: &str, points: &[Point]) {
+    let closed = points.len() > 2 && points.first() == points.last();
+    let points = if closed {
+        &points[..points.len() - 1]
+    } else {
+        points
+    };
+    write_entity(out, "LWPOLYLINE", layer, "AcDbPolyline");
+    write_pair(out, 90, &points.len().to_string());
+    write_pair(out, 70, if closed { "1" } else { "0" });
+    points.iter().for_each(|pt| {
+        write_number(out, 10, pt.x);
+        write_number(out, 20, pt.y);
+    });
+}
+
+fn write_lines(out: &mut Dxf, points: &[Point]) {
+    points
+        .windows(2)
+        .for_each(|pair| write_line(out, CUT_LAYER, pair[0], pair[1]));
+}
+
+fn write_line(out: &mut Dxf, layer: &str, from: Point, to: Point) {
+    write_entity(out, "LINE", layer, "AcDbLine");
+    write_number(out, 10, from.x);
+    write_number(out, 20, from.y);
+    write_number(out, 11, to.x);
+    write_number(out, 21, to.y);
+}
+
+fn write_circle(out: &mut Dxf, layer: &str, center: Point, radius: f64) {
+    write_entity(out, "CIRCLE", layer, "AcDbCircle");
+    write_number(out, 10, center.x);
+    write_number(out, 20, center.y);
+    write_number(out, 40, radius);
+}
+
+// single line text centered on `position`
+fn write_text(out: &mut Dxf, position: Point, height: f64, text: &str) {
+    write_entity(out, "TEXT", CUT_LAYER, "AcDbText");
+    write_number(out, 10, position.x);
+    write_number(out, 20, position.y);
+    write_number(out, 40, height);
+    write_pair(out, 1, text);
+    write_pair(out, 7, "Standard");
+    write_pair(out, 72, "4"); // middle
+    write_number(out, 11, position.x);
+    write_number(out, 21, position.y);
+    write_pair(out, 100, "AcDbText");
+}
+
+// writes a cubic spline passing through every point. each span is the
+// catmull-rom bezier between neighbouring points, expressed as a b-spline
+// with triple interior knots so cam packages don't have to fit it themselves.
+fn write_spline(out: &mut Dxf, points: &[Point]) {
+    let count = points.len();
+    if count < 2 {
+        return;
+    }
+    let at = |i: isize| points[i.clamp(0, count as isize - 1) as usize];
+
+    let mut control_points = vec![points[0]];
+    (0..count as isize - 1).for_each(|i| {
+        let (prev, start, end, next) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+        control_points.push(Point {
+            x: start.x + (end.x - prev.x) / 6.0,
+            y: start.y + (end.y - prev.y) / 6.0,
+        });
+        control_points.push(Point {
+            x: end.x - (next.x - start.x) / 6.0,
+            y: end.y - (next.y - start.y) / 6.0,
+        });
+        control_points.push(end);
+    });
+
+    let mut knots = vec![0.0; 4];
+    (1..count - 1).for_each(|k| knots.extend([k as f64; 3]));
+    knots.extend([(count - 1) as f64; 4]);
+
+    write_entity(out, "SPLINE", CUT_LAYER, "AcDbSpline");
+    write_pair(out, 70, "8"); // planar
+    write_pair(out, 71, "3"); // cubic
+    write_pair(out, 72, &knots.len().to_string());
+    write_pair(out, 73, &control_points.len().to_string());
+    write_pair(out, 74, "0");
+    knots.iter().for_each(|knot| write_number(out, 40, *knot));
+    control_points.iter().for_each(|pt| {
+        write_number(out, 10, pt.x);
+        write_number(out, 20, pt.y);
+        write_number(out, 30, 0.0);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the document's group codes and values, in order
+    fn pairs(text: &str) -> Vec<(u32, &str)> {
+        let lines: Vec<&str> = text.lines().collect();
+        lines
+            .chunks(2)
+            .map(|pair| (pair[0].parse().unwrap(), pair[1]))
+            .collect()
+    }
+
+    #[test]
+    fn documents_are_autocad_2000_with_every_handle_below_the_seed() {
+        let square = vec![
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 1.0, y: 0.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 0.0, y: 0.0 },
+        ];
+        let text = export_outline(&[OutlineSegment::Flank(square)]);
+        let pairs = pairs(&text);
+        let header_value = |name: &str| {
+            let at = pairs.iter().position(|pair| *pair == (9, name)).unwrap();
+            pairs[at + 1].1
+        };
+        assert_eq!(header_value("$ACADVER"), "AC1015");
+        assert_eq!(header_value("$INSUNITS"), "1");
+
+        let seed = u32::from_str_radix(header_value("$HANDSEED"), 16).unwrap();
+        let handles: Vec<u32> = pairs
+            .iter()
+            .filter(|(code, _)| *code == 5)
+            .map(|(_, value)| u32::from_str_radix(value, 16).unwrap())
+            .collect();
+        // the seed itself, then one for each table, record, block and entity
+        assert!(handles.len() > 1);
+        assert!(handles[1..].iter().all(|handle| *handle < seed));
+        let mut unique = handles[1..].to_vec();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), handles.len() - 1);
+
+        // the polyline carries its handle and subclass markers
+        let start = pairs
+            .iter()
+            .position(|pair| *pair == (0, "LWPOLYLINE"))
+            .unwrap();
+        assert_eq!(pairs[start + 1].0, 5);
+        assert!(pairs[start..].contains(&(100, "AcDbEntity")));
+        assert!(pairs[start..].contains(&(100, "AcDbPolyline")));
+        assert_eq!(pairs.last(), Some(&(0, "EOF")));
+    }
+}
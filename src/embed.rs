@@ -0,0 +1,96 @@
+use crate::storage::Design;
+use wasm_bindgen::prelude::*;
+
+// messages to and from the page embedding the designer. each is an object
+// with a `type`, and designs are sent as a `design` object shaped like the
+// saved json.
+//   geargen:ready   sent once the designer has loaded
+//   geargen:design  sent after every change, or received to load a design
+const READY_MESSAGE: &str = "geargen:ready";
+const DESIGN_MESSAGE: &str = "geargen:design";
+
+thread_local! {
+    static EMBEDDED: bool = read_embed_flag();
+}
+
+// `?embed` (or `?embed=1`) in the url shows just the gears, sized to the
+// frame, for embedding in other pages
+pub fn is_embedded() -> bool {
+    EMBEDDED.with(|embedded| *embedded)
+}
+
+fn read_embed_flag() -> bool {
+    let Some(search) = web_sys::window().and_then(|window| window.location().search().ok()) else {
+        return false;
+    };
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|param| {
+            let mut parts = param.splitn(2, '=');
+            (parts.next() == Some("embed")).then(|| parts.next().unwrap_or(""))
+        })
+        .any(|value| !matches!(value, "0" | "false"))
+}
+
+pub fn post_ready() {
+    post(READY_MESSAGE, None);
+}
+
+pub fn post_design(design: &Design) {
+    post(DESIGN_MESSAGE, Some(design));
+}
+
+fn post(message_type: &str, design: Option<&Design>) {
+    let Some(parent) = web_sys::window().and_then(|window| window.parent().ok().flatten()) else {
+        return;
+    };
+    let message = js_sys::Object::new();
+    js_sys::Reflect::set(&message, &"type".into(), &message_type.into()).unwrap();
+    if let Some(design) = design {
+        let json = serde_json::to_string(design).unwrap();
+        js_sys::Reflect::set(
+            &message,
+            &"design".into(),
+            &js_sys::JSON::parse(&json).unwrap(),
+        )
+        .unwrap();
+    }
+    // the design is the only thing sent, so any embedding page may read it
+    parent.post_message(&message, "*").unwrap();
+}
+
+// calls `on_design` with every design the embedding page posts. anything
+// else, including messages from other frames and designs that don't
+// deserialize or hold specs the inputs wouldn't allow, is ignored.
+pub fn listen(on_design: impl Fn(Design) + 'static) {
+    let closure = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+        let parent = web_sys::window().and_then(|window| window.parent().ok().flatten());
+        let from_parent = match (event.source(), parent) {
+            (Some(source), Some(parent)) => js_sys::Object::is(&source, &parent),
+            _ => false,
+        };
+        if !from_parent {
+            return;
+        }
+        let data = event.data();
+        let message_type = js_sys::Reflect::get(&data, &"type".into()).unwrap_or_default();
+        if message_type.as_string().as_deref() != Some(DESIGN_MESSAGE) {
+            return;
+        }
+        let design = js_sys::Reflect::get(&data, &"design".into())
+            .ok()
+            .and_then(|design| js_sys::JSON::stringify(&design).ok())
+            .and_then(|json| json.as_string())
+            .and_then(|json| serde_json::from_str::<Design>(&json).ok())
+            .filter(Design::is_usable);
+        if let Some(design) = design {
+            on_design(design);
+        }
+    }) as Box<dyn Fn(web_sys::MessageEvent)>);
+    web_sys::window()
+        .unwrap()
+        .add_event_listener_with_callback("message", closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
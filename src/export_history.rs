@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+// how many exports are kept around for downloading again
+const HISTORY_LIMIT: usize = 5;
+
+// a finished export, kept in memory for the rest of the session
+struct ExportRecord {
+    id: u32,
+    filename: String,
+    mime_type: &'static str,
+    bytes: Rc<Vec<u8>>,
+    // milliseconds since the epoch
+    exported_at: f64,
+    // the gear parameters it was made from
+    summary: String,
+}
+
+#[derive(Default)]
+struct ExportHistory {
+    // where the history is listed, once the sidebar is built
+    list: Option<web_sys::Element>,
+    records: Vec<ExportRecord>,
+    next_id: u32,
+}
+
+thread_local! {
+    static EXPORT_HISTORY: RefCell<ExportHistory> = RefCell::new(ExportHistory::default());
+}
+
+// lists the history in `list`, as buttons tagged with `data-export`
+pub fn attach(list: &web_sys::Element) {
+    EXPORT_HISTORY.with(|history| history.borrow_mut().list = Some(list.clone()));
+    update_list();
+}
+
+// remembers an export, newest first, dropping the oldest past the limit
+pub fn record(filename: &str, mime_type: &'static str, bytes: &[u8], summary: String) {
+    EXPORT_HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        let id = history.next_id;
+        history.next_id += 1;
+        history.records.insert(
+            0,
+            ExportRecord {
+                id,
+                filename: filename.to_string(),
+                mime_type,
+                bytes: Rc::new(bytes.to_vec()),
+                exported_at: js_sys::Date::now(),
+                summary,
+            },
+        );
+        history.records.truncate(HISTORY_LIMIT);
+    });
+    update_list();
+}
+
+// the file, mime type and contents of an earlier export
+pub fn find(id: u32) -> Option<(String, &'static str, Rc<Vec<u8>>)> {
+    EXPORT_HISTORY.with(|history| {
+        history
+            .borrow()
+            .records
+            .iter()
+            .find(|record| record.id == id)
+            .map(|record| {
+                (
+                    record.filename.clone(),
+                    record.mime_type,
+                    record.bytes.clone(),
+                )
+            })
+    })
+}
+
+fn update_list() {
+    EXPORT_HISTORY.with(|history| {
+        let history = history.borrow();
+        let Some(list) = &history.list else {
+            return;
+        };
+        build_list(list, &history.records).unwrap();
+    });
+}
+
+fn build_list(list: &web_sys::Element, records: &[ExportRecord]) -> Result<(), JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    list.set_inner_html("");
+    if records.is_empty() {
+        let empty = document.create_element("p")?;
+        empty.set_attribute("style", "text-align: center;")?;
+        empty.set_text_content(Some("Nothing exported yet"));
+        list.append_child(&empty)?;
+    }
+    for record in records {
+        let exported_at = js_sys::Date::new(&JsValue::from_f64(record.exported_at))
+            .to_locale_time_string("default");
+        let button = document.create_element("button")?;
+        button.set_attribute("data-export", &record.id.to_string())?;
+        button.set_attribute("title", &record.summary)?;
+        button.set_attribute(
+            "style",
+            "width: 80%; margin: 2px 10%; white-space: pre-line;",
+        )?;
+        button.set_text_content(Some(&format!(
+            "{} {}\n{}",
+            record.filename,
+            String::from(exported_at),
+            record.summary
+        )));
+        list.append_child(&button)?;
+    }
+    Ok(())
+}
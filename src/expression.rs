@@ -0,0 +1,240 @@
+use crate::locale;
+use std::str::FromStr;
+
+// a number or an expression typed into an input
+pub fn parse<T: FromStr>(text: &str) -> Option<T> {
+    locale::parse(text).or_else(|| evaluate(text)?.to_string().parse().ok())
+}
+
+// evaluates simple arithmetic typed into a numeric input, like "25.4/12" or
+// "(50 + 10) * 0.5"
+pub fn evaluate(text: &str) -> Option<f64> {
+    let value = Formula::parse(text, &[])?.eval(&[]);
+    value.is_finite().then_some(value)
+}
+
+// a parsed formula over named variables. supports + - * / and parentheses
+// with the usual precedence, numbers written with either decimal separator,
+// `pi`, and the functions sin, cos, tan, abs and sqrt.
+pub struct Formula {
+    expr: Expr,
+}
+
+impl Formula {
+    // `variables` names the values later passed to `eval`, in order
+    pub fn parse(text: &str, variables: &[&str]) -> Option<Self> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+            variables,
+        };
+        let expr = parser.sum()?;
+        if parser.position != tokens.len() {
+            return None;
+        }
+        Some(Self { expr })
+    }
+
+    pub fn eval(&self, values: &[f64]) -> f64 {
+        self.expr.eval(values)
+    }
+}
+
+enum Expr {
+    Number(f64),
+    // index into the values passed to `eval`
+    Variable(usize),
+    Negate(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Call(fn(f64) -> f64, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, values: &[f64]) -> f64 {
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Variable(index) => values[*index],
+            Expr::Negate(expr) => -expr.eval(values),
+            Expr::Binary(operator, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(values), rhs.eval(values));
+                match operator {
+                    '+' => lhs + rhs,
+                    '-' => lhs - rhs,
+                    '*' => lhs * rhs,
+                    _ => lhs / rhs,
+                }
+            }
+            Expr::Call(function, argument) => function(argument.eval(values)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Operator(char),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    // collects the run of characters matching `part`
+    let take_while = |chars: &mut std::iter::Peekable<std::str::Chars>, part: fn(char) -> bool| {
+        let mut run = String::new();
+        while let Some(&c) = chars.peek() {
+            if !part(c) {
+                break;
+            }
+            run.push(c);
+            chars.next();
+        }
+        run
+    };
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' => {
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' => {
+                tokens.push(Token::Operator(c));
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                chars.next();
+            }
+            '0'..='9' | '.' | ',' => {
+                let number = take_while(&mut chars, |c| matches!(c, '0'..='9' | '.' | ','));
+                tokens.push(Token::Number(locale::parse(&number)?));
+            }
+            'a'..='z' | 'A'..='Z' | '_' => {
+                let name = take_while(&mut chars, |c| c.is_ascii_alphanumeric() || c == '_');
+                tokens.push(Token::Name(name.to_lowercase()));
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+// recursive descent over the tokens, one method per precedence level
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    variables: &'a [&'a str],
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    // terms joined by + and -
+    fn sum(&mut self) -> Option<Expr> {
+        let mut expr = self.product()?;
+        while let Some(&Token::Operator(operator @ ('+' | '-'))) = self.peek() {
+            self.position += 1;
+            expr = Expr::Binary(operator, Box::new(expr), Box::new(self.product()?));
+        }
+        Some(expr)
+    }
+
+    // factors joined by * and /
+    fn product(&mut self) -> Option<Expr> {
+        let mut expr = self.factor()?;
+        while let Some(&Token::Operator(operator @ ('*' | '/'))) = self.peek() {
+            self.position += 1;
+            expr = Expr::Binary(operator, Box::new(expr), Box::new(self.factor()?));
+        }
+        Some(expr)
+    }
+
+    // a number, a name, a negated factor, a function call or a
+    // parenthesized sum
+    fn factor(&mut self) -> Option<Expr> {
+        match self.next()? {
+            Token::Number(value) => Some(Expr::Number(value)),
+            Token::Operator('-') => Some(Expr::Negate(Box::new(self.factor()?))),
+            Token::Open => self.closed_sum(),
+            Token::Name(name) => {
+                if let Some(index) = self.variables.iter().position(|variable| *variable == name) {
+                    return Some(Expr::Variable(index));
+                }
+                let function: fn(f64) -> f64 = match name.as_str() {
+                    "pi" => return Some(Expr::Number(std::f64::consts::PI)),
+                    "sin" => f64::sin,
+                    "cos" => f64::cos,
+                    "tan" => f64::tan,
+                    "abs" => f64::abs,
+                    "sqrt" => f64::sqrt,
+                    _ => return None,
+                };
+                match self.next()? {
+                    Token::Open => Some(Expr::Call(function, Box::new(self.closed_sum()?))),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // the rest of a parenthesized sum, after its opening parenthesis
+    fn closed_sum(&mut self) -> Option<Expr> {
+        let expr = self.sum()?;
+        match self.next()? {
+            Token::Close => Some(expr),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators_follow_the_usual_precedence() {
+        assert_eq!(evaluate("1 + 2 * 3"), Some(7.0));
+        assert_eq!(evaluate("(1 + 2) * 3"), Some(9.0));
+        assert_eq!(evaluate("8 / 2 / 2"), Some(2.0));
+        assert_eq!(evaluate("-2 * -3"), Some(6.0));
+    }
+
+    #[test]
+    fn unit_conversions_evaluate() {
+        assert_eq!(evaluate("25.4/12"), Some(25.4 / 12.0));
+        assert_eq!(evaluate("0,5*3"), Some(1.5));
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        assert_eq!(evaluate("1 +"), None);
+        assert_eq!(evaluate("(1 + 2"), None);
+        assert_eq!(evaluate("1 / 0"), None);
+        assert_eq!(evaluate("2x"), None);
+        assert_eq!(evaluate(""), None);
+    }
+
+    #[test]
+    fn formulas_take_variables_and_functions() {
+        let formula = Formula::parse("r + cos(n * theta)", &["theta", "r", "n"]).unwrap();
+        assert_eq!(formula.eval(&[0.0, 2.0, 10.0]), 3.0);
+        assert!((formula.eval(&[std::f64::consts::PI / 10.0, 2.0, 10.0]) - 1.0).abs() < 1e-12);
+        assert!(Formula::parse("r + x", &["r"]).is_none());
+        assert!(Formula::parse("cos 1", &[]).is_none());
+    }
+}
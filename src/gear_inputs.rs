@@ -0,0 +1,1161 @@
+use crate::cutouts::{ImportedCutout, ShapeCutout, ShapeKind};
+use crate::expression::{self, Formula};
+use crate::geometry::{
+    GearSpecs, Thinning, ToothProfile, CLEARANCE_RANGE, CUSTOM_VARIABLES, DEFAULT_CUSTOM_FORMULA,
+    MAX_TEETH, MIN_TEETH,
+};
+use crate::hub::Hub;
+use crate::locale;
+use crate::spokes::SpokeStyle;
+use crate::style::GearStyle;
+use crate::GearState;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlInputElement;
+use web_sys::HtmlSelectElement;
+
+// notch a gear starts with when it's switched to a star profile
+const DEFAULT_STAR_NOTCH: f64 = 0.2;
+
+// the standard pressure angles, and which gears each one meshes with
+const PRESSURE_ANGLE_PRESETS: [(f64, &str); 3] = [
+    (
+        14.5,
+        "14.5\u{b0} is the old standard, for repairs and replacement gears. It only meshes with other 14.5\u{b0} gears.",
+    ),
+    (
+        20.0,
+        "20\u{b0} is today's AGMA and ISO standard, what most stock gears and cutters are made to.",
+    ),
+    (
+        25.0,
+        "25\u{b0} makes stronger teeth for heavy loads, at some cost in noise. It only meshes with other 25\u{b0} gears.",
+    ),
+];
+
+// the sidebar inputs for one gear of the train
+pub struct GearInputs {
+    teeth: web_sys::Element,
+    helix_angle: web_sys::Element,
+    profile: web_sys::Element,
+    // only shown for star profiles
+    star_notch_container: web_sys::Element,
+    star_notch: web_sys::Element,
+    // only shown for custom profiles
+    formula_container: web_sys::Element,
+    formula: web_sys::Element,
+    thinning: web_sys::Element,
+    color: web_sys::Element,
+    fill: web_sys::Element,
+    // the first gear has no previous gear to share an arbor with
+    same_arbor: Option<web_sys::Element>,
+    hub_diameter: web_sys::Element,
+    hub_length: web_sys::Element,
+    flange_thickness: web_sys::Element,
+    bore: web_sys::Element,
+    key_width: web_sys::Element,
+    torque: web_sys::Element,
+    hub_recommendation: web_sys::Element,
+    spoke_style: web_sys::Element,
+    // hidden for a solid web
+    spoke_size_container: web_sys::Element,
+    spoke_count: web_sys::Element,
+    spoke_width: web_sys::Element,
+    // only shown for spokes, round holes have no corners
+    spoke_fillet_container: web_sys::Element,
+    spoke_fillet: web_sys::Element,
+    // only there once a shape has been imported
+    svg_cutout: Option<CutoutInputs>,
+    shapes: Vec<ShapeInputs>,
+    unlinked: UnlinkedInputs,
+}
+
+// replaces the contents of `container` with a section per gear. each section
+// ends with duplicate / swap buttons tagged with `data-action` and
+// `data-gear`, so a single click listener on the container handles them all.
+pub fn build_gear_sections(
+    container: &web_sys::Element,
+    gears: &[GearState],
+    linked: bool,
+) -> Result<Vec<GearInputs>, JsValue> {
+    container.set_inner_html("");
+    gears
+        .iter()
+        .enumerate()
+        .map(|(index, gear)| GearInputs::new(container, index, gears.len(), gear, linked))
+        .collect()
+}
+
+impl GearInputs {
+    fn new(
+        parent: &web_sys::Element,
+        index: usize,
+        gear_count: usize,
+        gear: &GearState,
+        linked: bool,
+    ) -> Result<Self, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let id_prefix = format!("gear_{}", index);
+
+        // add gear subtitle
+        let subtitle = document.create_element("h3")?;
+        subtitle.set_attribute("style", "text-align: center; width: 100%;")?;
+        subtitle.set_text_content(Some(&format!("Gear {}", index + 1)));
+        parent.append_child(&subtitle)?;
+
+        let teeth = append_text_input(
+            parent,
+            &format!("{}_teeth", id_prefix),
+            "Teeth:",
+            "Enter gear teeth",
+            &gear.spec.teeth.to_string(),
+        )?;
+        set_step(&teeth, 1.0, true)?;
+
+        // zero for spur gears. pitch and pressure angle are taken square to
+        // the teeth, so helical gears draw wider than spur gears.
+        let helix_angle = append_text_input(
+            parent,
+            &format!("{}_helix_angle", id_prefix),
+            "Helix Angle (deg):",
+            "Enter helix angle",
+            &locale::format(gear.spec.helix_angle),
+        )?;
+        set_step(&helix_angle, 0.5, false)?;
+
+        // label for tooth profile select
+        let profile_label = document.create_element("label")?;
+        profile_label.set_attribute("for", &format!("{}_profile", id_prefix))?;
+        profile_label.set_text_content(Some("Profile:"));
+        profile_label.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        parent.append_child(&profile_label)?;
+
+        // tooth profile select, star wheels have pointed straight sided teeth
+        // and custom teeth follow a formula
+        let profile = document.create_element("select")?;
+        profile.set_attribute("id", &format!("{}_profile", id_prefix))?;
+        profile.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        for (value, text) in [
+            ("involute", "Involute"),
+            ("star", "Star Wheel"),
+            ("custom", "Custom Formula"),
+        ] {
+            let option = document.create_element("option")?;
+            option.set_attribute("value", value)?;
+            option.set_text_content(Some(text));
+            if gear.spec.profile.value() == value {
+                option.set_attribute("selected", "")?;
+            }
+            profile.append_child(&option)?;
+        }
+        parent.append_child(&profile)?;
+
+        let star_notch_container = document.create_element("div")?;
+        parent.append_child(&star_notch_container)?;
+        let notch = match &gear.spec.profile {
+            ToothProfile::Star { notch } => *notch,
+            _ => DEFAULT_STAR_NOTCH,
+        };
+        let star_notch = append_text_input(
+            &star_notch_container,
+            &format!("{}_star_notch", id_prefix),
+            "Notch (x tooth pitch):",
+            "Enter notch width",
+            &locale::format(notch),
+        )?;
+        set_step(&star_notch, 0.05, false)?;
+        set_visible(
+            &star_notch_container,
+            matches!(gear.spec.profile, ToothProfile::Star { .. }),
+        );
+
+        let formula_container = document.create_element("div")?;
+        parent.append_child(&formula_container)?;
+        let formula = match &gear.spec.profile {
+            ToothProfile::Custom { formula } => formula.as_str(),
+            _ => DEFAULT_CUSTOM_FORMULA,
+        };
+        let formula = append_text_input(
+            &formula_container,
+            &format!("{}_formula", id_prefix),
+            "Radius (in) at theta:",
+            "Enter radius formula",
+            formula,
+        )?;
+        let formula_help = document.create_element("p")?;
+        formula_help.set_attribute(
+            "style",
+            "width: 80%; margin: 2px 10%; font-size: small; color: #666;",
+        )?;
+        formula_help.set_text_content(Some(&format!(
+            "theta runs across one tooth from its centerline, in radians. uses {}",
+            CUSTOM_VARIABLES[1..].join(", ")
+        )));
+        formula_container.append_child(&formula_help)?;
+        set_visible(
+            &formula_container,
+            matches!(gear.spec.profile, ToothProfile::Custom { .. }),
+        );
+
+        let unlinked = UnlinkedInputs::new(parent, &id_prefix, &gear.spec, linked)?;
+
+        // label for backlash thinning select
+        let thinning_label = document.create_element("label")?;
+        thinning_label.set_attribute("for", &format!("{}_thinning", id_prefix))?;
+        thinning_label.set_text_content(Some("Backlash Thinning:"));
+        thinning_label
+            .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        parent.append_child(&thinning_label)?;
+
+        // backlash thinning select, a hardened pinion is usually left full
+        // and its wheel takes it all
+        let thinning = document.create_element("select")?;
+        thinning.set_attribute("id", &format!("{}_thinning", id_prefix))?;
+        thinning.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        for (option_thinning, text) in [
+            (Thinning::Half, "Split with Mate"),
+            (Thinning::All, "Takes It All"),
+            (Thinning::None, "None, Full Thickness"),
+        ] {
+            let option = document.create_element("option")?;
+            option.set_attribute("value", option_thinning.value())?;
+            option.set_text_content(Some(text));
+            if gear.spec.thinning == option_thinning {
+                option.set_attribute("selected", "")?;
+            }
+            thinning.append_child(&option)?;
+        }
+        parent.append_child(&thinning)?;
+
+        // gear color input
+        let color_label = document.create_element("label")?;
+        color_label.set_attribute("for", &format!("{}_color", id_prefix))?;
+        color_label.set_text_content(Some("Color:"));
+        color_label.set_attribute("style", "margin-left: 10%;")?;
+        parent.append_child(&color_label)?;
+        let color = document.create_element("input")?;
+        color.set_attribute("id", &format!("{}_color", id_prefix))?;
+        color.set_attribute("type", "color")?;
+        color.set_attribute("value", &gear.style.color)?;
+        parent.append_child(&color)?;
+
+        // gear fill toggle
+        let fill = append_checkbox(
+            parent,
+            &format!("{}_fill", id_prefix),
+            "Fill:",
+            gear.style.fill,
+        )?;
+
+        // same arbor toggle, for compound trains like a clock's wheel and pinion
+        let same_arbor = if index > 0 {
+            Some(append_checkbox(
+                parent,
+                &format!("{}_same_arbor", id_prefix),
+                "Same Arbor as Previous:",
+                gear.same_arbor,
+            )?)
+        } else {
+            None
+        };
+
+        // hub and flange, only printed as a side view. a zero flange
+        // thickness leaves the gear as a bare profile.
+        let hub_diameter = append_text_input(
+            parent,
+            &format!("{}_hub_diameter", id_prefix),
+            "Hub Diameter (in):",
+            "Enter hub diameter",
+            &locale::format(gear.hub.diameter),
+        )?;
+        set_step(&hub_diameter, 0.125, false)?;
+        let hub_length = append_text_input(
+            parent,
+            &format!("{}_hub_length", id_prefix),
+            "Hub Length (in):",
+            "Enter overall hub length",
+            &locale::format(gear.hub.length),
+        )?;
+        set_step(&hub_length, 0.125, false)?;
+        let flange_thickness = append_text_input(
+            parent,
+            &format!("{}_flange_thickness", id_prefix),
+            "Flange Thickness (in):",
+            "Enter flange thickness",
+            &locale::format(gear.hub.flange_thickness),
+        )?;
+        set_step(&flange_thickness, 0.0625, false)?;
+
+        // what the hub has to carry, for a recommended size to apply with
+        // one click
+        let bore = append_text_input(
+            parent,
+            &format!("{}_bore", id_prefix),
+            "Bore (in):",
+            "Enter shaft diameter",
+            &locale::format(gear.hub.bore),
+        )?;
+        set_step(&bore, 0.0625, false)?;
+        let key_width = append_text_input(
+            parent,
+            &format!("{}_key_width", id_prefix),
+            "Key Width (in):",
+            "0 for no key",
+            &locale::format(gear.hub.key_width),
+        )?;
+        set_step(&key_width, 0.0625, false)?;
+        let torque = append_text_input(
+            parent,
+            &format!("{}_torque", id_prefix),
+            "Torque (lb-in):",
+            "Enter transmitted torque",
+            &locale::format(gear.hub.torque),
+        )?;
+        set_step(&torque, 10.0, false)?;
+        let hub_recommendation = document.create_element("p")?;
+        hub_recommendation.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+        parent.append_child(&hub_recommendation)?;
+        let apply_hub = document.create_element("button")?;
+        apply_hub.set_attribute("data-action", "apply_hub")?;
+        apply_hub.set_attribute("data-gear", &index.to_string())?;
+        apply_hub.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+        apply_hub.set_text_content(Some("Apply Recommended Hub"));
+        parent.append_child(&apply_hub)?;
+        describe_recommendation(&hub_recommendation, &gear.hub);
+
+        // spokes or cutouts lightening the web between the hub and the rim
+        let spoke_style_label = document.create_element("label")?;
+        spoke_style_label.set_attribute("for", &format!("{}_spoke_style", id_prefix))?;
+        spoke_style_label.set_text_content(Some("Web:"));
+        spoke_style_label
+            .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        parent.append_child(&spoke_style_label)?;
+        let spoke_style = document.create_element("select")?;
+        spoke_style.set_attribute("id", &format!("{}_spoke_style", id_prefix))?;
+        spoke_style.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        for (style, text) in [
+            (SpokeStyle::SolidWeb, "Solid Web"),
+            (SpokeStyle::Straight, "Straight Spokes"),
+            (SpokeStyle::Curved, "Curved Spokes"),
+            (SpokeStyle::Circles, "Circular Cutouts"),
+        ] {
+            let option = document.create_element("option")?;
+            option.set_attribute("value", style.value())?;
+            option.set_text_content(Some(text));
+            if gear.spokes.style == style {
+                option.set_attribute("selected", "")?;
+            }
+            spoke_style.append_child(&option)?;
+        }
+        parent.append_child(&spoke_style)?;
+
+        let spoke_size_container = document.create_element("div")?;
+        parent.append_child(&spoke_size_container)?;
+        let spoke_count = append_text_input(
+            &spoke_size_container,
+            &format!("{}_spoke_count", id_prefix),
+            "Spokes:",
+            "Enter spoke count",
+            &gear.spokes.count.to_string(),
+        )?;
+        set_step(&spoke_count, 1.0, true)?;
+        let spoke_width = append_text_input(
+            &spoke_size_container,
+            &format!("{}_spoke_width", id_prefix),
+            "Spoke Width (in):",
+            "Enter spoke width",
+            &locale::format(gear.spokes.width),
+        )?;
+        set_step(&spoke_width, 0.0625, false)?;
+        let spoke_fillet_container = document.create_element("div")?;
+        spoke_size_container.append_child(&spoke_fillet_container)?;
+        let spoke_fillet = append_text_input(
+            &spoke_fillet_container,
+            &format!("{}_spoke_fillet", id_prefix),
+            "Fillet Radius (in):",
+            "Enter fillet radius",
+            &locale::format(gear.spokes.fillet),
+        )?;
+        set_step(&spoke_fillet, 0.03125, false)?;
+        set_visible(
+            &spoke_size_container,
+            gear.spokes.style != SpokeStyle::SolidWeb,
+        );
+        set_visible(
+            &spoke_fillet_container,
+            gear.spokes.style != SpokeStyle::Circles,
+        );
+
+        // a shape from an svg cut out of the blank. the file input is tagged
+        // like the buttons below, so one change listener reads them all.
+        let cutout_file_label = document.create_element("label")?;
+        cutout_file_label.set_attribute("for", &format!("{}_cutout_file", id_prefix))?;
+        cutout_file_label.set_text_content(Some("Cutout SVG:"));
+        cutout_file_label
+            .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        parent.append_child(&cutout_file_label)?;
+        let cutout_file = document.create_element("input")?;
+        cutout_file.set_attribute("id", &format!("{}_cutout_file", id_prefix))?;
+        cutout_file.set_attribute("type", "file")?;
+        cutout_file.set_attribute("accept", ".svg")?;
+        cutout_file.set_attribute("data-action", "import_cutout")?;
+        cutout_file.set_attribute("data-gear", &index.to_string())?;
+        cutout_file.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        parent.append_child(&cutout_file)?;
+        let svg_cutout = match &gear.svg_cutout {
+            Some(cutout) => Some(CutoutInputs::new(parent, &id_prefix, index, cutout)?),
+            None => None,
+        };
+
+        // parametric cutouts, each with its own remove button
+        let shapes = gear
+            .shapes
+            .iter()
+            .enumerate()
+            .map(|(shape_index, shape)| {
+                ShapeInputs::new(parent, &id_prefix, index, shape_index, shape)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let add_shape = document.create_element("button")?;
+        add_shape.set_attribute("data-action", "add_shape")?;
+        add_shape.set_attribute("data-gear", &index.to_string())?;
+        add_shape.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+        add_shape.set_text_content(Some("Add Cutout Shape"));
+        parent.append_child(&add_shape)?;
+
+        // duplicate and swap buttons, the last gear has nothing to swap with.
+        // the index plate prints a dividing plate for the gear's tooth count.
+        let buttons = document.create_element("div")?;
+        buttons.set_attribute(
+            "style",
+            "width: 80%; margin: 10px 10% 0 10%; display: flex; flex-wrap: wrap; gap: 4px;",
+        )?;
+        parent.append_child(&buttons)?;
+        let mut actions = vec![("duplicate", "Duplicate")];
+        if index + 1 < gear_count {
+            actions.push(("swap", "Swap with Next"));
+        }
+        actions.push(("index_plate", "Index Plate"));
+        for (action, text) in actions {
+            let button = document.create_element("button")?;
+            button.set_attribute("data-action", action)?;
+            button.set_attribute("data-gear", &index.to_string())?;
+            button.set_attribute("style", "flex: 1;")?;
+            button.set_text_content(Some(text));
+            buttons.append_child(&button)?;
+        }
+
+        Ok(Self {
+            teeth,
+            helix_angle,
+            profile,
+            star_notch_container,
+            star_notch,
+            formula_container,
+            formula,
+            thinning,
+            color,
+            fill,
+            same_arbor,
+            hub_diameter,
+            hub_length,
+            flange_thickness,
+            bore,
+            key_width,
+            torque,
+            hub_recommendation,
+            spoke_style,
+            spoke_size_container,
+            spoke_count,
+            spoke_width,
+            spoke_fillet_container,
+            spoke_fillet,
+            svg_cutout,
+            shapes,
+            unlinked,
+        })
+    }
+
+    // updates the gear from the inputs, skipping any that don't hold valid
+    // values. pitch, angle and clearance come from here only while unlinked.
+    pub fn apply(&self, gear: &mut GearState, linked: bool) {
+        if let Some(teeth) =
+            parse_input::<u32>(&self.teeth).filter(|teeth| (MIN_TEETH..=MAX_TEETH).contains(teeth))
+        {
+            gear.spec.teeth = teeth as f64;
+        }
+        if let Some(helix_angle) = parse_input::<f64>(&self.helix_angle) {
+            // teeth square to the axis would never engage
+            if (0.0..90.0).contains(&helix_angle) {
+                gear.spec.helix_angle = helix_angle;
+            }
+        }
+        let notch = parse_input::<f64>(&self.star_notch)
+            .filter(|notch| (0.0..1.0).contains(notch))
+            .unwrap_or(DEFAULT_STAR_NOTCH);
+        // a formula that doesn't parse keeps the last one that did
+        let formula = self.formula.dyn_ref::<HtmlInputElement>().unwrap().value();
+        let formula = if Formula::parse(&formula, &CUSTOM_VARIABLES).is_some() {
+            formula
+        } else {
+            match &gear.spec.profile {
+                ToothProfile::Custom { formula } => formula.clone(),
+                _ => DEFAULT_CUSTOM_FORMULA.to_string(),
+            }
+        };
+        let value = self.profile.dyn_ref::<HtmlSelectElement>().unwrap().value();
+        if let Some(profile) = ToothProfile::from_value(&value, notch, &formula) {
+            gear.spec.profile = profile;
+        }
+        set_visible(
+            &self.star_notch_container,
+            matches!(gear.spec.profile, ToothProfile::Star { .. }),
+        );
+        set_visible(
+            &self.formula_container,
+            matches!(gear.spec.profile, ToothProfile::Custom { .. }),
+        );
+        let value = self
+            .thinning
+            .dyn_ref::<HtmlSelectElement>()
+            .unwrap()
+            .value();
+        if let Some(thinning) = Thinning::from_value(&value) {
+            gear.spec.thinning = thinning;
+        }
+        gear.style = GearStyle {
+            color: self.color.dyn_ref::<HtmlInputElement>().unwrap().value(),
+            fill: self.fill.dyn_ref::<HtmlInputElement>().unwrap().checked(),
+        };
+        gear.same_arbor = self
+            .same_arbor
+            .as_ref()
+            .is_some_and(|input| input.dyn_ref::<HtmlInputElement>().unwrap().checked());
+        for (input, value) in [
+            (&self.hub_diameter, &mut gear.hub.diameter),
+            (&self.hub_length, &mut gear.hub.length),
+            (&self.flange_thickness, &mut gear.hub.flange_thickness),
+            (&self.bore, &mut gear.hub.bore),
+            (&self.key_width, &mut gear.hub.key_width),
+            (&self.torque, &mut gear.hub.torque),
+        ] {
+            if let Some(parsed) = parse_input::<f64>(input).filter(|parsed| *parsed >= 0.0) {
+                *value = parsed;
+            }
+        }
+        describe_recommendation(&self.hub_recommendation, &gear.hub);
+        let value = self
+            .spoke_style
+            .dyn_ref::<HtmlSelectElement>()
+            .unwrap()
+            .value();
+        if let Some(style) = SpokeStyle::from_value(&value) {
+            gear.spokes.style = style;
+        }
+        if let Some(count) = parse_input::<u32>(&self.spoke_count).filter(|count| *count > 0) {
+            gear.spokes.count = count;
+        }
+        if let Some(width) = parse_input::<f64>(&self.spoke_width).filter(|width| *width >= 0.0) {
+            gear.spokes.width = width;
+        }
+        if let Some(fillet) = parse_input::<f64>(&self.spoke_fillet).filter(|fillet| *fillet >= 0.0)
+        {
+            gear.spokes.fillet = fillet;
+        }
+        set_visible(
+            &self.spoke_size_container,
+            gear.spokes.style != SpokeStyle::SolidWeb,
+        );
+        set_visible(
+            &self.spoke_fillet_container,
+            gear.spokes.style != SpokeStyle::Circles,
+        );
+        if let (Some(inputs), Some(cutout)) = (&self.svg_cutout, &mut gear.svg_cutout) {
+            inputs.apply(cutout);
+        }
+        for (inputs, shape) in self.shapes.iter().zip(&mut gear.shapes) {
+            inputs.apply(shape);
+        }
+        if !linked {
+            self.unlinked.apply(&mut gear.spec);
+        }
+    }
+
+    // shows or hides the gear's own pitch, angle and clearance, starting them
+    // off from its current spec
+    pub fn set_linked(&self, linked: bool, gear_spec: &GearSpecs) {
+        self.unlinked.set_values(gear_spec);
+        self.unlinked.set_visible(!linked);
+    }
+}
+
+// where an imported cutout sits on its gear, and a button to take it off
+struct CutoutInputs {
+    x: web_sys::Element,
+    y: web_sys::Element,
+    rotation: web_sys::Element,
+    scale: web_sys::Element,
+}
+
+impl CutoutInputs {
+    fn new(
+        parent: &web_sys::Element,
+        id_prefix: &str,
+        index: usize,
+        cutout: &ImportedCutout,
+    ) -> Result<Self, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let x = append_text_input(
+            parent,
+            &format!("{}_cutout_x", id_prefix),
+            "Cutout X (in):",
+            "Enter offset from the center",
+            &locale::format(cutout.offset.x),
+        )?;
+        set_step(&x, 0.01, false)?;
+        let y = append_text_input(
+            parent,
+            &format!("{}_cutout_y", id_prefix),
+            "Cutout Y (in):",
+            "Enter offset from the center",
+            &locale::format(cutout.offset.y),
+        )?;
+        set_step(&y, 0.01, false)?;
+        let rotation = append_text_input(
+            parent,
+            &format!("{}_cutout_rotation", id_prefix),
+            "Cutout Rotation (deg):",
+            "Enter rotation",
+            &locale::format(cutout.rotation),
+        )?;
+        set_step(&rotation, 1.0, false)?;
+        let scale = append_text_input(
+            parent,
+            &format!("{}_cutout_scale", id_prefix),
+            "Cutout Scale:",
+            "Enter scale",
+            &locale::format(cutout.scale),
+        )?;
+        set_step(&scale, 0.05, false)?;
+
+        let remove = document.create_element("button")?;
+        remove.set_attribute("data-action", "remove_cutout")?;
+        remove.set_attribute("data-gear", &index.to_string())?;
+        remove.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+        remove.set_text_content(Some(&format!("Remove {}", cutout.name)));
+        parent.append_child(&remove)?;
+        Ok(Self {
+            x,
+            y,
+            rotation,
+            scale,
+        })
+    }
+
+    fn apply(&self, cutout: &mut ImportedCutout) {
+        for (input, value) in [
+            (&self.x, &mut cutout.offset.x),
+            (&self.y, &mut cutout.offset.y),
+            (&self.rotation, &mut cutout.rotation),
+        ] {
+            if let Some(parsed) = parse_input::<f64>(input) {
+                *value = parsed;
+            }
+        }
+        if let Some(scale) = parse_input::<f64>(&self.scale).filter(|scale| *scale > 0.0) {
+            cutout.scale = scale;
+        }
+    }
+}
+
+// one parametric cutout's inputs. circles have no height, and only rounded
+// rectangles take a corner radius.
+struct ShapeInputs {
+    kind: web_sys::Element,
+    width: web_sys::Element,
+    height_container: web_sys::Element,
+    height: web_sys::Element,
+    corner_radius_container: web_sys::Element,
+    corner_radius: web_sys::Element,
+    x: web_sys::Element,
+    y: web_sys::Element,
+    rotation: web_sys::Element,
+    copies: web_sys::Element,
+}
+
+impl ShapeInputs {
+    fn new(
+        parent: &web_sys::Element,
+        id_prefix: &str,
+        index: usize,
+        shape_index: usize,
+        shape: &ShapeCutout,
+    ) -> Result<Self, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let id_prefix = format!("{}_shape_{}", id_prefix, shape_index);
+
+        let kind_label = document.create_element("label")?;
+        kind_label.set_attribute("for", &format!("{}_kind", id_prefix))?;
+        kind_label.set_text_content(Some(&format!("Cutout Shape {}:", shape_index + 1)));
+        kind_label.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        parent.append_child(&kind_label)?;
+        let kind = document.create_element("select")?;
+        kind.set_attribute("id", &format!("{}_kind", id_prefix))?;
+        kind.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        for (option_kind, text) in [
+            (ShapeKind::Circle, "Circle"),
+            (ShapeKind::Slot, "Slot"),
+            (ShapeKind::RoundedRectangle, "Rounded Rectangle"),
+        ] {
+            let option = document.create_element("option")?;
+            option.set_attribute("value", option_kind.value())?;
+            option.set_text_content(Some(text));
+            if shape.kind == option_kind {
+                option.set_attribute("selected", "")?;
+            }
+            kind.append_child(&option)?;
+        }
+        parent.append_child(&kind)?;
+
+        let width = append_text_input(
+            parent,
+            &format!("{}_width", id_prefix),
+            "Width (in):",
+            "Enter width",
+            &locale::format(shape.width),
+        )?;
+        set_step(&width, 0.0625, false)?;
+        let height_container = document.create_element("div")?;
+        parent.append_child(&height_container)?;
+        let height = append_text_input(
+            &height_container,
+            &format!("{}_height", id_prefix),
+            "Height (in):",
+            "Enter height",
+            &locale::format(shape.height),
+        )?;
+        set_step(&height, 0.0625, false)?;
+        let corner_radius_container = document.create_element("div")?;
+        parent.append_child(&corner_radius_container)?;
+        let corner_radius = append_text_input(
+            &corner_radius_container,
+            &format!("{}_corner_radius", id_prefix),
+            "Corner Radius (in):",
+            "Enter corner radius",
+            &locale::format(shape.corner_radius),
+        )?;
+        set_step(&corner_radius, 0.01, false)?;
+        let x = append_text_input(
+            parent,
+            &format!("{}_x", id_prefix),
+            "Center X (in):",
+            "Enter offset from the gear's center",
+            &locale::format(shape.offset.x),
+        )?;
+        set_step(&x, 0.0625, false)?;
+        let y = append_text_input(
+            parent,
+            &format!("{}_y", id_prefix),
+            "Center Y (in):",
+            "Enter offset from the gear's center",
+            &locale::format(shape.offset.y),
+        )?;
+        set_step(&y, 0.0625, false)?;
+        let rotation = append_text_input(
+            parent,
+            &format!("{}_rotation", id_prefix),
+            "Rotation (deg):",
+            "Enter rotation",
+            &locale::format(shape.rotation),
+        )?;
+        set_step(&rotation, 5.0, false)?;
+        let copies = append_text_input(
+            parent,
+            &format!("{}_copies", id_prefix),
+            "Copies Around Gear:",
+            "Enter copies",
+            &shape.copies.to_string(),
+        )?;
+        set_step(&copies, 1.0, true)?;
+
+        let remove = document.create_element("button")?;
+        remove.set_attribute("data-action", "remove_shape")?;
+        remove.set_attribute("data-gear", &index.to_string())?;
+        remove.set_attribute("data-shape", &shape_index.to_string())?;
+        remove.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+        remove.set_text_content(Some(&format!("Remove Shape {}", shape_index + 1)));
+        parent.append_child(&remove)?;
+
+        let inputs = Self {
+            kind,
+            width,
+            height_container,
+            height,
+            corner_radius_container,
+            corner_radius,
+            x,
+            y,
+            rotation,
+            copies,
+        };
+        inputs.set_visible(shape.kind);
+        Ok(inputs)
+    }
+
+    fn apply(&self, shape: &mut ShapeCutout) {
+        let value = self.kind.dyn_ref::<HtmlSelectElement>().unwrap().value();
+        if let Some(kind) = ShapeKind::from_value(&value) {
+            shape.kind = kind;
+        }
+        for (input, value) in [
+            (&self.width, &mut shape.width),
+            (&self.height, &mut shape.height),
+            (&self.corner_radius, &mut shape.corner_radius),
+        ] {
+            if let Some(parsed) = parse_input::<f64>(input).filter(|parsed| *parsed >= 0.0) {
+                *value = parsed;
+            }
+        }
+        for (input, value) in [
+            (&self.x, &mut shape.offset.x),
+            (&self.y, &mut shape.offset.y),
+            (&self.rotation, &mut shape.rotation),
+        ] {
+            if let Some(parsed) = parse_input::<f64>(input) {
+                *value = parsed;
+            }
+        }
+        if let Some(copies) = parse_input::<u32>(&self.copies).filter(|copies| *copies > 0) {
+            shape.copies = copies;
+        }
+        self.set_visible(shape.kind);
+    }
+
+    fn set_visible(&self, kind: ShapeKind) {
+        set_visible(&self.height_container, kind != ShapeKind::Circle);
+        set_visible(
+            &self.corner_radius_container,
+            kind == ShapeKind::RoundedRectangle,
+        );
+    }
+}
+
+// a gear's own pitch, pressure angle and clearance inputs, only shown while
+// the gears are unlinked
+struct UnlinkedInputs {
+    container: web_sys::Element,
+    diametric_pitch: web_sys::Element,
+    tooth_angle: web_sys::Element,
+    tooth_angle_presets: PressureAnglePresets,
+    clearance: web_sys::Element,
+}
+
+impl UnlinkedInputs {
+    fn new(
+        parent: &web_sys::Element,
+        id_prefix: &str,
+        gear_spec: &GearSpecs,
+        linked: bool,
+    ) -> Result<Self, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let container = document.create_element("div")?;
+        parent.append_child(&container)?;
+
+        let diametric_pitch = append_text_input(
+            &container,
+            &format!("{}_diametric_pitch", id_prefix),
+            "Diametric Pitch:",
+            "Enter gear diametric pitch",
+            "",
+        )?;
+        let tooth_angle = append_text_input(
+            &container,
+            &format!("{}_tooth_angle", id_prefix),
+            "Pressure Angle (deg):",
+            "Enter pressure angle",
+            "",
+        )?;
+        let tooth_angle_presets = PressureAnglePresets::new(
+            &container,
+            &format!("{}_tooth_angle", id_prefix),
+            gear_spec.tooth_angle,
+        )?;
+        let clearance = append_text_input(
+            &container,
+            &format!("{}_clearance", id_prefix),
+            "Clearance (x module):",
+            "Enter clearance",
+            "",
+        )?;
+        set_step(&diametric_pitch, 1.0, false)?;
+        set_step(&tooth_angle, 0.5, false)?;
+        set_step(&clearance, 0.01, false)?;
+
+        let inputs = Self {
+            container,
+            diametric_pitch,
+            tooth_angle,
+            tooth_angle_presets,
+            clearance,
+        };
+        inputs.set_values(gear_spec);
+        inputs.set_visible(!linked);
+        Ok(inputs)
+    }
+
+    fn set_values(&self, gear_spec: &GearSpecs) {
+        [
+            (&self.diametric_pitch, gear_spec.diametric_pitch),
+            (&self.tooth_angle, gear_spec.tooth_angle),
+            (&self.clearance, gear_spec.clearance_mult),
+        ]
+        .iter()
+        .for_each(|(input, value)| {
+            input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .set_value(&locale::format(*value))
+        });
+    }
+
+    // updates the spec from whichever inputs hold valid numbers
+    fn apply(&self, gear_spec: &mut GearSpecs) {
+        // a gear needs some size, and a negative pitch turns it inside out
+        if let Some(diametric_pitch) =
+            parse_input::<f64>(&self.diametric_pitch).filter(|pitch| *pitch > 0.0)
+        {
+            gear_spec.diametric_pitch = diametric_pitch;
+        }
+        self.tooth_angle_presets.apply(&self.tooth_angle);
+        if let Some(tooth_angle) = parse_input(&self.tooth_angle) {
+            gear_spec.tooth_angle = tooth_angle;
+        }
+        self.tooth_angle_presets.describe(gear_spec.tooth_angle);
+        if let Some(clearance_mult) = parse_input::<f64>(&self.clearance)
+            .filter(|clearance_mult| CLEARANCE_RANGE.contains(clearance_mult))
+        {
+            gear_spec.clearance_mult = clearance_mult;
+        }
+    }
+
+    fn set_visible(&self, visible: bool) {
+        set_visible(&self.container, visible);
+    }
+}
+
+// a quick pick of the standard pressure angles under a pressure angle
+// input, with a note on what the angle meshes with. picking one writes it
+// into the input and empties the select again, so whatever's typed in the
+// input afterwards is never overridden.
+pub struct PressureAnglePresets {
+    select: web_sys::Element,
+    note: web_sys::Element,
+}
+
+impl PressureAnglePresets {
+    pub fn new(parent: &web_sys::Element, id: &str, angle: f64) -> Result<Self, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let select = document.create_element("select")?;
+        select.set_attribute("id", &format!("{}_preset", id))?;
+        select.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+        let prompt = document.create_element("option")?;
+        prompt.set_attribute("value", "")?;
+        prompt.set_text_content(Some("Standard angles..."));
+        select.append_child(&prompt)?;
+        for (preset, _) in PRESSURE_ANGLE_PRESETS {
+            let option = document.create_element("option")?;
+            option.set_attribute("value", &preset.to_string())?;
+            option.set_text_content(Some(&format!("{}\u{b0}", locale::format(preset))));
+            select.append_child(&option)?;
+        }
+        parent.append_child(&select)?;
+        let note = document.create_element("p")?;
+        note.set_attribute(
+            "style",
+            "width: 80%; margin: 4px 10% 0 10%; font-size: small;",
+        )?;
+        parent.append_child(&note)?;
+        let presets = Self { select, note };
+        presets.describe(angle);
+        Ok(presets)
+    }
+
+    // writes a picked angle into `input`
+    pub fn apply(&self, input: &web_sys::Element) {
+        let select = self.select.dyn_ref::<HtmlSelectElement>().unwrap();
+        if let Ok(angle) = select.value().parse::<f64>() {
+            input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .set_value(&locale::format(angle));
+            select.set_value("");
+        }
+    }
+
+    pub fn describe(&self, angle: f64) {
+        self.note.set_text_content(Some(pressure_angle_note(angle)));
+    }
+}
+
+fn pressure_angle_note(angle: f64) -> &'static str {
+    PRESSURE_ANGLE_PRESETS
+        .iter()
+        .find(|(preset, _)| (preset - angle).abs() < 1e-9)
+        .map(|(_, note)| *note)
+        .unwrap_or("Not a standard angle. Gears only mesh with others cut to the same angle.")
+}
+
+// the hub size the bore, key and torque call for
+fn describe_recommendation(paragraph: &web_sys::Element, hub: &Hub) {
+    let text = match hub.recommendation() {
+        Some(recommended) => format!(
+            "Recommended hub: {} in across, {} in long.",
+            locale::format(recommended.diameter),
+            locale::format(recommended.length)
+        ),
+        None => "Enter a bore for a recommended hub size.".to_string(),
+    };
+    paragraph.set_text_content(Some(&text));
+}
+
+fn set_visible(container: &web_sys::Element, visible: bool) {
+    let display = if visible { "block" } else { "none" };
+    container
+        .set_attribute("style", &format!("display: {};", display))
+        .unwrap();
+}
+
+// adds a label and text input to `parent`, styled like the rest of the sidebar
+pub fn append_text_input(
+    parent: &web_sys::Element,
+    id: &str,
+    label: &str,
+    placeholder: &str,
+    value: &str,
+) -> Result<web_sys::Element, JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let label_element = document.create_element("label")?;
+    label_element.set_attribute("for", id)?;
+    label_element.set_text_content(Some(label));
+    label_element.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    parent.append_child(&label_element)?;
+
+    let input = document.create_element("input")?;
+    input.set_attribute("id", id)?;
+    input.set_attribute("type", "text")?;
+    input.set_attribute("placeholder", placeholder)?;
+    input.set_attribute("value", value)?;
+    input.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    parent.append_child(&input)?;
+    Ok(input)
+}
+
+// adds a label and checkbox to `parent`, checked to start with if `checked`
+pub fn append_checkbox(
+    parent: &web_sys::Element,
+    id: &str,
+    label: &str,
+    checked: bool,
+) -> Result<web_sys::Element, JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let label_element = document.create_element("label")?;
+    label_element.set_attribute("for", id)?;
+    label_element.set_text_content(Some(label));
+    label_element.set_attribute("style", "margin-left: 10%;")?;
+    parent.append_child(&label_element)?;
+
+    let input = document.create_element("input")?;
+    input.set_attribute("id", id)?;
+    input.set_attribute("type", "checkbox")?;
+    if checked {
+        input.set_attribute("checked", "")?;
+    }
+    parent.append_child(&input)?;
+    Ok(input)
+}
+
+// how far the arrow keys and scroll wheel move an input, shift moves ten
+// times as far and ctrl a tenth. integer inputs move by whole numbers and
+// stay at one or more.
+pub fn set_step(input: &web_sys::Element, step: f64, integer: bool) -> Result<(), JsValue> {
+    input.set_attribute("data-step", &step.to_string())?;
+    if integer {
+        input.set_attribute("data-integer", "")?;
+    }
+    Ok(())
+}
+
+// moves an input tagged by `set_step` up or down a step, returning whether
+// there was a number in it to move
+pub fn step_input(input: &web_sys::Element, up: bool, coarse: bool, fine: bool) -> bool {
+    let Some(step) = input
+        .get_attribute("data-step")
+        .and_then(|step| step.parse::<f64>().ok())
+    else {
+        return false;
+    };
+    let Some(value) = parse_input::<f64>(input) else {
+        return false;
+    };
+    let scale = match (coarse, fine) {
+        (true, _) => 10.0,
+        (_, true) => 0.1,
+        _ => 1.0,
+    };
+    let step = if up { step * scale } else { -step * scale };
+    let value = step_value(value, step, input.has_attribute("data-integer"));
+    input
+        .dyn_ref::<HtmlInputElement>()
+        .unwrap()
+        .set_value(&locale::format(value));
+    true
+}
+
+fn step_value(value: f64, step: f64, integer: bool) -> f64 {
+    if integer {
+        let step = if step.abs() < 1.0 {
+            step.signum()
+        } else {
+            step.round()
+        };
+        (value.round() + step).max(1.0)
+    } else {
+        // rounding keeps float error like 0.30000000000000004 out of the input
+        (((value + step) * 1e6).round() / 1e6).max(0.0)
+    }
+}
+
+// the input's number, written with either decimal separator or as an
+// expression like "25.4/12"
+pub fn parse_input<T: std::str::FromStr>(input: &web_sys::Element) -> Option<T> {
+    expression::parse(&input.dyn_ref::<HtmlInputElement>().unwrap().value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_land_on_round_numbers() {
+        assert_eq!(step_value(0.2, 0.1, false), 0.3);
+        assert_eq!(step_value(0.167, -0.01, false), 0.157);
+    }
+
+    #[test]
+    fn integer_steps_are_whole_and_stay_positive() {
+        assert_eq!(step_value(12.0, 0.1, true), 13.0);
+        assert_eq!(step_value(12.0, -10.0, true), 2.0);
+        assert_eq!(step_value(2.0, -10.0, true), 1.0);
+    }
+
+    #[test]
+    fn standard_angles_say_what_they_mesh_with() {
+        assert!(pressure_angle_note(14.5).starts_with("14.5"));
+        assert!(pressure_angle_note(20.0).contains("ISO"));
+        assert!(pressure_angle_note(22.5).starts_with("Not a standard"));
+    }
+
+    #[test]
+    fn decimal_steps_stop_at_zero() {
+        assert_eq!(step_value(0.05, -0.1, false), 0.0);
+    }
+}
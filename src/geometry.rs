@@ -0,0 +1,1062 @@
+use crate::expression::Formula;
+use serde::{Deserialize, Serialize};
+use std::f64;
+
+// struct for points
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl std::ops::Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+// struct for gear specs. pitch and pressure angle are in the normal plane,
+// square to the teeth, which is the transverse plane for spur gears.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GearSpecs {
+    pub teeth: f64,
+    pub diametric_pitch: f64,
+    pub tooth_angle: f64,
+    pub clearance_mult: f64,
+    pub backlash_mult: f64,
+    // points sampled along each involute flank
+    pub involute_steps: u32,
+    #[serde(default)]
+    pub profile: ToothProfile,
+    // degrees the teeth lean from the axis, 0 for spur gears
+    #[serde(default)]
+    pub helix_angle: f64,
+    #[serde(default)]
+    pub thinning: Thinning,
+}
+
+// what the clearance and backlash inputs accept, in modules. a negative
+// clearance puts the root above the mating tip and negative backlash folds
+// the flanks over each other, while past half a module the teeth are more
+// gap than tooth.
+pub const CLEARANCE_RANGE: std::ops::RangeInclusive<f64> = 0.0..=0.5;
+pub const BACKLASH_RANGE: std::ops::RangeInclusive<f64> = 0.0..=0.5;
+
+// the fewest teeth the inputs take. below this the tooth pitch angle leaves
+// no room between the root and the tip and the radii go negative.
+pub const MIN_TEETH: u32 = 3;
+// and the most, past which the outline gets too heavy to draw on every edit
+pub const MAX_TEETH: u32 = 1000;
+
+impl GearSpecs {
+    // whether the inputs could have made this spec. designs come in from
+    // storage, links and embedding pages as well as the inputs, and a spec
+    // none of them should produce can hang the page or draw nonsense.
+    pub fn is_valid(&self) -> bool {
+        let notch_valid = match self.profile {
+            ToothProfile::Star { notch } => (0.0..1.0).contains(&notch),
+            ToothProfile::Involute | ToothProfile::Custom { .. } => true,
+        };
+        self.teeth.fract() == 0.0
+            && (MIN_TEETH as f64..=MAX_TEETH as f64).contains(&self.teeth)
+            && self.diametric_pitch.is_finite()
+            && self.diametric_pitch > 0.0
+            && self.tooth_angle > 0.0
+            && self.tooth_angle < 90.0
+            && CLEARANCE_RANGE.contains(&self.clearance_mult)
+            && BACKLASH_RANGE.contains(&self.backlash_mult)
+            && (1..=MAX_INVOLUTE_STEPS).contains(&self.involute_steps)
+            && (0.0..90.0).contains(&self.helix_angle)
+            && notch_valid
+    }
+}
+
+// how much of a mesh's backlash comes off this gear's teeth. by default both
+// gears of a pair are thinned alike, but a shop cutting a hardened pinion
+// leaves it full and takes the whole allowance off the softer wheel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Thinning {
+    #[default]
+    Half,
+    All,
+    None,
+}
+
+impl Thinning {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "half" => Some(Thinning::Half),
+            "all" => Some(Thinning::All),
+            "none" => Some(Thinning::None),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            Thinning::Half => "half",
+            Thinning::All => "all",
+            Thinning::None => "none",
+        }
+    }
+
+    // the backlash allowance this gear's teeth are thinned by, in allowances
+    pub fn share(&self) -> f64 {
+        match self {
+            Thinning::Half => 1.0,
+            Thinning::All => 2.0,
+            Thinning::None => 0.0,
+        }
+    }
+}
+
+// shape of the teeth
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ToothProfile {
+    #[default]
+    Involute,
+    // pointed, straight sided teeth for star and count wheels. `notch` is the
+    // share of each tooth pitch left as a flat root between teeth.
+    Star {
+        notch: f64,
+    },
+    // the radius as a formula of the angle from the tooth's centerline, see
+    // `CUSTOM_VARIABLES`
+    Custom {
+        formula: String,
+    },
+}
+
+// what a custom profile's formula can use: `theta` is the angle from the
+// tooth's centerline in radians, the rest are the tooth count and the
+// module, pitch, root and tip radii in inches
+pub const CUSTOM_VARIABLES: [&str; 6] = ["theta", "teeth", "module", "pitch", "root", "tip"];
+
+// a custom profile starts as a wave around the pitch circle
+pub const DEFAULT_CUSTOM_FORMULA: &str = "pitch + module * cos(teeth * theta)";
+
+impl ToothProfile {
+    pub fn from_value(value: &str, notch: f64, formula: &str) -> Option<Self> {
+        match value {
+            "involute" => Some(ToothProfile::Involute),
+            "star" => Some(ToothProfile::Star { notch }),
+            "custom" => Some(ToothProfile::Custom {
+                formula: formula.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            ToothProfile::Involute => "involute",
+            ToothProfile::Star { .. } => "star",
+            ToothProfile::Custom { .. } => "custom",
+        }
+    }
+}
+
+// one piece of a gear outline. consecutive segments share their end points,
+// so walking them in order traces the closed profile.
+pub enum OutlineSegment {
+    // straight runs (root lands, tip lands, radial steps)
+    Line(Vec<Point>),
+    // sampled points along an involute flank
+    Flank(Vec<Point>),
+}
+
+impl OutlineSegment {
+    pub fn points(&self) -> &[Point] {
+        match self {
+            OutlineSegment::Line(points) => points,
+            OutlineSegment::Flank(points) => points,
+        }
+    }
+
+    pub fn rotated(&self, rotation: &Rotation) -> OutlineSegment {
+        let rotate = |points: &[Point]| points.iter().map(|pt| rotation.apply(pt)).collect();
+        match self {
+            OutlineSegment::Line(points) => OutlineSegment::Line(rotate(points)),
+            OutlineSegment::Flank(points) => OutlineSegment::Flank(rotate(points)),
+        }
+    }
+
+    // reflected left to right, across the y axis
+    pub fn mirrored(&self) -> OutlineSegment {
+        let mirror = |points: &[Point]| {
+            points
+                .iter()
+                .map(|pt| Point { x: -pt.x, y: pt.y })
+                .collect()
+        };
+        match self {
+            OutlineSegment::Line(points) => OutlineSegment::Line(mirror(points)),
+            OutlineSegment::Flank(points) => OutlineSegment::Flank(mirror(points)),
+        }
+    }
+}
+
+// tessellated gear placed at its center, in inches
+pub struct GearOutline {
+    pub center: Point,
+    pub geometry: GearGeometry,
+    pub segments: Vec<OutlineSegment>,
+    // closed outlines cut out of the gear's web, relative to its center
+    pub holes: Vec<Vec<Point>>,
+}
+
+impl GearOutline {
+    pub fn point_count(&self) -> usize {
+        self.segments
+            .iter()
+            .map(|segment| segment.points().len())
+            .sum()
+    }
+
+    // reflected left to right across the page's center, gear and all
+    pub fn mirrored(self) -> GearOutline {
+        GearOutline {
+            center: Point {
+                x: -self.center.x,
+                y: self.center.y,
+            },
+            segments: self.segments.iter().map(OutlineSegment::mirrored).collect(),
+            holes: self
+                .holes
+                .iter()
+                .map(|hole| hole.iter().map(|pt| Point { x: -pt.x, y: pt.y }).collect())
+                .collect(),
+            geometry: self.geometry,
+        }
+    }
+}
+
+// derived gear dimensions, all in inches
+#[derive(Clone)]
+pub struct GearGeometry {
+    pub teeth: f64,
+    pub base_radius: f64,
+    pub root_radius: f64,
+    pub outer_radius: f64,
+    pub pitch_radius: f64,
+    pub backlash_allowance: f64,
+    pub involute_steps: u32,
+    pub profile: ToothProfile,
+}
+
+impl GearGeometry {
+    pub fn new(gear_spec: &GearSpecs) -> Self {
+        let teeth = gear_spec.teeth;
+        // the outline is the transverse section, where helical teeth are
+        // spread further apart and their flanks lean further over. tooth
+        // heights are measured the same in either plane.
+        let helix_cos = gear_spec.helix_angle.to_radians().cos();
+        let normal_module = 1.0 / gear_spec.diametric_pitch;
+        let module = normal_module / helix_cos;
+        let pressure_angle_rads = (gear_spec.tooth_angle.to_radians().tan() / helix_cos).atan();
+        let pitch_diameter = teeth * module;
+        let base_diameter = pitch_diameter * pressure_angle_rads.cos();
+        let addendum = normal_module;
+        let clearance = gear_spec.clearance_mult * normal_module;
+        let dedendum = clearance + normal_module;
+        let root_diameter = pitch_diameter - 2.0 * dedendum;
+        let outer_diameter = pitch_diameter + 2.0 * addendum;
+
+        Self {
+            teeth,
+            base_radius: base_diameter / 2.0,
+            root_radius: root_diameter / 2.0,
+            outer_radius: outer_diameter / 2.0,
+            pitch_radius: pitch_diameter / 2.0,
+            backlash_allowance: gear_spec.backlash_mult
+                * normal_module
+                * gear_spec.thinning.share(),
+            involute_steps: gear_spec.involute_steps,
+            profile: gear_spec.profile.clone(),
+        }
+    }
+
+    // angle between neighbouring teeth
+    pub fn tooth_pitch_angle(&self) -> f64 {
+        2.0 * f64::consts::PI / self.teeth
+    }
+
+    // the first tooth is symmetric about a ray at this angle, halfway between
+    // the roots on either side of it
+    pub fn tooth_centerline_angle(&self) -> f64 {
+        self.tooth_pitch_angle() / 4.0
+    }
+
+    // closed outline of the gear centered on the origin
+    pub fn outline(&self) -> Vec<OutlineSegment> {
+        let tooth = self.tooth();
+        self.tooth_rotations()
+            .iter()
+            .flat_map(|rotation| tooth.iter().map(move |segment| segment.rotated(rotation)))
+            .collect()
+    }
+
+    // rotation of every tooth, so placing teeth needs no per point trig
+    pub fn tooth_rotations(&self) -> Vec<Rotation> {
+        let tooth_angle = self.tooth_pitch_angle();
+        (0..self.teeth as u32)
+            .map(|i| Rotation::new(i as f64 * tooth_angle))
+            .collect()
+    }
+
+    // segments of the first tooth, from the root before its rising flank to
+    // the root before the next tooth. every other tooth is a rotated copy.
+    pub fn tooth(&self) -> Vec<OutlineSegment> {
+        match &self.profile {
+            ToothProfile::Involute => self.involute_tooth(),
+            ToothProfile::Star { notch } => self.star_tooth(*notch),
+            ToothProfile::Custom { formula } => self.custom_tooth(formula),
+        }
+    }
+
+    // a custom profile's formula sampled across one tooth pitch, as angles
+    // from the centerline and unclamped radii. `None` if it doesn't parse.
+    pub fn custom_radii(&self, formula: &str) -> Option<Vec<(f64, f64)>> {
+        let formula = Formula::parse(formula, &CUSTOM_VARIABLES)?;
+        let half_pitch = self.tooth_pitch_angle() / 2.0;
+        let samples = 2 * self.involute_steps.max(1);
+        let module = self.outer_radius - self.pitch_radius;
+        Some(
+            (0..=samples)
+                .map(|i| {
+                    let theta = -half_pitch + 2.0 * half_pitch * i as f64 / samples as f64;
+                    let values = [
+                        theta,
+                        self.teeth,
+                        module,
+                        self.pitch_radius,
+                        self.root_radius,
+                        self.outer_radius,
+                    ];
+                    (theta, formula.eval(&values))
+                })
+                .collect(),
+        )
+    }
+
+    // the formula's radii kept between the root and tip circles, as one flank
+    // across the whole pitch and a step over to the next tooth. a formula that
+    // doesn't parse leaves a bare root circle.
+    fn custom_tooth(&self, formula: &str) -> Vec<OutlineSegment> {
+        let centerline = self.tooth_centerline_angle();
+        let radii = self.custom_radii(formula).unwrap_or_else(|| {
+            let half_pitch = self.tooth_pitch_angle() / 2.0;
+            vec![
+                (-half_pitch, self.root_radius),
+                (half_pitch, self.root_radius),
+            ]
+        });
+        let points: Vec<Point> = radii
+            .iter()
+            .map(|&(theta, radius)| {
+                let radius = if radius.is_finite() {
+                    radius.clamp(self.root_radius, self.outer_radius)
+                } else {
+                    self.root_radius
+                };
+                Point {
+                    x: radius * (centerline + theta).cos(),
+                    y: radius * (centerline + theta).sin(),
+                }
+            })
+            .collect();
+        let next_tooth = Rotation::new(self.tooth_pitch_angle()).apply(&points[0]);
+        let last = points[points.len() - 1];
+        vec![
+            OutlineSegment::Flank(points),
+            OutlineSegment::Line(vec![last, next_tooth]),
+        ]
+    }
+
+    // straight sides from the root up to a point at the tip, centered on the
+    // same line as an involute tooth
+    fn star_tooth(&self, notch: f64) -> Vec<OutlineSegment> {
+        let tooth_angle = self.tooth_pitch_angle();
+        let centerline = self.tooth_centerline_angle();
+        // a notch as wide as the whole pitch would leave no tooth at all
+        let half_width = (1.0 - notch.clamp(0.0, 0.9)) * tooth_angle / 2.0;
+        let polar = |radius: f64, angle: f64| Point {
+            x: radius * angle.cos(),
+            y: radius * angle.sin(),
+        };
+
+        let rising_start = polar(self.root_radius, centerline - half_width);
+        let tip = polar(self.outer_radius, centerline);
+        let falling_end = polar(self.root_radius, centerline + half_width);
+        let next_root = polar(self.root_radius, centerline - half_width + tooth_angle);
+        vec![
+            OutlineSegment::Line(vec![rising_start, tip]),
+            OutlineSegment::Line(vec![tip, falling_end]),
+            OutlineSegment::Line(vec![falling_end, next_root]),
+        ]
+    }
+
+    // unwinding angles where the involute leaves the root (or the base circle,
+    // if the root is inside it) and where it reaches the tip
+    pub fn flank_theta_range(&self) -> (f64, f64) {
+        let theta_min = if self.root_radius > self.base_radius {
+            f64::sqrt((self.root_radius / self.base_radius).powi(2) - 1.0)
+        } else {
+            0.0
+        };
+        let theta_max = f64::sqrt((self.outer_radius / self.base_radius).powi(2) - 1.0); // Max theta for the involute
+        (theta_min, theta_max)
+    }
+
+    // angle the involute is turned back by so the tooth is the right
+    // thickness at the pitch circle, less half the backlash. the rising
+    // flank is the involute rotated by minus this.
+    pub fn pitch_correction(&self) -> f64 {
+        let base_radius = self.base_radius;
+        let pitch_radius = self.pitch_radius;
+        let theta_pitch = f64::sqrt((pitch_radius / base_radius).powi(2) - 1.0); // Max theta for the involute
+        let pitch_correction = (involute(base_radius, theta_pitch).x / pitch_radius).acos();
+        let clearance_correction = ((self.backlash_allowance / 2.0) / pitch_radius).asin();
+        pitch_correction - clearance_correction
+    }
+
+    // the furthest the drawn involute flank strays from the true curve, in
+    // inches. the flank stops at its last point and the tip is drawn
+    // straight across from there, so the top step is measured against the
+    // tip instead of a chord.
+    pub fn chordal_error(&self) -> f64 {
+        let steps = self.involute_steps.max(1);
+        let (theta_min, theta_max) = self.flank_theta_range();
+        let step = (theta_max - theta_min) / steps as f64;
+        let pitch_correction = self.pitch_correction();
+        let rising_rotation = Rotation::new(-pitch_correction);
+        let falling_rotation =
+            Rotation::new(2.0 * self.tooth_centerline_angle() + pitch_correction);
+        let flank = |theta: f64| rising_rotation.apply(&involute(self.base_radius, theta));
+        let last = involute(self.base_radius, theta_min + (steps - 1) as f64 * step);
+        let falling_start = falling_rotation.apply(&Point {
+            x: last.x,
+            y: -last.y,
+        });
+
+        (0..steps)
+            .flat_map(|i| {
+                let from_theta = theta_min + i as f64 * step;
+                let from = flank(from_theta);
+                let to = if i + 1 < steps {
+                    flank(from_theta + step)
+                } else {
+                    falling_start
+                };
+                (1..CHORD_SAMPLES).map(move |j| {
+                    let theta = from_theta + step * j as f64 / CHORD_SAMPLES as f64;
+                    distance_to_segment(&flank(theta), &from, &to)
+                })
+            })
+            .fold(0.0, f64::max)
+    }
+
+    fn involute_tooth(&self) -> Vec<OutlineSegment> {
+        let base_radius = self.base_radius;
+        let root_radius = self.root_radius;
+
+        // Generate the involute gear profile
+        let tooth_angle = self.tooth_pitch_angle();
+        let involute_steps = self.involute_steps; // Resolution for the involute curve
+        let (theta_min, theta_max) = self.flank_theta_range();
+        let theta: Vec<f64> = (0..involute_steps)
+            .map(|i| i as f64 * (theta_max - theta_min) / involute_steps as f64 + theta_min)
+            .collect();
+
+        let pitch_correction = self.pitch_correction();
+
+        let rising_rotation = Rotation::new(-pitch_correction);
+        let falling_rotation =
+            Rotation::new(2.0 * self.tooth_centerline_angle() + pitch_correction);
+        let next_root_rotation = Rotation::new(tooth_angle - pitch_correction);
+
+        // generate involute points. the falling flank is the rising one
+        // mirrored across the x axis, so the involute is only evaluated once.
+        let involute_points: Vec<Point> = theta
+            .iter()
+            .map(|theta| involute(base_radius, *theta))
+            .collect();
+        let rising: Vec<Point> = involute_points
+            .iter()
+            .map(|pt| rising_rotation.apply(pt))
+            .collect();
+        let falling: Vec<Point> = involute_points
+            .iter()
+            .rev()
+            .map(|pt| falling_rotation.apply(&Point { x: pt.x, y: -pt.y }))
+            .collect();
+
+        let root_point = Point {
+            x: root_radius,
+            y: 0.0,
+        };
+        let rising_start = rising[0];
+        let rising_end = rising[rising.len() - 1];
+        let falling_start = falling[0];
+        let falling_end = falling[falling.len() - 1];
+
+        vec![
+            OutlineSegment::Line(vec![rising_rotation.apply(&root_point), rising_start]),
+            OutlineSegment::Flank(rising),
+            OutlineSegment::Line(vec![rising_end, falling_start]),
+            OutlineSegment::Flank(falling),
+            OutlineSegment::Line(vec![
+                falling_end,
+                falling_rotation.apply(&root_point),
+                next_root_rotation.apply(&root_point),
+            ]),
+        ]
+    }
+}
+
+// points checked against each step of a flank for its chordal error
+const CHORD_SAMPLES: u32 = 16;
+// the most points per flank, typed in or asked for by a tolerance. every
+// tooth's outline is redrawn each time anything changes.
+pub const MAX_INVOLUTE_STEPS: u32 = 1000;
+
+// the fewest points per flank that keep an involute gear's flanks within
+// `tolerance` inches of the true curve, up to `MAX_INVOLUTE_STEPS`
+pub fn involute_steps_within(gear_spec: &GearSpecs, tolerance: f64) -> u32 {
+    let within = |involute_steps: u32| {
+        let spec = GearSpecs {
+            involute_steps,
+            ..gear_spec.clone()
+        };
+        GearGeometry::new(&spec).chordal_error() <= tolerance
+    };
+    // doubling to find enough, then bisecting back down
+    let mut enough = 1;
+    while !within(enough) {
+        if enough >= MAX_INVOLUTE_STEPS {
+            return MAX_INVOLUTE_STEPS;
+        }
+        enough = (enough * 2).min(MAX_INVOLUTE_STEPS);
+    }
+    let mut too_few = enough / 2;
+    while enough - too_few > 1 {
+        let middle = (too_few + enough) / 2;
+        if within(middle) {
+            enough = middle;
+        } else {
+            too_few = middle;
+        }
+    }
+    enough
+}
+
+pub fn distance_to_segment(point: &Point, from: &Point, to: &Point) -> f64 {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    let length_squared = dx * dx + dy * dy;
+    let along = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.x - from.x) * dx + (point.y - from.y) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    (point.x - from.x - along * dx).hypot(point.y - from.y - along * dy)
+}
+
+// whether `point` is inside the closed loop `edge`, by counting crossings
+pub fn inside(point: &Point, edge: &[Point]) -> bool {
+    edge.windows(2)
+        .filter(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            (a.y > point.y) != (b.y > point.y)
+                && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        })
+        .count()
+        % 2
+        == 1
+}
+
+// points sampled along each rounded rack tip corner
+const RACK_TIP_STEPS: u32 = 8;
+
+// closed outline of the basic rack that generates this gear, the tooth form a
+// hob or fly cutter is ground to. the pitch line runs along the x axis with
+// `teeth` teeth pointing up (+y) and a solid back below them. the cutter tip
+// makes the gear's root, so it reaches one module plus clearance above the
+// pitch line, and its corners are rounded by `tip_radius_mult` modules, or as
+// much as fits.
+//
+// a protuberance of `protuberance_mult` modules widens the cutter's tips so
+// they relieve the gear's roots below the working flanks, leaving room for
+// grinding them afterwards. it tapers back to the true flank where the
+// mating gear's tips stop, one module above the pitch line, and is limited
+// to what the clearance leaves room for.
+pub fn basic_rack(
+    gear_spec: &GearSpecs,
+    tip_radius_mult: f64,
+    protuberance_mult: f64,
+    teeth: u32,
+) -> Vec<OutlineSegment> {
+    let module = 1.0 / gear_spec.diametric_pitch;
+    let pressure_angle = gear_spec.tooth_angle.to_radians();
+    let (tan, cos) = (pressure_angle.tan(), pressure_angle.cos());
+    let pitch = f64::consts::PI * module;
+    let tip_height = module * (1.0 + gear_spec.clearance_mult);
+    let root_depth = module;
+    // cutter teeth fill the gear's tooth spaces, which are widened by the backlash
+    let half_thickness =
+        (pitch / 2.0 + gear_spec.backlash_mult * gear_spec.thinning.share() * module) / 2.0;
+    let flank_x = |y: f64| half_thickness - y * tan;
+
+    // the flank near the tip, leaning less than the true flank when there's
+    // a protuberance so that it stands out by it at the tip. past the point
+    // where it stands straight up it would undercut the cutter itself.
+    let relief_height = tip_height - module;
+    let protuberance =
+        (protuberance_mult * module).clamp(0.0, (relief_height * pressure_angle.sin()).max(0.0));
+    let tip_x = flank_x(tip_height) + protuberance / cos;
+    let tip_tan = if relief_height > 0.0 {
+        (flank_x(module) - tip_x) / relief_height
+    } else {
+        tan
+    };
+    let tip_angle = tip_tan.atan();
+    let tip_cos = tip_angle.cos();
+
+    // the fillet can't run past the middle of the tip, or with a protuberance
+    // below where it tapers back
+    let mut max_tip_radius = tip_x / (1.0 / tip_cos - tip_tan);
+    if protuberance > 0.0 {
+        max_tip_radius = max_tip_radius.min(relief_height / (1.0 - tip_angle.sin()));
+    }
+    let tip_radius = (tip_radius_mult * module).clamp(0.0, max_tip_radius.max(0.0));
+    let fillet_center = Point {
+        x: tip_x + tip_radius * tip_tan - tip_radius / tip_cos,
+        y: tip_height - tip_radius,
+    };
+
+    // right half of a tooth centered on x = 0, from the middle of the tip
+    // down to the root
+    let mut right_half = vec![Point {
+        x: 0.0,
+        y: tip_height,
+    }];
+    right_half.extend((0..=RACK_TIP_STEPS).map(|i| {
+        let angle = f64::consts::FRAC_PI_2
+            - (f64::consts::FRAC_PI_2 - tip_angle) * i as f64 / RACK_TIP_STEPS as f64;
+        Point {
+            x: fillet_center.x + tip_radius * angle.cos(),
+            y: fillet_center.y + tip_radius * angle.sin(),
+        }
+    }));
+    if protuberance > 0.0 {
+        right_half.push(Point {
+            x: flank_x(module),
+            y: module,
+        });
+    }
+    right_half.push(Point {
+        x: flank_x(-root_depth),
+        y: -root_depth,
+    });
+
+    // each tooth runs from root to root, left flank up and right flank down
+    let first_tooth = -(teeth as f64 - 1.0) / 2.0 * pitch;
+    let mut top: Vec<Point> = Vec::new();
+    (0..teeth).for_each(|tooth| {
+        let offset = first_tooth + tooth as f64 * pitch;
+        let left = right_half.iter().rev().map(|pt| Point {
+            x: offset - pt.x,
+            y: pt.y,
+        });
+        let right = right_half.iter().skip(1).map(|pt| Point {
+            x: offset + pt.x,
+            y: pt.y,
+        });
+        top.extend(left.chain(right));
+    });
+
+    // half a root land either side, then a back two modules deep
+    let end = teeth as f64 / 2.0 * pitch;
+    let back = -root_depth - 2.0 * module;
+    let start = Point {
+        x: -end,
+        y: -root_depth,
+    };
+    let finish = Point {
+        x: end,
+        y: -root_depth,
+    };
+    let first = top[0];
+    let last = top[top.len() - 1];
+    vec![
+        OutlineSegment::Line(vec![start, first]),
+        OutlineSegment::Line(top),
+        OutlineSegment::Line(vec![
+            last,
+            finish,
+            Point { x: end, y: back },
+            Point { x: -end, y: back },
+            start,
+        ]),
+    ]
+}
+
+// Functions for the involute curve generation
+pub fn involute(base_radius: f64, theta: f64) -> Point {
+    let x = base_radius * (theta.cos() + theta * theta.sin());
+    let y = base_radius * (theta.sin() - theta * theta.cos());
+    Point { x, y }
+}
+
+// rotation about the origin with its sin/cos worked out up front, rotating
+// many points by the same angle then costs a few multiplies each
+#[derive(Clone, Copy)]
+pub struct Rotation {
+    cos: f64,
+    sin: f64,
+}
+
+impl Rotation {
+    pub fn new(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self { cos, sin }
+    }
+
+    pub fn apply(&self, point: &Point) -> Point {
+        Point {
+            x: point.x * self.cos - point.y * self.sin,
+            y: point.x * self.sin + point.y * self.cos,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // relative to the gear's outer radius
+    const TOLERANCE: f64 = 1e-9;
+
+    fn gear_specs() -> impl Strategy<Value = GearSpecs> {
+        (
+            6u32..200,
+            1.0f64..48.0,
+            14.5f64..25.0,
+            0.0f64..0.35,
+            0.0f64..0.1,
+            2u32..400,
+            prop_oneof![Just(0.0), 0.0f64..45.0],
+        )
+            .prop_map(
+                |(
+                    teeth,
+                    diametric_pitch,
+                    tooth_angle,
+                    clearance_mult,
+                    backlash_mult,
+                    involute_steps,
+                    helix_angle,
+                )| GearSpecs {
+                    teeth: teeth as f64,
+                    diametric_pitch,
+                    tooth_angle,
+                    clearance_mult,
+                    backlash_mult,
+                    involute_steps,
+                    profile: ToothProfile::Involute,
+                    helix_angle,
+                    thinning: Thinning::Half,
+                },
+            )
+    }
+
+    fn star_specs() -> impl Strategy<Value = GearSpecs> {
+        (gear_specs(), 0.0f64..1.0).prop_map(|(specs, notch)| GearSpecs {
+            profile: ToothProfile::Star { notch },
+            ..specs
+        })
+    }
+
+    fn custom_specs() -> impl Strategy<Value = GearSpecs> {
+        (gear_specs(), 0.0f64..3.0).prop_map(|(specs, depth)| GearSpecs {
+            profile: ToothProfile::Custom {
+                formula: format!("pitch + {} * module * cos(teeth * theta)", depth),
+            },
+            ..specs
+        })
+    }
+
+    fn radius(pt: &Point) -> f64 {
+        pt.x.hypot(pt.y)
+    }
+
+    fn close(a: &Point, b: &Point, scale: f64) -> bool {
+        (a.x - b.x).abs() < TOLERANCE * scale && (a.y - b.y).abs() < TOLERANCE * scale
+    }
+
+    fn flanks(segments: &[OutlineSegment]) -> Vec<&[Point]> {
+        segments
+            .iter()
+            .filter_map(|segment| match segment {
+                OutlineSegment::Flank(points) => Some(points.as_slice()),
+                OutlineSegment::Line(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn specs_out_of_range_are_invalid() {
+        let valid = GearSpecs {
+            teeth: 20.0,
+            diametric_pitch: 10.0,
+            tooth_angle: 20.0,
+            clearance_mult: 0.25,
+            backlash_mult: 0.0,
+            involute_steps: 20,
+            profile: ToothProfile::Involute,
+            helix_angle: 0.0,
+            thinning: Thinning::Half,
+        };
+        assert!(valid.is_valid());
+        for invalid in [
+            GearSpecs {
+                teeth: 2.0,
+                ..valid.clone()
+            },
+            GearSpecs {
+                teeth: 1e9,
+                ..valid.clone()
+            },
+            GearSpecs {
+                teeth: 20.5,
+                ..valid.clone()
+            },
+            GearSpecs {
+                diametric_pitch: -10.0,
+                ..valid.clone()
+            },
+            GearSpecs {
+                diametric_pitch: f64::INFINITY,
+                ..valid.clone()
+            },
+            GearSpecs {
+                tooth_angle: f64::NAN,
+                ..valid.clone()
+            },
+            GearSpecs {
+                clearance_mult: -1.0,
+                ..valid.clone()
+            },
+            GearSpecs {
+                backlash_mult: 2.0,
+                ..valid.clone()
+            },
+            GearSpecs {
+                involute_steps: 1_000_000_000,
+                ..valid.clone()
+            },
+            GearSpecs {
+                helix_angle: 90.0,
+                ..valid.clone()
+            },
+            GearSpecs {
+                profile: ToothProfile::Star { notch: 1.5 },
+                ..valid.clone()
+            },
+        ] {
+            assert!(!invalid.is_valid());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn outline_is_closed(specs in gear_specs()) {
+            let geometry = GearGeometry::new(&specs);
+            let outline = geometry.outline();
+            let scale = geometry.outer_radius;
+
+            // every segment starts where the last one ended
+            for pair in outline.windows(2) {
+                let end = pair[0].points().last().unwrap();
+                let start = pair[1].points().first().unwrap();
+                prop_assert!(close(end, start, scale));
+            }
+
+            // and the last tooth comes back around to the first
+            let first = outline.first().unwrap().points().first().unwrap();
+            let last = outline.last().unwrap().points().last().unwrap();
+            prop_assert!(close(first, last, scale));
+        }
+
+        #[test]
+        fn star_outline_is_closed(specs in star_specs()) {
+            let geometry = GearGeometry::new(&specs);
+            let outline = geometry.outline();
+            let scale = geometry.outer_radius;
+            for pair in outline.windows(2) {
+                let end = pair[0].points().last().unwrap();
+                let start = pair[1].points().first().unwrap();
+                prop_assert!(close(end, start, scale));
+            }
+            let first = outline.first().unwrap().points().first().unwrap();
+            let last = outline.last().unwrap().points().last().unwrap();
+            prop_assert!(close(first, last, scale));
+        }
+
+        #[test]
+        fn custom_outline_is_closed_and_clamped(specs in custom_specs()) {
+            let geometry = GearGeometry::new(&specs);
+            let outline = geometry.outline();
+            let scale = geometry.outer_radius;
+            let slack = TOLERANCE * scale;
+            for pair in outline.windows(2) {
+                let end = pair[0].points().last().unwrap();
+                let start = pair[1].points().first().unwrap();
+                prop_assert!(close(end, start, scale));
+            }
+            let first = outline.first().unwrap().points().first().unwrap();
+            let last = outline.last().unwrap().points().last().unwrap();
+            prop_assert!(close(first, last, scale));
+            for segment in &outline {
+                for pt in segment.points() {
+                    prop_assert!(radius(pt) >= geometry.root_radius - slack);
+                    prop_assert!(radius(pt) <= geometry.outer_radius + slack);
+                }
+            }
+        }
+
+        #[test]
+        fn star_teeth_come_to_a_point_at_the_tip(specs in star_specs()) {
+            let geometry = GearGeometry::new(&specs);
+            let tooth = geometry.tooth();
+            let tip = tooth[0].points().last().unwrap();
+            let slack = TOLERANCE * geometry.outer_radius;
+            prop_assert!((radius(tip) - geometry.outer_radius).abs() < slack);
+            for segment in &tooth {
+                for pt in segment.points() {
+                    prop_assert!(radius(pt) >= geometry.root_radius - slack);
+                }
+            }
+        }
+
+        #[test]
+        fn basic_rack_is_closed_and_within_its_depth(
+            specs in gear_specs(),
+            tip_radius_mult in 0.0f64..1.0,
+            protuberance_mult in prop_oneof![Just(0.0), 0.0f64..0.1],
+            teeth in 1u32..10,
+        ) {
+            let rack = basic_rack(&specs, tip_radius_mult, protuberance_mult, teeth);
+            let module = 1.0 / specs.diametric_pitch;
+            let slack = TOLERANCE * module;
+            for pair in rack.windows(2) {
+                let end = pair[0].points().last().unwrap();
+                let start = pair[1].points().first().unwrap();
+                prop_assert!(close(end, start, module));
+            }
+            let first = rack.first().unwrap().points().first().unwrap();
+            let last = rack.last().unwrap().points().last().unwrap();
+            prop_assert!(close(first, last, module));
+
+            // nothing pokes past the cutter tip or the rack's length
+            let tip_height = module * (1.0 + specs.clearance_mult);
+            let half_length = teeth as f64 / 2.0 * f64::consts::PI * module;
+            for pt in rack.iter().flat_map(|segment| segment.points()) {
+                prop_assert!(pt.y <= tip_height + slack);
+                prop_assert!(pt.x.abs() <= half_length + slack);
+            }
+        }
+
+        #[test]
+        fn rack_protuberance_leaves_the_working_flanks_alone(
+            specs in gear_specs(),
+            protuberance_mult in 0.0f64..0.1,
+        ) {
+            let rack = basic_rack(&specs, 0.0, protuberance_mult, 1);
+            let module = 1.0 / specs.diametric_pitch;
+            let slack = TOLERANCE * module;
+            let half_thickness =
+                (f64::consts::PI * module / 2.0 + specs.backlash_mult * module) / 2.0;
+            let tan = specs.tooth_angle.to_radians().tan();
+            // below where the mating gear's tips reach, the flanks are true
+            for pt in rack.iter().flat_map(|segment| segment.points()) {
+                if pt.y > -module + slack && pt.y < module - slack {
+                    prop_assert!((pt.x.abs() - (half_thickness - pt.y * tan)).abs() < slack);
+                }
+            }
+        }
+
+        #[test]
+        fn outline_stays_between_root_and_tip(specs in gear_specs()) {
+            let geometry = GearGeometry::new(&specs);
+            let slack = TOLERANCE * geometry.outer_radius;
+            for segment in geometry.outline() {
+                for pt in segment.points() {
+                    prop_assert!(radius(pt) >= geometry.root_radius - slack);
+                    prop_assert!(radius(pt) <= geometry.outer_radius + slack);
+                }
+            }
+        }
+
+        #[test]
+        fn flank_radius_is_monotonic(specs in gear_specs()) {
+            let geometry = GearGeometry::new(&specs);
+            let tooth = geometry.tooth();
+            let flanks = flanks(&tooth);
+            prop_assert_eq!(flanks.len(), 2);
+
+            // the rising flank climbs from root to tip, the falling one comes back down
+            for pair in flanks[0].windows(2) {
+                prop_assert!(radius(&pair[1]) > radius(&pair[0]));
+            }
+            for pair in flanks[1].windows(2) {
+                prop_assert!(radius(&pair[1]) < radius(&pair[0]));
+            }
+        }
+
+        #[test]
+        fn tooth_is_symmetric_about_centerline(specs in gear_specs()) {
+            let geometry = GearGeometry::new(&specs);
+            let tooth = geometry.tooth();
+            let flanks = flanks(&tooth);
+
+            // mirroring across the centerline turns the rising flank into the falling one
+            let mirror = Rotation::new(2.0 * geometry.tooth_centerline_angle());
+            let mirrored: Vec<Point> = flanks[0]
+                .iter()
+                .rev()
+                .map(|pt| mirror.apply(&Point { x: pt.x, y: -pt.y }))
+                .collect();
+            prop_assert_eq!(mirrored.len(), flanks[1].len());
+            for (a, b) in mirrored.iter().zip(flanks[1]) {
+                prop_assert!(close(a, b, geometry.outer_radius));
+            }
+        }
+
+        #[test]
+        fn tolerance_sets_enough_points(specs in gear_specs(), tolerance in 1e-5f64..1e-2) {
+            let involute_steps = involute_steps_within(&specs, tolerance);
+            let geometry = GearGeometry::new(&GearSpecs {
+                involute_steps,
+                ..specs
+            });
+            prop_assert!(
+                geometry.chordal_error() <= tolerance || involute_steps == MAX_INVOLUTE_STEPS
+            );
+        }
+
+        #[test]
+        fn outline_has_one_tooth_per_spec_tooth(specs in gear_specs()) {
+            let geometry = GearGeometry::new(&specs);
+            let teeth = specs.teeth as usize;
+            prop_assert_eq!(geometry.tooth_rotations().len(), teeth);
+            prop_assert_eq!(flanks(&geometry.outline()).len(), 2 * teeth);
+            prop_assert_eq!(geometry.outline().len(), teeth * geometry.tooth().len());
+        }
+    }
+}
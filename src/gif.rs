@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+// codes never grow past 12 bits
+const MAX_CODES: u16 = 4096;
+// every frame's pixels are 8 bit indices into the shared palette
+const MIN_CODE_SIZE: u8 = 8;
+
+// the shared palette: 3 bits of red, 3 of green and 2 of blue. coarse, but
+// gear drawings are a few flat colors on white.
+fn palette() -> Vec<u8> {
+    (0..=255u32)
+        .flat_map(|index| {
+            let (r, g, b) = (index >> 5, (index >> 2) & 7, index & 3);
+            [
+                (r * 255 / 7) as u8,
+                (g * 255 / 7) as u8,
+                (b * 255 / 3) as u8,
+            ]
+        })
+        .collect()
+}
+
+// rgba pixels as indices into the palette, alpha over white
+pub fn quantize(rgba: &[u8]) -> Vec<u8> {
+    rgba.chunks_exact(4)
+        .map(|pixel| {
+            let alpha = pixel[3] as u32;
+            let channel = |value: u8, levels: u32| {
+                let over_white = (value as u32 * alpha + 255 * (255 - alpha)) / 255;
+                (over_white * levels + 127) / 255
+            };
+            (channel(pixel[0], 7) << 5 | channel(pixel[1], 7) << 2 | channel(pixel[2], 3)) as u8
+        })
+        .collect()
+}
+
+// an animated gif of `frames`, each `width` by `height` palette indices,
+// shown `delay` hundredths of a second apart and looping forever
+pub fn encode(width: u16, height: u16, frames: &[Vec<u8>], delay: u16) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend(b"GIF89a");
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    // a global table of 256 colors, 8 bits per channel
+    out.extend([0xf7, 0, 0]);
+    out.extend(palette());
+    // the netscape extension, repeating forever
+    out.extend([0x21, 0xff, 11]);
+    out.extend(b"NETSCAPE2.0");
+    out.extend([3, 1, 0, 0, 0]);
+    for frame in frames {
+        out.extend([0x21, 0xf9, 4, 0]);
+        out.extend(delay.to_le_bytes());
+        out.extend([0, 0]);
+        out.push(0x2c);
+        out.extend([0, 0, 0, 0]);
+        out.extend(width.to_le_bytes());
+        out.extend(height.to_le_bytes());
+        out.push(0);
+        out.push(MIN_CODE_SIZE);
+        for block in compress(frame).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3b);
+    out
+}
+
+// lzw codes for `indices`, packed least significant bit first. the table
+// starts over when it fills up.
+fn compress(indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+    let mut bits = Bits::default();
+
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut size = MIN_CODE_SIZE as u32 + 1;
+    bits.write(clear, size);
+    let mut current: Option<u16> = None;
+    for &index in indices {
+        let Some(prefix) = current else {
+            current = Some(index as u16);
+            continue;
+        };
+        if let Some(&code) = table.get(&(prefix, index)) {
+            current = Some(code);
+            continue;
+        }
+        bits.write(prefix, size);
+        if next < MAX_CODES {
+            table.insert((prefix, index), next);
+            next += 1;
+            // the decoder widens its codes a step behind the encoder
+            if next > 1 << size && size < 12 {
+                size += 1;
+            }
+        } else {
+            bits.write(clear, size);
+            table.clear();
+            next = end + 1;
+            size = MIN_CODE_SIZE as u32 + 1;
+        }
+        current = Some(index as u16);
+    }
+    if let Some(code) = current {
+        bits.write(code, size);
+    }
+    bits.write(end, size);
+    bits.finish()
+}
+
+// codes packed into bytes as they're written
+#[derive(Default)]
+struct Bits {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl Bits {
+    fn write(&mut self, code: u16, size: u32) {
+        self.buffer |= (code as u32) << self.count;
+        self.count += size;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // the bytes, the last one padded out with zeros
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // reads the codes back out, following the decoder's side of the format
+    fn decompress(bytes: &[u8]) -> Vec<u8> {
+        let clear = 1u16 << MIN_CODE_SIZE;
+        let end = clear + 1;
+        let mut out = Vec::new();
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut size = MIN_CODE_SIZE as u32 + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let (mut position, total) = (0usize, bytes.len() * 8);
+        while position + size as usize <= total {
+            let code = (0..size).fold(0u16, |code, bit| {
+                let at = position + bit as usize;
+                code | ((bytes[at / 8] >> (at % 8) & 1) as u16) << bit
+            });
+            position += size as usize;
+            if code == clear {
+                table = (0..clear).map(|index| vec![index as u8]).collect();
+                table.extend([Vec::new(), Vec::new()]);
+                size = MIN_CODE_SIZE as u32 + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                break;
+            }
+            let entry = match (table.get(code as usize), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.clone(), vec![previous[0]]].concat(),
+                (None, None) => panic!("code {} before any other", code),
+            };
+            if let Some(previous) = previous {
+                if table.len() < MAX_CODES as usize {
+                    table.push([previous, vec![entry[0]]].concat());
+                }
+            }
+            if table.len() == 1 << size && size < 12 {
+                size += 1;
+            }
+            out.extend(&entry);
+            previous = Some(entry);
+        }
+        out
+    }
+
+    #[test]
+    fn frames_compress_and_read_back() {
+        // long enough to fill the table and start over at least once
+        let indices: Vec<u8> = (0..60_000u32)
+            .map(|i| ((i * 7919) % 251 % (1 + i / 4000)) as u8)
+            .collect();
+        assert_eq!(decompress(&compress(&indices)), indices);
+        let flat = vec![255u8; 5000];
+        assert_eq!(decompress(&compress(&flat)), flat);
+
+        let gif = encode(2, 1, &[vec![0, 255], vec![255, 0]], 5);
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(*gif.last().unwrap(), 0x3b);
+    }
+
+    #[test]
+    fn colors_land_on_the_nearest_palette_entry() {
+        // white, black, pure red, and anything transparent is white
+        let rgba = [255, 255, 255, 255, 0, 0, 0, 255, 255, 0, 0, 255, 0, 0, 0, 0];
+        assert_eq!(quantize(&rgba), vec![255, 0, 0b1110_0000, 255]);
+        let palette = palette();
+        assert_eq!(&palette[255 * 3..], &[255, 255, 255]);
+    }
+}
@@ -0,0 +1,86 @@
+// crossed helical (screw) gears: two helical gears on shafts at an angle
+// that don't intersect. the helix angles add up to the shaft angle when both
+// gears have the same hand, and any split between them meshes. the split
+// used here puts the shafts as close together as they can be.
+
+// how finely the helix split is found, in degrees
+const SEARCH_TOLERANCE: f64 = 1e-9;
+
+pub struct CrossedPair {
+    // in degrees, both the same hand
+    pub helix_angles: [f64; 2],
+    // between the shafts, in inches
+    pub center_distance: f64,
+}
+
+// the helix angles for gears of `teeth` on shafts `shaft_angle` degrees
+// apart, and how far apart the shafts end up. `None` if the shaft angle
+// leaves no split where both gears' teeth lean less than square to their
+// axes.
+pub fn crossed_pair(
+    shaft_angle: f64,
+    teeth: [f64; 2],
+    diametric_pitch: f64,
+) -> Option<CrossedPair> {
+    if !(0.0..180.0).contains(&shaft_angle) || diametric_pitch <= 0.0 {
+        return None;
+    }
+    // the sum of the pitch diameters, in normal modules, is smallest where
+    // its rates of change with each helix angle balance. the difference
+    // between them only grows with the first angle, so it's bisected.
+    let rate = |teeth: f64, angle: f64| {
+        let angle = angle.to_radians();
+        teeth * angle.sin() / angle.cos().powi(2)
+    };
+    let imbalance = |first: f64| rate(teeth[0], first) - rate(teeth[1], shaft_angle - first);
+    let (mut low, mut high) = ((shaft_angle - 90.0).max(0.0), shaft_angle.min(90.0));
+    if high - low <= SEARCH_TOLERANCE && shaft_angle > 0.0 {
+        return None;
+    }
+    while high - low > SEARCH_TOLERANCE {
+        let middle = (low + high) / 2.0;
+        if imbalance(middle) < 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    let first = (low + high) / 2.0;
+    Some(CrossedPair {
+        helix_angles: [first, shaft_angle - first],
+        center_distance: (teeth[0] / first.to_radians().cos()
+            + teeth[1] / (shaft_angle - first).to_radians().cos())
+            / diametric_pitch
+            / 2.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_gears_split_the_shaft_angle_evenly() {
+        let pair = crossed_pair(90.0, [20.0, 20.0], 10.0).unwrap();
+        assert!((pair.helix_angles[0] - 45.0).abs() < 1e-6);
+        assert!((pair.helix_angles[1] - 45.0).abs() < 1e-6);
+        // each pitch diameter is 20 / 10 / cos 45
+        assert!((pair.center_distance - 2.0 * 2.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_split_minimizes_the_center_distance() {
+        let pair = crossed_pair(90.0, [10.0, 30.0], 12.0).unwrap();
+        assert!((pair.helix_angles[0] + pair.helix_angles[1] - 90.0).abs() < 1e-9);
+        // the larger gear takes the smaller helix angle
+        assert!(pair.helix_angles[1] < pair.helix_angles[0]);
+        // where the pitch diameters' rates of change balance
+        let rate = |teeth: f64, angle: f64| {
+            let angle = angle.to_radians();
+            teeth * angle.sin() / angle.cos().powi(2)
+        };
+        let (a, b) = (pair.helix_angles[0], pair.helix_angles[1]);
+        assert!((rate(10.0, a) - rate(30.0, b)).abs() < 1e-6);
+        assert!(crossed_pair(180.0, [10.0, 30.0], 12.0).is_none());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::geometry::{basic_rack, GearGeometry, GearSpecs, Point};
+use std::f64;
+
+// one pass of the rack rolling through, then a pause on the finished space
+const SWEEP_MS: f64 = 5000.0;
+const HOLD_MS: f64 = 1500.0;
+
+// rack positions left behind as it rolls, their envelope is the tooth space
+const GENERATING_STEPS: u32 = 24;
+// enough cutter teeth to cover the space through the whole roll
+const RACK_TEETH: u32 = 5;
+
+// how far through the roll to draw at `ms`, from 0 to 1
+pub fn progress_at(ms: f64) -> f64 {
+    f64::min(ms % (SWEEP_MS + HOLD_MS) / SWEEP_MS, 1.0)
+}
+
+// a point of the basic rack, in its own coordinates, as it sits against a
+// gear that has rolled `roll` radians along it. the rack's pitch line rolls
+// around the pitch circle without slipping, touching it at `space_angle`
+// before the roll, with the rack's teeth pointing in at the gear's center.
+fn place_rack(pitch_radius: f64, space_angle: f64, roll: f64, pt: &Point) -> Point {
+    let angle = space_angle + roll;
+    let (radial, tangent) = (
+        Point {
+            x: angle.cos(),
+            y: angle.sin(),
+        },
+        Point {
+            x: -angle.sin(),
+            y: angle.cos(),
+        },
+    );
+    let along = pt.x - pitch_radius * roll;
+    Point {
+        x: (pitch_radius - pt.y) * radial.x + along * tangent.x,
+        y: (pitch_radius - pt.y) * radial.y + along * tangent.y,
+    }
+}
+
+// the basic rack rolling through the tooth space before the first gear's
+// first tooth, leaving its earlier positions behind so the space it cuts
+// shows as their envelope. the cutter is the one the rack export draws.
+// `center` is in pixels, `scale` in pixels per inch.
+pub fn draw_hobbing(
+    context: &web_sys::CanvasRenderingContext2d,
+    gear_spec: &GearSpecs,
+    tip_radius_mult: f64,
+    protuberance_mult: f64,
+    center: Point,
+    scale: f64,
+    progress: f64,
+) {
+    let geometry = GearGeometry::new(gear_spec);
+    let pitch_radius = geometry.pitch_radius;
+    // nothing for the rack to roll on, and canvas throws on a negative radius
+    if pitch_radius <= 0.0 {
+        return;
+    }
+    let space_angle = geometry.tooth_centerline_angle() - geometry.tooth_pitch_angle() / 2.0;
+    let max_roll = geometry.tooth_pitch_angle();
+    let roll = -max_roll + 2.0 * max_roll * progress;
+    let rack: Vec<Point> = basic_rack(gear_spec, tip_radius_mult, protuberance_mult, RACK_TEETH)
+        .iter()
+        .flat_map(|segment| segment.points().to_vec())
+        .collect();
+    let to_screen = |pt: Point| Point {
+        x: center.x + pt.x * scale,
+        y: center.y + pt.y * scale,
+    };
+    let draw_rack = |roll: f64| {
+        context.begin_path();
+        rack.iter().enumerate().for_each(|(i, pt)| {
+            let pt = to_screen(place_rack(pitch_radius, space_angle, roll, pt));
+            if i == 0 {
+                context.move_to(pt.x, pt.y);
+            } else {
+                context.line_to(pt.x, pt.y);
+            }
+        });
+        context.stroke();
+    };
+
+    // pitch circle the rack's pitch line rolls on
+    context.set_stroke_style_str("gray");
+    context.set_line_width(1.0);
+    context
+        .set_line_dash(&js_sys::Array::of2(&4.0.into(), &4.0.into()))
+        .unwrap();
+    context.begin_path();
+    context
+        .arc(
+            center.x,
+            center.y,
+            pitch_radius * scale,
+            0.0,
+            2.0 * f64::consts::PI,
+        )
+        .unwrap();
+    context.stroke();
+    context.set_line_dash(&js_sys::Array::new()).unwrap();
+
+    // where the rack has been
+    context.set_stroke_style_str("rgba(0, 90, 200, 0.25)");
+    (0..=GENERATING_STEPS)
+        .map(|step| -max_roll + 2.0 * max_roll * step as f64 / GENERATING_STEPS as f64)
+        .filter(|&step_roll| step_roll < roll)
+        .for_each(draw_rack);
+
+    // the rack as it is now
+    context.set_stroke_style_str("darkorange");
+    context.set_line_width(2.0);
+    draw_rack(roll);
+    context.set_line_width(1.0);
+
+    // below the involute construction's caption, in case both are shown
+    let caption = format!(
+        "rolled {:.1}\u{b0}, rack moved = pitch radius x angle = {:.3} in",
+        roll.to_degrees(),
+        pitch_radius * roll
+    );
+    context.set_font("12px sans-serif");
+    context.set_text_align("left");
+    context.set_text_baseline("top");
+    context.set_fill_style_str("black");
+    context.fill_text(&caption, 10.0, 28.0).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rack_rolls_on_the_pitch_circle_without_slipping() {
+        let (pitch_radius, space_angle) = (2.0, 0.3);
+        let radius = |pt: Point| (pt.x * pt.x + pt.y * pt.y).sqrt();
+        let angle = |pt: Point| pt.y.atan2(pt.x);
+
+        // the cutter tooth starts centered in the space, pointing inward
+        let tip = place_rack(pitch_radius, space_angle, 0.0, &Point { x: 0.0, y: 0.5 });
+        assert!((radius(tip) - 1.5).abs() < 1e-12);
+        assert!((angle(tip) - space_angle).abs() < 1e-12);
+
+        // after rolling, the point of the pitch line that has come to rest on
+        // the pitch circle is as far along the line as the arc rolled
+        for roll in [-0.4, 0.1, 0.7] {
+            let pt = Point {
+                x: pitch_radius * roll,
+                y: 0.0,
+            };
+            let contact = place_rack(pitch_radius, space_angle, roll, &pt);
+            assert!((radius(contact) - pitch_radius).abs() < 1e-12);
+            assert!((angle(contact) - (space_angle + roll)).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use crate::geometry::Point;
+use crate::paper;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+// how far dimensions stand off the part, in inches
+const DIMENSION_OFFSET: f64 = 0.3;
+// arrowhead length, in inches
+const ARROW_LENGTH: f64 = 0.06;
+// dimension text is printed at 9pt
+const TEXT_SIZE: f64 = 9.0 / 72.0;
+// rules of thumb for a steel hub: its outside 1.8 bores across and its
+// length 1.25 bores, or more if the key needs it
+const HUB_DIAMETER_PER_BORE: f64 = 1.8;
+const HUB_LENGTH_PER_BORE: f64 = 1.25;
+// what a mild steel key can take in shear and against the side of its
+// keyway, in psi, with a safety factor of about three
+const KEY_SHEAR_STRESS: f64 = 10_000.0;
+const KEY_BEARING_STRESS: f64 = 20_000.0;
+// recommendations are rounded up to a sixteenth
+const ROUND_TO: f64 = 1.0 / 16.0;
+
+// the body a gear's teeth are cut on, in inches. the flange is the plate the
+// teeth stand on, and the hub stands out of one face of it around the bore.
+// the hub's length is overall, flange included, so a hub no longer than the
+// flange is flush with it. a gear with no flange thickness is only a profile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Hub {
+    pub diameter: f64,
+    pub length: f64,
+    pub flange_thickness: f64,
+    // the shaft it's sized for, a square key's width with zero for no key,
+    // and the torque it carries in lb-in. only used to recommend a size.
+    #[serde(default)]
+    pub bore: f64,
+    #[serde(default)]
+    pub key_width: f64,
+    #[serde(default)]
+    pub torque: f64,
+}
+
+// the smallest hub the rules of thumb allow, in inches
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Recommendation {
+    pub diameter: f64,
+    pub length: f64,
+}
+
+impl Hub {
+    // a hub big enough around the bore, and long enough for the key to carry
+    // the torque without shearing or crushing. the key is square, so half
+    // its height bears on the hub. never shorter than the flange it stands
+    // on. `None` without a bore to size it for.
+    pub fn recommendation(&self) -> Option<Recommendation> {
+        if self.bore <= 0.0 {
+            return None;
+        }
+        let mut length = HUB_LENGTH_PER_BORE * self.bore;
+        if self.key_width > 0.0 && self.torque > 0.0 {
+            // the key's force is the torque over the shaft's radius
+            let force = 2.0 * self.torque / self.bore;
+            let shear = force / (KEY_SHEAR_STRESS * self.key_width);
+            let bearing = force / (KEY_BEARING_STRESS * self.key_width / 2.0);
+            length = length.max(shear).max(bearing);
+        }
+        let round_up = |value: f64| (value / ROUND_TO - 1e-9).ceil() * ROUND_TO;
+        Some(Recommendation {
+            diameter: round_up(HUB_DIAMETER_PER_BORE * self.bore),
+            length: round_up(length.max(self.flange_thickness)),
+        })
+    }
+}
+
+// a measurement between two points on the part. the dimension line runs
+// parallel to them, `offset` away, with extension lines back to the part.
+pub struct Dimension {
+    pub from: Point,
+    pub to: Point,
+    pub offset: Point,
+    pub text: String,
+}
+
+// the gear seen edge on, in inches, with the axis running down the middle
+// and y increasing from the flange face the hub stands away from
+pub struct SideView {
+    // closed, the last point repeating the first
+    pub outline: Vec<Point>,
+    pub dimensions: Vec<Dimension>,
+    // how far the view reaches above and below its origin, dimensions
+    // included
+    pub above: f64,
+    pub below: f64,
+    // and either side of the axis
+    pub half_width: f64,
+}
+
+pub fn side_view(outer_diameter: f64, hub: &Hub) -> Option<SideView> {
+    if hub.flange_thickness <= 0.0 || outer_diameter <= 0.0 {
+        return None;
+    }
+    let point = |x: f64, y: f64| Point { x, y };
+    let radius = outer_diameter / 2.0;
+    let thickness = hub.flange_thickness;
+    let hub_radius = hub.diameter.min(outer_diameter) / 2.0;
+    let standing = hub_radius > 0.0 && hub.length > thickness;
+
+    let mut outline = vec![
+        point(-radius, 0.0),
+        point(radius, 0.0),
+        point(radius, thickness),
+    ];
+    if standing {
+        outline.extend([
+            point(hub_radius, thickness),
+            point(hub_radius, hub.length),
+            point(-hub_radius, hub.length),
+            point(-hub_radius, thickness),
+        ]);
+    }
+    outline.extend([point(-radius, thickness), point(-radius, 0.0)]);
+
+    let mut dimensions = vec![
+        Dimension {
+            from: point(-radius, 0.0),
+            to: point(radius, 0.0),
+            offset: point(0.0, -DIMENSION_OFFSET),
+            text: format!("{:.3} in dia", outer_diameter),
+        },
+        Dimension {
+            from: point(radius, 0.0),
+            to: point(radius, thickness),
+            offset: point(DIMENSION_OFFSET, 0.0),
+            text: format!("{:.3} in", thickness),
+        },
+    ];
+    let mut bottom = thickness;
+    if standing {
+        bottom = hub.length;
+        dimensions.extend([
+            Dimension {
+                from: point(-hub_radius, hub.length),
+                to: point(hub_radius, hub.length),
+                offset: point(0.0, DIMENSION_OFFSET),
+                text: format!("{:.3} in dia", 2.0 * hub_radius),
+            },
+            Dimension {
+                from: point(-radius, 0.0),
+                to: point(-radius, hub.length),
+                offset: point(-DIMENSION_OFFSET, 0.0),
+                text: format!("{:.3} in", hub.length),
+            },
+        ]);
+    }
+
+    // side dimensions' text sits beyond them, the others' is centered
+    let half_width = dimensions
+        .iter()
+        .map(|dimension| {
+            let text = paper::text_width(&dimension.text, TEXT_SIZE);
+            if dimension.offset.x == 0.0 {
+                text / 2.0
+            } else {
+                dimension.from.x.abs() + DIMENSION_OFFSET + 0.05 + text
+            }
+        })
+        .fold(radius, f64::max);
+    Some(SideView {
+        outline,
+        dimensions,
+        above: DIMENSION_OFFSET + 2.0 * TEXT_SIZE,
+        below: bottom + DIMENSION_OFFSET + 2.0 * TEXT_SIZE,
+        half_width,
+    })
+}
+
+// draws `view` with its origin at `origin`, both in pixels at `ppi`
+pub fn draw(context: &web_sys::CanvasRenderingContext2d, view: &SideView, origin: Point, ppi: f64) {
+    let to_px = |pt: &Point| (origin.x + pt.x * ppi, origin.y + pt.y * ppi);
+    context.set_stroke_style_str("black");
+    context.set_fill_style_str("black");
+    context
+        .set_line_dash(&JsValue::from(Vec::<f64>::new()))
+        .unwrap();
+
+    context.begin_path();
+    for (i, pt) in view.outline.iter().enumerate() {
+        let (x, y) = to_px(pt);
+        if i == 0 {
+            context.move_to(x, y);
+        } else {
+            context.line_to(x, y);
+        }
+    }
+    context.stroke();
+
+    // the axis, dashed through the middle
+    context
+        .set_line_dash(&JsValue::from(vec![
+            JsValue::from(0.1 * ppi),
+            JsValue::from(0.05 * ppi),
+        ]))
+        .unwrap();
+    let bottom = view.outline.iter().map(|pt| pt.y).fold(0.0, f64::max);
+    context.begin_path();
+    context.move_to(origin.x, origin.y - 0.1 * ppi);
+    context.line_to(origin.x, origin.y + (bottom + 0.1) * ppi);
+    context.stroke();
+    context
+        .set_line_dash(&JsValue::from(Vec::<f64>::new()))
+        .unwrap();
+
+    context.set_font(&format!("{}px sans-serif", TEXT_SIZE * ppi));
+    context.set_text_align("center");
+    for dimension in &view.dimensions {
+        draw_dimension(context, dimension, &to_px, ppi);
+    }
+}
+
+fn draw_dimension(
+    context: &web_sys::CanvasRenderingContext2d,
+    dimension: &Dimension,
+    to_px: &impl Fn(&Point) -> (f64, f64),
+    ppi: f64,
+) {
+    let shifted = |pt: &Point| Point {
+        x: pt.x + dimension.offset.x,
+        y: pt.y + dimension.offset.y,
+    };
+    let (from, to) = (shifted(&dimension.from), shifted(&dimension.to));
+
+    // extension lines from the part out past the dimension line
+    let overshoot = 0.05 / DIMENSION_OFFSET;
+    context.begin_path();
+    for (part, line) in [(&dimension.from, &from), (&dimension.to, &to)] {
+        let (x, y) = to_px(part);
+        context.move_to(x, y);
+        let (x, y) = to_px(&Point {
+            x: line.x + dimension.offset.x * overshoot,
+            y: line.y + dimension.offset.y * overshoot,
+        });
+        context.line_to(x, y);
+    }
+    let (from_x, from_y) = to_px(&from);
+    let (to_x, to_y) = to_px(&to);
+    context.move_to(from_x, from_y);
+    context.line_to(to_x, to_y);
+    context.stroke();
+
+    // arrowheads pointing out to the extension lines
+    let length = ((to_x - from_x).powi(2) + (to_y - from_y).powi(2)).sqrt();
+    if length > 0.0 {
+        let (dx, dy) = ((to_x - from_x) / length, (to_y - from_y) / length);
+        let arrow = ARROW_LENGTH * ppi;
+        for (x, y, sign) in [(from_x, from_y, 1.0), (to_x, to_y, -1.0)] {
+            context.begin_path();
+            context.move_to(x, y);
+            context.line_to(
+                x + sign * arrow * dx - arrow / 3.0 * dy,
+                y + sign * arrow * dy + arrow / 3.0 * dx,
+            );
+            context.line_to(
+                x + sign * arrow * dx + arrow / 3.0 * dy,
+                y + sign * arrow * dy - arrow / 3.0 * dx,
+            );
+            context.close_path();
+            context.fill();
+        }
+    }
+
+    // text sits beyond the dimension line, away from the part
+    let (text_x, text_y) = ((from_x + to_x) / 2.0, (from_y + to_y) / 2.0);
+    if dimension.offset.y != 0.0 {
+        context.set_text_baseline(if dimension.offset.y < 0.0 {
+            "bottom"
+        } else {
+            "top"
+        });
+        context
+            .fill_text(
+                &dimension.text,
+                text_x,
+                text_y + dimension.offset.y.signum() * 0.03 * ppi,
+            )
+            .unwrap();
+    } else {
+        context.set_text_baseline("middle");
+        context.set_text_align(if dimension.offset.x < 0.0 {
+            "right"
+        } else {
+            "left"
+        });
+        context
+            .fill_text(
+                &dimension.text,
+                text_x + dimension.offset.x.signum() * 0.05 * ppi,
+                text_y,
+            )
+            .unwrap();
+        context.set_text_align("center");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn side_view_outlines_the_flange_and_hub() {
+        let hub = Hub {
+            diameter: 1.0,
+            length: 0.75,
+            flange_thickness: 0.25,
+            ..Hub::default()
+        };
+        let view = side_view(4.0, &hub).unwrap();
+        let first = view.outline.first().unwrap();
+        let last = view.outline.last().unwrap();
+        assert_eq!((first.x, first.y), (last.x, last.y));
+        let widest = view.outline.iter().map(|pt| pt.x.abs()).fold(0.0, f64::max);
+        let deepest = view.outline.iter().map(|pt| pt.y).fold(0.0, f64::max);
+        assert_eq!((widest, deepest), (2.0, 0.75));
+        assert!(view.half_width > 2.0 + DIMENSION_OFFSET);
+        let texts: Vec<&str> = view.dimensions.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            ["4.000 in dia", "0.250 in", "1.000 in dia", "0.750 in"]
+        );
+
+        // a hub flush with the flange is just the plate
+        let flush = Hub {
+            length: 0.25,
+            ..hub
+        };
+        let view = side_view(4.0, &flush).unwrap();
+        assert_eq!(view.outline.len(), 5);
+        assert_eq!(view.dimensions.len(), 2);
+        assert!(side_view(4.0, &Hub::default()).is_none());
+    }
+
+    #[test]
+    fn recommended_hubs_grow_with_the_torque() {
+        let hub = Hub {
+            bore: 0.5,
+            key_width: 0.125,
+            ..Hub::default()
+        };
+        // 0.9 in across and 0.625 in long, rounded up to sixteenths
+        let light = hub.recommendation().unwrap();
+        assert_eq!(light.diameter, 0.9375);
+        assert_eq!(light.length, 0.625);
+        // 500 lb-in puts 2000 lb on the key, which shears or crushes it under
+        // 1.6 in
+        let heavy = Hub {
+            torque: 500.0,
+            ..hub
+        };
+        assert_eq!(heavy.recommendation().unwrap().length, 1.625);
+        assert_eq!(heavy.recommendation().unwrap().diameter, light.diameter);
+        assert!(Hub::default().recommendation().is_none());
+    }
+}
@@ -0,0 +1,235 @@
+// working out the pitch and pressure angle of an existing gear from what
+// can be measured on it: its outside diameter and tooth count, and
+// optionally a measurement over two pins laid in opposite tooth spaces.
+// teeth are taken as standard, full depth with no profile shift, so a worn
+// or shifted gear can land on a near miss.
+
+// common inch diametric pitches
+const DIAMETRIC_PITCHES: [f64; 22] = [
+    2.0, 2.5, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0, 12.0, 14.0, 16.0, 18.0, 20.0, 24.0, 32.0, 40.0, 48.0,
+    64.0, 72.0, 80.0, 96.0, 120.0,
+];
+// common metric modules, in millimeters
+const MODULES: [f64; 20] = [
+    0.3, 0.4, 0.5, 0.6, 0.7, 0.75, 0.8, 1.0, 1.25, 1.5, 2.0, 2.5, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0,
+    12.0, 16.0,
+];
+// pressure angles gears are commonly cut at, in degrees
+const PRESSURE_ANGLES: [f64; 3] = [14.5, 20.0, 25.0];
+// the pressure angle taken when there's no pin measurement to go on
+pub const ASSUMED_PRESSURE_ANGLE: f64 = 20.0;
+// pins this many over the diametric pitch touch the flanks near the pitch
+// circle
+pub const PIN_DIAMETER_CONSTANT: f64 = 1.728;
+const MM_PER_INCH: f64 = 25.4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pitch {
+    Diametric(f64),
+    // in millimeters
+    Module(f64),
+}
+
+impl Pitch {
+    pub fn diametric_pitch(&self) -> f64 {
+        match self {
+            Pitch::Diametric(pitch) => *pitch,
+            Pitch::Module(module) => MM_PER_INCH / module,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Pitch::Diametric(pitch) => format!("{} DP", pitch),
+            Pitch::Module(module) => format!("module {}", module),
+        }
+    }
+}
+
+// a measurement over two pins, in inches
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pins {
+    pub measurement: f64,
+    pub diameter: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Identified {
+    pub pitch: Pitch,
+    // the diametric pitch the outside diameter works out to before it's
+    // matched to a standard one
+    pub measured_pitch: f64,
+    // `None` without pins to tell the angles apart
+    pub pressure_angle: Option<f64>,
+    // what the outside and pin measurements should be for a new gear of
+    // this pitch and angle, in inches
+    pub outside_diameter: f64,
+    pub over_pins: Option<f64>,
+}
+
+impl Identified {
+    pub fn pressure_angle_or_assumed(&self) -> f64 {
+        self.pressure_angle.unwrap_or(ASSUMED_PRESSURE_ANGLE)
+    }
+}
+
+// the standard pitch closest to an `outside_diameter` inch gear of `teeth`,
+// and the pressure angle whose pin measurement comes closest if there is
+// one. `None` for measurements no gear could have.
+pub fn identify(outside_diameter: f64, teeth: u32, pins: Option<Pins>) -> Option<Identified> {
+    if outside_diameter <= 0.0 || teeth < 3 {
+        return None;
+    }
+    // a full depth tooth's tip is one addendum, a module, outside the pitch
+    // circle on each side
+    let measured_pitch = (teeth as f64 + 2.0) / outside_diameter;
+    let candidates = DIAMETRIC_PITCHES
+        .iter()
+        .map(|&pitch| Pitch::Diametric(pitch))
+        .chain(MODULES.iter().map(|&module| Pitch::Module(module)));
+    let pitch = candidates.min_by(|a, b| {
+        let miss = |pitch: &Pitch| (pitch.diametric_pitch() - measured_pitch).abs();
+        miss(a).total_cmp(&miss(b))
+    })?;
+    let diametric_pitch = pitch.diametric_pitch();
+
+    let pressure_angle = match pins {
+        Some(pins) if pins.measurement > 0.0 && pins.diameter > 0.0 => {
+            PRESSURE_ANGLES.iter().copied().min_by(|&a, &b| {
+                let miss = |angle: f64| {
+                    over_pins(teeth, diametric_pitch, angle, pins.diameter)
+                        .map_or(f64::INFINITY, |expected| {
+                            (expected - pins.measurement).abs()
+                        })
+                };
+                miss(a).total_cmp(&miss(b))
+            })
+        }
+        _ => None,
+    };
+    Some(Identified {
+        pitch,
+        measured_pitch,
+        pressure_angle,
+        outside_diameter: (teeth as f64 + 2.0) / diametric_pitch,
+        over_pins: pins
+            .zip(pressure_angle)
+            .and_then(|(pins, angle)| over_pins(teeth, diametric_pitch, angle, pins.diameter)),
+    })
+}
+
+// the measurement over two `pin_diameter` pins for a standard gear, with
+// the pins in opposite spaces, or as near opposite as an odd count allows.
+// `None` if the pins are too small to reach the flanks.
+pub fn over_pins(
+    teeth: u32,
+    diametric_pitch: f64,
+    pressure_angle: f64,
+    pin_diameter: f64,
+) -> Option<f64> {
+    let tooth_thickness = std::f64::consts::PI / (2.0 * diametric_pitch);
+    over_pins_with_thickness(
+        teeth,
+        diametric_pitch,
+        pressure_angle,
+        pin_diameter,
+        tooth_thickness,
+    )
+}
+
+// the same for teeth `tooth_thickness` inches thick at the pitch circle,
+// like a gear thinned for backlash
+pub fn over_pins_with_thickness(
+    teeth: u32,
+    diametric_pitch: f64,
+    pressure_angle: f64,
+    pin_diameter: f64,
+    tooth_thickness: f64,
+) -> Option<f64> {
+    let teeth = teeth as f64;
+    let pitch_diameter = teeth / diametric_pitch;
+    let base_diameter = pitch_diameter * pressure_angle.to_radians().cos();
+    // a pin touches both flanks of its space where their involutes are a
+    // pin's width apart along the base circle
+    let pin_involute = tooth_thickness / pitch_diameter
+        + involute_function(pressure_angle.to_radians())
+        + pin_diameter / base_diameter
+        - std::f64::consts::PI / teeth;
+    if pin_involute <= 0.0 {
+        return None;
+    }
+    let pin_circle = base_diameter / inverse_involute_function(pin_involute).cos();
+    let span = if (teeth as u32).is_multiple_of(2) {
+        pin_circle
+    } else {
+        pin_circle * (std::f64::consts::PI / (2.0 * teeth)).cos()
+    };
+    Some(span + pin_diameter)
+}
+
+// tan(a) - a, the angle an involute has turned through at pressure angle `a`
+pub fn involute_function(angle: f64) -> f64 {
+    angle.tan() - angle
+}
+
+// the pressure angle, in radians, where the involute function is `value`
+fn inverse_involute_function(value: f64) -> f64 {
+    // newton's method from the small angle approximation inv(a) ~ a^3 / 3
+    let mut angle = (3.0 * value).cbrt().min(1.4);
+    for _ in 0..50 {
+        let step = (involute_function(angle) - value) / angle.tan().powi(2);
+        angle -= step;
+        if step.abs() < 1e-14 {
+            break;
+        }
+    }
+    angle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_gears_are_recognized() {
+        // a slightly worn 30 tooth 12 DP gear
+        let found = identify(2.66, 30, None).unwrap();
+        assert_eq!(found.pitch, Pitch::Diametric(12.0));
+        assert_eq!(found.pressure_angle, None);
+        assert!((found.outside_diameter - 32.0 / 12.0).abs() < 1e-12);
+
+        // a module 2 gear of 20 teeth is 44 mm across
+        let found = identify(44.0 / 25.4, 20, None).unwrap();
+        assert_eq!(found.pitch, Pitch::Module(2.0));
+
+        assert_eq!(identify(0.0, 20, None), None);
+    }
+
+    #[test]
+    fn pins_tell_the_pressure_angle() {
+        for teeth in [24, 25] {
+            for angle in PRESSURE_ANGLES {
+                let diameter = PIN_DIAMETER_CONSTANT / 10.0;
+                let measurement = over_pins(teeth, 10.0, angle, diameter).unwrap();
+                let pins = Pins {
+                    measurement,
+                    diameter,
+                };
+                let found = identify((teeth as f64 + 2.0) / 10.0, teeth, Some(pins)).unwrap();
+                assert_eq!(found.pressure_angle, Some(angle));
+                assert_eq!(found.over_pins, Some(measurement));
+            }
+        }
+        // the published figure for 1 DP, 20 degrees and 24 teeth
+        let measurement = over_pins(24, 1.0, 20.0, PIN_DIAMETER_CONSTANT).unwrap();
+        assert!((measurement - 26.3997).abs() < 1e-4);
+    }
+
+    #[test]
+    fn inverse_involute_round_trips() {
+        for degrees in [5.0, 14.5, 20.0, 30.0, 45.0] {
+            let angle = f64::to_radians(degrees);
+            assert!((inverse_involute_function(involute_function(angle)) - angle).abs() < 1e-10);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::indexing::{self, Indexing};
+use std::f64;
+
+// the plate's outer circle, leaves room on the short side of the page for
+// the numbers and on the left for the indexing chart
+const PLATE_RADIUS_INCHES: f64 = 3.0;
+const TICK_INCHES: f64 = 0.2;
+// every fifth tooth gets a longer, numbered tick
+const MAJOR_TICK_INCHES: f64 = 0.4;
+
+// a printable dividing plate for `teeth` teeth on the right of the page. one
+// tick per tooth starting from the top and going clockwise, numbered every
+// five, with a center mark to line it up on a rotary table. the left of the
+// page charts the crank moves for common dividing heads.
+pub fn draw_index_plate(
+    context: &web_sys::CanvasRenderingContext2d,
+    width: u32,
+    height: u32,
+    ppi: u32,
+    teeth: u32,
+) {
+    let scale = ppi as f64;
+    let center = (
+        width as f64 - (PLATE_RADIUS_INCHES + 0.5) * scale,
+        height as f64 / 2.0,
+    );
+    let radius = PLATE_RADIUS_INCHES * scale;
+
+    context.set_fill_style_str("white");
+    context.fill_rect(0.0, 0.0, width as f64, height as f64);
+    context.set_stroke_style_str("black");
+    context.set_fill_style_str("black");
+    context.set_line_width(scale / 100.0);
+
+    // outer circle and center cross
+    context.begin_path();
+    context
+        .arc(center.0, center.1, radius, 0.0, 2.0 * f64::consts::PI)
+        .unwrap();
+    let cross = 0.25 * scale;
+    context.move_to(center.0 - cross, center.1);
+    context.line_to(center.0 + cross, center.1);
+    context.move_to(center.0, center.1 - cross);
+    context.line_to(center.0, center.1 + cross);
+    context.stroke();
+
+    // ticks, canvas y points down so increasing angles run clockwise
+    context.set_font(&format!("{}px sans-serif", (0.18 * scale) as u32));
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    (0..teeth).for_each(|tooth| {
+        let angle = tooth as f64 / teeth as f64 * 2.0 * f64::consts::PI - f64::consts::FRAC_PI_2;
+        let (sin, cos) = angle.sin_cos();
+        let major = tooth % 5 == 0;
+        let tick_inches = if major {
+            MAJOR_TICK_INCHES
+        } else {
+            TICK_INCHES
+        };
+        let length = tick_inches * scale;
+        context.begin_path();
+        context.move_to(center.0 + radius * cos, center.1 + radius * sin);
+        context.line_to(
+            center.0 + (radius - length) * cos,
+            center.1 + (radius - length) * sin,
+        );
+        context.stroke();
+        if major {
+            let label_radius = radius + 0.2 * scale;
+            context
+                .fill_text(
+                    &tooth.to_string(),
+                    center.0 + label_radius * cos,
+                    center.1 + label_radius * sin,
+                )
+                .unwrap();
+        }
+    });
+
+    // what the plate is for, under the center mark
+    let caption = format!(
+        "{} teeth, {:.3}\u{b0} per tooth",
+        teeth,
+        360.0 / teeth as f64
+    );
+    context
+        .fill_text(&caption, center.0, center.1 + 0.6 * scale)
+        .unwrap();
+
+    draw_indexing_chart(context, scale, teeth);
+}
+
+// crank moves per tooth for each common head ratio, in the top left corner
+fn draw_indexing_chart(context: &web_sys::CanvasRenderingContext2d, scale: f64, teeth: u32) {
+    let line_height = 0.3 * scale;
+    let mut lines = vec![
+        format!("Indexing for {} teeth", teeth),
+        "Brown & Sharpe plates".to_string(),
+    ];
+    lines.extend(indexing::HEAD_RATIOS.iter().map(|&ratio| {
+        let moves = match indexing::simple_indexing(ratio, teeth) {
+            Indexing::Plate {
+                turns,
+                holes,
+                plate,
+                circle,
+            } => format!(
+                "{} turns + {} holes on {} (plate {})",
+                turns, holes, circle, plate
+            ),
+            Indexing::WholeTurns(turns) => format!("{} turns", turns),
+            Indexing::Unavailable => "differential indexing".to_string(),
+        };
+        format!("{}:1  {}", ratio, moves)
+    }));
+
+    context.set_font(&format!("{}px sans-serif", (0.16 * scale) as u32));
+    context.set_text_align("left");
+    context.set_text_baseline("top");
+    lines.iter().enumerate().for_each(|(i, line)| {
+        context
+            .fill_text(line, 0.3 * scale, 0.5 * scale + i as f64 * line_height)
+            .unwrap();
+    });
+}
@@ -0,0 +1,91 @@
+// worm ratios of common dividing heads and rotary tables
+pub const HEAD_RATIOS: [u32; 4] = [40, 60, 72, 90];
+
+// hole circles on the three brown & sharpe index plates
+const PLATES: [(u32, [u32; 6]); 3] = [
+    (1, [15, 16, 17, 18, 19, 20]),
+    (2, [21, 23, 27, 29, 31, 33]),
+    (3, [37, 39, 41, 43, 47, 49]),
+];
+
+// how to move the crank between teeth by simple indexing
+#[derive(Debug, PartialEq)]
+pub enum Indexing {
+    // whole turns of the crank, plus `holes` holes on `circle` of `plate`
+    Plate {
+        turns: u32,
+        holes: u32,
+        plate: u32,
+        circle: u32,
+    },
+    // the division comes out in whole turns, no plate needed
+    WholeTurns(u32),
+    // no hole circle divides it, it needs differential indexing
+    Unavailable,
+}
+
+// crank movement per tooth on a head with a `ratio`:1 worm. picks the
+// smallest hole circle that divides the leftover fraction of a turn.
+pub fn simple_indexing(ratio: u32, teeth: u32) -> Indexing {
+    if teeth == 0 {
+        return Indexing::Unavailable;
+    }
+    let turns = ratio / teeth;
+    let divisor = gcd(ratio % teeth, teeth);
+    let (numerator, denominator) = ((ratio % teeth) / divisor, teeth / divisor);
+    if numerator == 0 {
+        return Indexing::WholeTurns(turns);
+    }
+    let mut circles: Vec<(u32, u32)> = PLATES
+        .iter()
+        .flat_map(|(plate, circles)| circles.iter().map(move |&circle| (*plate, circle)))
+        .filter(|(_, circle)| circle % denominator == 0)
+        .collect();
+    circles.sort_by_key(|&(_, circle)| circle);
+    match circles.first() {
+        Some(&(plate, circle)) => Indexing::Plate {
+            turns,
+            holes: numerator * circle / denominator,
+            plate,
+            circle,
+        },
+        None => Indexing::Unavailable,
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forty_teeth_on_a_forty_to_one_head_is_one_turn() {
+        assert_eq!(simple_indexing(40, 40), Indexing::WholeTurns(1));
+    }
+
+    #[test]
+    fn fractions_land_on_the_smallest_circle_that_divides_them() {
+        // 40 / 50 = 4/5 of a turn, 12 holes on the 15 hole circle
+        assert_eq!(
+            simple_indexing(40, 50),
+            Indexing::Plate {
+                turns: 0,
+                holes: 12,
+                plate: 1,
+                circle: 15,
+            }
+        );
+    }
+
+    #[test]
+    fn primes_beyond_the_plates_need_differential_indexing() {
+        assert_eq!(simple_indexing(40, 53), Indexing::Unavailable);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::geometry::{GearGeometry, GearSpecs, ToothProfile};
+use crate::identify::{self, PIN_DIAMETER_CONSTANT};
+use std::f64::consts::PI;
+
+// the sheet's text sizes and row height, in inches
+const TITLE_SIZE: f64 = 0.25;
+const TEXT_SIZE: f64 = 0.15;
+const ROW_HEIGHT: f64 = 0.4;
+// where each column of the table starts, in inches from the left margin.
+// the last column is left empty to write the measurement in.
+const COLUMNS: [(&str, f64); 5] = [
+    ("Measurement", 0.0),
+    ("Nominal (in)", 3.6),
+    ("Min (in)", 5.0),
+    ("Max (in)", 6.4),
+    ("Measured", 7.8),
+];
+const TABLE_WIDTH: f64 = 10.0;
+
+// something to check on the bench, in inches
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub name: String,
+    pub nominal: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Measurement {
+    fn within(name: String, nominal: f64, tolerance: f64) -> Self {
+        Self {
+            name,
+            nominal,
+            min: nominal - tolerance,
+            max: nominal + tolerance,
+        }
+    }
+}
+
+// a gear's measurements for the qc bench, and how they were worked out
+pub struct Inspection {
+    pub measurements: Vec<Measurement>,
+    pub notes: Vec<String>,
+}
+
+// what to check on a gear made to `spec`, with every flank held within
+// `tolerance` inches of the true profile. tooth thicknesses are the gear's
+// thinned for its share of the backlash, so they're what the part should
+// measure, not the textbook tooth.
+pub fn inspect(spec: &GearSpecs, tolerance: f64) -> Inspection {
+    let geometry = GearGeometry::new(spec);
+    let outside_diameter = 2.0 * geometry.outer_radius;
+    let mut measurements = vec![Measurement::within(
+        "Outside diameter".to_string(),
+        outside_diameter,
+        2.0 * tolerance,
+    )];
+    let mut notes = Vec::new();
+    let teeth = spec.teeth.round() as u32;
+    if spec.profile != ToothProfile::Involute || spec.helix_angle != 0.0 || teeth < 3 {
+        notes.push(
+            "Tooth thickness, pins and span are only worked out for involute spur gears."
+                .to_string(),
+        );
+        return Inspection {
+            measurements,
+            notes,
+        };
+    }
+
+    let pitch = spec.diametric_pitch;
+    let angle = spec.tooth_angle.to_radians();
+    let pitch_diameter = 2.0 * geometry.pitch_radius;
+    let thickness = PI / (2.0 * pitch) - geometry.backlash_allowance;
+    // moving both flanks `tolerance` along their normals changes the
+    // thickness at the pitch circle by this much
+    let thickness_tolerance = 2.0 * tolerance / angle.cos();
+
+    // what a gear tooth caliper reads, set to the chordal addendum
+    let half_angle = thickness / pitch_diameter;
+    let chordal_addendum = 1.0 / pitch + pitch_diameter / 2.0 * (1.0 - half_angle.cos());
+    measurements.push(Measurement::within(
+        "Chordal tooth thickness".to_string(),
+        pitch_diameter * half_angle.sin(),
+        thickness_tolerance,
+    ));
+    notes.push(format!(
+        "Set the tooth caliper's depth to the chordal addendum, {:.4} in.",
+        chordal_addendum
+    ));
+
+    let pin_diameter = PIN_DIAMETER_CONSTANT / pitch;
+    let over_pins = |thickness: f64| {
+        identify::over_pins_with_thickness(teeth, pitch, spec.tooth_angle, pin_diameter, thickness)
+    };
+    if let (Some(nominal), Some(min), Some(max)) = (
+        over_pins(thickness),
+        over_pins(thickness - thickness_tolerance),
+        over_pins(thickness + thickness_tolerance),
+    ) {
+        measurements.push(Measurement {
+            name: "Measurement over pins".to_string(),
+            nominal,
+            min,
+            max,
+        });
+        let placement = if teeth.is_multiple_of(2) {
+            "opposite spaces"
+        } else {
+            "spaces as near opposite as the odd tooth count allows"
+        };
+        notes.push(format!(
+            "Pins are {:.4} in across, in {}.",
+            pin_diameter, placement
+        ));
+    }
+
+    // a span micrometer across `spanned` teeth touches their outer flanks
+    // near the pitch circle, and reads along the flanks' normal
+    let spanned =
+        ((teeth as f64 * spec.tooth_angle / 180.0 + 0.5).round() as u32).clamp(1, teeth - 1);
+    let base_pitch = PI * angle.cos() / pitch;
+    let base_thickness = pitch_diameter
+        * angle.cos()
+        * (thickness / pitch_diameter + identify::involute_function(angle));
+    measurements.push(Measurement::within(
+        format!("Span over {} teeth", spanned),
+        (spanned - 1) as f64 * base_pitch + base_thickness,
+        2.0 * tolerance,
+    ));
+
+    // runout moves the teeth in and out of mesh. this much uses up no more
+    // than half the pair's backlash at the worst point of a turn.
+    let backlash = spec.backlash_mult / pitch;
+    measurements.push(Measurement {
+        name: "Radial runout (TIR)".to_string(),
+        nominal: 0.0,
+        min: 0.0,
+        max: backlash / (2.0 * angle.tan()),
+    });
+    notes.push(
+        "Runout is read over a pin or ball in the tooth spaces, turning the gear on its bore."
+            .to_string(),
+    );
+
+    Inspection {
+        measurements,
+        notes,
+    }
+}
+
+// a page for the bench: the title, the table with an empty column to write
+// in, and the notes underneath
+pub fn draw(
+    context: &web_sys::CanvasRenderingContext2d,
+    width: u32,
+    height: u32,
+    ppi: u32,
+    title: &str,
+    inspection: &Inspection,
+) {
+    let scale = ppi as f64;
+    context.set_fill_style_str("white");
+    context.fill_rect(0.0, 0.0, width as f64, height as f64);
+    context.set_fill_style_str("black");
+    context.set_stroke_style_str("black");
+    context.set_line_width(scale / 100.0);
+    context.set_text_align("left");
+    context.set_text_baseline("middle");
+
+    let left = 0.4 * scale;
+    let mut y = 0.6 * scale;
+    context.set_font(&format!("bold {}px sans-serif", TITLE_SIZE * scale));
+    context.fill_text(title, left, y).unwrap();
+    y += 0.6 * scale;
+
+    let row_height = ROW_HEIGHT * scale;
+    let rule = |y: f64| {
+        context.begin_path();
+        context.move_to(left, y);
+        context.line_to(left + TABLE_WIDTH * scale, y);
+        context.stroke();
+    };
+    context.set_font(&format!("bold {}px sans-serif", TEXT_SIZE * scale));
+    for (heading, x) in COLUMNS {
+        context
+            .fill_text(heading, left + (x + 0.05) * scale, y + row_height / 2.0)
+            .unwrap();
+    }
+    rule(y);
+    y += row_height;
+    rule(y);
+    context.set_font(&format!("{}px sans-serif", TEXT_SIZE * scale));
+    for measurement in &inspection.measurements {
+        let cells = [
+            measurement.name.clone(),
+            format!("{:.4}", measurement.nominal),
+            format!("{:.4}", measurement.min),
+            format!("{:.4}", measurement.max),
+        ];
+        for (text, (_, x)) in cells.iter().zip(COLUMNS) {
+            context
+                .fill_text(text, left + (x + 0.05) * scale, y + row_height / 2.0)
+                .unwrap();
+        }
+        y += row_height;
+        rule(y);
+    }
+    // the columns' dividers, down to the last row
+    let top = y - row_height * (inspection.measurements.len() + 1) as f64;
+    let dividers = COLUMNS
+        .iter()
+        .skip(1)
+        .map(|(_, x)| x - 0.1)
+        .chain([0.0, TABLE_WIDTH]);
+    for x in dividers {
+        context.begin_path();
+        context.move_to(left + x * scale, top);
+        context.line_to(left + x * scale, y);
+        context.stroke();
+    }
+
+    y += 0.5 * scale;
+    for note in &inspection.notes {
+        context.fill_text(note, left, y).unwrap();
+        y += 0.3 * scale;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Thinning;
+
+    fn spec(backlash_mult: f64) -> GearSpecs {
+        GearSpecs {
+            teeth: 20.0,
+            diametric_pitch: 10.0,
+            tooth_angle: 20.0,
+            clearance_mult: 0.25,
+            backlash_mult,
+            involute_steps: 20,
+            profile: ToothProfile::Involute,
+            helix_angle: 0.0,
+            thinning: Thinning::Half,
+        }
+    }
+
+    #[test]
+    fn full_teeth_match_the_handbook() {
+        let inspection = inspect(&spec(0.0), 0.001);
+        let [outside, chordal, pins, span, runout] = &inspection.measurements[..] else {
+            panic!("expected five measurements");
+        };
+        assert!((outside.nominal - 2.2).abs() < 1e-12);
+        assert!((outside.max - 2.202).abs() < 1e-12);
+        // 20 teeth at 10 dp: a chordal thickness of 0.1569 in
+        assert!((chordal.nominal - 0.15692).abs() < 1e-5);
+        assert!(
+            (pins.nominal - identify::over_pins(20, 10.0, 20.0, 0.1728).unwrap()).abs() < 1e-12
+        );
+        assert!(pins.min < pins.nominal && pins.nominal < pins.max);
+        // the usual m cos(a) (pi (k - 0.5) + z inv(a)) over 3 teeth
+        assert_eq!(span.name, "Span over 3 teeth");
+        assert!((span.nominal - 0.76604).abs() < 1e-5);
+        assert_eq!(runout.max, 0.0);
+    }
+
+    #[test]
+    fn backlash_thins_the_teeth() {
+        let full = inspect(&spec(0.0), 0.0);
+        let thinned = inspect(&spec(0.1), 0.0);
+        for row in 1..4 {
+            assert!(thinned.measurements[row].nominal < full.measurements[row].nominal);
+        }
+        assert!(thinned.measurements[4].max > 0.0);
+
+        let star = GearSpecs {
+            profile: ToothProfile::Star { notch: 0.2 },
+            ..spec(0.0)
+        };
+        assert_eq!(inspect(&star, 0.0).measurements.len(), 1);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::geometry::Point;
+
+const MM: f64 = 1.0 / 25.4;
+
+// each slot and pin is drawn this much over or under the nominal size, in mm
+const OFFSET_STEP_MM: f64 = 0.05;
+// steps either side of nominal
+const STEPS: i32 = 4;
+
+// how deep the slots are cut and how far the pins stand, in mm
+const FEATURE_DEPTH_MM: f64 = 10.0;
+// solid material around the features, in mm
+const MARGIN_MM: f64 = 4.0;
+const LABEL_HEIGHT_MM: f64 = 2.0;
+
+// text to engrave, in inches, centered on `position`
+pub struct Label {
+    pub position: Point,
+    pub height: f64,
+    pub text: String,
+}
+
+// two pieces to cut: a card with a row of slots in its top edge, and a comb
+// of pins below it. the slots and pins step from a little under to a little
+// over the nominal size, each labelled with its offset. the laser widens
+// slots and narrows pins by its kerf, so a pin drawn `a` over nominal fits
+// snugly in a slot drawn `b` over when the kerf is (a - b) / 2.
+pub struct KerfTest {
+    // closed outlines in inches, the last point repeating the first
+    pub outlines: Vec<Vec<Point>>,
+    pub labels: Vec<Label>,
+}
+
+pub fn kerf_test(nominal_mm: f64) -> KerfTest {
+    let offsets: Vec<f64> = (-STEPS..=STEPS)
+        .map(|step| step as f64 * OFFSET_STEP_MM)
+        .collect();
+    // wide enough for the largest feature and its label between neighbours
+    let pitch = nominal_mm + OFFSET_STEP_MM * STEPS as f64 + 2.0 * MARGIN_MM;
+    let width = offsets.len() as f64 * pitch + 2.0 * MARGIN_MM;
+    let centers: Vec<f64> = (0..offsets.len())
+        .map(|i| MARGIN_MM + pitch * (i as f64 + 0.5))
+        .collect();
+    let card_height = FEATURE_DEPTH_MM + 2.0 * MARGIN_MM + LABEL_HEIGHT_MM;
+    let comb_base = MARGIN_MM + LABEL_HEIGHT_MM;
+    // the comb sits below the card with a margin between them
+    let comb_bottom = -(MARGIN_MM + comb_base + FEATURE_DEPTH_MM);
+
+    let point = |x: f64, y: f64| Point {
+        x: x * MM,
+        y: y * MM,
+    };
+
+    // the card, cutting each slot down from the top edge right to left
+    let mut card = vec![
+        point(0.0, 0.0),
+        point(width, 0.0),
+        point(width, card_height),
+    ];
+    for (center, offset) in centers.iter().zip(&offsets).rev() {
+        let half = (nominal_mm + offset) / 2.0;
+        let bottom = card_height - FEATURE_DEPTH_MM;
+        card.extend([
+            point(center + half, card_height),
+            point(center + half, bottom),
+            point(center - half, bottom),
+            point(center - half, card_height),
+        ]);
+    }
+    card.extend([point(0.0, card_height), point(0.0, 0.0)]);
+
+    // the comb, raising each pin from its base right to left
+    let comb_top = comb_bottom + comb_base;
+    let mut comb = vec![
+        point(0.0, comb_bottom),
+        point(width, comb_bottom),
+        point(width, comb_top),
+    ];
+    for (center, offset) in centers.iter().zip(&offsets).rev() {
+        let half = (nominal_mm + offset) / 2.0;
+        comb.extend([
+            point(center + half, comb_top),
+            point(center + half, comb_top + FEATURE_DEPTH_MM),
+            point(center - half, comb_top + FEATURE_DEPTH_MM),
+            point(center - half, comb_top),
+        ]);
+    }
+    comb.extend([point(0.0, comb_top), point(0.0, comb_bottom)]);
+
+    let label_y = [
+        MARGIN_MM + LABEL_HEIGHT_MM / 2.0,
+        comb_bottom + MARGIN_MM / 2.0 + LABEL_HEIGHT_MM / 2.0,
+    ];
+    let labels = label_y
+        .iter()
+        .flat_map(|&y| {
+            centers
+                .iter()
+                .zip(&offsets)
+                .map(move |(&center, &offset)| Label {
+                    position: point(center, y),
+                    height: LABEL_HEIGHT_MM * MM,
+                    text: format!("{:+.2}", offset),
+                })
+        })
+        .collect();
+
+    KerfTest {
+        outlines: vec![card, comb],
+        labels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_and_pins_step_around_nominal() {
+        let test = kerf_test(3.0);
+        assert_eq!(test.outlines.len(), 2);
+        for outline in &test.outlines {
+            assert_eq!(
+                outline.first().map(|pt| (pt.x, pt.y)),
+                outline.last().map(|pt| (pt.x, pt.y))
+            );
+            // every feature is a pair of horizontal edges across its width
+            let widths: Vec<f64> = outline
+                .windows(2)
+                .filter(|pair| pair[0].y == pair[1].y && (pair[0].x - pair[1].x).abs() < 4.0 * MM)
+                .map(|pair| ((pair[0].x - pair[1].x).abs() / MM * 100.0).round() / 100.0)
+                .collect();
+            assert_eq!(
+                widths,
+                vec![3.2, 3.15, 3.1, 3.05, 3.0, 2.95, 2.9, 2.85, 2.8]
+            );
+        }
+        assert_eq!(test.labels.len(), 18);
+        assert_eq!(test.labels[0].text, "-0.20");
+        assert_eq!(test.labels[4].text, "+0.00");
+    }
+}
@@ -0,0 +1,170 @@
+use crate::preview::Preview;
+use crate::svg::SvgRenderer;
+use crate::webgl::WebGlRenderer;
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+
+// a canvas and its 2d drawing context
+pub struct Layer {
+    pub canvas: web_sys::HtmlCanvasElement,
+    pub context: web_sys::CanvasRenderingContext2d,
+}
+
+impl Layer {
+    // a canvas that isn't attached to the page, used for exports
+    pub fn offscreen() -> Result<Self, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let canvas = document
+            .create_element("canvas")?
+            .dyn_into::<web_sys::HtmlCanvasElement>()?;
+        let context = canvas
+            .get_context("2d")?
+            .unwrap()
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+        Ok(Self { canvas, context })
+    }
+
+    fn stacked(container: &web_sys::Element) -> Result<Self, JsValue> {
+        Self::stacked_before(container, None)
+    }
+
+    // a layer slotted in under `before`, or on top without one
+    fn stacked_before(
+        container: &web_sys::Element,
+        before: Option<&web_sys::Node>,
+    ) -> Result<Self, JsValue> {
+        let layer = Self::offscreen()?;
+        layer
+            .canvas
+            .set_attribute("style", "position: absolute; left: 0; top: 0;")?;
+        container.insert_before(&layer.canvas, before)?;
+        Ok(layer)
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        self.canvas.set_width(width);
+        self.canvas.set_height(height);
+    }
+
+    // wipes the layer and resets any transform left by the last draw
+    pub fn clear(&self) {
+        self.context
+            .set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+            .unwrap();
+        self.context.clear_rect(
+            0.0,
+            0.0,
+            self.canvas.width() as f64,
+            self.canvas.height() as f64,
+        );
+    }
+}
+
+// stacked on-screen canvases, bottom to top. splitting them up means the
+// static grid and the gears don't need re-stroking when only an overlay changes.
+pub struct CanvasLayers {
+    container: web_sys::Element,
+    pub background: Layer,
+    // one layer per gear so an edit only re-strokes the gear that changed
+    pub gears: RefCell<Vec<Layer>>,
+    // optional gpu path for the gears, `None` without webgl2 support
+    pub webgl: Option<WebGlRenderer>,
+    // vector dom path for the whole scene
+    pub svg: SvgRenderer,
+    pub overlay: Layer,
+    // animated involute construction, redrawn every frame while it's shown
+    pub construction: Layer,
+    // rulers along the edges, on top of everything and left out of prints
+    pub rulers: Layer,
+    // the gears as solids, covering the rest while it's shown. `None`
+    // without webgl2 support
+    pub preview: Option<Preview>,
+}
+
+impl CanvasLayers {
+    // creates the layers inside a container placed `left` pixels in, right
+    // of the sidebar
+    pub fn new(
+        document: &web_sys::Document,
+        gear_count: usize,
+        left: u32,
+    ) -> Result<Self, JsValue> {
+        let container = document.create_element("div")?;
+        container.set_attribute("id", "canvas_layers")?;
+        container.set_attribute(
+            "style",
+            &format!("position: absolute; left: {}px; top: 0;", left),
+        )?;
+        document.body().unwrap().append_child(&container)?;
+
+        let background = Layer::stacked(&container)?;
+        let gears = (0..gear_count)
+            .map(|_| Layer::stacked(&container))
+            .collect::<Result<_, _>>()?;
+        let overlay = Layer::stacked(&container)?;
+        let construction = Layer::stacked(&container)?;
+        let rulers = Layer::stacked(&container)?;
+        rulers.canvas.set_attribute("id", "rulers")?;
+        let webgl = WebGlRenderer::new(&container, &overlay.canvas)?;
+        let svg = SvgRenderer::new(&container, &overlay.canvas)?;
+        let preview = Preview::new(&container)?;
+
+        Ok(Self {
+            container,
+            background,
+            gears: RefCell::new(gears),
+            webgl,
+            svg,
+            overlay,
+            construction,
+            rulers,
+            preview,
+        })
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        self.background.resize(width, height);
+        self.gears
+            .borrow()
+            .iter()
+            .for_each(|layer| layer.resize(width, height));
+        if let Some(webgl) = &self.webgl {
+            webgl.resize(width, height);
+        }
+        self.overlay.resize(width, height);
+        self.construction.resize(width, height);
+        self.rulers.resize(width, height);
+        if let Some(preview) = &self.preview {
+            preview.resize(width, height);
+        }
+    }
+
+    // adds or removes gear layers to match the train, new layers go on top
+    // of the existing gears but under the webgl, svg and overlay layers
+    pub fn set_gear_count(&self, count: usize) {
+        let mut gears = self.gears.borrow_mut();
+        while gears.len() < count {
+            let before = gears
+                .last()
+                .map(|layer| layer.canvas.next_sibling())
+                .unwrap_or_else(|| self.background.canvas.next_sibling());
+            let layer = Layer::stacked_before(&self.container, before.as_ref()).unwrap();
+            layer.resize(self.width(), self.height());
+            gears.push(layer);
+        }
+        let keep = count.min(gears.len());
+        gears.drain(keep..).for_each(|layer| layer.canvas.remove());
+        if let Some(webgl) = &self.webgl {
+            webgl.truncate(count);
+        }
+        self.svg.truncate(count);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.background.canvas.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.background.canvas.height()
+    }
+}
@@ -1,98 +1,984 @@
-use base64::engine::general_purpose;
 use base64::Engine;
-use printpdf;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::f64;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use web_sys::console;
 use web_sys::HtmlInputElement;
+use web_sys::HtmlSelectElement;
+
+mod animation;
+mod background;
+mod backlash;
+mod bridges;
+mod calibration;
+mod cam;
+mod clock;
+mod construction;
+mod cutouts;
+mod drill;
+mod dxf;
+mod embed;
+mod export_history;
+mod expression;
+mod gear_inputs;
+mod geometry;
+mod gif;
+mod helical;
+mod hobbing;
+mod hub;
+mod identify;
+mod index_plate;
+mod indexing;
+mod inspection;
+mod kerf_test;
+mod layers;
+mod locale;
+mod mold;
+mod offline;
+mod overlay;
+mod palette;
+mod paper;
+mod pdf;
+mod perf;
+mod preview;
+mod progress;
+mod qr;
+mod reference;
+mod roughing;
+mod rulers;
+mod session;
+mod share;
+mod sheets;
+mod spacers;
+mod spokes;
+mod storage;
+mod store;
+mod style;
+mod svg;
+mod tabs;
+mod tolerance;
+mod underlay;
+mod warnings;
+mod watermark;
+mod webgl;
+
+use background::BackgroundStyle;
+use clock::{ClockStage, ClockTrain};
+use dxf::DxfCurveMode;
+use gear_inputs::{append_checkbox, append_text_input, build_gear_sections, GearInputs};
+use geometry::{
+    GearGeometry, GearOutline, GearSpecs, OutlineSegment, Point, Rotation, Thinning, ToothProfile,
+};
+use hub::Hub;
+use layers::{CanvasLayers, Layer};
+use overlay::RotationDirection;
+use progress::ExportProgress;
+use spacers::Spacers;
+use spokes::Spokes;
+use storage::Design;
+use style::GearStyle;
+use tabs::DesignTabs;
 
 #[wasm_bindgen(start)]
 fn start() -> Result<(), JsValue> {
-    let document = web_sys::window().unwrap().document().unwrap();
-    let canvas = document
-        .create_element("canvas")?
-        .dyn_into::<web_sys::HtmlCanvasElement>()?;
-    document.body().unwrap().append_child(&canvas)?;
-    let canvas_rc = Rc::new(RefCell::new(canvas));
+    // the pdf worker loads this module too, but only needs `build_pdf`
+    let Some(window) = web_sys::window() else {
+        return Ok(());
+    };
+    let document = window.document().unwrap();
+
+    // open a shared link's design, or pick up where the page was left, or
+    // start from the default pair
+    let design = share::take_design_from_url()
+        .or_else(storage::load_autosave)
+        .unwrap_or_default();
 
     // create page state
     let page_state = PageState {
-        left_gear_spec: GearSpecs {
-            teeth: 50.0,
-            diametric_pitch: 12.0,
-            tooth_angle: 20.0,
-            clearance_mult: 0.167,
-            backlash_mult: 0.05,
-        },
-        right_gear_spec: GearSpecs {
-            teeth: 10.0,
-            diametric_pitch: 12.0,
-            tooth_angle: 20.0,
-            clearance_mult: 0.167,
-            backlash_mult: 0.05,
+        name: design.name,
+        gears: design.gears,
+        dxf_curve_mode: DxfCurveMode::Polyline,
+        export_colors: false,
+        mirror_exports: false,
+        oversize: paper::Oversize::default(),
+        export_layout: paper::Layout::default(),
+        exploded_gap: DEFAULT_EXPLODED_GAP,
+        drill_marks: false,
+        svg_units: svg::SvgUnits::default(),
+        line_widths: style::LineWidths::default(),
+        high_contrast: false,
+        // the iso 53 basic rack's fillet
+        rack_tip_radius_mult: 0.38,
+        rack_protuberance_mult: 0.0,
+        kerf_test_size_mm: 3.0,
+        mold_blank: mold::BlankShape::default(),
+        roughing: false,
+        roughing_tool: roughing::Tool::default(),
+        holding_tabs: bridges::Bridges::default(),
+        mold_wall: mold::DEFAULT_WALL,
+        sheet_layout: None,
+        animation_format: animation::Format::default(),
+        animation_turns: 1.0,
+        flank_tolerance_mm: None,
+        spacers: Spacers::default(),
+        cam_mode: false,
+        cam_program: DEFAULT_CAM_PROGRAM.to_string(),
+        cam_base_radius: 1.0,
+        cam_follower: cam::Follower::Roller {
+            radius: DEFAULT_ROLLER_RADIUS,
         },
+        renderer: Renderer::Canvas,
+        input_direction: RotationDirection::Clockwise,
+        show_rotation_arrows: true,
+        show_labels: true,
+        show_construction: false,
+        show_hobbing: false,
+        show_preview: false,
+        show_tolerance_band: false,
+        profile_tolerance_mm: DEFAULT_PROFILE_TOLERANCE_MM,
+        show_terminology: false,
+        debug: DebugConfig::default(),
+        highlighted_gears: Vec::new(),
+        linked: design.linked,
+        changed_gears: Vec::new(),
+        overlay_changed: false,
+        background: BackgroundStyle::default(),
+        // embedded pages only show the gears
+        show_rulers: !embed::is_embedded(),
+        actual_size: false,
+        calibrated_ppi: storage::load_screen_ppi(),
+        ruler_units: rulers::RulerUnits::default(),
+        reference: None,
+        reference_alignment: reference::Alignment::default(),
+        underlay: None,
+        underlay_placement: underlay::Placement::default(),
+        calibration_picks: None,
+        background_changed: false,
     };
+    let layers = Rc::new(CanvasLayers::new(
+        &document,
+        page_state.gears.len(),
+        sidebar_width(),
+    )?);
     let page_state_rc = Rc::new(RefCell::new(page_state));
-
-    // setup canvas drawing context + do initial redraw
-    let context = canvas_rc
-        .borrow()
-        .get_context("2d")
-        .unwrap()
-        .unwrap()
-        .dyn_into::<web_sys::CanvasRenderingContext2d>()
-        .unwrap();
-    let context_rc = Rc::new(RefCell::new(context));
+    // every change to the state goes through the store, and everything that
+    // follows the state hears about it there
+    let store = Rc::new(store::Store::new(page_state_rc.clone()));
 
     // do initial redraw
-    full_redraw(
-        &canvas_rc.borrow(),
-        &context_rc.borrow(),
-        &page_state_rc.borrow(),
-    );
+    full_redraw(&layers, &mut page_state_rc.borrow_mut());
 
     // Add event listener for window resize + redraw
     let page_state_rc_clone = page_state_rc.clone();
-    let canvas_rc_clone = canvas_rc.clone();
-    let context_rc_clone = context_rc.clone();
+    let layers_clone = layers.clone();
     let closure = Closure::wrap(Box::new(move || {
-        full_redraw(
-            &canvas_rc_clone.borrow(),
-            &context_rc_clone.borrow(),
-            &page_state_rc_clone.borrow(),
-        );
+        full_redraw(&layers_clone, &mut page_state_rc_clone.borrow_mut());
     }) as Box<dyn Fn()>);
     web_sys::window()
         .unwrap()
         .add_event_listener_with_callback("resize", closure.as_ref().unchecked_ref())
         .unwrap();
 
+    let construction_layers = layers.clone();
+
+    // the rulers follow the pointer across the workspace, and let go of it
+    // when it leaves
+    let cursor_state = page_state_rc.clone();
+    let cursor_layers = layers.clone();
+    let cursor_closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+        let cursor = (event.type_() == "mousemove")
+            .then(|| (event.offset_x() as f64, event.offset_y() as f64));
+        draw_rulers(&cursor_layers, &cursor_state.borrow(), cursor);
+    }) as Box<dyn Fn(web_sys::MouseEvent)>);
+    if let Some(workspace) = document.get_element_by_id("canvas_layers") {
+        for event in ["mousemove", "mouseleave"] {
+            workspace
+                .add_event_listener_with_callback(event, cursor_closure.as_ref().unchecked_ref())?;
+        }
+    }
+    cursor_closure.forget();
+
+    // dragging the 3d preview orbits it and the wheel zooms it
+    if let Some(preview) = &layers.preview {
+        let orbit_layers = layers.clone();
+        let dragging = Rc::new(std::cell::Cell::new(false));
+        let orbit_closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+            let Some(preview) = &orbit_layers.preview else {
+                return;
+            };
+            match event.type_().as_str() {
+                "mousedown" => dragging.set(true),
+                "mousemove" if dragging.get() => {
+                    let mut camera = preview.camera.get();
+                    camera.orbit(event.movement_x() as f64, event.movement_y() as f64);
+                    preview.camera.set(camera);
+                    preview.draw();
+                }
+                "mouseup" | "mouseleave" => dragging.set(false),
+                _ => {}
+            }
+        }) as Box<dyn Fn(web_sys::MouseEvent)>);
+        for event in ["mousedown", "mousemove", "mouseup", "mouseleave"] {
+            preview
+                .canvas
+                .add_event_listener_with_callback(event, orbit_closure.as_ref().unchecked_ref())?;
+        }
+        orbit_closure.forget();
+
+        let zoom_layers = layers.clone();
+        let zoom_closure = Closure::wrap(Box::new(move |event: web_sys::WheelEvent| {
+            event.prevent_default();
+            if let Some(preview) = &zoom_layers.preview {
+                let mut camera = preview.camera.get();
+                camera.zoom(event.delta_y());
+                preview.camera.set(camera);
+                preview.draw();
+            }
+        }) as Box<dyn Fn(web_sys::WheelEvent)>);
+        preview
+            .canvas
+            .add_event_listener_with_callback("wheel", zoom_closure.as_ref().unchecked_ref())?;
+        zoom_closure.forget();
+    }
+
+    // saves the view as it is on screen
+    let snapshot_layers = layers.clone();
+    let page_state_rc_snapshot_clone = page_state_rc.clone();
+    let save_view_closure = Closure::wrap(Box::new(move || {
+        save_view_png(&snapshot_layers, &page_state_rc_snapshot_clone.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+
+    // gear changes only need the changed gears' layers redrawn
+    let page_state_rc_gears_clone = page_state_rc.clone();
+    store.subscribe(move |_: &Action| {
+        redraw_changed_gears(&layers, &mut page_state_rc_gears_clone.borrow_mut());
+    });
+
     // create left sidebar
     let page_state_rc_sidebar_clone = page_state_rc.clone();
-    let canvas_rc_sidebar = canvas_rc.clone();
-    let context_rc_sidebar = context_rc.clone();
     let print_gears_closure = Closure::wrap(Box::new(move || {
-        print_gears(
-            &canvas_rc_sidebar.borrow(),
-            &context_rc_sidebar.borrow(),
-            &page_state_rc_sidebar_clone.borrow(),
-        )
-        .unwrap();
+        print_gears(page_state_rc_sidebar_clone.clone()).unwrap();
     }) as Box<dyn Fn()>);
-    let sidebar = create_sidebar(page_state_rc, &closure, &print_gears_closure)?;
+    let construction_state = page_state_rc.clone();
+    let sidebar = create_sidebar(
+        store.clone(),
+        page_state_rc,
+        &print_gears_closure,
+        &save_view_closure,
+    )?;
     document.body().unwrap().append_child(&sidebar)?;
+    palette::create(&document)?;
+
+    // the involute construction and hobbing simulation animate on their own
+    // layer until they're both turned off
+    let construction_running = Rc::new(std::cell::Cell::new(false));
+    store.subscribe(move |_: &Action| {
+        let shown = {
+            let state = construction_state.borrow();
+            !state.cam_mode && (state.show_construction || state.show_hobbing)
+        };
+        if shown && !construction_running.get() {
+            construction_running.set(true);
+            animate_construction(
+                construction_layers.clone(),
+                construction_state.clone(),
+                construction_running.clone(),
+            );
+        }
+    });
     print_gears_closure.forget();
+    save_view_closure.forget();
     closure.forget();
 
     Ok(())
 }
 
-fn print_gears(
-    canvas: &web_sys::HtmlCanvasElement,
-    context: &web_sys::CanvasRenderingContext2d,
-    page_state: &PageState,
+// the side of a module in the printed share code, in inches
+const SHARE_CODE_MODULE: f64 = 0.015;
+
+// a fresh cam's lift program and roller
+const DEFAULT_CAM_PROGRAM: &str = "rise 120 0.5, dwell 60, fall 120 0.5, dwell 60";
+const DEFAULT_ROLLER_RADIUS: f64 = 0.25;
+
+// room between exploded gears, enough for a saw blade and some slop
+const DEFAULT_EXPLODED_GAP: f64 = 0.25;
+
+const MM_PER_INCH: f64 = 25.4;
+
+// a typical profile tolerance for laser cut or printed gears
+const DEFAULT_PROFILE_TOLERANCE_MM: f64 = 0.05;
+
+// prints the gears at full scale
+fn print_gears(page_state: Rc<RefCell<PageState>>) -> Result<(), JsValue> {
+    if page_state.borrow().cam_mode {
+        return print_cam(&page_state.borrow());
+    }
+    let summary = design_summary(&page_state.borrow());
+    let info = pdf::PdfInfo {
+        title: design_title(&page_state.borrow()),
+        keywords: design_keywords(&page_state.borrow()),
+    };
+    let share_url = share::url(&page_state.borrow().design());
+    let oversize = page_state.borrow().oversize;
+    let print = Print::new(&page_state.borrow());
+    export_page_pdf(
+        "gears.pdf",
+        summary,
+        info,
+        oversize,
+        None,
+        move || {
+            let page_state = page_state.borrow();
+            let share_code = qr::QrCode::encode(share_url.as_bytes());
+            let outlines = tessellate_for_export(&page_state);
+            let side_views = side_views(&page_state, &outlines);
+            let side_views_bottom = side_views
+                .iter()
+                .map(|(origin, view)| origin.y + view.below)
+                .fold(f64::MIN, f64::max);
+            let spacer_nest = nest_spacers(&page_state, &outlines, side_views_bottom);
+            let bounds = layout_bounds(&outlines, &side_views, spacer_nest.as_ref());
+            let marks = drill_marks(&page_state, &outlines, spacer_nest.as_ref());
+            (
+                (
+                    outlines,
+                    export_styles(&page_state),
+                    share_code,
+                    side_views,
+                    spacer_nest,
+                    marks,
+                ),
+                bounds,
+            )
+        },
+        move |page,
+              width,
+              height,
+              dpi,
+              view,
+              (outlines, styles, share_code, side_views, spacer_nest, marks)| {
+            start_page(page, width, height, dpi, view, &print);
+            draw_gears(
+                &page.context,
+                outlines,
+                styles,
+                &DebugConfig::default(),
+                dpi,
+            );
+            // still centered from drawing the gears
+            for (origin, side_view) in side_views {
+                let origin = Point {
+                    x: origin.x * dpi as f64,
+                    y: origin.y * dpi as f64,
+                };
+                hub::draw(&page.context, side_view, origin, dpi as f64);
+            }
+            if let Some(spacer_nest) = spacer_nest {
+                spacers::draw(&page.context, spacer_nest, dpi as f64);
+            }
+            set_print_line_width(&page.context, print.widths.engrave, dpi, view);
+            drill::draw(&page.context, marks, dpi as f64);
+            // a link back to the design in the top left corner, so the
+            // printed sheet can be traced to the exact parameters it was
+            // made from. designs too big for a qr code go without.
+            if let Some(share_code) = share_code {
+                let dpi = dpi as f64;
+                let module = (SHARE_CODE_MODULE * dpi).round().max(1.0);
+                page.context
+                    .set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+                    .unwrap();
+                qr::draw(&page.context, share_code, 0.1 * dpi, 0.1 * dpi, module);
+            }
+        },
+    )
+}
+
+// pixels per inch the sheets are drawn at while they're arranged
+const SHEET_EDITOR_PPI: u32 = 40;
+
+// the pdf's sheets arranged by hand before it's made. parts are dragged
+// about, across onto other sheets too, and the buttons act on the last one
+// picked.
+struct SheetEditor {
+    page_state: Rc<RefCell<PageState>>,
+    store: Rc<store::Store<PageState, Action>>,
+    overlay: web_sys::Element,
+    sheets: web_sys::Element,
+    // each gear unturned at the origin, flipped for mirrored exports
+    outlines: Vec<GearOutline>,
+    styles: Vec<GearStyle>,
+    radii: Vec<f64>,
+    layout: RefCell<sheets::SheetLayout>,
+    selected: std::cell::Cell<Option<usize>>,
+    // the dragged part's center less the pointer, and the sheet it started on
+    dragging: std::cell::Cell<Option<(Point, usize)>>,
+}
+
+impl SheetEditor {
+    fn open(
+        page_state: Rc<RefCell<PageState>>,
+        store: Rc<store::Store<PageState, Action>>,
+    ) -> Result<(), JsValue> {
+        let window = web_sys::window().unwrap();
+        if page_state.borrow().cam_mode {
+            window.alert_with_message("Turn off cam mode to arrange the gears on sheets.")?;
+            return Ok(());
+        }
+        let document = window.document().unwrap();
+        let (outlines, styles, layout) = {
+            let state = page_state.borrow();
+            let origin = Point { x: 0.0, y: 0.0 };
+            let outlines: Vec<GearOutline> = state
+                .gears
+                .iter()
+                .map(|gear| {
+                    let outline = tessellate_gear(gear, origin);
+                    if state.mirror_exports {
+                        outline.mirrored()
+                    } else {
+                        outline
+                    }
+                })
+                .collect();
+            let radii: Vec<f64> = outlines
+                .iter()
+                .map(|outline| outline.geometry.outer_radius)
+                .collect();
+            // a layout from before gears were added or taken away starts over
+            let layout = state
+                .sheet_layout
+                .clone()
+                .filter(|layout| layout.gears == state.gears.len())
+                .unwrap_or_else(|| sheets::SheetLayout::new(&radii));
+            let styles = state.gears.iter().map(|gear| gear.style.clone()).collect();
+            (outlines, styles, layout)
+        };
+
+        let overlay = document.create_element("div")?;
+        overlay.set_attribute("style", "position: fixed; left: 0; top: 0; width: 100%; height: 100%; overflow: auto; background-color: rgba(0, 0, 0, 0.3);")?;
+        let dialog = document.create_element("div")?;
+        dialog.set_attribute(
+            "style",
+            "margin: 20px; padding: 20px; background-color: #f0f0f0;",
+        )?;
+        overlay.append_child(&dialog)?;
+        let title = document.create_element("h3")?;
+        title.set_text_content(Some("Arrange PDF Sheets"));
+        dialog.append_child(&title)?;
+        let hint = document.create_element("p")?;
+        hint.set_text_content(Some(
+            "Drag the gears around their sheets, or onto another one. Click a gear to pick it for the buttons. Gears outlined in red hang off the printable area.",
+        ));
+        dialog.append_child(&hint)?;
+        let toolbar = document.create_element("div")?;
+        toolbar.set_attribute("style", "display: flex; gap: 4px; margin-bottom: 10px;")?;
+        for (action, text) in [
+            ("rotate", "Rotate 90\u{b0}"),
+            ("duplicate", "Duplicate"),
+            ("remove", "Remove"),
+            ("add_sheet", "Add Sheet"),
+            ("reset", "Start Over"),
+            ("export", "Export PDF"),
+            ("close", "Close"),
+        ] {
+            let button = document.create_element("button")?;
+            button.set_attribute("data-action", action)?;
+            button.set_text_content(Some(text));
+            toolbar.append_child(&button)?;
+        }
+        dialog.append_child(&toolbar)?;
+        let sheets = document.create_element("div")?;
+        sheets.set_attribute("style", "display: flex; flex-wrap: wrap; gap: 10px;")?;
+        dialog.append_child(&sheets)?;
+        document.body().unwrap().append_child(&overlay)?;
+
+        let editor = Rc::new(SheetEditor {
+            page_state,
+            store,
+            overlay,
+            sheets,
+            radii: outlines
+                .iter()
+                .map(|outline| outline.geometry.outer_radius)
+                .collect(),
+            outlines,
+            styles,
+            layout: RefCell::new(layout),
+            selected: std::cell::Cell::new(None),
+            dragging: std::cell::Cell::new(None),
+        });
+        editor.build()?;
+
+        let action_editor = editor.clone();
+        let action_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            let Some(target) = event
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            else {
+                return;
+            };
+            let action = target.get_attribute("data-action").unwrap_or_default();
+            action_editor.act(&action).unwrap();
+        }) as Box<dyn Fn(web_sys::Event)>);
+        toolbar
+            .add_event_listener_with_callback("click", action_closure.as_ref().unchecked_ref())?;
+        action_closure.forget();
+
+        let drag_editor = editor.clone();
+        let drag_closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+            drag_editor.drag(&event);
+        }) as Box<dyn Fn(web_sys::MouseEvent)>);
+        for event in ["mousedown", "mousemove", "mouseup", "mouseleave"] {
+            editor
+                .sheets
+                .add_event_listener_with_callback(event, drag_closure.as_ref().unchecked_ref())?;
+        }
+        drag_closure.forget();
+        Ok(())
+    }
+
+    // a canvas per sheet, numbered underneath
+    fn build(&self) -> Result<(), JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        self.sheets.set_inner_html("");
+        let ppi = SHEET_EDITOR_PPI as f64;
+        for sheet in 0..self.layout.borrow().sheets {
+            let figure = document.create_element("div")?;
+            let canvas = document
+                .create_element("canvas")?
+                .dyn_into::<web_sys::HtmlCanvasElement>()?;
+            canvas.set_width((paper::PRINTABLE_WIDTH * ppi) as u32);
+            canvas.set_height((paper::PRINTABLE_HEIGHT * ppi) as u32);
+            canvas.set_attribute("data-sheet", &sheet.to_string())?;
+            canvas.set_attribute("style", "display: block; border: 1px solid #999;")?;
+            figure.append_child(&canvas)?;
+            let label = document.create_element("div")?;
+            label.set_attribute("style", "text-align: center;")?;
+            label.set_text_content(Some(&format!("Sheet {}", sheet + 1)));
+            figure.append_child(&label)?;
+            self.sheets.append_child(&figure)?;
+        }
+        self.draw();
+        Ok(())
+    }
+
+    fn draw(&self) {
+        let layout = self.layout.borrow();
+        let overhanging = layout.overhanging(&self.radii);
+        let canvases = self.sheets.query_selector_all("canvas").unwrap();
+        let debug_config = DebugConfig::default();
+        for sheet in 0..canvases.length() {
+            let canvas = canvases
+                .get(sheet)
+                .unwrap()
+                .dyn_into::<web_sys::HtmlCanvasElement>()
+                .unwrap();
+            let context = canvas
+                .get_context("2d")
+                .unwrap()
+                .unwrap()
+                .dyn_into::<web_sys::CanvasRenderingContext2d>()
+                .unwrap();
+            let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+            context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap();
+            context.set_fill_style_str("white");
+            context.fill_rect(0.0, 0.0, width, height);
+            context.translate(width / 2.0, height / 2.0).unwrap();
+            for (index, part) in layout.parts.iter().enumerate() {
+                if part.sheet != sheet as usize {
+                    continue;
+                }
+                let outline = placed_part(&self.outlines[part.gear], part);
+                draw_gear(
+                    &context,
+                    &outline,
+                    &self.styles[part.gear],
+                    &debug_config,
+                    SHEET_EDITOR_PPI,
+                );
+                let marker = if self.selected.get() == Some(index) {
+                    Some("#1e90ff")
+                } else if overhanging.contains(&index) {
+                    Some("red")
+                } else {
+                    None
+                };
+                if let Some(color) = marker {
+                    let scale = SHEET_EDITOR_PPI as f64;
+                    context.set_stroke_style_str(color);
+                    context.set_line_width(2.0);
+                    draw_circle(
+                        &context,
+                        part.center.x * scale,
+                        part.center.y * scale,
+                        self.radii[part.gear] * scale,
+                    );
+                }
+            }
+        }
+    }
+
+    fn act(&self, action: &str) -> Result<(), JsValue> {
+        let selected = self.selected.get();
+        match (action, selected) {
+            ("rotate", Some(index)) => self.layout.borrow_mut().rotate(index, 90.0),
+            ("duplicate", Some(index)) => {
+                let copy = self.layout.borrow_mut().duplicate(index, &self.radii);
+                self.selected.set(Some(copy));
+            }
+            ("remove", Some(index)) => {
+                self.layout.borrow_mut().remove(index);
+                self.selected.set(None);
+            }
+            ("add_sheet", _) => self.layout.borrow_mut().add_sheet(),
+            ("reset", _) => {
+                *self.layout.borrow_mut() = sheets::SheetLayout::new(&self.radii);
+                self.selected.set(None);
+            }
+            ("export", _) => {
+                self.close();
+                return print_sheets(&self.page_state.borrow());
+            }
+            ("close", _) => {
+                self.close();
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+        self.build()
+    }
+
+    // picks up the part under the pointer and carries it, onto whichever
+    // sheet the pointer is over
+    fn drag(&self, event: &web_sys::MouseEvent) {
+        let sheet = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|target| target.get_attribute("data-sheet"))
+            .and_then(|sheet| sheet.parse::<usize>().ok());
+        let scale = SHEET_EDITOR_PPI as f64;
+        let pointer = Point {
+            x: event.offset_x() as f64 / scale - paper::PRINTABLE_WIDTH / 2.0,
+            y: event.offset_y() as f64 / scale - paper::PRINTABLE_HEIGHT / 2.0,
+        };
+        match event.type_().as_str() {
+            "mousedown" => {
+                let Some(sheet) = sheet else {
+                    return;
+                };
+                let picked = self.layout.borrow().part_at(sheet, pointer, &self.radii);
+                self.selected.set(picked);
+                self.dragging.set(picked.map(|index| {
+                    let center = self.layout.borrow().parts[index].center;
+                    (
+                        Point {
+                            x: center.x - pointer.x,
+                            y: center.y - pointer.y,
+                        },
+                        sheet,
+                    )
+                }));
+                self.draw();
+            }
+            "mousemove" => {
+                let (Some((offset, _)), Some(index), Some(sheet)) =
+                    (self.dragging.get(), self.selected.get(), sheet)
+                else {
+                    return;
+                };
+                let mut layout = self.layout.borrow_mut();
+                layout.parts[index].sheet = sheet;
+                layout.parts[index].center = Point {
+                    x: pointer.x + offset.x,
+                    y: pointer.y + offset.y,
+                };
+                drop(layout);
+                self.draw();
+            }
+            _ => {
+                let Some((_, from)) = self.dragging.take() else {
+                    return;
+                };
+                // a sheet left empty by the move goes
+                let Some(index) = self.selected.get() else {
+                    return;
+                };
+                if self.layout.borrow().parts[index].sheet != from {
+                    self.layout.borrow_mut().drop_empty_sheets();
+                    self.build().unwrap();
+                }
+            }
+        }
+    }
+
+    // keeps the arrangement for next time
+    fn close(&self) {
+        self.store
+            .dispatch(Action::ArrangeSheets(self.layout.borrow().clone()));
+        self.overlay.remove();
+    }
+}
+
+// a gear placed on its sheet as a part, turned about its own center
+fn placed_part(outline: &GearOutline, part: &sheets::Part) -> GearOutline {
+    let rotation = Rotation::new(part.rotation.to_radians());
+    GearOutline {
+        center: part.center,
+        geometry: outline.geometry.clone(),
+        segments: outline
+            .segments
+            .iter()
+            .map(|segment| segment.rotated(&rotation))
+            .collect(),
+        holes: outline
+            .holes
+            .iter()
+            .map(|hole| hole.iter().map(|pt| rotation.apply(pt)).collect())
+            .collect(),
+    }
+}
+
+// prints the gears as they were arranged on their sheets, a page each, with
+// the share code on the first
+fn print_sheets(page_state: &PageState) -> Result<(), JsValue> {
+    let Some(layout) = page_state.sheet_layout.clone() else {
+        return Ok(());
+    };
+    let info = pdf::PdfInfo {
+        title: design_title(page_state),
+        keywords: design_keywords(page_state),
+    };
+    let share_url = share::url(&page_state.design());
+    let gears = page_state.gears.clone();
+    let styles = export_styles(page_state);
+    let mirror = page_state.mirror_exports;
+    let print = Print::new(page_state);
+    export_page_pdf(
+        "gears.pdf",
+        format!("{}, {} sheets", design_summary(page_state), layout.sheets),
+        info,
+        page_state.oversize,
+        Some(layout.sheets),
+        move || {
+            let origin = Point { x: 0.0, y: 0.0 };
+            let outlines: Vec<GearOutline> = gears
+                .iter()
+                .map(|gear| {
+                    let outline = tessellate_gear(gear, origin);
+                    if mirror {
+                        outline.mirrored()
+                    } else {
+                        outline
+                    }
+                })
+                .collect();
+            let parts: Vec<(usize, GearOutline, GearStyle)> = layout
+                .parts
+                .iter()
+                .map(|part| {
+                    (
+                        part.sheet,
+                        placed_part(&outlines[part.gear], part),
+                        styles[part.gear].clone(),
+                    )
+                })
+                .collect();
+            let share_code = qr::QrCode::encode(share_url.as_bytes());
+            ((parts, share_code), None)
+        },
+        move |page, width, height, dpi, view, (parts, share_code)| {
+            start_page(page, width, height, dpi, view, &print);
+            let debug_config = DebugConfig::default();
+            for (_, outline, style) in parts.iter().filter(|(sheet, _, _)| *sheet == view.sheet) {
+                draw_gear(&page.context, outline, style, &debug_config, dpi);
+            }
+            if let (Some(share_code), 0) = (share_code, view.sheet) {
+                let dpi = dpi as f64;
+                let module = (SHARE_CODE_MODULE * dpi).round().max(1.0);
+                page.context
+                    .set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+                    .unwrap();
+                qr::draw(&page.context, share_code, 0.1 * dpi, 0.1 * dpi, module);
+            }
+        },
+    )
+}
+
+// prints an inspection sheet for each gear, a page each, with the flanks
+// held to the profile tolerance
+fn print_inspection(page_state: &PageState) -> Result<(), JsValue> {
+    if page_state.cam_mode {
+        let window = web_sys::window().unwrap();
+        window.alert_with_message("Turn off cam mode to print inspection sheets.")?;
+        return Ok(());
+    }
+    let info = pdf::PdfInfo {
+        title: format!("{} inspection", design_title(page_state)),
+        keywords: design_keywords(page_state),
+    };
+    let title = design_title(page_state);
+    let specs: Vec<GearSpecs> = page_state
+        .gears
+        .iter()
+        .map(|gear| gear.spec.clone())
+        .collect();
+    let tolerance = page_state.profile_tolerance_mm / MM_PER_INCH;
+    export_page_pdf(
+        "inspection.pdf",
+        format!("{} inspection", design_summary(page_state)),
+        info,
+        paper::Oversize::default(),
+        Some(specs.len()),
+        move || {
+            let sheets: Vec<(String, inspection::Inspection)> = specs
+                .iter()
+                .enumerate()
+                .map(|(index, spec)| {
+                    (
+                        format!(
+                            "{}, gear {}: {} teeth, {} DP, {}\u{b0} pressure angle",
+                            title,
+                            index + 1,
+                            spec.teeth,
+                            spec.diametric_pitch,
+                            spec.tooth_angle
+                        ),
+                        inspection::inspect(spec, tolerance),
+                    )
+                })
+                .collect();
+            (sheets, None)
+        },
+        |page, width, height, dpi, view, sheets| {
+            page.resize(width, height);
+            let (title, sheet) = &sheets[view.sheet];
+            inspection::draw(&page.context, width, height, dpi, title, sheet);
+        },
+    )
+}
+
+// prints the cam's profile at full scale, centered on the page
+fn print_cam(page_state: &PageState) -> Result<(), JsValue> {
+    let cam = match page_state.cam() {
+        Ok(cam) => cam,
+        Err(err) => {
+            let window = web_sys::window().unwrap();
+            window.alert_with_message(&format!("The lift program doesn't work: {}", err))?;
+            return Ok(());
+        }
+    };
+    let mirror = page_state.mirror_exports;
+    let print = Print::new(page_state);
+    let info = pdf::PdfInfo {
+        title: design_title(page_state),
+        keywords: design_keywords(page_state),
+    };
+    export_page_pdf(
+        "cam.pdf",
+        cam_summary(page_state),
+        info,
+        page_state.oversize,
+        None,
+        move || {
+            let outline: Vec<Point> = cam
+                .outline()
+                .into_iter()
+                .map(|pt| Point {
+                    x: if mirror { -pt.x } else { pt.x },
+                    y: pt.y,
+                })
+                .collect();
+            let bounds = paper::bounds(outline.iter().map(|pt| paper::Bounds::around(*pt, 0.0)));
+            (outline, bounds)
+        },
+        move |page, width, height, dpi, view, outline| {
+            start_page(page, width, height, dpi, view, &print);
+            // still centered from starting the page
+            page.context.set_stroke_style_str("black");
+            cam::draw_outline(&page.context, outline, Point { x: 0.0, y: 0.0 }, dpi as f64);
+        },
+    )
+}
+
+// prints through the browser's own dialog. the print css hides everything
+// but the drawing and crops it to a landscape letter page around its center.
+// canvases are drawn at 96 px per inch, which is what css means by a px, so
+// the page comes out at full scale as long as the dialog isn't set to shrink it.
+fn print_via_browser(page_state: &PageState) -> Result<(), JsValue> {
+    let window = web_sys::window().unwrap();
+    // the gpu buffer is thrown away once it's on screen, so it prints blank
+    if page_state.renderer == Renderer::WebGl {
+        window.alert_with_message(
+            "Switch to the Canvas 2D or SVG renderer to print through the browser.",
+        )?;
+        return Ok(());
+    }
+    // the print css expects css inches
+    if page_state.screen_ppi() != SCREEN_PPI {
+        window.alert_with_message("Turn off Actual Size to print through the browser.")?;
+        return Ok(());
+    }
+    let document = window.document().unwrap();
+    let style = match document.get_element_by_id("print_css") {
+        Some(style) => style,
+        None => {
+            let style = document.create_element("style")?;
+            style.set_attribute("id", "print_css")?;
+            document.body().unwrap().append_child(&style)?;
+            style
+        }
+    };
+    let width = calculate_window_width_pixels() - sidebar_width();
+    let height = calculate_window_height_pixels();
+    style.set_text_content(Some(&format!(
+        "@page {{ size: letter landscape; margin: 0.25in; }}
+        @media print {{
+            body > :not(#canvas_layers), #rulers {{ display: none !important; }}
+            #canvas_layers {{ position: relative !important; left: 0 !important; width: 10.5in; height: 8in; overflow: hidden; }}
+            #canvas_layers > * {{ left: calc(5.25in - {}px) !important; top: calc(4in - {}px) !important; }}
+        }}",
+        width as f64 / 2.0,
+        height as f64 / 2.0
+    )));
+    window.print()
+}
+
+// prints a dividing plate for cutting a gear with `teeth` teeth by hand
+fn print_index_plate(teeth: u32) -> Result<(), JsValue> {
+    let info = pdf::PdfInfo {
+        title: format!("Index plate for {} teeth", teeth),
+        keywords: vec!["index plate".to_string(), format!("{} teeth", teeth)],
+    };
+    export_page_pdf(
+        &format!("index_plate_{}t.pdf", teeth),
+        format!("{}T index plate", teeth),
+        info,
+        paper::Oversize::default(),
+        None,
+        || ((), None),
+        move |page, width, height, dpi, _, ()| {
+            page.resize(width, height);
+            index_plate::draw_index_plate(&page.context, width, height, dpi, teeth);
+        },
+    )
+}
+
+// exports landscape letter pages as a pdf in stages, updating the progress
+// bar between each blocking step so the user can see where it is and cancel.
+// `prepare` does any geometry work up front, along with the bounds of what
+// it'll draw, and `render` draws a page showing the given view of it.
+// layouts too big for a page are tiled, scaled or clipped as `oversize`
+// says, asking first before clipping. without bounds there's one page.
+fn export_page_pdf<T: 'static>(
+    filename: &str,
+    summary: String,
+    info: pdf::PdfInfo,
+    oversize: paper::Oversize,
+    sheets: Option<usize>,
+    prepare: impl FnOnce() -> (T, Option<paper::Bounds>) + 'static,
+    render: impl Fn(&Layer, u32, u32, u32, &paper::View, &T) + 'static,
 ) -> Result<(), JsValue> {
     let dpi = 300.0;
     let margin_inches = 0.25;
@@ -101,75 +987,774 @@ fn print_gears(
     let width = dpi * (11.0 - margin_inches);
     let height = dpi * (8.5 - margin_inches);
 
-    redraw(
-        canvas,
-        context,
-        width as u32,
-        height as u32,
-        page_state,
-        dpi as u32,
-    );
+    let filename = filename.to_string();
+    let progress = Rc::new(ExportProgress::show("Exporting PDF", pdf::cancel_pdf)?);
+    progress.set_stage(0);
+    perf::start_export();
+    after_paint(move || {
+        if progress.is_cancelled() {
+            return;
+        }
+        let stage_start = perf::now();
+        let (prepared, bounds) = prepare();
+        perf::record_export_stage("tessellate", perf::now() - stage_start);
 
-    // export canvas to png. this could use blobs, but
-    // dealing with promises is a pain
-    let data_url = canvas.to_data_url()?;
+        // clipping is the one outcome worth stopping for
+        if oversize == paper::Oversize::Clip {
+            if let Some(warning) = bounds.and_then(|bounds| paper::warning(&bounds, oversize)) {
+                let window = web_sys::window().unwrap();
+                let message = format!("{}\n\nExport anyway?", warning);
+                if !window.confirm_with_message(&message).unwrap_or(false) {
+                    progress.finish();
+                    return;
+                }
+            }
+        }
+        // sheets laid out by hand print as they are, a page each
+        let views = match sheets {
+            Some(count) => paper::sheets(count),
+            None => paper::views(bounds, oversize),
+        };
 
-    console::log_1(&JsValue::from_str("Exporting to PDF"));
-    let mut doc = printpdf::PdfDocument::new("Export");
-    let image_bytes = base64::engine::general_purpose::STANDARD
-        .decode(data_url.split(',').last().unwrap())
-        .unwrap();
-    let image = printpdf::RawImage::decode_from_bytes(&image_bytes).unwrap();
+        progress.set_stage(1);
+        after_paint(move || {
+            if progress.is_cancelled() {
+                return;
+            }
+            // render the pages off screen so the on-screen view is left alone
+            let stage_start = perf::now();
+            let page = Layer::offscreen().unwrap();
+            let pages: Vec<Vec<u8>> = views
+                .iter()
+                .map(|view| {
+                    render(
+                        &page,
+                        width as u32,
+                        height as u32,
+                        dpi as u32,
+                        view,
+                        &prepared,
+                    );
+                    watermark::draw(&page.context, width as u32, height as u32, dpi as u32);
+                    if views.len() > 1 && sheets.is_none() {
+                        draw_tile_label(&page.context, view, views.len(), dpi);
+                    }
 
-    // In the PDF, an image is an `XObject`, identified by a unique `ImageId`
-    let image_xobject_id = doc.add_image(&image);
+                    // export canvas to png. this could use blobs, but
+                    // dealing with promises is a pain
+                    let data_url = page.canvas.to_data_url().unwrap();
+                    base64::engine::general_purpose::STANDARD
+                        .decode(data_url.split(',').next_back().unwrap())
+                        .unwrap()
+                })
+                .collect();
+            perf::record_export_stage("rasterize", perf::now() - stage_start);
 
-    let mut transform = printpdf::XObjectTransform::default();
-    transform.rotate = Some(printpdf::XObjectRotation {
-        angle_ccw_degrees: 90.0,
-        rotation_center_x: printpdf::Px(0),
-        rotation_center_y: printpdf::Px(0),
+            progress.set_stage(2);
+            after_paint(move || {
+                if progress.is_cancelled() {
+                    return;
+                }
+                console::log_1(&JsValue::from_str("Exporting to PDF"));
+                let stage_start = perf::now();
+                let failed = progress.clone();
+                let result = pdf::export_pdf(&pages, margin_inches, &info, move |result| {
+                    perf::record_export_stage("pdf", perf::now() - stage_start);
+                    progress.finish();
+                    match result {
+                        Ok(pdf_bytes) => {
+                            save_export(&pdf_bytes, "application/pdf", &filename, summary).unwrap();
+                        }
+                        Err(err) => pdf_failed(&err),
+                    }
+                });
+                // the message to the worker couldn't be sent
+                if let Err(err) = result {
+                    failed.finish();
+                    pdf_failed(&format!("{:?}", err));
+                }
+            });
+        });
     });
-    transform.translate_x = Some(printpdf::Pt(72.0 * (8.5 - margin_inches / 2.0)));
-    transform.translate_y = Some(printpdf::Pt(72.0 * (margin_inches / 2.0)));
-    let page1_contents = vec![printpdf::Op::UseXObject {
-        id: image_xobject_id.clone(),
-        transform: transform,
-    }];
 
-    let page1 = printpdf::PdfPage::new(
-        printpdf::Mm(25.4 * 8.5),
-        printpdf::Mm(25.4 * 11.0),
-        page1_contents,
+    Ok(())
+}
+
+fn pdf_failed(err: &str) {
+    console::error_1(&JsValue::from_str(err));
+    web_sys::window()
+        .unwrap()
+        .alert_with_message(&format!("Couldn't build the PDF: {}", err))
+        .unwrap();
+}
+
+// says which tile a page is, across the top, so the pages can be put back
+// together. the transform is already reset by the watermark.
+fn draw_tile_label(
+    context: &web_sys::CanvasRenderingContext2d,
+    view: &paper::View,
+    count: usize,
+    dpi: f64,
+) {
+    let text = format!(
+        "row {}, column {} of {} pages, overlapping its neighbours by {} in",
+        view.row + 1,
+        view.column + 1,
+        count,
+        paper::TILE_OVERLAP
     );
-    let pdf_bytes: Vec<u8> = doc
-        .with_pages(vec![page1])
-        .save(&printpdf::PdfSaveOptions::default());
+    context.set_fill_style_str("black");
+    context.set_font(&format!("{}px sans-serif", 10.0 / 72.0 * dpi));
+    context.set_text_align("center");
+    context.set_text_baseline("top");
+    context
+        .fill_text(&text, paper::PRINTABLE_WIDTH / 2.0 * dpi, 0.1 * dpi)
+        .unwrap();
+}
+
+// draws a frame of the involute construction and hobbing simulation over the
+// first gear and asks for the next one, clearing the layer and stopping once
+// they're both switched off
+fn animate_construction(
+    layers: Rc<CanvasLayers>,
+    page_state: Rc<RefCell<PageState>>,
+    running: Rc<std::cell::Cell<bool>>,
+) {
+    let layer = &layers.construction;
+    layer.clear();
+    let state = page_state.borrow();
+    // both animate the first gear, which cam mode hides
+    if state.cam_mode || (!state.show_construction && !state.show_hobbing) {
+        drop(state);
+        running.set(false);
+        return;
+    }
+    let first_gear = &state.gears[0];
+    // only involute teeth have a construction to show
+    if first_gear.spec.profile == ToothProfile::Involute {
+        let scale = state.screen_ppi() as f64;
+        let gear_center = gear_centers(&state.gears)[0];
+        let center = Point {
+            x: layers.width() as f64 / 2.0 + gear_center.x * scale,
+            y: layers.height() as f64 / 2.0 + gear_center.y * scale,
+        };
+        if state.show_construction {
+            construction::draw_construction(
+                &layer.context,
+                &GearGeometry::new(&first_gear.spec),
+                center,
+                scale,
+                construction::progress_at(perf::now()),
+            );
+        }
+        // helical teeth are cut by a rack in the normal plane, not the
+        // transverse one the gear is drawn in
+        if state.show_hobbing && first_gear.spec.helix_angle == 0.0 {
+            hobbing::draw_hobbing(
+                &layer.context,
+                &first_gear.spec,
+                state.rack_tip_radius_mult,
+                state.rack_protuberance_mult,
+                center,
+                scale,
+                hobbing::progress_at(perf::now()),
+            );
+        }
+    }
+    drop(state);
+    let next_frame =
+        Closure::once_into_js(move || animate_construction(layers, page_state, running));
+    web_sys::window()
+        .unwrap()
+        .request_animation_frame(next_frame.unchecked_ref())
+        .unwrap();
+}
+
+// runs `f` after the browser has had a chance to paint, so progress updates
+// show up between blocking export stages
+fn after_paint(f: impl FnOnce() + 'static) {
+    let callback = Closure::once_into_js(move || {
+        let f = Closure::once_into_js(f);
+        web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback(f.unchecked_ref())
+            .unwrap();
+    });
+    web_sys::window()
+        .unwrap()
+        .request_animation_frame(callback.unchecked_ref())
+        .unwrap();
+}
+
+// downloads bytes as a named file. object urls don't have the size limits
+// that data urls hit on large high-dpi exports.
+fn download_file(bytes: &[u8], mime_type: &str, filename: &str) -> Result<(), JsValue> {
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(
+        &js_sys::Array::of1(&js_sys::Uint8Array::from(bytes)),
+        &options,
+    )?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
 
-    // download pdf bytes
     let document = web_sys::window().unwrap().document().unwrap();
     let a = document
         .create_element("a")?
         .dyn_into::<web_sys::HtmlAnchorElement>()?;
-    a.set_attribute(
-        "href",
-        &("data:application/pdf;base64,".to_string()
-            + &general_purpose::STANDARD.encode(pdf_bytes)),
-    )?;
-    a.set_attribute("target", "_blank")?;
+    a.set_href(&url);
+    a.set_download(filename);
     a.click();
 
+    // give the browser a moment to start the download before releasing the blob
+    let revoke = Closure::once_into_js(move || {
+        web_sys::Url::revoke_object_url(&url).unwrap();
+    });
+    web_sys::window()
+        .unwrap()
+        .set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 1000)?;
+
+    Ok(())
+}
+
+fn export_dxf(page_state: &PageState) -> Result<(), JsValue> {
+    if page_state.cam_mode {
+        return export_cam_dxf(page_state);
+    }
+    let outlines = tessellate_for_export(page_state);
+    let spacers = nest_spacers(page_state, &outlines, f64::MIN);
+    let marks = drill_marks(page_state, &outlines, spacers.as_ref());
+    let roughing = page_state
+        .roughing
+        .then(|| roughing::paths(&outlines, page_state.roughing_tool));
+    let dxf = dxf::export_gears(
+        &outlines,
+        spacers.as_ref(),
+        &marks,
+        roughing.as_ref(),
+        page_state.holding_tabs,
+        page_state.dxf_curve_mode,
+    );
+    save_export(
+        dxf.as_bytes(),
+        "application/dxf",
+        "gears.dxf",
+        design_summary(page_state),
+    )
+}
+
+// the cam's profile as one closed polyline
+fn export_cam_dxf(page_state: &PageState) -> Result<(), JsValue> {
+    let cam = match page_state.cam() {
+        Ok(cam) => cam,
+        Err(err) => {
+            let window = web_sys::window().unwrap();
+            window.alert_with_message(&format!("The lift program doesn't work: {}", err))?;
+            return Ok(());
+        }
+    };
+    let outline = OutlineSegment::Line(cam.outline());
+    let outline = if page_state.mirror_exports {
+        outline.mirrored()
+    } else {
+        outline
+    };
+    let dxf = dxf::export_outline(&[outline]);
+    save_export(
+        dxf.as_bytes(),
+        "application/dxf",
+        "cam.dxf",
+        cam_summary(page_state),
+    )
+}
+
+// the same drawing as the dxf, as an svg sized in real units so it imports
+// at full scale
+fn export_svg(page_state: &PageState) -> Result<(), JsValue> {
+    if page_state.cam_mode {
+        return export_cam_svg(page_state);
+    }
+    let outlines = tessellate_for_export(page_state);
+    let spacers = nest_spacers(page_state, &outlines, f64::MIN);
+    let marks = drill_marks(page_state, &outlines, spacers.as_ref());
+    let Some(bounds) = layout_bounds(&outlines, &[], spacers.as_ref()) else {
+        return Ok(());
+    };
+    let svg = svg::export_gears(
+        &outlines,
+        spacers.as_ref(),
+        &marks,
+        bounds,
+        page_state.svg_units,
+        page_state.line_widths,
+        &watermark::saved(),
+    );
+    save_export(
+        svg.as_bytes(),
+        "image/svg+xml",
+        "gears.svg",
+        design_summary(page_state),
+    )
+}
+
+fn export_cam_svg(page_state: &PageState) -> Result<(), JsValue> {
+    let cam = match page_state.cam() {
+        Ok(cam) => cam,
+        Err(err) => {
+            let window = web_sys::window().unwrap();
+            window.alert_with_message(&format!("The lift program doesn't work: {}", err))?;
+            return Ok(());
+        }
+    };
+    let mirror = if page_state.mirror_exports { -1.0 } else { 1.0 };
+    let outline: Vec<Point> = cam
+        .outline()
+        .into_iter()
+        .map(|pt| Point {
+            x: mirror * pt.x,
+            y: pt.y,
+        })
+        .collect();
+    let Some(bounds) = paper::bounds(outline.iter().map(|pt| paper::Bounds::around(*pt, 0.0)))
+    else {
+        return Ok(());
+    };
+    let svg = svg::export_outline(
+        &outline,
+        bounds,
+        page_state.svg_units,
+        page_state.line_widths,
+    );
+    save_export(
+        svg.as_bytes(),
+        "image/svg+xml",
+        "cam.svg",
+        cam_summary(page_state),
+    )
+}
+
+fn cam_summary(page_state: &PageState) -> String {
+    format!(
+        "cam, {} in base circle, {}",
+        page_state.cam_base_radius, page_state.cam_program
+    )
+}
+
+// teeth on the exported rack, enough to check a cutter against
+const RACK_TEETH: u32 = 5;
+
+// the basic rack for the first gear's pitch, pressure angle and clearance
+fn export_rack_dxf(page_state: &PageState) -> Result<(), JsValue> {
+    let rack = geometry::basic_rack(
+        &page_state.gears[0].spec,
+        page_state.rack_tip_radius_mult,
+        page_state.rack_protuberance_mult,
+        RACK_TEETH,
+    );
+    let rack = if page_state.mirror_exports {
+        rack.iter().map(|segment| segment.mirrored()).collect()
+    } else {
+        rack
+    };
+    let dxf = dxf::export_outline(&rack);
+    let spec = &page_state.gears[0].spec;
+    let summary = format!(
+        "rack, {} DP, {}\u{b0}, {} tip radius, {} protuberance",
+        spec.diametric_pitch,
+        spec.tooth_angle,
+        page_state.rack_tip_radius_mult,
+        page_state.rack_protuberance_mult
+    );
+    save_export(dxf.as_bytes(), "application/dxf", "rack.dxf", summary)
+}
+
+// every gear as a cavity in its own blank, for machining molds or casting
+// masters
+fn export_mold_dxf(page_state: &PageState) -> Result<(), JsValue> {
+    if page_state.cam_mode {
+        let window = web_sys::window().unwrap();
+        window.alert_with_message("Turn off cam mode to export mold cavities.")?;
+        return Ok(());
+    }
+    let radii: Vec<f64> = page_state
+        .gears
+        .iter()
+        .map(|gear| GearGeometry::new(&gear.spec).outer_radius)
+        .collect();
+    let blanks = mold::blanks(page_state.mold_blank, &radii, page_state.mold_wall);
+    let outlines: Vec<GearOutline> = page_state
+        .gears
+        .iter()
+        .zip(&blanks)
+        .map(|(gear, blank)| tessellate_gear(gear, blank.center))
+        .collect();
+    let (outlines, blanks) = if page_state.mirror_exports {
+        (
+            outlines.into_iter().map(GearOutline::mirrored).collect(),
+            blanks.into_iter().map(mold::Blank::mirrored).collect(),
+        )
+    } else {
+        (outlines, blanks)
+    };
+    let dxf = dxf::export_mold(&outlines, &blanks, page_state.dxf_curve_mode);
+    let summary = format!(
+        "mold cavities, {} blanks, {} in wall, {}",
+        page_state.mold_blank.value(),
+        page_state.mold_wall,
+        design_summary(page_state)
+    );
+    save_export(dxf.as_bytes(), "application/dxf", "mold.dxf", summary)
+}
+
+// a card of slots and a comb of pins for measuring the cutter's kerf
+fn export_kerf_test_dxf(page_state: &PageState) -> Result<(), JsValue> {
+    let dxf = dxf::export_kerf_test(&kerf_test::kerf_test(page_state.kerf_test_size_mm));
+    let summary = format!("kerf test, {} mm", page_state.kerf_test_size_mm);
+    save_export(dxf.as_bytes(), "application/dxf", "kerf_test.dxf", summary)
+}
+
+// flattens the on-screen layers into a png. webgl and svg gears are drawn
+// from the same outlines through the canvas path, which looks the same and
+// avoids reading back from the gpu or rasterizing the svg.
+fn save_view_png(layers: &CanvasLayers, page_state: &PageState) -> Result<(), JsValue> {
+    let (width, height) = (layers.width(), layers.height());
+    let view = Layer::offscreen()?;
+    view.resize(width, height);
+    let context = &view.context;
+    context.draw_image_with_html_canvas_element(&layers.background.canvas, 0.0, 0.0)?;
+    if page_state.renderer == Renderer::Canvas {
+        for layer in layers.gears.borrow().iter() {
+            context.draw_image_with_html_canvas_element(&layer.canvas, 0.0, 0.0)?;
+        }
+    } else {
+        let styles: Vec<GearStyle> = page_state
+            .gears
+            .iter()
+            .map(|gear| gear.style.clone())
+            .collect();
+        context.translate(width as f64 / 2.0, height as f64 / 2.0)?;
+        draw_gears(
+            context,
+            &tessellate(page_state),
+            &styles,
+            &page_state.debug,
+            page_state.screen_ppi(),
+        );
+        context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)?;
+    }
+    context.draw_image_with_html_canvas_element(&layers.overlay.canvas, 0.0, 0.0)?;
+    context.draw_image_with_html_canvas_element(&layers.construction.canvas, 0.0, 0.0)?;
+
+    let data_url = view.canvas.to_data_url_with_type("image/png")?;
+    let encoded = data_url
+        .split_once(',')
+        .map(|(_, encoded)| encoded)
+        .unwrap_or_default();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    save_export(&bytes, "image/png", "gears.png", design_summary(page_state))
+}
+
+// the longest side of a recording, in pixels
+const ANIMATION_SIZE: f64 = 600.0;
+
+// the gears drawn turning on an offscreen canvas, framed around the train
+struct Animation {
+    layer: Layer,
+    // each gear tessellated about its own center, turned as it's drawn
+    outlines: Vec<GearOutline>,
+    centers: Vec<Point>,
+    styles: Vec<GearStyle>,
+    turning: Vec<animation::Turning>,
+    ppi: u32,
+    // where the page's origin lands on the canvas, in pixels
+    origin: Point,
+}
+
+impl Animation {
+    fn new(page_state: &PageState) -> Result<Self, JsValue> {
+        let centers = gear_centers(&page_state.gears);
+        let geometries: Vec<GearGeometry> = page_state
+            .gears
+            .iter()
+            .map(|gear| GearGeometry::new(&gear.spec))
+            .collect();
+        let bounds = paper::bounds(
+            geometries
+                .iter()
+                .zip(&centers)
+                .map(|(geometry, center)| paper::Bounds::around(*center, geometry.outer_radius)),
+        )
+        .unwrap_or(paper::Bounds::around(Point { x: 0.0, y: 0.0 }, 1.0));
+        // a little room around the tips
+        let margin = 0.05 * (bounds.max.x - bounds.min.x).max(bounds.max.y - bounds.min.y);
+        let (width, height) = (
+            bounds.max.x - bounds.min.x + 2.0 * margin,
+            bounds.max.y - bounds.min.y + 2.0 * margin,
+        );
+        let ppi = (ANIMATION_SIZE / width.max(height)).floor().max(1.0) as u32;
+        let layer = Layer::offscreen()?;
+        layer.resize(
+            (width * ppi as f64).ceil() as u32,
+            (height * ppi as f64).ceil() as u32,
+        );
+        Ok(Self {
+            layer,
+            outlines: page_state
+                .gears
+                .iter()
+                .map(|gear| tessellate_gear(gear, Point { x: 0.0, y: 0.0 }))
+                .collect(),
+            centers,
+            styles: page_state
+                .gears
+                .iter()
+                .map(|gear| gear.style.clone())
+                .collect(),
+            turning: page_state
+                .gears
+                .iter()
+                .zip(&geometries)
+                .map(|(gear, geometry)| animation::Turning {
+                    teeth: gear.spec.teeth,
+                    same_arbor: gear.same_arbor,
+                    tooth_center: animation::tooth_center(geometry),
+                })
+                .collect(),
+            ppi,
+            origin: Point {
+                x: (margin - bounds.min.x) * ppi as f64,
+                y: (margin - bounds.min.y) * ppi as f64,
+            },
+        })
+    }
+
+    // the train on white with the first gear turned `turn` radians
+    fn draw(&self, turn: f64) {
+        let context = &self.layer.context;
+        let scale = self.ppi as f64;
+        context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap();
+        context.set_fill_style_str("white");
+        context.fill_rect(
+            0.0,
+            0.0,
+            self.layer.canvas.width() as f64,
+            self.layer.canvas.height() as f64,
+        );
+        let debug_config = DebugConfig::default();
+        let angles = animation::angles(&self.turning, turn);
+        for (index, outline) in self.outlines.iter().enumerate() {
+            let center = self.centers[index];
+            context
+                .set_transform(
+                    1.0,
+                    0.0,
+                    0.0,
+                    1.0,
+                    self.origin.x + center.x * scale,
+                    self.origin.y + center.y * scale,
+                )
+                .unwrap();
+            context.rotate(angles[index]).unwrap();
+            draw_gear(
+                context,
+                outline,
+                &self.styles[index],
+                &debug_config,
+                self.ppi,
+            );
+        }
+        context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap();
+    }
+}
+
+// records the gears turning, as a gif drawn frame by frame or a webm
+// captured from the canvas as it plays
+fn export_animation(page_state: &PageState) -> Result<(), JsValue> {
+    if page_state.cam_mode {
+        let window = web_sys::window().unwrap();
+        window.alert_with_message("Turn off cam mode to export an animation.")?;
+        return Ok(());
+    }
+    let animation = Animation::new(page_state)?;
+    let frames = (page_state.animation_turns * animation::FRAMES_PER_TURN as f64).round() as u32;
+    let summary = format!(
+        "{}, {} turns",
+        design_summary(page_state),
+        page_state.animation_turns
+    );
+    match page_state.animation_format {
+        animation::Format::Gif => {
+            let (width, height) = (
+                animation.layer.canvas.width(),
+                animation.layer.canvas.height(),
+            );
+            let pixels: Vec<Vec<u8>> = (0..frames.max(1))
+                .map(|frame| {
+                    let turn =
+                        2.0 * f64::consts::PI * frame as f64 / animation::FRAMES_PER_TURN as f64;
+                    animation.draw(turn);
+                    let image = animation
+                        .layer
+                        .context
+                        .get_image_data(0.0, 0.0, width as f64, height as f64)
+                        .unwrap();
+                    gif::quantize(&image.data())
+                })
+                .collect();
+            let bytes = gif::encode(width as u16, height as u16, &pixels, animation::FRAME_DELAY);
+            save_export(&bytes, "image/gif", "gears.gif", summary)
+        }
+        animation::Format::WebM => record_webm(animation, frames, summary),
+    }
+}
+
+// plays the animation on its canvas in real time while a media recorder
+// captures it, then saves the video once it's stopped
+fn record_webm(animation: Animation, frames: u32, summary: String) -> Result<(), JsValue> {
+    let stream = animation.layer.canvas.capture_stream()?;
+    let options = web_sys::MediaRecorderOptions::new();
+    options.set_mime_type("video/webm");
+    let recorder = web_sys::MediaRecorder::new_with_media_stream_and_media_recorder_options(
+        &stream, &options,
+    )?;
+    let chunks = js_sys::Array::new();
+    let data_chunks = chunks.clone();
+    let on_data = Closure::wrap(Box::new(move |event: web_sys::BlobEvent| {
+        if let Some(data) = event.data() {
+            data_chunks.push(&data);
+        }
+    }) as Box<dyn Fn(web_sys::BlobEvent)>);
+    recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+    on_data.forget();
+
+    // the recorder hands over a blob, which is read back as bytes so the
+    // video goes in the export history like any other export
+    let on_stop = Closure::once_into_js(move || {
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type("video/webm");
+        let blob = web_sys::Blob::new_with_blob_sequence_and_options(&chunks, &options).unwrap();
+        let reader = web_sys::FileReader::new().unwrap();
+        let loaded_reader = reader.clone();
+        let on_load = Closure::once_into_js(move || {
+            let bytes = js_sys::Uint8Array::new(&loaded_reader.result().unwrap()).to_vec();
+            save_export(&bytes, "video/webm", "gears.webm", summary).unwrap();
+        });
+        reader.set_onload(Some(on_load.unchecked_ref()));
+        reader.read_as_array_buffer(&blob).unwrap();
+    });
+    recorder.set_onstop(Some(on_stop.unchecked_ref()));
+
+    animation.draw(0.0);
+    recorder.start()?;
+    let duration = frames as f64 * animation::FRAME_DELAY as f64 * 10.0;
+    let seconds_per_turn =
+        animation::FRAMES_PER_TURN as f64 * animation::FRAME_DELAY as f64 / 100.0;
+    play_webm(
+        Rc::new(animation),
+        recorder,
+        perf::now(),
+        duration,
+        seconds_per_turn,
+    );
     Ok(())
 }
 
+// draws the frame for the time since `start`, in milliseconds, and asks for
+// the next one until `duration` is up
+fn play_webm(
+    animation: Rc<Animation>,
+    recorder: web_sys::MediaRecorder,
+    start: f64,
+    duration: f64,
+    seconds_per_turn: f64,
+) {
+    let elapsed = (perf::now() - start).min(duration);
+    animation.draw(2.0 * f64::consts::PI * elapsed / 1000.0 / seconds_per_turn);
+    if elapsed >= duration {
+        recorder.stop().unwrap();
+        return;
+    }
+    let next_frame = Closure::once_into_js(move || {
+        play_webm(animation, recorder, start, duration, seconds_per_turn)
+    });
+    web_sys::window()
+        .unwrap()
+        .request_animation_frame(next_frame.unchecked_ref())
+        .unwrap();
+}
+
+// downloads a finished export and keeps it in the export history
+fn save_export(
+    bytes: &[u8],
+    mime_type: &'static str,
+    filename: &str,
+    summary: String,
+) -> Result<(), JsValue> {
+    export_history::record(filename, mime_type, bytes, summary);
+    download_file(bytes, mime_type, filename)
+}
+
+// tooth counts, pitch and pressure angle of the first gear, like
+// "50/10T, 12 DP, 20°"
+fn design_summary(page_state: &PageState) -> String {
+    let teeth = page_state
+        .gears
+        .iter()
+        .map(|gear| gear.spec.teeth.to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+    let spec = &page_state.gears[0].spec;
+    format!(
+        "{}T, {} DP, {}\u{b0}",
+        teeth, spec.diametric_pitch, spec.tooth_angle
+    )
+}
+
+// the design's name, or what it is when it hasn't been given one
+fn design_title(page_state: &PageState) -> String {
+    if page_state.name.is_empty() {
+        "Gears".to_string()
+    } else {
+        page_state.name.clone()
+    }
+}
+
+// the parameters of every gear, for searching exported files by
+fn design_keywords(page_state: &PageState) -> Vec<String> {
+    let mut keywords = vec!["gears".to_string()];
+    for gear in &page_state.gears {
+        let spec = &gear.spec;
+        for keyword in [
+            format!("{} teeth", spec.teeth),
+            format!("{} DP", spec.diametric_pitch),
+            format!("{}\u{b0} pressure angle", spec.tooth_angle),
+        ] {
+            if !keywords.contains(&keyword) {
+                keywords.push(keyword);
+            }
+        }
+    }
+    keywords
+}
+
 fn create_sidebar(
+    store: Rc<store::Store<PageState, Action>>,
     state: Rc<RefCell<PageState>>,
-    redraw_closure: &Closure<dyn Fn()>,
     print_gears_closure: &Closure<dyn Fn()>,
+    save_view_closure: &Closure<dyn Fn()>,
 ) -> Result<web_sys::Element, JsValue> {
     let document = web_sys::window().unwrap().document().unwrap();
     let sidebar = document.create_element("div")?;
-    sidebar.set_attribute("style", "position: fixed; left: 0; top: 0; width: 200px; height: 100%; background-color: #f0f0f0;").unwrap();
+    sidebar.set_attribute("style", &format!("position: fixed; left: 0; top: 0; width: {}px; height: 100%; background-color: #f0f0f0;", SIDEBAR_WIDTH)).unwrap();
+    // embedded pages only show the gears, and are driven over postMessage
+    if embed::is_embedded() {
+        sidebar
+            .dyn_ref::<web_sys::HtmlElement>()
+            .unwrap()
+            .style()
+            .set_property("display", "none")?;
+    }
 
     // add title
     let title = document.create_element("h2")?;
@@ -179,7 +1764,64 @@ fn create_sidebar(
     title.set_text_content(Some("Gear Designer"));
     sidebar.append_child(&title)?;
 
-    // add gear specs subtitle
+    // offers back the designs a crashed or abandoned page left unsaved
+    let recovery_banner = document.create_element("div")?;
+    recovery_banner.set_attribute(
+        "style",
+        "display: none; width: 80%; margin: 0 10% 1em 10%; padding: 4px; box-sizing: border-box; background-color: #fff3c4; border: 1px solid #e0c050;",
+    )?;
+    sidebar.append_child(&recovery_banner)?;
+    let recovery_text = document.create_element("p")?;
+    recovery_text.set_attribute("style", "margin: 0 0 4px 0;")?;
+    recovery_banner.append_child(&recovery_text)?;
+    for (action, text) in [("restore", "Restore"), ("discard", "Discard")] {
+        let button = document.create_element("button")?;
+        button.set_attribute("data-session", action)?;
+        button.set_attribute("style", "margin-right: 4px;")?;
+        button.set_text_content(Some(text));
+        recovery_banner.append_child(&button)?;
+    }
+    // the session waiting on the user, snapshots hold off until it's handled
+    let pending_session: Rc<RefCell<Option<session::Session>>> = Rc::new(RefCell::new(None));
+    let offer_banner = recovery_banner.clone();
+    let offer_pending = pending_session.clone();
+    session::load(move |session| {
+        let saved_at = js_sys::Date::new(&JsValue::from_f64(session.saved_at))
+            .to_locale_string("default", &JsValue::UNDEFINED);
+        let designs = match session.designs.len() {
+            1 => "1 design".to_string(),
+            count => format!("{} designs", count),
+        };
+        recovery_text.set_text_content(Some(&format!(
+            "Restore the unsaved session from {} ({})?",
+            String::from(saved_at),
+            designs
+        )));
+        set_display(&offer_banner, true);
+        *offer_pending.borrow_mut() = Some(session);
+    });
+
+    // a tab per design open in the session, plus one to open a copy
+    let tabs_bar = document.create_element("div")?;
+    tabs_bar.set_attribute(
+        "style",
+        "display: flex; flex-wrap: wrap; width: 80%; margin: 0 10%;",
+    )?;
+    sidebar.append_child(&tabs_bar)?;
+    let tabs = Rc::new(RefCell::new(DesignTabs::new(state.borrow().design())));
+    build_tabs_bar(&tabs_bar, &tabs.borrow(), &state.borrow().design())?;
+
+    // every problem with the train under one badge, clicking one picks out
+    // the gears it's about
+    let warnings_section = document.create_element("details")?;
+    sidebar.append_child(&warnings_section)?;
+    let warnings_summary = document.create_element("summary")?;
+    warnings_section.append_child(&warnings_summary)?;
+    let warnings_list = document.create_element("div")?;
+    warnings_section.append_child(&warnings_list)?;
+    build_warnings(&warnings_summary, &warnings_list, &state.borrow())?;
+
+    // add gear specs subtitle
     let gear_specs_subtitle = document.create_element("h3")?;
     gear_specs_subtitle
         .set_attribute("style", "text-align: center; width: 100%;")
@@ -212,199 +1854,4538 @@ fn create_sidebar(
     gear_diametric_pitch_input
         .set_attribute(
             "value",
-            &state.borrow().left_gear_spec.diametric_pitch.to_string(),
+            &locale::format(state.borrow().gears[0].spec.diametric_pitch),
         )
         .unwrap();
     gear_diametric_pitch_input
         .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
         .unwrap();
     sidebar.append_child(&gear_diametric_pitch_input)?;
+    gear_inputs::set_step(&gear_diametric_pitch_input, 1.0, false)?;
+
+    // pressure angle input
+    let tooth_angle_input = append_text_input(
+        &sidebar,
+        "tooth_angle",
+        "Pressure Angle (deg):",
+        "Enter pressure angle",
+        &locale::format(state.borrow().gears[0].spec.tooth_angle),
+    )?;
+    gear_inputs::set_step(&tooth_angle_input, 0.5, false)?;
+    let tooth_angle_presets = gear_inputs::PressureAnglePresets::new(
+        &sidebar,
+        "tooth_angle",
+        state.borrow().gears[0].spec.tooth_angle,
+    )?;
+
+    // linked toggle, unlinking gives each gear its own pitch, angle and clearance
+    let linked_input = append_checkbox(&sidebar, "linked", "Linked:", state.borrow().linked)?;
+
+    // a section per gear in the train, rebuilt whenever gears are added or moved
+    let gear_sections = document.create_element("div")?;
+    sidebar.append_child(&gear_sections)?;
+    let gear_inputs = Rc::new(RefCell::new(build_gear_sections(
+        &gear_sections,
+        &state.borrow().gears,
+        state.borrow().linked,
+    )?));
+
+    // duplicate inserts a copy right after the gear, swap trades specs with
+    // the next one and leaves the colors in place. the index plate is an
+    // export and leaves the design alone. cutouts and recommended hubs are
+    // handled here too, since the inputs have to be rebuilt.
+    let action_state = state.clone();
+    let action_store = store.clone();
+    let gear_action_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(target) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        else {
+            return;
+        };
+        let action = target.get_attribute("data-action").unwrap_or_default();
+        let Some(index) = target
+            .get_attribute("data-gear")
+            .and_then(|index| index.parse::<usize>().ok())
+        else {
+            return;
+        };
+        let action = match action.as_str() {
+            "index_plate" => {
+                let teeth = action_state.borrow().gears[index].spec.teeth as u32;
+                print_index_plate(teeth).unwrap();
+                return;
+            }
+            "duplicate" => Action::DuplicateGear(index),
+            "swap" => Action::SwapGears(index),
+            "apply_hub" => Action::ApplyRecommendedHub(index),
+            "remove_cutout" => Action::RemoveCutout(index),
+            "add_shape" => Action::AddShape(index),
+            "remove_shape" => {
+                let Some(shape) = target
+                    .get_attribute("data-shape")
+                    .and_then(|shape| shape.parse::<usize>().ok())
+                else {
+                    return;
+                };
+                Action::RemoveShape { gear: index, shape }
+            }
+            _ => return,
+        };
+        action_store.dispatch(action);
+    }) as Box<dyn Fn(web_sys::Event)>);
+    gear_sections
+        .add_event_listener_with_callback("click", gear_action_closure.as_ref().unchecked_ref())?;
+    gear_action_closure.forget();
+
+    // an svg picked for a gear's cutout is read as text and starts centered
+    // on the gear at its drawn size
+    let cutout_store = store.clone();
+    let cutout_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(input) = event
+            .target()
+            .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+            .filter(|input| input.get_attribute("data-action").as_deref() == Some("import_cutout"))
+        else {
+            return;
+        };
+        let Some(index) = input
+            .get_attribute("data-gear")
+            .and_then(|index| index.parse::<usize>().ok())
+        else {
+            return;
+        };
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        let reader = web_sys::FileReader::new().unwrap();
+        let loaded_reader = reader.clone();
+        let store = cutout_store.clone();
+        let name = file.name();
+        let on_load = Closure::once_into_js(move || {
+            let text = loaded_reader
+                .result()
+                .ok()
+                .and_then(|result| result.as_string())
+                .unwrap_or_default();
+            match cutouts::ImportedCutout::from_svg(&name, &text) {
+                Ok(cutout) => store.dispatch(Action::ImportCutout {
+                    gear: index,
+                    cutout,
+                }),
+                Err(err) => {
+                    web_sys::window()
+                        .unwrap()
+                        .alert_with_message(&format!("Couldn't read the cutout: {}", err))
+                        .unwrap();
+                }
+            }
+        });
+        reader.set_onload(Some(on_load.unchecked_ref()));
+        reader.read_as_text(&file).unwrap();
+    }) as Box<dyn Fn(web_sys::Event)>);
+    gear_sections
+        .add_event_listener_with_callback("change", cutout_closure.as_ref().unchecked_ref())?;
+    cutout_closure.forget();
+
+    // add button to reset the design
+    let reset_button = document.create_element("button")?;
+    reset_button.set_attribute("id", "reset_button").unwrap();
+    reset_button.set_text_content(Some("Reset to Defaults"));
+    reset_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    sidebar.append_child(&reset_button)?;
+
+    // named designs, collapsed by default. saving stores the current design
+    // under the name above the list.
+    let designs_section = document.create_element("details")?;
+    sidebar.append_child(&designs_section)?;
+    let designs_summary = document.create_element("summary")?;
+    designs_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    designs_summary.set_text_content(Some("Designs"));
+    designs_section.append_child(&designs_summary)?;
+    let design_name_input = append_text_input(
+        &designs_section,
+        "design_name",
+        "Name:",
+        "Untitled",
+        &state.borrow().name,
+    )?;
+    let save_design_button = document.create_element("button")?;
+    save_design_button
+        .set_attribute("id", "save_design_button")
+        .unwrap();
+    save_design_button.set_text_content(Some("Save Design"));
+    save_design_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    designs_section.append_child(&save_design_button)?;
+    let designs_list = document.create_element("div")?;
+    designs_list
+        .set_attribute("style", "margin-top: 10px;")
+        .unwrap();
+    designs_section.append_child(&designs_list)?;
+    build_designs_list(&designs_list)?;
+
+    // recent designs, collapsed by default and listed from the autosaves
+    // whenever it's opened
+    let recent_section = document.create_element("details")?;
+    sidebar.append_child(&recent_section)?;
+    let recent_summary = document.create_element("summary")?;
+    recent_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    recent_summary.set_text_content(Some("Recent Designs"));
+    recent_section.append_child(&recent_summary)?;
+    let recent_list = document.create_element("div")?;
+    recent_section.append_child(&recent_list)?;
+
+    // clock train assistant, suggests tooth counts and replaces the design
+    // with the train they make
+    let clock_section = document.create_element("details")?;
+    sidebar.append_child(&clock_section)?;
+    let clock_summary = document.create_element("summary")?;
+    clock_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    clock_summary.set_text_content(Some("Clock Train"));
+    clock_section.append_child(&clock_summary)?;
+
+    // label for clock train select
+    let clock_train_label = document.create_element("label")?;
+    clock_train_label
+        .set_attribute("for", "clock_train")
+        .unwrap();
+    clock_train_label.set_text_content(Some("Train:"));
+    clock_train_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    clock_section.append_child(&clock_train_label)?;
+
+    // clock train select
+    let clock_train_select = document.create_element("select")?;
+    clock_train_select
+        .set_attribute("id", "clock_train")
+        .unwrap();
+    clock_train_select
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    for (train, text) in [
+        (ClockTrain::Going, "Going Train"),
+        (ClockTrain::MotionWork, "Motion Work (12:1)"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", train.value())?;
+        option.set_text_content(Some(text));
+        clock_train_select.append_child(&option)?;
+    }
+    clock_section.append_child(&clock_train_select)?;
+
+    // the going train's ratio comes from the pendulum and escape wheel
+    let pendulum_period_input = append_text_input(
+        &clock_section,
+        "pendulum_period",
+        "Pendulum Period (s):",
+        "Enter pendulum period",
+        "2",
+    )?;
+    gear_inputs::set_step(&pendulum_period_input, 0.1, false)?;
+    let escape_teeth_input = append_text_input(
+        &clock_section,
+        "escape_teeth",
+        "Escape Wheel Teeth:",
+        "Enter escape wheel teeth",
+        "30",
+    )?;
+    gear_inputs::set_step(&escape_teeth_input, 1.0, true)?;
+
+    let clock_button = document.create_element("button")?;
+    clock_button.set_attribute("id", "clock_button").unwrap();
+    clock_button.set_text_content(Some("Generate Train"));
+    clock_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    clock_section.append_child(&clock_button)?;
+    let clock_result = document.create_element("p")?;
+    clock_result
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    clock_section.append_child(&clock_result)?;
+
+    // crossed helical assistant, works out the helix angles for a pair on
+    // shafts at an angle and replaces the design with it
+    let crossed_section = document.create_element("details")?;
+    sidebar.append_child(&crossed_section)?;
+    let crossed_summary = document.create_element("summary")?;
+    crossed_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    crossed_summary.set_text_content(Some("Crossed Helical Pair"));
+    crossed_section.append_child(&crossed_summary)?;
+
+    let shaft_angle_input = append_text_input(
+        &crossed_section,
+        "shaft_angle",
+        "Shaft Angle (deg):",
+        "Enter shaft angle",
+        "90",
+    )?;
+    gear_inputs::set_step(&shaft_angle_input, 5.0, false)?;
+    let crossed_ratio_input = append_text_input(
+        &crossed_section,
+        "crossed_ratio",
+        "Ratio (driven : driver):",
+        "Enter ratio",
+        "2",
+    )?;
+    gear_inputs::set_step(&crossed_ratio_input, 0.5, false)?;
+
+    let crossed_button = document.create_element("button")?;
+    crossed_button
+        .set_attribute("id", "crossed_helical_button")
+        .unwrap();
+    crossed_button.set_text_content(Some("Generate Pair"));
+    crossed_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    crossed_section.append_child(&crossed_button)?;
+    let crossed_result = document.create_element("p")?;
+    crossed_result
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    crossed_section.append_child(&crossed_result)?;
+
+    // identifying an existing gear from its measurements, and starting the
+    // first gear off as a replacement for it
+    let identify_section = document.create_element("details")?;
+    sidebar.append_child(&identify_section)?;
+    let identify_summary = document.create_element("summary")?;
+    identify_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    identify_summary.set_text_content(Some("Measure an Existing Gear"));
+    identify_section.append_child(&identify_summary)?;
+
+    let identify_diameter_input = append_text_input(
+        &identify_section,
+        "identify_outside_diameter",
+        "Outside Diameter (in):",
+        "Enter measured outside diameter",
+        "",
+    )?;
+    gear_inputs::set_step(&identify_diameter_input, 0.001, false)?;
+    let identify_teeth_input = append_text_input(
+        &identify_section,
+        "identify_teeth",
+        "Teeth:",
+        "Enter tooth count",
+        "",
+    )?;
+    gear_inputs::set_step(&identify_teeth_input, 1.0, true)?;
+    // pins are optional, without them the pressure angle is assumed
+    let identify_over_pins_input = append_text_input(
+        &identify_section,
+        "identify_over_pins",
+        "Over Pins (in, optional):",
+        "Enter measurement over pins",
+        "",
+    )?;
+    gear_inputs::set_step(&identify_over_pins_input, 0.001, false)?;
+    let identify_pin_diameter_input = append_text_input(
+        &identify_section,
+        "identify_pin_diameter",
+        "Pin Diameter (in):",
+        "Enter pin diameter",
+        "",
+    )?;
+    gear_inputs::set_step(&identify_pin_diameter_input, 0.001, false)?;
+
+    let identify_button = document.create_element("button")?;
+    identify_button
+        .set_attribute("id", "identify_button")
+        .unwrap();
+    identify_button.set_text_content(Some("Identify and Use"));
+    identify_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    identify_section.append_child(&identify_button)?;
+    let identify_result = document.create_element("p")?;
+    identify_result
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    identify_section.append_child(&identify_result)?;
+
+    // cam mode swaps the gears for a cam driven by a lift program, drawn and
+    // exported in their place
+    let cam_section = document.create_element("details")?;
+    sidebar.append_child(&cam_section)?;
+    let cam_summary = document.create_element("summary")?;
+    cam_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    cam_summary.set_text_content(Some("Cam"));
+    cam_section.append_child(&cam_summary)?;
+
+    let cam_mode_input = append_checkbox(
+        &cam_section,
+        "cam_mode",
+        "Cam Mode:",
+        state.borrow().cam_mode,
+    )?;
+
+    let cam_program_input = append_text_input(
+        &cam_section,
+        "cam_program",
+        "Lift Program:",
+        "e.g. rise 120 0.5 cycloidal, dwell 60, ...",
+        &state.borrow().cam_program,
+    )?;
+    let cam_program_help = document.create_element("p")?;
+    cam_program_help.set_attribute(
+        "style",
+        "width: 80%; margin: 2px 10%; font-size: small; color: #666;",
+    )?;
+    cam_program_help.set_text_content(Some(
+        "dwell, rise or fall with degrees, then lift in inches and uniform, harmonic or cycloidal motion for rises and falls. covers 360\u{b0} and ends at zero lift.",
+    ));
+    cam_section.append_child(&cam_program_help)?;
+    let cam_base_radius_input = append_text_input(
+        &cam_section,
+        "cam_base_radius",
+        "Base Circle Radius (in):",
+        "Enter base circle radius",
+        &locale::format(state.borrow().cam_base_radius),
+    )?;
+    gear_inputs::set_step(&cam_base_radius_input, 0.125, false)?;
+
+    let cam_follower_label = document.create_element("label")?;
+    cam_follower_label.set_attribute("for", "cam_follower")?;
+    cam_follower_label.set_text_content(Some("Follower:"));
+    cam_follower_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    cam_section.append_child(&cam_follower_label)?;
+    let cam_follower_select = document.create_element("select")?;
+    cam_follower_select.set_attribute("id", "cam_follower")?;
+    cam_follower_select
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    let follower_value = match state.borrow().cam_follower {
+        cam::Follower::KnifeEdge => "knife",
+        cam::Follower::Roller { .. } => "roller",
+        cam::Follower::FlatFaced => "flat",
+    };
+    for (value, text) in [
+        ("knife", "Knife Edge"),
+        ("roller", "Roller"),
+        ("flat", "Flat Faced"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", value)?;
+        option.set_text_content(Some(text));
+        if value == follower_value {
+            option.set_attribute("selected", "")?;
+        }
+        cam_follower_select.append_child(&option)?;
+    }
+    cam_section.append_child(&cam_follower_select)?;
+    let roller_radius = match state.borrow().cam_follower {
+        cam::Follower::Roller { radius } => radius,
+        _ => DEFAULT_ROLLER_RADIUS,
+    };
+    let cam_roller_radius_input = append_text_input(
+        &cam_section,
+        "cam_roller_radius",
+        "Roller Radius (in):",
+        "Enter roller radius",
+        &locale::format(roller_radius),
+    )?;
+    gear_inputs::set_step(&cam_roller_radius_input, 0.0625, false)?;
+
+    // an imported outline of an existing gear, drawn over the generated one
+    // to match them up
+    let reference_section = document.create_element("details")?;
+    sidebar.append_child(&reference_section)?;
+    let reference_summary = document.create_element("summary")?;
+    reference_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    reference_summary.set_text_content(Some("Reference Outline"));
+    reference_section.append_child(&reference_summary)?;
+
+    let reference_file_label = document.create_element("label")?;
+    reference_file_label.set_attribute("for", "reference_file")?;
+    reference_file_label.set_text_content(Some("DXF or SVG:"));
+    reference_file_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    reference_section.append_child(&reference_file_label)?;
+    let reference_file_input = document
+        .create_element("input")?
+        .dyn_into::<HtmlInputElement>()?;
+    reference_file_input.set_attribute("id", "reference_file")?;
+    reference_file_input.set_attribute("type", "file")?;
+    reference_file_input.set_attribute("accept", ".dxf,.svg")?;
+    reference_file_input
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    reference_section.append_child(&reference_file_input)?;
+
+    let reference_x_input = append_text_input(
+        &reference_section,
+        "reference_x",
+        "Offset X (in):",
+        "Enter horizontal offset",
+        &locale::format(state.borrow().reference_alignment.offset.x),
+    )?;
+    gear_inputs::set_step(&reference_x_input, 0.01, false)?;
+    let reference_y_input = append_text_input(
+        &reference_section,
+        "reference_y",
+        "Offset Y (in):",
+        "Enter vertical offset",
+        &locale::format(state.borrow().reference_alignment.offset.y),
+    )?;
+    gear_inputs::set_step(&reference_y_input, 0.01, false)?;
+    let reference_rotation_input = append_text_input(
+        &reference_section,
+        "reference_rotation",
+        "Rotation (deg):",
+        "Enter rotation",
+        &locale::format(state.borrow().reference_alignment.rotation),
+    )?;
+    gear_inputs::set_step(&reference_rotation_input, 0.5, false)?;
+
+    let reference_center_button = document.create_element("button")?;
+    reference_center_button.set_attribute("id", "reference_center_button")?;
+    reference_center_button.set_text_content(Some("Center on First Gear"));
+    reference_center_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    reference_section.append_child(&reference_center_button)?;
+    let reference_remove_button = document.create_element("button")?;
+    reference_remove_button.set_attribute("id", "reference_remove_button")?;
+    reference_remove_button.set_text_content(Some("Remove Reference"));
+    reference_remove_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    reference_section.append_child(&reference_remove_button)?;
+
+    // moves the reference so it sits on the first gear, offset inputs and all
+    let center_reference = {
+        let state = state.clone();
+        let store = store.clone();
+        let x_input = reference_x_input.clone();
+        let y_input = reference_y_input.clone();
+        Rc::new(move || {
+            let alignment = {
+                let state = state.borrow();
+                let Some(reference) = &state.reference else {
+                    return;
+                };
+                let offset = reference.offset_to(gear_centers(&state.gears)[0]);
+                reference::Alignment {
+                    offset: Point {
+                        x: (offset.x * 1000.0).round() / 1000.0,
+                        y: (offset.y * 1000.0).round() / 1000.0,
+                    },
+                    ..state.reference_alignment
+                }
+            };
+            for (input, value) in [
+                (&x_input, alignment.offset.x),
+                (&y_input, alignment.offset.y),
+            ] {
+                input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .set_value(&locale::format(value));
+            }
+            store.dispatch(Action::AlignReference(alignment));
+        })
+    };
+
+    // files are read as text, then centered on the first gear to start from
+    let reference_load_store = store.clone();
+    let reference_load_input = reference_file_input.clone();
+    let reference_load_center = center_reference.clone();
+    let reference_load_closure = Closure::wrap(Box::new(move || {
+        let Some(file) = reference_load_input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        let reader = web_sys::FileReader::new().unwrap();
+        let loaded_reader = reader.clone();
+        let store = reference_load_store.clone();
+        let center = reference_load_center.clone();
+        let name = file.name();
+        let on_load = Closure::once_into_js(move || {
+            let text = loaded_reader
+                .result()
+                .ok()
+                .and_then(|result| result.as_string())
+                .unwrap_or_default();
+            match reference::Reference::load(&name, &text) {
+                Ok(reference) => {
+                    store.dispatch(Action::LoadReference(reference));
+                    center();
+                }
+                Err(err) => {
+                    web_sys::window()
+                        .unwrap()
+                        .alert_with_message(&format!("Couldn't read the outline: {}", err))
+                        .unwrap();
+                }
+            }
+        });
+        reader.set_onload(Some(on_load.unchecked_ref()));
+        reader.read_as_text(&file).unwrap();
+    }) as Box<dyn Fn()>);
+    reference_file_input.add_event_listener_with_callback(
+        "change",
+        reference_load_closure.as_ref().unchecked_ref(),
+    )?;
+    reference_load_closure.forget();
+
+    let reference_center_closure = Closure::wrap(Box::new(move || {
+        center_reference();
+    }) as Box<dyn Fn()>);
+    reference_center_button.add_event_listener_with_callback(
+        "click",
+        reference_center_closure.as_ref().unchecked_ref(),
+    )?;
+    reference_center_closure.forget();
+
+    let reference_remove_store = store.clone();
+    let reference_remove_input = reference_file_input.clone();
+    let reference_remove_closure = Closure::wrap(Box::new(move || {
+        reference_remove_input.set_value("");
+        reference_remove_store.dispatch(Action::RemoveReference);
+    }) as Box<dyn Fn()>);
+    reference_remove_button.add_event_listener_with_callback(
+        "click",
+        reference_remove_closure.as_ref().unchecked_ref(),
+    )?;
+    reference_remove_closure.forget();
+
+    // a photo of an existing gear under the generated one, scaled by
+    // picking two points a known distance apart on it
+    let underlay_section = document.create_element("details")?;
+    sidebar.append_child(&underlay_section)?;
+    let underlay_summary = document.create_element("summary")?;
+    underlay_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    underlay_summary.set_text_content(Some("Photo Underlay"));
+    underlay_section.append_child(&underlay_summary)?;
+
+    let underlay_file_label = document.create_element("label")?;
+    underlay_file_label.set_attribute("for", "underlay_file")?;
+    underlay_file_label.set_text_content(Some("Photo:"));
+    underlay_file_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    underlay_section.append_child(&underlay_file_label)?;
+    let underlay_file_input = document
+        .create_element("input")?
+        .dyn_into::<HtmlInputElement>()?;
+    underlay_file_input.set_attribute("id", "underlay_file")?;
+    underlay_file_input.set_attribute("type", "file")?;
+    underlay_file_input.set_attribute("accept", "image/*")?;
+    underlay_file_input
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    underlay_section.append_child(&underlay_file_input)?;
+
+    let underlay_x_input = append_text_input(
+        &underlay_section,
+        "underlay_x",
+        "Offset X (in):",
+        "Enter horizontal offset",
+        &locale::format(state.borrow().underlay_placement.offset.x),
+    )?;
+    gear_inputs::set_step(&underlay_x_input, 0.01, false)?;
+    let underlay_y_input = append_text_input(
+        &underlay_section,
+        "underlay_y",
+        "Offset Y (in):",
+        "Enter vertical offset",
+        &locale::format(state.borrow().underlay_placement.offset.y),
+    )?;
+    gear_inputs::set_step(&underlay_y_input, 0.01, false)?;
+    let underlay_opacity_input = append_text_input(
+        &underlay_section,
+        "underlay_opacity",
+        "Opacity (0 to 1):",
+        "Enter photo opacity",
+        &locale::format(state.borrow().underlay_placement.opacity),
+    )?;
+    gear_inputs::set_step(&underlay_opacity_input, 0.1, false)?;
+    let underlay_distance_input = append_text_input(
+        &underlay_section,
+        "underlay_distance",
+        "Known Distance (in):",
+        "Enter distance between the points",
+        "1",
+    )?;
+    gear_inputs::set_step(&underlay_distance_input, 0.125, false)?;
+
+    let underlay_calibrate_button = document.create_element("button")?;
+    underlay_calibrate_button.set_attribute("id", "underlay_calibrate_button")?;
+    underlay_calibrate_button.set_text_content(Some("Calibrate Scale"));
+    underlay_calibrate_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    underlay_section.append_child(&underlay_calibrate_button)?;
+    let underlay_remove_button = document.create_element("button")?;
+    underlay_remove_button.set_attribute("id", "underlay_remove_button")?;
+    underlay_remove_button.set_text_content(Some("Remove Photo"));
+    underlay_remove_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    underlay_section.append_child(&underlay_remove_button)?;
+    let underlay_status = document.create_element("p")?;
+    underlay_status
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    underlay_section.append_child(&underlay_status)?;
+
+    // photos are read as data urls, and drawn once the browser has decoded
+    // them. a new photo starts uncalibrated.
+    let underlay_load_store = store.clone();
+    let underlay_load_input = underlay_file_input.clone();
+    let underlay_load_status = underlay_status.clone();
+    let underlay_load_closure = Closure::wrap(Box::new(move || {
+        let Some(file) = underlay_load_input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        let reader = web_sys::FileReader::new().unwrap();
+        let loaded_reader = reader.clone();
+        let store = underlay_load_store.clone();
+        let status = underlay_load_status.clone();
+        let on_load = Closure::once_into_js(move || {
+            let Some(source) = loaded_reader
+                .result()
+                .ok()
+                .and_then(|result| result.as_string())
+            else {
+                return;
+            };
+            let image = web_sys::HtmlImageElement::new().unwrap();
+            let decoded_store = store.clone();
+            let on_decode = Closure::once_into_js(move || {
+                decoded_store.dispatch(Action::UnderlayDecoded);
+            });
+            image.set_onload(Some(on_decode.unchecked_ref()));
+            image.set_src(&source);
+            store.dispatch(Action::LoadUnderlay(underlay::Underlay { image, source }));
+            status.set_text_content(Some(
+                "Calibrate the scale by picking two points a known distance apart.",
+            ));
+        });
+        reader.set_onload(Some(on_load.unchecked_ref()));
+        reader.read_as_data_url(&file).unwrap();
+    }) as Box<dyn Fn()>);
+    underlay_file_input.add_event_listener_with_callback(
+        "change",
+        underlay_load_closure.as_ref().unchecked_ref(),
+    )?;
+    underlay_load_closure.forget();
+
+    let underlay_calibrate_state = state.clone();
+    let underlay_calibrate_store = store.clone();
+    let underlay_calibrate_status = underlay_status.clone();
+    let underlay_calibrate_closure = Closure::wrap(Box::new(move || {
+        if underlay_calibrate_state.borrow().underlay.is_none() {
+            underlay_calibrate_status.set_text_content(Some("Choose a photo first."));
+            return;
+        }
+        underlay_calibrate_status.set_text_content(Some("Click the first point on the photo."));
+        underlay_calibrate_store.dispatch(Action::StartCalibration);
+    }) as Box<dyn Fn()>);
+    underlay_calibrate_button.add_event_listener_with_callback(
+        "click",
+        underlay_calibrate_closure.as_ref().unchecked_ref(),
+    )?;
+    underlay_calibrate_closure.forget();
+
+    // while calibrating, clicks on the workspace pick the two points. the
+    // second one rescales the photo and moves the offset inputs to match.
+    let pick_state = state.clone();
+    let pick_store = store.clone();
+    let pick_status = underlay_status.clone();
+    let pick_x_input = underlay_x_input.clone();
+    let pick_y_input = underlay_y_input.clone();
+    let pick_distance_input = underlay_distance_input.clone();
+    let pick_closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+        let first = match pick_state.borrow().calibration_picks.as_deref() {
+            None => return,
+            Some(picks) => picks.first().copied(),
+        };
+        let Some(workspace) = web_sys::window()
+            .unwrap()
+            .document()
+            .unwrap()
+            .get_element_by_id("canvas_layers")
+            .and_then(|layers| layers.first_element_child())
+            .and_then(|canvas| canvas.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+        else {
+            return;
+        };
+        let ppi = pick_state.borrow().screen_ppi() as f64;
+        let pick = Point {
+            x: (event.offset_x() as f64 - workspace.width() as f64 / 2.0) / ppi,
+            y: (event.offset_y() as f64 - workspace.height() as f64 / 2.0) / ppi,
+        };
+        let Some(first) = first else {
+            pick_status.set_text_content(Some("Click the second point on the photo."));
+            pick_store.dispatch(Action::PickCalibrationPoint(pick));
+            return;
+        };
+        let distance = gear_inputs::parse_input::<f64>(&pick_distance_input).unwrap_or(0.0);
+        let calibrated = pick_state
+            .borrow()
+            .underlay_placement
+            .calibrated(first, pick, distance);
+        match calibrated {
+            Ok(placement) => {
+                for (input, value) in [
+                    (&pick_x_input, placement.offset.x),
+                    (&pick_y_input, placement.offset.y),
+                ] {
+                    input
+                        .dyn_ref::<HtmlInputElement>()
+                        .unwrap()
+                        .set_value(&locale::format((value * 1000.0).round() / 1000.0));
+                }
+                pick_status.set_text_content(Some(&format!(
+                    "Scale set to {:.1} photo pixels per inch.",
+                    placement.pixels_per_inch
+                )));
+            }
+            Err(ref err) => {
+                pick_status.set_text_content(Some(&format!("Couldn't calibrate, {}.", err)))
+            }
+        }
+        pick_store.dispatch(Action::FinishCalibration(calibrated.ok()));
+    }) as Box<dyn Fn(web_sys::MouseEvent)>);
+    if let Some(workspace) = document.get_element_by_id("canvas_layers") {
+        workspace
+            .add_event_listener_with_callback("click", pick_closure.as_ref().unchecked_ref())?;
+    }
+    pick_closure.forget();
+
+    let underlay_remove_store = store.clone();
+    let underlay_remove_input = underlay_file_input.clone();
+    let underlay_remove_status = underlay_status.clone();
+    let underlay_remove_closure = Closure::wrap(Box::new(move || {
+        underlay_remove_input.set_value("");
+        underlay_remove_status.set_text_content(None);
+        underlay_remove_store.dispatch(Action::RemoveUnderlay);
+    }) as Box<dyn Fn()>);
+    underlay_remove_button.add_event_listener_with_callback(
+        "click",
+        underlay_remove_closure.as_ref().unchecked_ref(),
+    )?;
+    underlay_remove_closure.forget();
+
+    // advanced section, collapsed by default so the common inputs stay front and center
+    let advanced_section = document.create_element("details")?;
+    sidebar.append_child(&advanced_section)?;
+    let advanced_summary = document.create_element("summary")?;
+    advanced_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    advanced_summary.set_text_content(Some("Advanced"));
+    advanced_section.append_child(&advanced_summary)?;
+
+    // label for clearance input
+    let clearance_label = document.create_element("label")?;
+    clearance_label.set_attribute("for", "clearance").unwrap();
+    clearance_label.set_text_content(Some("Clearance (x module):"));
+    clearance_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    advanced_section.append_child(&clearance_label)?;
+
+    // clearance input, shared by every gear while linked
+    let clearance_input = document.create_element("input")?;
+    clearance_input.set_attribute("id", "clearance").unwrap();
+    clearance_input.set_attribute("type", "text").unwrap();
+    clearance_input
+        .set_attribute("placeholder", "Enter clearance")
+        .unwrap();
+    clearance_input
+        .set_attribute(
+            "value",
+            &locale::format(state.borrow().gears[0].spec.clearance_mult),
+        )
+        .unwrap();
+    clearance_input
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    advanced_section.append_child(&clearance_input)?;
+    gear_inputs::set_step(&clearance_input, 0.01, false)?;
+    let linked_inputs = [
+        gear_diametric_pitch_input.clone(),
+        tooth_angle_input.clone(),
+        clearance_input.clone(),
+    ];
+    linked_inputs.iter().for_each(|input| {
+        input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .set_disabled(!state.borrow().linked)
+    });
+
+    // label for backlash input
+    let backlash_label = document.create_element("label")?;
+    backlash_label.set_attribute("for", "backlash").unwrap();
+    backlash_label.set_text_content(Some("Backlash (x module):"));
+    backlash_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    advanced_section.append_child(&backlash_label)?;
+
+    // backlash input, shared by every gear
+    let backlash_input = document.create_element("input")?;
+    backlash_input.set_attribute("id", "backlash").unwrap();
+    backlash_input.set_attribute("type", "text").unwrap();
+    backlash_input
+        .set_attribute("placeholder", "Enter backlash")
+        .unwrap();
+    backlash_input
+        .set_attribute(
+            "value",
+            &locale::format(state.borrow().gears[0].spec.backlash_mult),
+        )
+        .unwrap();
+    backlash_input
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    advanced_section.append_child(&backlash_input)?;
+    gear_inputs::set_step(&backlash_input, 0.01, false)?;
+
+    // the backlash as an angle, which is how drives usually budget it
+    let angular_backlash = document.create_element("p")?;
+    angular_backlash.set_attribute("id", "angular_backlash")?;
+    angular_backlash.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    angular_backlash.set_text_content(Some(&describe_backlash(&state.borrow())));
+    advanced_section.append_child(&angular_backlash)?;
+    let angular_backlash_state = state.clone();
+    store.subscribe(move |_: &Action| {
+        angular_backlash
+            .set_text_content(Some(&describe_backlash(&angular_backlash_state.borrow())));
+    });
+
+    // label for tessellation input
+    let involute_steps_label = document.create_element("label")?;
+    involute_steps_label
+        .set_attribute("for", "involute_steps")
+        .unwrap();
+    involute_steps_label.set_text_content(Some("Points per Flank:"));
+    involute_steps_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    advanced_section.append_child(&involute_steps_label)?;
+
+    // tessellation input, more points give smoother flanks but slower redraws
+    let involute_steps_input = document.create_element("input")?;
+    involute_steps_input
+        .set_attribute("id", "involute_steps")
+        .unwrap();
+    involute_steps_input.set_attribute("type", "text").unwrap();
+    involute_steps_input
+        .set_attribute("placeholder", "Enter points per flank")
+        .unwrap();
+    involute_steps_input
+        .set_attribute(
+            "value",
+            &state.borrow().gears[0].spec.involute_steps.to_string(),
+        )
+        .unwrap();
+    involute_steps_input
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    advanced_section.append_child(&involute_steps_input)?;
+    gear_inputs::set_step(&involute_steps_input, 10.0, true)?;
+    let flank_tolerance_input = append_text_input(
+        &advanced_section,
+        "flank_tolerance",
+        "Flank Tolerance (mm):",
+        "Blank to set points per flank",
+        &state
+            .borrow()
+            .flank_tolerance_mm
+            .map(locale::format)
+            .unwrap_or_default(),
+    )?;
+    gear_inputs::set_step(&flank_tolerance_input, 0.001, false)?;
+
+    // how far the drawn flanks stray from the true involute
+    let flank_error = document.create_element("p")?;
+    flank_error.set_attribute("id", "flank_error")?;
+    flank_error.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    flank_error.set_text_content(Some(&describe_flank_error(&state.borrow())));
+    advanced_section.append_child(&flank_error)?;
+    let flank_error_state = state.clone();
+    store.subscribe(move |_: &Action| {
+        flank_error.set_text_content(Some(&describe_flank_error(&flank_error_state.borrow())));
+    });
+
+    // the inputs that change when a whole design is swapped in, and the gear
+    // sections, rebuilt whenever gears are added, moved or take a cutout
+    let design_inputs = Rc::new(DesignInputs {
+        name: design_name_input.clone(),
+        gear_sections: gear_sections.clone(),
+        gear_inputs: gear_inputs.clone(),
+        linked: linked_input.clone(),
+        diametric_pitch: gear_diametric_pitch_input.clone(),
+        tooth_angle: tooth_angle_input.clone(),
+        tooth_angle_presets,
+        clearance: clearance_input.clone(),
+        backlash: backlash_input.clone(),
+        involute_steps: involute_steps_input.clone(),
+    });
+    let shown_design_inputs = design_inputs.clone();
+    let shown_state = state.clone();
+    store.subscribe(move |action: &Action| {
+        let state = shown_state.borrow();
+        match action {
+            Action::LoadDesign(_) => shown_design_inputs.show(&state),
+            Action::RenameDesign(name) => shown_design_inputs
+                .name
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .set_value(name),
+            Action::DuplicateGear(_)
+            | Action::SwapGears(_)
+            | Action::ApplyRecommendedHub(_)
+            | Action::RemoveCutout(_)
+            | Action::ImportCutout { .. }
+            | Action::AddShape(_)
+            | Action::RemoveShape { .. } => shown_design_inputs.rebuild_gear_sections(&state),
+            _ => {}
+        }
+    });
+
+    // reset puts back the default gears, shared inputs and linking after
+    // checking with the user. view and export settings are left alone.
+    let reset_store = store.clone();
+    let reset_closure = Closure::wrap(Box::new(move || {
+        let confirmed = web_sys::window()
+            .unwrap()
+            .confirm_with_message("Reset the design to the default gears?")
+            .unwrap_or(false);
+        if !confirmed {
+            return;
+        }
+        reset_store.dispatch(Action::LoadDesign(Design::default()));
+    }) as Box<dyn Fn()>);
+    reset_button
+        .add_event_listener_with_callback("click", reset_closure.as_ref().unchecked_ref())?;
+    reset_closure.forget();
+
+    // saving needs a name, and replaces the design already saved under it
+    let save_state = state.clone();
+    let save_designs_list = designs_list.clone();
+    let save_design_closure = Closure::wrap(Box::new(move || {
+        let design = save_state.borrow().design();
+        if design.name.trim().is_empty() {
+            web_sys::window()
+                .unwrap()
+                .alert_with_message("Name the design before saving it.")
+                .unwrap();
+            return;
+        }
+        storage::save_named(&design);
+        build_designs_list(&save_designs_list).unwrap();
+    }) as Box<dyn Fn()>);
+    save_design_button
+        .add_event_listener_with_callback("click", save_design_closure.as_ref().unchecked_ref())?;
+    save_design_closure.forget();
+
+    // open, rename and delete buttons on each saved design
+    let designs_state = state.clone();
+    let designs_store = store.clone();
+    let designs_list_clone = designs_list.clone();
+    let designs_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(target) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        else {
+            return;
+        };
+        let action = target.get_attribute("data-action").unwrap_or_default();
+        let Some(name) = target.get_attribute("data-name") else {
+            return;
+        };
+        let window = web_sys::window().unwrap();
+        match action.as_str() {
+            "open" => {
+                let Some(design) = storage::named_design(&name) else {
+                    return;
+                };
+                designs_store.dispatch(Action::LoadDesign(design));
+            }
+            "rename" => {
+                let Some(new_name) = window
+                    .prompt_with_message_and_default("Rename the design to:", &name)
+                    .unwrap_or(None)
+                    .map(|new_name| new_name.trim().to_string())
+                    .filter(|new_name| !new_name.is_empty() && *new_name != name)
+                else {
+                    return;
+                };
+                if !storage::rename_named(&name, &new_name) {
+                    window
+                        .alert_with_message(&format!(
+                            "There's already a design called {}.",
+                            new_name
+                        ))
+                        .unwrap();
+                    return;
+                }
+                // the open design follows its saved copy
+                if designs_state.borrow().name == name {
+                    designs_store.dispatch(Action::RenameDesign(new_name));
+                }
+            }
+            "delete" => {
+                let confirmed = window
+                    .confirm_with_message(&format!("Delete the design {}?", name))
+                    .unwrap_or(false);
+                if !confirmed {
+                    return;
+                }
+                storage::delete_named(&name);
+            }
+            _ => return,
+        }
+        build_designs_list(&designs_list_clone).unwrap();
+    }) as Box<dyn Fn(web_sys::Event)>);
+    designs_list
+        .add_event_listener_with_callback("click", designs_closure.as_ref().unchecked_ref())?;
+    designs_closure.forget();
+
+    // generating a clock train replaces the design with linked gears in mesh
+    // order, a wheel and pinion sharing each intermediate arbor
+    let clock_state = state.clone();
+    let clock_store = store.clone();
+    let clock_closure = Closure::wrap(Box::new(move || {
+        let value = clock_train_select
+            .dyn_ref::<HtmlSelectElement>()
+            .unwrap()
+            .value();
+        let Some(train) = ClockTrain::from_value(&value) else {
+            return;
+        };
+        let ratio = match train {
+            ClockTrain::Going => {
+                let (Some(period), Some(escape_teeth)) = (
+                    gear_inputs::parse_input::<f64>(&pendulum_period_input),
+                    gear_inputs::parse_input::<u32>(&escape_teeth_input),
+                ) else {
+                    clock_result
+                        .set_text_content(Some("Enter a pendulum period and escape wheel teeth."));
+                    return;
+                };
+                clock::going_train_ratio(period, escape_teeth)
+            }
+            ClockTrain::MotionWork => clock::MOTION_WORK_RATIO,
+        };
+        let Some(stages) = clock::two_stage_train(train, ratio) else {
+            clock_result.set_text_content(Some("No train fits that ratio."));
+            return;
+        };
+        clock_result.set_text_content(Some(&format!(
+            "{}/{} x {}/{} = {:.3}:1 (target {:.3}:1)",
+            stages[0].wheel,
+            stages[0].pinion,
+            stages[1].wheel,
+            stages[1].pinion,
+            stages[0].ratio() * stages[1].ratio(),
+            ratio
+        )));
+
+        let template = clock_state.borrow().gears[0].spec.clone();
+        let design = clock_design(train, &stages, &template);
+        clock_store.dispatch(Action::LoadDesign(design));
+    }) as Box<dyn Fn()>);
+    clock_button
+        .add_event_listener_with_callback("click", clock_closure.as_ref().unchecked_ref())?;
+    clock_closure.forget();
+
+    // generating a crossed helical pair keeps the first gear as the driver
+    // and sizes the driven gear from the ratio
+    let crossed_state = state.clone();
+    let crossed_store = store.clone();
+    let crossed_closure = Closure::wrap(Box::new(move || {
+        let (Some(shaft_angle), Some(ratio)) = (
+            gear_inputs::parse_input::<f64>(&shaft_angle_input),
+            gear_inputs::parse_input::<f64>(&crossed_ratio_input),
+        ) else {
+            crossed_result.set_text_content(Some("Enter a shaft angle and ratio."));
+            return;
+        };
+        let template = crossed_state.borrow().gears[0].spec.clone();
+        let teeth = [template.teeth, (template.teeth * ratio).round()];
+        let pair = (ratio > 0.0 && teeth[1] >= 1.0)
+            .then(|| helical::crossed_pair(shaft_angle, teeth, template.diametric_pitch))
+            .flatten();
+        let Some(pair) = pair else {
+            crossed_result.set_text_content(Some("No pair fits that shaft angle and ratio."));
+            return;
+        };
+        crossed_result.set_text_content(Some(&format!(
+            "{}/{} teeth, helix angles {:.2}\u{b0} / {:.2}\u{b0}, same hand. Center distance {:.4} in.",
+            teeth[0],
+            teeth[1],
+            pair.helix_angles[0],
+            pair.helix_angles[1],
+            pair.center_distance
+        )));
+
+        let design = crossed_helical_design(teeth, pair.helix_angles, &template);
+        crossed_store.dispatch(Action::LoadDesign(design));
+    }) as Box<dyn Fn()>);
+    crossed_button
+        .add_event_listener_with_callback("click", crossed_closure.as_ref().unchecked_ref())?;
+    crossed_closure.forget();
+
+    // identifying a gear keeps the design, giving the first gear the tooth
+    // count and the shared pitch and angle, or just its own when unlinked
+    let identify_state = state.clone();
+    let identify_store = store.clone();
+    let identify_closure = Closure::wrap(Box::new(move || {
+        let (Some(outside_diameter), Some(teeth)) = (
+            gear_inputs::parse_input::<f64>(&identify_diameter_input),
+            gear_inputs::parse_input::<u32>(&identify_teeth_input),
+        ) else {
+            identify_result.set_text_content(Some("Enter an outside diameter and tooth count."));
+            return;
+        };
+        let pins = gear_inputs::parse_input::<f64>(&identify_over_pins_input).map(|measurement| {
+            identify::Pins {
+                measurement,
+                diameter: gear_inputs::parse_input::<f64>(&identify_pin_diameter_input)
+                    .unwrap_or(0.0),
+            }
+        });
+        if pins.is_some_and(|pins| pins.diameter <= 0.0) {
+            identify_result.set_text_content(Some("Enter the diameter of the pins."));
+            return;
+        }
+        let Some(found) = identify::identify(outside_diameter, teeth, pins) else {
+            identify_result.set_text_content(Some("No standard gear fits those measurements."));
+            return;
+        };
+        let angle = match found.pressure_angle {
+            Some(angle) => format!("{}\u{b0} pressure angle", angle),
+            None => format!(
+                "{}\u{b0} pressure angle assumed, measure over {:.4} in pins to check",
+                identify::ASSUMED_PRESSURE_ANGLE,
+                identify::PIN_DIAMETER_CONSTANT / found.pitch.diametric_pitch()
+            ),
+        };
+        let over_pins = found
+            .over_pins
+            .map(|over_pins| format!(", {:.4} in over pins", over_pins))
+            .unwrap_or_default();
+        identify_result.set_text_content(Some(&format!(
+            "{} (measured {:.3} DP), {}. A new gear is {:.4} in across{}.",
+            found.pitch.describe(),
+            found.measured_pitch,
+            angle,
+            found.outside_diameter,
+            over_pins
+        )));
+
+        let mut design = identify_state.borrow().design();
+        let shared = if design.linked { design.gears.len() } else { 1 };
+        for gear in design.gears.iter_mut().take(shared) {
+            gear.spec.diametric_pitch = found.pitch.diametric_pitch();
+            gear.spec.tooth_angle = found.pressure_angle_or_assumed();
+        }
+        design.gears[0].spec.teeth = teeth as f64;
+        identify_store.dispatch(Action::LoadDesign(design));
+    }) as Box<dyn Fn()>);
+    identify_button
+        .add_event_listener_with_callback("click", identify_closure.as_ref().unchecked_ref())?;
+    identify_closure.forget();
 
-    // add left gear subtitle
-    let left_gear_subtitle = document.create_element("h3")?;
-    left_gear_subtitle
+    // opening the recent designs lists them, picking one loads it
+    let recent_list_closure_list = recent_list.clone();
+    let recent_toggle_closure = Closure::wrap(Box::new(move || {
+        build_recent_list(&recent_list_closure_list).unwrap();
+    }) as Box<dyn Fn()>);
+    recent_section.add_event_listener_with_callback(
+        "toggle",
+        recent_toggle_closure.as_ref().unchecked_ref(),
+    )?;
+    recent_toggle_closure.forget();
+
+    // switching tabs keeps the design being left in its tab. each tab
+    // autosaves to its own recent entry.
+    let tabs_state = state.clone();
+    let tabs_store = store.clone();
+    let tabs_closure_tabs = tabs.clone();
+    let tabs_closure_bar = tabs_bar.clone();
+    let tabs_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(target) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        else {
+            return;
+        };
+        let index = |name: &str| {
+            target
+                .get_attribute(name)
+                .and_then(|index| index.parse::<usize>().ok())
+        };
+        let current = tabs_state.borrow().design();
+        let mut tabs = tabs_closure_tabs.borrow_mut();
+        let design = if let Some(index) = index("data-tab") {
+            tabs.switch(current, index)
+        } else if let Some(index) = index("data-close-tab") {
+            tabs.close(current, index)
+        } else if target.has_attribute("data-new-tab") {
+            tabs.duplicate(current.clone());
+            Some(current)
+        } else {
+            return;
+        };
+        drop(tabs);
+        // the new tab's copy is loaded as if it had been opened, so its edits
+        // autosave to a recent entry of its own. closing another tab only
+        // changes the bar.
+        if let Some(design) = design {
+            tabs_store.dispatch(Action::LoadDesign(design));
+        } else {
+            build_tabs_bar(
+                &tabs_closure_bar,
+                &tabs_closure_tabs.borrow(),
+                &tabs_state.borrow().design(),
+            )
+            .unwrap();
+        }
+    }) as Box<dyn Fn(web_sys::Event)>);
+    tabs_bar.add_event_listener_with_callback("click", tabs_closure.as_ref().unchecked_ref())?;
+    tabs_closure.forget();
+
+    let recovery_store = store.clone();
+    let recovery_tabs = tabs.clone();
+    let recovery_pending = pending_session.clone();
+    let recovery_closure_banner = recovery_banner.clone();
+    let recovery_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(action) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|target| target.get_attribute("data-session"))
+        else {
+            return;
+        };
+        let Some(pending) = recovery_pending.borrow_mut().take() else {
+            return;
+        };
+        set_display(&recovery_closure_banner, false);
+        if action != "restore" {
+            session::clear();
+            return;
+        }
+        let design = recovery_tabs
+            .borrow_mut()
+            .replace(pending.designs, pending.active);
+        if let Some(design) = design {
+            recovery_store.dispatch(Action::LoadDesign(design));
+        }
+    }) as Box<dyn Fn(web_sys::Event)>);
+    recovery_banner
+        .add_event_listener_with_callback("click", recovery_closure.as_ref().unchecked_ref())?;
+    recovery_closure.forget();
+
+    // snapshots the open designs while any have unsaved edits, and clears
+    // the snapshot once they're all saved. leaving with unsaved edits keeps
+    // it for next time.
+    let snapshot_state = state.clone();
+    let snapshot_tabs = tabs.clone();
+    let last_snapshot: Rc<RefCell<Option<Vec<Design>>>> = Rc::new(RefCell::new(None));
+    let snapshot_session = Rc::new(move || {
+        if pending_session.borrow().is_some() {
+            return;
+        }
+        let designs = snapshot_tabs
+            .borrow()
+            .designs(&snapshot_state.borrow().design());
+        if last_snapshot.borrow().as_ref() == Some(&designs) {
+            return;
+        }
+        if session::has_unsaved_edits(&designs) {
+            session::save(&session::Session {
+                saved_at: js_sys::Date::now(),
+                designs: designs.clone(),
+                active: snapshot_tabs.borrow().active(),
+            });
+        } else {
+            session::clear();
+        }
+        *last_snapshot.borrow_mut() = Some(designs);
+    });
+    let snapshot_timer_session = snapshot_session.clone();
+    let snapshot_closure = Closure::wrap(Box::new(move || {
+        snapshot_timer_session();
+    }) as Box<dyn Fn()>);
+    let window = web_sys::window().unwrap();
+    window.set_interval_with_callback_and_timeout_and_arguments_0(
+        snapshot_closure.as_ref().unchecked_ref(),
+        session::SNAPSHOT_INTERVAL_MS,
+    )?;
+    snapshot_closure.forget();
+    let leave_closure = Closure::wrap(Box::new(move || {
+        snapshot_session();
+    }) as Box<dyn Fn()>);
+    window.add_event_listener_with_callback("pagehide", leave_closure.as_ref().unchecked_ref())?;
+    leave_closure.forget();
+
+    let recent_store = store.clone();
+    let recent_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(saved_at) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|target| target.get_attribute("data-saved-at"))
+        else {
+            return;
+        };
+        let Some(snapshot) = storage::recent_designs()
+            .into_iter()
+            .find(|snapshot| snapshot.saved_at.to_string() == saved_at)
+        else {
+            return;
+        };
+        recent_store.dispatch(Action::LoadDesign(snapshot.design));
+    }) as Box<dyn Fn(web_sys::Event)>);
+    recent_list
+        .add_event_listener_with_callback("click", recent_closure.as_ref().unchecked_ref())?;
+    recent_closure.forget();
+
+    // add view subtitle
+    let view_subtitle = document.create_element("h3")?;
+    view_subtitle
         .set_attribute("style", "text-align: center; width: 100%;")
         .unwrap();
-    left_gear_subtitle.set_text_content(Some("Left Gear"));
-    sidebar.append_child(&left_gear_subtitle)?;
+    view_subtitle.set_text_content(Some("View"));
+    sidebar.append_child(&view_subtitle)?;
 
-    // label for left gear teeth input
-    let left_gear_teeth_label = document.create_element("label")?;
-    left_gear_teeth_label
-        .set_attribute("for", "left_gear_teeth")
+    // label for renderer select
+    let renderer_label = document.create_element("label")?;
+    renderer_label.set_attribute("for", "renderer").unwrap();
+    renderer_label.set_text_content(Some("Renderer:"));
+    renderer_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
         .unwrap();
-    left_gear_teeth_label.set_text_content(Some("Teeth:"));
-    left_gear_teeth_label
+    sidebar.append_child(&renderer_label)?;
+
+    // renderer select, webgl keeps big trains smooth and svg stays crisp when zoomed
+    let renderer_select = document.create_element("select")?;
+    renderer_select.set_attribute("id", "renderer").unwrap();
+    renderer_select
         .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
         .unwrap();
-    sidebar.append_child(&left_gear_teeth_label)?;
+    for (renderer, text) in [
+        (Renderer::Canvas, "Canvas 2D"),
+        (Renderer::WebGl, "WebGL2"),
+        (Renderer::Svg, "SVG"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", renderer.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().renderer == renderer {
+            option.set_attribute("selected", "")?;
+        }
+        renderer_select.append_child(&option)?;
+    }
+    sidebar.append_child(&renderer_select)?;
+
+    // label for background select
+    let background_label = document.create_element("label")?;
+    background_label.set_attribute("for", "background")?;
+    background_label.set_text_content(Some("Background:"));
+    background_label.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    sidebar.append_child(&background_label)?;
+
+    // background select, shown on screen and in saved views but never printed
+    let background_select = document.create_element("select")?;
+    background_select.set_attribute("id", "background")?;
+    background_select.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    for (style, text) in [
+        (BackgroundStyle::Grid, "Half Inch Grid"),
+        (BackgroundStyle::White, "Plain White"),
+        (BackgroundStyle::Transparent, "Transparent"),
+        (BackgroundStyle::GraphPaper, "Graph Paper"),
+        (BackgroundStyle::Isometric, "Isometric Grid"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", style.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().background == style {
+            option.set_attribute("selected", "")?;
+        }
+        background_select.append_child(&option)?;
+    }
+    sidebar.append_child(&background_select)?;
+
+    // rulers toggle, with the cursor's position read out in the corner
+    let show_rulers_input = append_checkbox(
+        &sidebar,
+        "show_rulers",
+        "Rulers:",
+        state.borrow().show_rulers,
+    )?;
+
+    // label for ruler units select
+    let ruler_units_label = document.create_element("label")?;
+    ruler_units_label.set_attribute("for", "ruler_units")?;
+    ruler_units_label.set_text_content(Some("Ruler Units:"));
+    ruler_units_label.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    sidebar.append_child(&ruler_units_label)?;
+
+    let ruler_units_select = document.create_element("select")?;
+    ruler_units_select.set_attribute("id", "ruler_units")?;
+    ruler_units_select
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    for (units, text) in [
+        (rulers::RulerUnits::Inches, "Inches"),
+        (rulers::RulerUnits::Millimeters, "Millimeters"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", units.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().ruler_units == units {
+            option.set_attribute("selected", "")?;
+        }
+        ruler_units_select.append_child(&option)?;
+    }
+    sidebar.append_child(&ruler_units_select)?;
+
+    // actual size toggle, for holding a part up to the screen. it needs the
+    // screen's pixels per inch, measured off a ruler held against it.
+    let actual_size_input = append_checkbox(
+        &sidebar,
+        "actual_size",
+        "Actual Size:",
+        state.borrow().actual_size,
+    )?;
+    let screen_ppi_input = append_text_input(
+        &sidebar,
+        "screen_ppi",
+        "Screen Pixels per Inch:",
+        "Measure and enter screen ppi",
+        &state
+            .borrow()
+            .calibrated_ppi
+            .map(locale::format)
+            .unwrap_or_default(),
+    )?;
+    gear_inputs::set_step(&screen_ppi_input, 1.0, false)?;
+
+    // measures the screen against a card or ruler, and turns on actual size
+    // with what it finds
+    let calibrate_screen_button = document.create_element("button")?;
+    calibrate_screen_button.set_attribute("id", "calibrate_screen_button")?;
+    calibrate_screen_button.set_text_content(Some("Calibrate Screen"));
+    calibrate_screen_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    sidebar.append_child(&calibrate_screen_button)?;
+    let calibrate_state = state.clone();
+    let calibrate_store = store.clone();
+    let calibrate_actual_size = actual_size_input.clone();
+    let calibrate_ppi = screen_ppi_input.clone();
+    let calibrate_closure = Closure::wrap(Box::new(move || {
+        let initial_ppi = calibrate_state
+            .borrow()
+            .calibrated_ppi
+            .unwrap_or(SCREEN_PPI as f64);
+        let store = calibrate_store.clone();
+        let actual_size = calibrate_actual_size.clone();
+        let ppi_input = calibrate_ppi.clone();
+        calibration::show(initial_ppi, move |ppi| {
+            let ppi = (ppi * 10.0).round() / 10.0;
+            ppi_input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .set_value(&locale::format(ppi));
+            actual_size
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .set_checked(true);
+            store.dispatch(Action::CalibrateScreen(ppi));
+        })
+        .unwrap();
+    }) as Box<dyn Fn()>);
+    calibrate_screen_button
+        .add_event_listener_with_callback("click", calibrate_closure.as_ref().unchecked_ref())?;
+    calibrate_closure.forget();
+
+    // the screen's pixels per inch are kept for next time, however they were
+    // set
+    let saved_ppi_state = state.clone();
+    let saved_ppi = std::cell::Cell::new(state.borrow().calibrated_ppi);
+    store.subscribe(move |_: &Action| {
+        let ppi = saved_ppi_state.borrow().calibrated_ppi;
+        if saved_ppi.replace(ppi) != ppi {
+            storage::save_screen_ppi(ppi);
+        }
+    });
+
+    // label for input direction select
+    let input_direction_label = document.create_element("label")?;
+    input_direction_label
+        .set_attribute("for", "input_direction")
+        .unwrap();
+    input_direction_label.set_text_content(Some("First Gear Turns:"));
+    input_direction_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    sidebar.append_child(&input_direction_label)?;
+
+    // input direction select, the first gear drives the rest
+    let input_direction_select = document.create_element("select")?;
+    input_direction_select
+        .set_attribute("id", "input_direction")
+        .unwrap();
+    input_direction_select
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    for (direction, text) in [
+        (RotationDirection::Clockwise, "Clockwise"),
+        (RotationDirection::CounterClockwise, "Counter-clockwise"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", direction.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().input_direction == direction {
+            option.set_attribute("selected", "")?;
+        }
+        input_direction_select.append_child(&option)?;
+    }
+    sidebar.append_child(&input_direction_select)?;
+
+    // rotation arrows toggle
+    let rotation_arrows_input = append_checkbox(
+        &sidebar,
+        "rotation_arrows",
+        "Rotation Arrows:",
+        state.borrow().show_rotation_arrows,
+    )?;
+
+    // labels toggle, tooth counts and the mesh ratio
+    let labels_input = append_checkbox(&sidebar, "labels", "Labels:", state.borrow().show_labels)?;
+
+    // terminology toggle, labels the first gear's circles, pitch and angles
+    let terminology_input = append_checkbox(
+        &sidebar,
+        "terminology",
+        "Terminology:",
+        state.borrow().show_terminology,
+    )?;
+
+    // involute construction toggle, a teaching aid
+    let construction_input = append_checkbox(
+        &sidebar,
+        "construction",
+        "Involute Construction:",
+        state.borrow().show_construction,
+    )?;
+
+    // hobbing simulation toggle, shows the rack cutter generating a tooth space
+    let hobbing_input = append_checkbox(
+        &sidebar,
+        "hobbing",
+        "Hobbing Simulation:",
+        state.borrow().show_hobbing,
+    )?;
+
+    // 3d preview toggle, drag to orbit and scroll to zoom
+    let preview_input = append_checkbox(
+        &sidebar,
+        "preview",
+        "3D Preview:",
+        state.borrow().show_preview,
+    )?;
+
+    // tolerance band toggle, outlines every gear as cut at either end of the
+    // profile tolerance and says whether each pair still meshes
+    let tolerance_band_input = append_checkbox(
+        &sidebar,
+        "tolerance_band",
+        "Tolerance Band:",
+        state.borrow().show_tolerance_band,
+    )?;
+    let profile_tolerance_input = append_text_input(
+        &sidebar,
+        "profile_tolerance",
+        "Profile Tolerance (\u{b1} mm):",
+        "Enter profile tolerance",
+        &locale::format(state.borrow().profile_tolerance_mm),
+    )?;
+    gear_inputs::set_step(&profile_tolerance_input, 0.01, false)?;
+    let tolerance_band_result = document.create_element("p")?;
+    tolerance_band_result
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    sidebar.append_child(&tolerance_band_result)?;
+    let tolerance_band_state = state.clone();
+    store.subscribe(move |_: &Action| {
+        tolerance_band_result.set_text_content(Some(&describe_tolerance_band(
+            &tolerance_band_state.borrow(),
+        )));
+    });
+
+    // add export subtitle
+    let export_subtitle = document.create_element("h3")?;
+    export_subtitle
+        .set_attribute("style", "text-align: center; width: 100%;")
+        .unwrap();
+    export_subtitle.set_text_content(Some("Export"));
+    sidebar.append_child(&export_subtitle)?;
+
+    // label for dxf curve mode select
+    let dxf_curve_mode_label = document.create_element("label")?;
+    dxf_curve_mode_label
+        .set_attribute("for", "dxf_curve_mode")
+        .unwrap();
+    dxf_curve_mode_label.set_text_content(Some("DXF Curves:"));
+    dxf_curve_mode_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    sidebar.append_child(&dxf_curve_mode_label)?;
+
+    // dxf curve mode select, some cam packages prefer one over the other
+    let dxf_curve_mode_select = document.create_element("select")?;
+    dxf_curve_mode_select
+        .set_attribute("id", "dxf_curve_mode")
+        .unwrap();
+    dxf_curve_mode_select
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
+        .unwrap();
+    for (mode, text) in [
+        (DxfCurveMode::Polyline, "Polylines"),
+        (DxfCurveMode::Spline, "Splines"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", mode.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().dxf_curve_mode == mode {
+            option.set_attribute("selected", "")?;
+        }
+        dxf_curve_mode_select.append_child(&option)?;
+    }
+    sidebar.append_child(&dxf_curve_mode_select)?;
+
+    // roughing passes toggle, clears the tooth spaces and web cutouts for
+    // simple 2.5D milling without another cam step
+    let roughing_input = append_checkbox(
+        &sidebar,
+        "roughing",
+        "Roughing Passes in DXF:",
+        state.borrow().roughing,
+    )?;
+    let roughing_tool_input = append_text_input(
+        &sidebar,
+        "roughing_tool",
+        "Tool Diameter (in):",
+        "Enter tool diameter",
+        &locale::format(state.borrow().roughing_tool.diameter),
+    )?;
+    gear_inputs::set_step(&roughing_tool_input, 0.0625, false)?;
+    let roughing_stepover_input = append_text_input(
+        &sidebar,
+        "roughing_stepover",
+        "Stepover (in):",
+        "Enter stepover",
+        &locale::format(state.borrow().roughing_tool.stepover),
+    )?;
+    gear_inputs::set_step(&roughing_stepover_input, 0.01, false)?;
+    for input in [&roughing_tool_input, &roughing_stepover_input] {
+        input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .set_disabled(!state.borrow().roughing);
+    }
+
+    // holding tabs, so parts don't come loose from the stock mid-cut
+    let holding_tabs_input = append_text_input(
+        &sidebar,
+        "holding_tabs",
+        "Holding Tabs per Outline:",
+        "0 for none",
+        &state.borrow().holding_tabs.count.to_string(),
+    )?;
+    gear_inputs::set_step(&holding_tabs_input, 1.0, false)?;
+    let tab_width_input = append_text_input(
+        &sidebar,
+        "tab_width",
+        "Tab Width (in):",
+        "Enter tab width",
+        &locale::format(state.borrow().holding_tabs.width),
+    )?;
+    gear_inputs::set_step(&tab_width_input, 0.0625, false)?;
+    tab_width_input
+        .dyn_ref::<HtmlInputElement>()
+        .unwrap()
+        .set_disabled(state.borrow().holding_tabs.count == 0);
+
+    // export colors toggle, off prints plain black outlines
+    let export_colors_input = append_checkbox(
+        &sidebar,
+        "export_colors",
+        "Colors in PDF:",
+        state.borrow().export_colors,
+    )?;
+
+    // mirror exports toggle, for cutting from the back face of the stock
+    let mirror_exports_input = append_checkbox(
+        &sidebar,
+        "mirror_exports",
+        "Mirror Exports:",
+        state.borrow().mirror_exports,
+    )?;
+
+    // high contrast toggle, thick black outlines and no grid for templates
+    // cut out by hand
+    let high_contrast_input = append_checkbox(
+        &sidebar,
+        "high_contrast",
+        "High Contrast Prints:",
+        state.borrow().high_contrast,
+    )?;
+
+    // how thick exported lines are, for pdfs and svgs alike
+    let line_widths = state.borrow().line_widths;
+    let cut_width_input = append_text_input(
+        &sidebar,
+        "cut_line_width",
+        "Cut Line Width (mm):",
+        "Enter cut line width",
+        &locale::format(line_widths.cut),
+    )?;
+    gear_inputs::set_step(&cut_width_input, 0.05, false)?;
+    let engrave_width_input = append_text_input(
+        &sidebar,
+        "engrave_line_width",
+        "Engrave Line Width (mm):",
+        "Enter engrave line width",
+        &locale::format(line_widths.engrave),
+    )?;
+    gear_inputs::set_step(&engrave_width_input, 0.05, false)?;
+    let construction_width_input = append_text_input(
+        &sidebar,
+        "construction_line_width",
+        "Construction Line Width (mm):",
+        "Enter construction line width",
+        &locale::format(line_widths.construction),
+    )?;
+    gear_inputs::set_step(&construction_width_input, 0.05, false)?;
+
+    // label for the oversize select
+    let oversize_label = document.create_element("label")?;
+    oversize_label.set_attribute("for", "oversize")?;
+    oversize_label.set_text_content(Some("Too Big for a Page:"));
+    oversize_label.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    sidebar.append_child(&oversize_label)?;
+
+    // pdfs can't grow past a letter page, so big layouts are split up,
+    // shrunk or cut off
+    let oversize_select = document.create_element("select")?;
+    oversize_select.set_attribute("id", "oversize")?;
+    oversize_select.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    for (oversize, text) in [
+        (paper::Oversize::Tile, "Tile Across Pages"),
+        (paper::Oversize::Scale, "Scale to Fit"),
+        (paper::Oversize::Clip, "Clip"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", oversize.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().oversize == oversize {
+            option.set_attribute("selected", "")?;
+        }
+        oversize_select.append_child(&option)?;
+    }
+    sidebar.append_child(&oversize_select)?;
+
+    // label for the export layout select
+    let export_layout_label = document.create_element("label")?;
+    export_layout_label.set_attribute("for", "export_layout")?;
+    export_layout_label.set_text_content(Some("Export Layout:"));
+    export_layout_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    sidebar.append_child(&export_layout_label)?;
+
+    // assembly drawings want the gears in mesh, cutting templates want them
+    // apart so the outlines don't overlap
+    let export_layout_select = document.create_element("select")?;
+    export_layout_select.set_attribute("id", "export_layout")?;
+    export_layout_select
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    for (layout, text) in [
+        (paper::Layout::Meshed, "Drawn in Mesh"),
+        (paper::Layout::Exploded, "Separated"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", layout.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().export_layout == layout {
+            option.set_attribute("selected", "")?;
+        }
+        export_layout_select.append_child(&option)?;
+    }
+    sidebar.append_child(&export_layout_select)?;
+
+    // only used when separated
+    let exploded_gap_input = append_text_input(
+        &sidebar,
+        "exploded_gap",
+        "Separation (in):",
+        "Enter gap between gears",
+        &locale::format(state.borrow().exploded_gap),
+    )?;
+    gear_inputs::set_step(&exploded_gap_input, 0.125, false)?;
+    exploded_gap_input
+        .dyn_ref::<HtmlInputElement>()
+        .unwrap()
+        .set_disabled(state.borrow().export_layout == paper::Layout::Meshed);
+
+    // drill marks toggle, marking goes on its own dxf layer apart from the
+    // cut lines
+    let drill_marks_input = append_checkbox(
+        &sidebar,
+        "drill_marks",
+        "Drill Marks:",
+        state.borrow().drill_marks,
+    )?;
+
+    // spacer rings or thrust washers nested under the gears in exports, none
+    // until a count is given
+    let spacer_count_input = append_text_input(
+        &sidebar,
+        "spacer_count",
+        "Spacers:",
+        "Enter spacer count",
+        &state.borrow().spacers.count.to_string(),
+    )?;
+    gear_inputs::set_step(&spacer_count_input, 1.0, true)?;
+    let spacer_inner_input = append_text_input(
+        &sidebar,
+        "spacer_inner_diameter",
+        "Spacer ID (in):",
+        "Enter bore or shaft diameter",
+        &locale::format(state.borrow().spacers.inner_diameter),
+    )?;
+    gear_inputs::set_step(&spacer_inner_input, 0.0625, false)?;
+    let spacer_outer_input = append_text_input(
+        &sidebar,
+        "spacer_outer_diameter",
+        "Spacer OD (in):",
+        "Enter spacer outside diameter",
+        &locale::format(state.borrow().spacers.outer_diameter),
+    )?;
+    gear_inputs::set_step(&spacer_outer_input, 0.0625, false)?;
+    let spacer_thickness_input = append_text_input(
+        &sidebar,
+        "spacer_thickness",
+        "Spacer Thickness (in):",
+        "Enter spacer thickness",
+        &locale::format(state.borrow().spacers.thickness),
+    )?;
+    gear_inputs::set_step(&spacer_thickness_input, 0.0625, false)?;
+
+    // add button for printing through the browser, lighter than building a pdf
+    let browser_print_button = document.create_element("button")?;
+    browser_print_button
+        .set_attribute("id", "browser_print_button")
+        .unwrap();
+    browser_print_button.set_text_content(Some("Print via Browser"));
+    browser_print_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    sidebar.append_child(&browser_print_button)?;
+
+    let browser_print_state = state.clone();
+    let browser_print_closure = Closure::wrap(Box::new(move || {
+        print_via_browser(&browser_print_state.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+    browser_print_button.add_event_listener_with_callback(
+        "click",
+        browser_print_closure.as_ref().unchecked_ref(),
+    )?;
+    browser_print_closure.forget();
+
+    // add button for a sheet of measurements per gear for the qc bench
+    let inspection_button = document.create_element("button")?;
+    inspection_button.set_attribute("id", "inspection_button")?;
+    inspection_button.set_text_content(Some("Print Inspection Sheets"));
+    inspection_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    sidebar.append_child(&inspection_button)?;
+
+    let inspection_state = state.clone();
+    let inspection_closure = Closure::wrap(Box::new(move || {
+        print_inspection(&inspection_state.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+    inspection_button
+        .add_event_listener_with_callback("click", inspection_closure.as_ref().unchecked_ref())?;
+    inspection_closure.forget();
+
+    // add button for arranging the gears on the pdf's sheets by hand
+    let sheets_button = document.create_element("button")?;
+    sheets_button.set_attribute("id", "sheets_button")?;
+    sheets_button.set_text_content(Some("Arrange PDF Sheets"));
+    sheets_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    sidebar.append_child(&sheets_button)?;
+
+    let sheets_state = state.clone();
+    let sheets_store = store.clone();
+    let sheets_closure = Closure::wrap(Box::new(move || {
+        SheetEditor::open(sheets_state.clone(), sheets_store.clone()).unwrap();
+    }) as Box<dyn Fn()>);
+    sheets_button
+        .add_event_listener_with_callback("click", sheets_closure.as_ref().unchecked_ref())?;
+    sheets_closure.forget();
+
+    // add button for a png of the view, overlays and all, for sharing
+    // rather than cutting
+    let save_view_button = document.create_element("button")?;
+    save_view_button
+        .set_attribute("id", "save_view_button")
+        .unwrap();
+    save_view_button.set_text_content(Some("Save View as PNG"));
+    save_view_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    sidebar.append_child(&save_view_button)?;
+    save_view_button
+        .add_event_listener_with_callback("click", save_view_closure.as_ref().unchecked_ref())?;
+
+    // label for the animation format select
+    let animation_format_label = document.create_element("label")?;
+    animation_format_label.set_attribute("for", "animation_format")?;
+    animation_format_label.set_text_content(Some("Animation Format:"));
+    animation_format_label
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    sidebar.append_child(&animation_format_label)?;
+
+    // animation format select, gifs play anywhere and webm is smaller
+    let animation_format_select = document.create_element("select")?;
+    animation_format_select.set_attribute("id", "animation_format")?;
+    animation_format_select
+        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    for (format, text) in [
+        (animation::Format::Gif, "GIF"),
+        (animation::Format::WebM, "WebM Video"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", format.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().animation_format == format {
+            option.set_attribute("selected", "")?;
+        }
+        animation_format_select.append_child(&option)?;
+    }
+    sidebar.append_child(&animation_format_select)?;
+    let animation_turns_input = append_text_input(
+        &sidebar,
+        "animation_turns",
+        "Animation Turns:",
+        "Enter turns of the first gear",
+        &locale::format(state.borrow().animation_turns),
+    )?;
+    gear_inputs::set_step(&animation_turns_input, 1.0, false)?;
+
+    // add button for a recording of the gears turning
+    let animation_button = document.create_element("button")?;
+    animation_button.set_attribute("id", "animation_button")?;
+    animation_button.set_text_content(Some("Export Animation"));
+    animation_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    sidebar.append_child(&animation_button)?;
+
+    let animation_state = state.clone();
+    let animation_closure = Closure::wrap(Box::new(move || {
+        export_animation(&animation_state.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+    animation_button
+        .add_event_listener_with_callback("click", animation_closure.as_ref().unchecked_ref())?;
+    animation_closure.forget();
+
+    // label for the svg units select
+    let svg_units_label = document.create_element("label")?;
+    svg_units_label.set_attribute("for", "svg_units")?;
+    svg_units_label.set_text_content(Some("SVG Units:"));
+    svg_units_label.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    sidebar.append_child(&svg_units_label)?;
+
+    // svgs are sized in real units, so they import at full scale
+    let svg_units_select = document.create_element("select")?;
+    svg_units_select.set_attribute("id", "svg_units")?;
+    svg_units_select.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    for (units, text) in [
+        (svg::SvgUnits::Millimeters, "Millimeters"),
+        (svg::SvgUnits::Inches, "Inches"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", units.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().svg_units == units {
+            option.set_attribute("selected", "")?;
+        }
+        svg_units_select.append_child(&option)?;
+    }
+    sidebar.append_child(&svg_units_select)?;
+
+    // add button for the svg export, for laser and cad software
+    let svg_button = document.create_element("button")?;
+    svg_button.set_attribute("id", "svg_button")?;
+    svg_button.set_text_content(Some("Export SVG"));
+    svg_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    sidebar.append_child(&svg_button)?;
+
+    let svg_state = state.clone();
+    let export_svg_closure = Closure::wrap(Box::new(move || {
+        export_svg(&svg_state.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+    svg_button
+        .add_event_listener_with_callback("click", export_svg_closure.as_ref().unchecked_ref())?;
+    export_svg_closure.forget();
+
+    // rack tip radius input, rounds the cutter corners that form the gear's root fillet
+    let rack_tip_radius_input = append_text_input(
+        &sidebar,
+        "rack_tip_radius",
+        "Rack Tip Radius (x module):",
+        "Enter rack tip radius",
+        &locale::format(state.borrow().rack_tip_radius_mult),
+    )?;
+    gear_inputs::set_step(&rack_tip_radius_input, 0.01, false)?;
+
+    // rack protuberance input, relieves the roots of gears that will be ground
+    let rack_protuberance_input = append_text_input(
+        &sidebar,
+        "rack_protuberance",
+        "Rack Protuberance (x module):",
+        "Enter rack protuberance",
+        &locale::format(state.borrow().rack_protuberance_mult),
+    )?;
+    gear_inputs::set_step(&rack_protuberance_input, 0.005, false)?;
+
+    // add button for the basic rack, the form to grind a hob or fly cutter to
+    let rack_button = document.create_element("button")?;
+    rack_button.set_attribute("id", "rack_button").unwrap();
+    rack_button.set_text_content(Some("Export Rack DXF"));
+    rack_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    sidebar.append_child(&rack_button)?;
+
+    let rack_state = state.clone();
+    let export_rack_closure = Closure::wrap(Box::new(move || {
+        export_rack_dxf(&rack_state.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+    rack_button
+        .add_event_listener_with_callback("click", export_rack_closure.as_ref().unchecked_ref())?;
+    export_rack_closure.forget();
+
+    // kerf test size input, the slots and pins step around it
+    let kerf_test_size_input = append_text_input(
+        &sidebar,
+        "kerf_test_size",
+        "Kerf Test Size (mm):",
+        "Enter kerf test size",
+        &locale::format(state.borrow().kerf_test_size_mm),
+    )?;
+    gear_inputs::set_step(&kerf_test_size_input, 0.1, false)?;
+
+    // add button for the kerf test, cut before trusting a laser's fit
+    let kerf_test_button = document.create_element("button")?;
+    kerf_test_button
+        .set_attribute("id", "kerf_test_button")
+        .unwrap();
+    kerf_test_button.set_text_content(Some("Export Kerf Test DXF"));
+    kerf_test_button
+        .set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")
+        .unwrap();
+    sidebar.append_child(&kerf_test_button)?;
+
+    let kerf_test_state = state.clone();
+    let export_kerf_test_closure = Closure::wrap(Box::new(move || {
+        export_kerf_test_dxf(&kerf_test_state.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+    kerf_test_button.add_event_listener_with_callback(
+        "click",
+        export_kerf_test_closure.as_ref().unchecked_ref(),
+    )?;
+    export_kerf_test_closure.forget();
+
+    // label for mold blank select
+    let mold_blank_label = document.create_element("label")?;
+    mold_blank_label.set_attribute("for", "mold_blank")?;
+    mold_blank_label.set_text_content(Some("Mold Blank:"));
+    mold_blank_label.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    sidebar.append_child(&mold_blank_label)?;
+
+    // mold blank select, the stock each gear's cavity is cut into
+    let mold_blank_select = document.create_element("select")?;
+    mold_blank_select.set_attribute("id", "mold_blank")?;
+    mold_blank_select.set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")?;
+    for (shape, text) in [
+        (mold::BlankShape::Rectangle, "Rectangle"),
+        (mold::BlankShape::Circle, "Circle"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", shape.value())?;
+        option.set_text_content(Some(text));
+        if state.borrow().mold_blank == shape {
+            option.set_attribute("selected", "")?;
+        }
+        mold_blank_select.append_child(&option)?;
+    }
+    sidebar.append_child(&mold_blank_select)?;
+    let mold_wall_input = append_text_input(
+        &sidebar,
+        "mold_wall",
+        "Mold Wall (in):",
+        "Enter mold wall",
+        &locale::format(state.borrow().mold_wall),
+    )?;
+    gear_inputs::set_step(&mold_wall_input, 0.125, false)?;
+
+    // add button for mold cavities, the negative of each gear in a blank
+    let mold_button = document.create_element("button")?;
+    mold_button.set_attribute("id", "mold_button")?;
+    mold_button.set_text_content(Some("Export Mold Cavity DXF"));
+    mold_button.set_attribute("style", "width: 80%; margin: 10px 10% 0 10%;")?;
+    sidebar.append_child(&mold_button)?;
+
+    let mold_state = state.clone();
+    let export_mold_closure = Closure::wrap(Box::new(move || {
+        export_mold_dxf(&mold_state.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+    mold_button
+        .add_event_listener_with_callback("click", export_mold_closure.as_ref().unchecked_ref())?;
+    export_mold_closure.forget();
+
+    // branding printed in a corner of pdf exports
+    let watermark_section = document.create_element("details")?;
+    sidebar.append_child(&watermark_section)?;
+    let watermark_summary = document.create_element("summary")?;
+    watermark_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    watermark_summary.set_text_content(Some("Watermark"));
+    watermark_section.append_child(&watermark_summary)?;
+    watermark::attach(&watermark_section)?;
+
+    // recent exports, kept for the session so they can be downloaded again
+    let export_history_section = document.create_element("details")?;
+    sidebar.append_child(&export_history_section)?;
+    let export_history_summary = document.create_element("summary")?;
+    export_history_summary
+        .set_attribute(
+            "style",
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0;",
+        )
+        .unwrap();
+    export_history_summary.set_text_content(Some("Export History"));
+    export_history_section.append_child(&export_history_summary)?;
+    let export_history_list = document.create_element("div")?;
+    export_history_section.append_child(&export_history_list)?;
+    export_history::attach(&export_history_list);
+
+    let export_history_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(id) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|target| target.get_attribute("data-export"))
+            .and_then(|id| id.parse::<u32>().ok())
+        else {
+            return;
+        };
+        if let Some((filename, mime_type, bytes)) = export_history::find(id) {
+            download_file(&bytes, mime_type, &filename).unwrap();
+        }
+    }) as Box<dyn Fn(web_sys::Event)>);
+    export_history_list.add_event_listener_with_callback(
+        "click",
+        export_history_closure.as_ref().unchecked_ref(),
+    )?;
+    export_history_closure.forget();
+
+    // add debug subtitle
+    let debug_subtitle = document.create_element("h3")?;
+    debug_subtitle
+        .set_attribute("style", "text-align: center; width: 100%;")
+        .unwrap();
+    debug_subtitle.set_text_content(Some("Debug"));
+    sidebar.append_child(&debug_subtitle)?;
+
+    // performance hud toggle, shows redraw and export timings
+    let perf_hud_input = append_checkbox(&sidebar, "perf_hud", "Performance HUD:", false)?;
+
+    let perf_hud_toggle = perf_hud_input.clone();
+    let perf_hud_closure = Closure::wrap(Box::new(move || {
+        let checked = perf_hud_toggle
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .checked();
+        perf::set_hud_visible(checked);
+    }) as Box<dyn Fn()>);
+    perf_hud_input
+        .add_event_listener_with_callback("change", perf_hud_closure.as_ref().unchecked_ref())?;
+    perf_hud_closure.forget();
+
+    DebugPanel::create(&document, state.clone(), store.clone())?;
+
+    // add button for dxf export
+    let dxf_button = document.create_element("button")?;
+    dxf_button.set_attribute("id", "dxf_button").unwrap();
+    dxf_button.set_text_content(Some("Export DXF"));
+    dxf_button
+        .set_attribute(
+            "style",
+            "width: 100px; position: fixed; bottom: 50px; left: 20px;",
+        )
+        .unwrap();
+    sidebar.append_child(&dxf_button)?;
+
+    let dxf_state = state.clone();
+    let export_dxf_closure = Closure::wrap(Box::new(move || {
+        export_dxf(&dxf_state.borrow()).unwrap();
+    }) as Box<dyn Fn()>);
+    dxf_button
+        .add_event_listener_with_callback("click", export_dxf_closure.as_ref().unchecked_ref())?;
+    export_dxf_closure.forget();
+
+    // add button for print
+    let print_button = document.create_element("button")?;
+    print_button.set_attribute("id", "print_button").unwrap();
+    print_button.set_text_content(Some("Print"));
+    print_button
+        .set_attribute(
+            "style",
+            "width: 100px; position: fixed; bottom: 20px; left: 20px;",
+        )
+        .unwrap();
+    sidebar.append_child(&print_button)?;
+
+    // update print button to create an alert with the current gear specs
+    print_button
+        .add_event_listener_with_callback("click", print_gears_closure.as_ref().unchecked_ref())?;
+
+    let autosave_state = state.clone();
+    let tabs_bar_state = state.clone();
+    let embed_state = state.clone();
+    let warnings_state = state.clone();
+
+    // reads every control and dispatches their values together, the store
+    // applies them and works out what needs redrawing
+    let sidebar_store = store.clone();
+    let read_controls = Rc::new(move || {
+        let name = design_name_input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .value()
+            .trim()
+            .to_string();
+        let mut gears = state.borrow().gears.clone();
+
+        // linking copies the first gear's pitch, angle and clearance to the
+        // rest, unlinking starts every gear off from the shared values
+        let linked = linked_input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .checked();
+        if state.borrow().linked != linked {
+            let first_spec = gears[0].spec.clone();
+            if linked {
+                let values = [
+                    first_spec.diametric_pitch,
+                    first_spec.tooth_angle,
+                    first_spec.clearance_mult,
+                ];
+                linked_inputs.iter().zip(values).for_each(|(input, value)| {
+                    input
+                        .dyn_ref::<HtmlInputElement>()
+                        .unwrap()
+                        .set_value(&locale::format(value))
+                });
+            }
+            gear_inputs
+                .borrow()
+                .iter()
+                .zip(&gears)
+                .for_each(|(inputs, gear)| inputs.set_linked(linked, &gear.spec));
+            linked_inputs.iter().for_each(|input| {
+                input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .set_disabled(!linked)
+            });
+        }
+
+        // each gear's own inputs
+        gear_inputs
+            .borrow()
+            .iter()
+            .zip(gears.iter_mut())
+            .for_each(|(inputs, gear)| inputs.apply(gear, linked));
+
+        if linked {
+            // gear diametric pitch, pressure angle and clearance apply to every gear
+            let value = gear_diametric_pitch_input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .value();
+            if let Some(diametric_pitch) =
+                expression::parse::<f64>(&value).filter(|pitch| *pitch > 0.0)
+            {
+                gears
+                    .iter_mut()
+                    .for_each(|gear| gear.spec.diametric_pitch = diametric_pitch);
+            }
+            design_inputs.tooth_angle_presets.apply(&tooth_angle_input);
+            let value = tooth_angle_input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .value();
+            if let Some(tooth_angle) = expression::parse::<f64>(&value) {
+                gears
+                    .iter_mut()
+                    .for_each(|gear| gear.spec.tooth_angle = tooth_angle);
+            }
+            design_inputs
+                .tooth_angle_presets
+                .describe(gears[0].spec.tooth_angle);
+            let value = clearance_input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .value();
+            if let Some(clearance_mult) = expression::parse::<f64>(&value)
+                .filter(|clearance_mult| geometry::CLEARANCE_RANGE.contains(clearance_mult))
+            {
+                gears
+                    .iter_mut()
+                    .for_each(|gear| gear.spec.clearance_mult = clearance_mult);
+            }
+        }
+
+        // advanced inputs, these apply to every gear
+        let value = backlash_input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .value();
+        if let Some(backlash_mult) = expression::parse::<f64>(&value)
+            .filter(|backlash_mult| geometry::BACKLASH_RANGE.contains(backlash_mult))
+        {
+            gears
+                .iter_mut()
+                .for_each(|gear| gear.spec.backlash_mult = backlash_mult);
+        }
+        let value = involute_steps_input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .value();
+        // a flank needs at least one point, and too many would stall every
+        // redraw
+        if let Some(involute_steps) = expression::parse::<u32>(&value)
+            .filter(|steps| (1..=geometry::MAX_INVOLUTE_STEPS).contains(steps))
+        {
+            gears
+                .iter_mut()
+                .for_each(|gear| gear.spec.involute_steps = involute_steps);
+        }
+        // a tolerance picks each gear's points per flank, bigger gears
+        // needing more
+        let flank_tolerance_mm = gear_inputs::parse_input::<f64>(&flank_tolerance_input)
+            .filter(|tolerance| *tolerance > 0.0);
+        involute_steps_input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .set_disabled(flank_tolerance_mm.is_some());
+        fit_flank_tolerance(&mut gears, flank_tolerance_mm);
+        let design = Action::EditDesign {
+            name,
+            linked,
+            gears,
+            flank_tolerance_mm,
+        };
+
+        // export settings, falling back to the current ones for inputs that
+        // don't hold valid values
+        let exports = {
+            let state = state.borrow();
+            let value = dxf_curve_mode_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let dxf_curve_mode = DxfCurveMode::from_value(&value).unwrap_or(state.dxf_curve_mode);
+            let roughing_tool = roughing::Tool {
+                diameter: gear_inputs::parse_input::<f64>(&roughing_tool_input)
+                    .filter(|diameter| *diameter > 0.0)
+                    .unwrap_or(state.roughing_tool.diameter),
+                stepover: gear_inputs::parse_input::<f64>(&roughing_stepover_input)
+                    .filter(|stepover| *stepover > 0.0)
+                    .unwrap_or(state.roughing_tool.stepover),
+            };
+            let holding_tabs = bridges::Bridges {
+                count: gear_inputs::parse_input::<usize>(&holding_tabs_input)
+                    .unwrap_or(state.holding_tabs.count),
+                width: gear_inputs::parse_input::<f64>(&tab_width_input)
+                    .filter(|width| *width > 0.0)
+                    .unwrap_or(state.holding_tabs.width),
+            };
+            let value = oversize_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let oversize = paper::Oversize::from_value(&value).unwrap_or(state.oversize);
+            let value = svg_units_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let svg_units = svg::SvgUnits::from_value(&value).unwrap_or(state.svg_units);
+            let value = export_layout_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let export_layout = paper::Layout::from_value(&value).unwrap_or(state.export_layout);
+            let exploded_gap = gear_inputs::parse_input::<f64>(&exploded_gap_input)
+                .filter(|gap| *gap >= 0.0)
+                .unwrap_or(state.exploded_gap);
+            let mut line_widths = state.line_widths;
+            for (input, width) in [
+                (&cut_width_input, &mut line_widths.cut),
+                (&engrave_width_input, &mut line_widths.engrave),
+                (&construction_width_input, &mut line_widths.construction),
+            ] {
+                if let Some(value) =
+                    gear_inputs::parse_input::<f64>(input).filter(|value| *value > 0.0)
+                {
+                    *width = value;
+                }
+            }
+            let mut spacers = state.spacers;
+            if let Some(count) = gear_inputs::parse_input::<u32>(&spacer_count_input) {
+                spacers.count = count;
+            }
+            for (input, value) in [
+                (&spacer_inner_input, &mut spacers.inner_diameter),
+                (&spacer_outer_input, &mut spacers.outer_diameter),
+                (&spacer_thickness_input, &mut spacers.thickness),
+            ] {
+                if let Some(parsed) =
+                    gear_inputs::parse_input::<f64>(input).filter(|parsed| *parsed >= 0.0)
+                {
+                    *value = parsed;
+                }
+            }
+            let value = mold_blank_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let mold_blank = mold::BlankShape::from_value(&value).unwrap_or(state.mold_blank);
+            let value = animation_format_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let animation_format =
+                animation::Format::from_value(&value).unwrap_or(state.animation_format);
+            ExportOptions {
+                dxf_curve_mode,
+                roughing: roughing_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                roughing_tool,
+                holding_tabs,
+                oversize,
+                svg_units,
+                export_layout,
+                exploded_gap,
+                drill_marks: drill_marks_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                export_colors: export_colors_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                mirror_exports: mirror_exports_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                high_contrast: high_contrast_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                line_widths,
+                spacers,
+                rack_tip_radius_mult: gear_inputs::parse_input::<f64>(&rack_tip_radius_input)
+                    .filter(|mult| *mult >= 0.0)
+                    .unwrap_or(state.rack_tip_radius_mult),
+                rack_protuberance_mult: gear_inputs::parse_input::<f64>(&rack_protuberance_input)
+                    .filter(|mult| *mult >= 0.0)
+                    .unwrap_or(state.rack_protuberance_mult),
+                kerf_test_size_mm: gear_inputs::parse_input::<f64>(&kerf_test_size_input)
+                    .filter(|size| *size > 0.0)
+                    .unwrap_or(state.kerf_test_size_mm),
+                mold_blank,
+                mold_wall: gear_inputs::parse_input::<f64>(&mold_wall_input)
+                    .filter(|wall| *wall >= 0.0)
+                    .unwrap_or(state.mold_wall),
+                animation_format,
+                animation_turns: gear_inputs::parse_input::<f64>(&animation_turns_input)
+                    .filter(|turns| *turns > 0.0)
+                    .unwrap_or(state.animation_turns),
+            }
+        };
+        for input in [&roughing_tool_input, &roughing_stepover_input] {
+            input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .set_disabled(!exports.roughing);
+        }
+        tab_width_input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .set_disabled(exports.holding_tabs.count == 0);
+        exploded_gap_input
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .set_disabled(exports.export_layout == paper::Layout::Meshed);
+
+        // how the workspace is drawn
+        let view = {
+            let state = state.borrow();
+            let value = renderer_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let renderer = Renderer::from_value(&value).unwrap_or(state.renderer);
+            let value = background_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let background = BackgroundStyle::from_value(&value).unwrap_or(state.background);
+            let value = ruler_units_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let ruler_units = rulers::RulerUnits::from_value(&value).unwrap_or_default();
+            let value = input_direction_select
+                .dyn_ref::<HtmlSelectElement>()
+                .unwrap()
+                .value();
+            let input_direction =
+                RotationDirection::from_value(&value).unwrap_or(state.input_direction);
+            ViewOptions {
+                renderer,
+                background,
+                show_rulers: show_rulers_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                ruler_units,
+                actual_size: actual_size_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                calibrated_ppi: gear_inputs::parse_input::<f64>(&screen_ppi_input)
+                    .filter(|ppi| *ppi >= 1.0),
+                input_direction,
+                show_rotation_arrows: rotation_arrows_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                show_labels: labels_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                show_terminology: terminology_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                show_construction: construction_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                show_hobbing: hobbing_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                show_preview: preview_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                show_tolerance_band: tolerance_band_input
+                    .dyn_ref::<HtmlInputElement>()
+                    .unwrap()
+                    .checked(),
+                profile_tolerance_mm: gear_inputs::parse_input::<f64>(&profile_tolerance_input)
+                    .filter(|tolerance| *tolerance >= 0.0)
+                    .unwrap_or(state.profile_tolerance_mm),
+            }
+        };
+
+        // the cam only touches the overlay, but switching in or out of cam
+        // mode hides or brings back every gear
+        let cam_base_radius = gear_inputs::parse_input::<f64>(&cam_base_radius_input)
+            .filter(|radius| *radius > 0.0)
+            .unwrap_or(state.borrow().cam_base_radius);
+        let roller_radius = gear_inputs::parse_input::<f64>(&cam_roller_radius_input)
+            .filter(|radius| *radius > 0.0)
+            .unwrap_or(DEFAULT_ROLLER_RADIUS);
+        let cam_follower = match cam_follower_select
+            .dyn_ref::<HtmlSelectElement>()
+            .unwrap()
+            .value()
+            .as_str()
+        {
+            "knife" => cam::Follower::KnifeEdge,
+            "flat" => cam::Follower::FlatFaced,
+            _ => cam::Follower::Roller {
+                radius: roller_radius,
+            },
+        };
+        let cam = Action::SetCam {
+            mode: cam_mode_input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .checked(),
+            program: cam_program_input
+                .dyn_ref::<HtmlInputElement>()
+                .unwrap()
+                .value(),
+            base_radius: cam_base_radius,
+            follower: cam_follower,
+        };
+
+        let alignment = {
+            let current = state.borrow().reference_alignment;
+            reference::Alignment {
+                offset: Point {
+                    x: gear_inputs::parse_input::<f64>(&reference_x_input)
+                        .unwrap_or(current.offset.x),
+                    y: gear_inputs::parse_input::<f64>(&reference_y_input)
+                        .unwrap_or(current.offset.y),
+                },
+                rotation: gear_inputs::parse_input::<f64>(&reference_rotation_input)
+                    .unwrap_or(current.rotation),
+            }
+        };
+
+        let placement = {
+            let current = state.borrow().underlay_placement;
+            underlay::Placement {
+                offset: Point {
+                    x: gear_inputs::parse_input::<f64>(&underlay_x_input)
+                        .unwrap_or(current.offset.x),
+                    y: gear_inputs::parse_input::<f64>(&underlay_y_input)
+                        .unwrap_or(current.offset.y),
+                },
+                opacity: gear_inputs::parse_input::<f64>(&underlay_opacity_input)
+                    .unwrap_or(current.opacity),
+                ..current
+            }
+        };
+        sidebar_store.dispatch(Action::Batch(vec![
+            design,
+            Action::SetExportOptions(exports),
+            Action::SetView(view),
+            cam,
+            Action::AlignReference(alignment),
+            Action::PlaceUnderlay(placement),
+        ]));
+    });
+    let input_read_controls = read_controls.clone();
+    let closure = Closure::wrap(Box::new(move || {
+        input_read_controls();
+    }) as Box<dyn Fn()>);
+    sidebar.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+
+    // autosave whenever the state changes, refreshing the recent designs if
+    // they're showing. a loaded design starts a new recent entry, so edits
+    // to it don't overwrite the design being left.
+    store.subscribe(move |action: &Action| {
+        let loaded = matches!(action, Action::LoadDesign(_));
+        storage::autosave(&autosave_state.borrow().design(), loaded);
+        if recent_section.has_attribute("open") {
+            build_recent_list(&recent_list).unwrap();
+        }
+    });
+
+    // arrow keys and the scroll wheel step numeric inputs, shift for coarse
+    // steps and ctrl for fine ones. the wheel only steps the focused input so
+    // scrolling past the sidebar still scrolls.
+    let step_key_read_controls = read_controls.clone();
+    let step_key_closure = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+        let up = match event.key().as_str() {
+            "ArrowUp" => true,
+            "ArrowDown" => false,
+            _ => return,
+        };
+        let Some(target) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        else {
+            return;
+        };
+        let fine = event.ctrl_key() || event.meta_key();
+        if gear_inputs::step_input(&target, up, event.shift_key(), fine) {
+            event.prevent_default();
+            step_key_read_controls();
+        }
+    }) as Box<dyn Fn(web_sys::KeyboardEvent)>);
+    sidebar
+        .add_event_listener_with_callback("keydown", step_key_closure.as_ref().unchecked_ref())?;
+    step_key_closure.forget();
+
+    let step_wheel_read_controls = read_controls;
+    let step_wheel_closure = Closure::wrap(Box::new(move |event: web_sys::WheelEvent| {
+        let Some(target) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        else {
+            return;
+        };
+        let document = web_sys::window().unwrap().document().unwrap();
+        if document.active_element().as_ref() != Some(&target) || event.delta_y() == 0.0 {
+            return;
+        }
+        let fine = event.ctrl_key() || event.meta_key();
+        if gear_inputs::step_input(&target, event.delta_y() < 0.0, event.shift_key(), fine) {
+            event.prevent_default();
+            step_wheel_read_controls();
+        }
+    }) as Box<dyn Fn(web_sys::WheelEvent)>);
+    // passive listeners can't stop the page scrolling
+    let step_wheel_options = web_sys::AddEventListenerOptions::new();
+    step_wheel_options.set_passive(false);
+    sidebar.add_event_listener_with_callback_and_add_event_listener_options(
+        "wheel",
+        step_wheel_closure.as_ref().unchecked_ref(),
+        &step_wheel_options,
+    )?;
+    step_wheel_closure.forget();
+
+    // expressions are replaced by their value once the input is committed
+    let evaluate_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(input) = event
+            .target()
+            .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+            .filter(|input| input.has_attribute("data-step"))
+        else {
+            return;
+        };
+        let value = input.value();
+        if locale::parse::<f64>(&value).is_none() {
+            if let Some(result) = expression::evaluate(&value) {
+                input.set_value(&locale::format(result));
+            }
+        }
+    }) as Box<dyn Fn(web_sys::Event)>);
+    sidebar
+        .add_event_listener_with_callback("change", evaluate_closure.as_ref().unchecked_ref())?;
+    evaluate_closure.forget();
+
+    // embedding pages can send designs in and hear about every change. the
+    // change a received design makes isn't echoed back.
+    if embed::is_embedded() {
+        let loading = Rc::new(std::cell::Cell::new(false));
+        let listen_store = store.clone();
+        let listen_loading = loading.clone();
+        embed::listen(move |design| {
+            listen_loading.set(true);
+            listen_store.dispatch(Action::LoadDesign(design));
+            listen_loading.set(false);
+        });
+        store.subscribe(move |_: &Action| {
+            if !loading.get() {
+                embed::post_design(&embed_state.borrow().design());
+            }
+        });
+        embed::post_ready();
+    }
+
+    // tab labels follow the active design's name
+    store.subscribe(move |_: &Action| {
+        build_tabs_bar(&tabs_bar, &tabs.borrow(), &tabs_bar_state.borrow().design()).unwrap();
+    });
+
+    // recheck the train once the state is up to date
+    let warnings_closure_list = warnings_list.clone();
+    store.subscribe(move |_: &Action| {
+        build_warnings(
+            &warnings_summary,
+            &warnings_closure_list,
+            &warnings_state.borrow(),
+        )
+        .unwrap();
+    });
+
+    // picking a warning highlights its gears, picking it again clears them
+    let highlight_store = store.clone();
+    let highlight_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(gears) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|target| target.get_attribute("data-gears"))
+        else {
+            return;
+        };
+        let gears: Vec<usize> = gears
+            .split(',')
+            .filter_map(|index| index.parse().ok())
+            .collect();
+        highlight_store.dispatch(Action::ToggleHighlight(gears));
+    }) as Box<dyn Fn(web_sys::Event)>);
+    warnings_list
+        .add_event_listener_with_callback("click", highlight_closure.as_ref().unchecked_ref())?;
+    highlight_closure.forget();
+
+    Ok(sidebar)
+}
+
+// gears for a two stage clock train in mesh order. the going train's wheels
+// drive its pinions, the motion work's pinions drive its wheels. every gear
+// takes the pitch and other shared specs from `template`.
+fn clock_design(train: ClockTrain, stages: &[ClockStage; 2], template: &GearSpecs) -> Design {
+    let teeth = match train {
+        ClockTrain::Going => [
+            stages[0].wheel,
+            stages[0].pinion,
+            stages[1].wheel,
+            stages[1].pinion,
+        ],
+        ClockTrain::MotionWork => [
+            stages[0].pinion,
+            stages[0].wheel,
+            stages[1].pinion,
+            stages[1].wheel,
+        ],
+    };
+    let gears = teeth
+        .iter()
+        .enumerate()
+        .map(|(index, &teeth)| GearState {
+            spec: GearSpecs {
+                teeth: teeth as f64,
+                thinning: Thinning::Half,
+                ..template.clone()
+            },
+            style: GearStyle::plain(),
+            // the middle arbor carries the second and third gears
+            same_arbor: index == 2,
+            hub: Hub::default(),
+            spokes: Spokes::default(),
+            svg_cutout: None,
+            shapes: Vec::new(),
+        })
+        .collect();
+    Design {
+        name: String::new(),
+        gears,
+        linked: true,
+    }
+}
+
+// a driver and driven gear on crossed shafts, taking the pitch and other
+// shared specs from `template`. drawn side by side, their pitch circles
+// touch at the crossed center distance.
+fn crossed_helical_design(teeth: [f64; 2], helix_angles: [f64; 2], template: &GearSpecs) -> Design {
+    let gears = teeth
+        .iter()
+        .zip(helix_angles)
+        .map(|(&teeth, helix_angle)| GearState {
+            spec: GearSpecs {
+                teeth,
+                helix_angle,
+                thinning: Thinning::Half,
+                ..template.clone()
+            },
+            style: GearStyle::plain(),
+            same_arbor: false,
+            hub: Hub::default(),
+            spokes: Spokes::default(),
+            svg_cutout: None,
+            shapes: Vec::new(),
+        })
+        .collect();
+    Design {
+        name: String::new(),
+        gears,
+        linked: true,
+    }
+}
+
+// lists the saved designs with open, rename and delete buttons tagged with
+// `data-action` and `data-name`
+fn build_designs_list(container: &web_sys::Element) -> Result<(), JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    container.set_inner_html("");
+    let designs = storage::named_designs();
+    if designs.is_empty() {
+        let empty = document.create_element("p")?;
+        empty.set_attribute("style", "text-align: center;")?;
+        empty.set_text_content(Some("No saved designs"));
+        container.append_child(&empty)?;
+    }
+    for design in designs {
+        let row = document.create_element("div")?;
+        row.set_attribute(
+            "style",
+            "width: 80%; margin: 2px 10%; display: flex; gap: 4px; align-items: center;",
+        )?;
+        container.append_child(&row)?;
+        let name = document.create_element("span")?;
+        name.set_attribute(
+            "style",
+            "flex: 1; overflow: hidden; text-overflow: ellipsis; white-space: nowrap;",
+        )?;
+        name.set_text_content(Some(&design.name));
+        row.append_child(&name)?;
+        for (action, text) in [("open", "Open"), ("rename", "Rename"), ("delete", "Delete")] {
+            let button = document.create_element("button")?;
+            button.set_attribute("data-action", action)?;
+            button.set_attribute("data-name", &design.name)?;
+            button.set_text_content(Some(text));
+            row.append_child(&button)?;
+        }
+    }
+    Ok(())
+}
+
+// lists the recent designs as buttons tagged with `data-saved-at`, newest first
+fn build_recent_list(container: &web_sys::Element) -> Result<(), JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    container.set_inner_html("");
+    let recent = storage::recent_designs();
+    if recent.is_empty() {
+        let empty = document.create_element("p")?;
+        empty.set_attribute("style", "text-align: center;")?;
+        empty.set_text_content(Some("Nothing saved yet"));
+        container.append_child(&empty)?;
+    }
+    for snapshot in recent {
+        let saved_at = js_sys::Date::new(&JsValue::from_f64(snapshot.saved_at))
+            .to_locale_string("default", &JsValue::UNDEFINED);
+        let teeth = snapshot
+            .design
+            .gears
+            .iter()
+            .map(|gear| gear.spec.teeth.to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        let button = document.create_element("button")?;
+        button.set_attribute("data-saved-at", &snapshot.saved_at.to_string())?;
+        button.set_attribute("style", "width: 80%; margin: 2px 10%;")?;
+        let mut text = format!("{} ({}T)", String::from(saved_at), teeth);
+        if !snapshot.design.name.is_empty() {
+            text = format!("{}: {}", snapshot.design.name, text);
+        }
+        button.set_text_content(Some(&text));
+        container.append_child(&button)?;
+    }
+    Ok(())
+}
+
+fn set_display(element: &web_sys::Element, visible: bool) {
+    let display = if visible { "block" } else { "none" };
+    element
+        .dyn_ref::<web_sys::HtmlElement>()
+        .unwrap()
+        .style()
+        .set_property("display", display)
+        .unwrap();
+}
+
+// a button per tab tagged with `data-tab`, close buttons tagged with
+// `data-close-tab` while there's more than one, and a `data-new-tab` button
+fn build_tabs_bar(
+    bar: &web_sys::Element,
+    tabs: &DesignTabs,
+    current: &Design,
+) -> Result<(), JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    bar.set_inner_html("");
+    let labels = tabs.labels(current);
+    let closable = labels.len() > 1;
+    for (index, label) in labels.iter().enumerate() {
+        let tab = document.create_element("button")?;
+        tab.set_attribute("data-tab", &index.to_string())?;
+        let style = if index == tabs.active() {
+            "margin: 2px 0 2px 2px; font-weight: bold; background-color: white;"
+        } else {
+            "margin: 2px 0 2px 2px;"
+        };
+        tab.set_attribute("style", style)?;
+        tab.set_text_content(Some(label));
+        bar.append_child(&tab)?;
+        if closable {
+            let close = document.create_element("button")?;
+            close.set_attribute("data-close-tab", &index.to_string())?;
+            close.set_attribute("title", "Close tab")?;
+            close.set_attribute("style", "margin: 2px 2px 2px 0; padding: 0 4px;")?;
+            close.set_text_content(Some("\u{d7}"));
+            bar.append_child(&close)?;
+        }
+    }
+    let new_tab = document.create_element("button")?;
+    new_tab.set_attribute("data-new-tab", "")?;
+    new_tab.set_attribute("title", "Open a copy in a new tab")?;
+    new_tab.set_attribute("style", "margin: 2px;")?;
+    new_tab.set_text_content(Some("+"));
+    bar.append_child(&new_tab)?;
+    Ok(())
+}
+
+// the least and most backlash each mesh has across the profile tolerance
+fn describe_tolerance_band(page_state: &PageState) -> String {
+    if !page_state.show_tolerance_band || page_state.cam_mode {
+        return String::new();
+    }
+    let tolerance = page_state.profile_tolerance_mm / MM_PER_INCH;
+    let inches = |value: f64| locale::format((value * 1e4).round() / 1e4);
+    page_state
+        .gears
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| !pair[1].same_arbor)
+        .map(|(index, pair)| {
+            let backlash: f64 = pair
+                .iter()
+                .map(|gear| GearGeometry::new(&gear.spec).backlash_allowance)
+                .sum();
+            // helical teeth lean further over in the plane of the outline
+            let spec = &pair[1].spec;
+            let pressure_angle = (spec.tooth_angle.to_radians().tan()
+                / spec.helix_angle.to_radians().cos())
+            .atan()
+            .to_degrees();
+            let band = tolerance::mesh_band(backlash, tolerance, pressure_angle);
+            if band.tightest < 0.0 {
+                format!(
+                    "Gears {} and {} bind at maximum material, {} in too tight.",
+                    index + 1,
+                    index + 2,
+                    inches(-band.tightest)
+                )
+            } else {
+                format!(
+                    "Gears {} and {} have {} to {} in backlash.",
+                    index + 1,
+                    index + 2,
+                    inches(band.tightest),
+                    inches(band.loosest)
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// the worst flank error across the involute gears, and the gear it's on
+fn describe_flank_error(page_state: &PageState) -> String {
+    let worst = page_state
+        .gears
+        .iter()
+        .enumerate()
+        .filter(|(_, gear)| gear.spec.profile == ToothProfile::Involute)
+        .map(|(index, gear)| {
+            let geometry = GearGeometry::new(&gear.spec);
+            (index, geometry.chordal_error(), gear.spec.involute_steps)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    let Some((index, error, involute_steps)) = worst else {
+        return String::new();
+    };
+    format!(
+        "Flanks stray up to {} in ({} mm) from the true involute, on gear {} at {} points per flank.",
+        locale::format((error * 1e5).round() / 1e5),
+        locale::format((error * MM_PER_INCH * 1e4).round() / 1e4),
+        index + 1,
+        involute_steps
+    )
+}
+
+// each gear's angular backlash with the first held, ending at the output
+fn describe_backlash(page_state: &PageState) -> String {
+    let gears: Vec<(&GearSpecs, bool)> = page_state
+        .gears
+        .iter()
+        .map(|gear| (&gear.spec, gear.same_arbor))
+        .collect();
+    let play = backlash::angular(&gears);
+    if play.len() < 2 {
+        return String::new();
+    }
+    let arcminutes =
+        |radians: f64| locale::format((backlash::arcminutes(radians) * 10.0).round() / 10.0);
+    let gears = play[1..play.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(index, &radians)| format!("gear {} {}'", index + 2, arcminutes(radians)))
+        .collect::<Vec<_>>();
+    let output = format!(
+        "{} arcmin at the output, gear {}",
+        arcminutes(play[play.len() - 1]),
+        play.len()
+    );
+    if gears.is_empty() {
+        format!("Backlash with gear 1 held: {}.", output)
+    } else {
+        format!(
+            "Backlash with gear 1 held: {}, and {}.",
+            gears.join(", "),
+            output
+        )
+    }
+}
+
+// the warnings badge and a button per warning, tagged with `data-gears`
+fn build_warnings(
+    summary: &web_sys::Element,
+    list: &web_sys::Element,
+    page_state: &PageState,
+) -> Result<(), JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let gears: Vec<(&GearSpecs, bool)> = page_state
+        .gears
+        .iter()
+        .map(|gear| (&gear.spec, gear.same_arbor))
+        .collect();
+    // in cam mode only the cam's problems matter
+    let warnings = if page_state.cam_mode {
+        match page_state.cam() {
+            Ok(cam) => cam
+                .problems()
+                .into_iter()
+                .map(|message| warnings::Warning {
+                    gears: vec![],
+                    message,
+                })
+                .collect(),
+            Err(err) => vec![warnings::Warning {
+                gears: vec![],
+                message: format!("The lift program doesn't work: {}", err),
+            }],
+        }
+    } else {
+        let mut warnings = warnings::check_train(&gears);
+        // the gears alone, hubs and spacers are only checked on export
+        let centers = export_centers(page_state);
+        let bounds = paper::bounds(page_state.gears.iter().zip(centers).map(|(gear, center)| {
+            paper::Bounds::around(center, GearGeometry::new(&gear.spec).outer_radius)
+        }));
+        if let Some(message) =
+            bounds.and_then(|bounds| paper::warning(&bounds, page_state.oversize))
+        {
+            warnings.push(warnings::Warning {
+                gears: (0..page_state.gears.len()).collect(),
+                message,
+            });
+        }
+        warnings.extend(
+            blank_collisions(page_state)
+                .into_iter()
+                .map(|collision| collision.warning),
+        );
+        warnings
+    };
+    let (text, color) = match warnings.len() {
+        0 => ("No Warnings".to_string(), "#666"),
+        1 => ("1 Warning".to_string(), "#b00"),
+        count => (format!("{} Warnings", count), "#b00"),
+    };
+    summary.set_attribute(
+        "style",
+        &format!(
+            "text-align: center; width: 100%; font-weight: bold; margin: 1em 0; color: {};",
+            color
+        ),
+    )?;
+    summary.set_text_content(Some(&text));
+    list.set_inner_html("");
+    for warning in warnings {
+        let gears = warning
+            .gears
+            .iter()
+            .map(|index| index.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let button = document.create_element("button")?;
+        button.set_attribute("data-gears", &gears)?;
+        button.set_attribute("style", "width: 80%; margin: 2px 10%;")?;
+        button.set_text_content(Some(&warning.message));
+        list.append_child(&button)?;
+    }
+    Ok(())
+}
+
+// 96 is a _reasonable_ default ppi, it's not exposed at all in browsers.
+// it's what css means by an inch, so it's also what browser prints use.
+const SCREEN_PPI: u32 = 96;
+
+fn full_redraw(layers: &CanvasLayers, page_state: &mut PageState) {
+    let start = perf::now();
+    let width = calculate_window_width_pixels() - sidebar_width();
+    let height = calculate_window_height_pixels();
+    layers.resize(width, height);
+    draw_workspace_background(layers, page_state);
+    page_state.changed_gears.clear();
+    let gear_count = if page_state.cam_mode {
+        0
+    } else {
+        page_state.gears.len()
+    };
+    layers.set_gear_count(gear_count);
+    perf::retain_gears(gear_count);
+    (0..gear_count).for_each(|index| redraw_gear(layers, page_state, index));
+    present(layers, page_state);
+    draw_overlay(layers, page_state);
+    update_preview(layers, page_state);
+    perf::record_redraw(perf::now() - start);
+}
+
+// the background under the gears on screen, in whichever style was picked
+fn draw_workspace_background(layers: &CanvasLayers, page_state: &mut PageState) {
+    page_state.background_changed = false;
+    let (width, height) = (layers.width(), layers.height());
+    let style = page_state.background;
+    let ppi = page_state.screen_ppi();
+    layers.svg.resize(width, height, ppi, style);
+    background::draw(&layers.background.context, style, width, height, ppi);
+    let underlay = page_state.underlay.as_ref();
+    if let Some(underlay) = underlay {
+        let context = &layers.background.context;
+        context.save();
+        context
+            .translate(width as f64 / 2.0, height as f64 / 2.0)
+            .unwrap();
+        underlay::draw(
+            context,
+            underlay,
+            &page_state.underlay_placement,
+            ppi as f64,
+        );
+        context.restore();
+    }
+    layers.svg.set_underlay(
+        underlay.map(|underlay| (underlay, &page_state.underlay_placement)),
+        width,
+        height,
+        ppi,
+    );
+    draw_rulers(layers, page_state, None);
+    // a checkerboard behind the canvas shows where it's see-through
+    let canvas_style = layers.background.canvas.style();
+    if style == BackgroundStyle::Transparent {
+        canvas_style
+            .set_property(
+                "background",
+                "repeating-conic-gradient(#eee 0 25%, white 0 50%) 0 0 / 16px 16px",
+            )
+            .unwrap();
+    } else {
+        canvas_style.remove_property("background").unwrap();
+    }
+}
+
+// the rulers, with the cursor marked on them when it's at `cursor` pixels
+// on the workspace
+fn draw_rulers(layers: &CanvasLayers, page_state: &PageState, cursor: Option<(f64, f64)>) {
+    if !page_state.show_rulers {
+        layers.rulers.clear();
+        return;
+    }
+    rulers::draw(
+        &layers.rulers.context,
+        layers.width() as f64,
+        layers.height() as f64,
+        page_state.screen_ppi() as f64,
+        page_state.ruler_units,
+        cursor,
+    );
+}
+
+// redraws just the gears whose specs changed since the last draw, big tooth
+// counts are slow enough that redrawing everything on each keystroke lags
+fn redraw_changed_gears(layers: &CanvasLayers, page_state: &mut PageState) {
+    let mut changed_gears: Vec<usize> = page_state.changed_gears.drain(..).collect();
+    if changed_gears.is_empty() && !page_state.overlay_changed && !page_state.background_changed {
+        return;
+    }
+    let start = perf::now();
+    if page_state.background_changed {
+        draw_workspace_background(layers, page_state);
+    }
+    // the cam takes the gears' place in cam mode
+    let gear_count = if page_state.cam_mode {
+        0
+    } else {
+        page_state.gears.len()
+    };
+    layers.set_gear_count(gear_count);
+    perf::retain_gears(gear_count);
+    changed_gears.sort();
+    changed_gears.dedup();
+    changed_gears
+        .iter()
+        .filter(|&&index| index < gear_count)
+        .for_each(|&index| redraw_gear(layers, page_state, index));
+    present(layers, page_state);
+    // overlays follow the gears around, so they're redrawn with any of them
+    draw_overlay(layers, page_state);
+    update_preview(layers, page_state);
+    perf::record_redraw(perf::now() - start);
+}
+
+// rebuilds the 3d preview while it's shown. gears on a shared arbor stack up
+// on the one below, meshing gears stand beside it at the same height, and
+// each is turned to mesh with its neighbour. helical teeth swap hands from
+// gear to meshing gear, like a parallel helical pair.
+fn update_preview(layers: &CanvasLayers, page_state: &PageState) {
+    let Some(preview) = &layers.preview else {
+        return;
+    };
+    if !page_state.show_preview || page_state.cam_mode {
+        preview.set_visible(false);
+        return;
+    }
+    let centers = gear_centers(&page_state.gears);
+    let geometries: Vec<GearGeometry> = page_state
+        .gears
+        .iter()
+        .map(|gear| GearGeometry::new(&gear.spec))
+        .collect();
+    let turning: Vec<animation::Turning> = page_state
+        .gears
+        .iter()
+        .zip(&geometries)
+        .map(|(gear, geometry)| animation::Turning {
+            teeth: gear.spec.teeth,
+            same_arbor: gear.same_arbor,
+            tooth_center: animation::tooth_center(geometry),
+        })
+        .collect();
+    let angles = animation::angles(&turning, 0.0);
+
+    let mut mesh = preview::Mesh::default();
+    let (mut base, mut top, mut hand) = (0.0, 0.0f64, 1.0);
+    let mut previous_top = 0.0;
+    for (index, (gear, geometry)) in page_state.gears.iter().zip(&geometries).enumerate() {
+        if index > 0 {
+            if gear.same_arbor {
+                base = previous_top;
+            } else {
+                hand = -hand;
+            }
+        }
+        let face_width = if gear.hub.flange_thickness > 0.0 {
+            gear.hub.flange_thickness
+        } else {
+            preview::DEFAULT_FACE_WIDTH
+        };
+        let placement = preview::Placement {
+            center: centers[index],
+            base,
+            angle: angles[index],
+            face_width,
+            twist: hand * preview::twist(gear.spec.helix_angle, face_width, geometry.pitch_radius),
+        };
+        let outline = tolerance::outline_points(&geometry.outline());
+        let (r, g, b) = gear.style.rgb();
+        let color = [r, g, b].map(|value| value as f32 / 255.0);
+        preview::add_gear(&mut mesh, &outline, &placement, &gear.hub, color);
+        previous_top = base + face_width.max(gear.hub.length);
+        top = top.max(previous_top);
+    }
+    preview.upload(&mesh);
+
+    // framed round the train when it's first shown, after that the camera
+    // stays where it was left
+    if !preview.is_visible() {
+        let bounds = paper::bounds(
+            geometries
+                .iter()
+                .zip(&centers)
+                .map(|(geometry, center)| paper::Bounds::around(*center, geometry.outer_radius)),
+        );
+        if let Some(bounds) = bounds {
+            let (width, height) = (bounds.max.x - bounds.min.x, bounds.max.y - bounds.min.y);
+            let mut camera = preview.camera.get();
+            camera.frame(
+                [
+                    (bounds.min.x + bounds.max.x) / 2.0,
+                    -(bounds.min.y + bounds.max.y) / 2.0,
+                    top / 2.0,
+                ],
+                width.hypot(height).hypot(top) / 2.0,
+            );
+            preview.camera.set(camera);
+        }
+        preview.set_visible(true);
+    }
+    preview.draw();
+}
+
+fn redraw_gear(layers: &CanvasLayers, page_state: &PageState, index: usize) {
+    let gear_layers = layers.gears.borrow();
+    let layer = &gear_layers[index];
+    layer.clear();
+    let gear = &page_state.gears[index];
+    let center = gear_centers(&page_state.gears)[index];
+    let points = match (&layers.webgl, page_state.renderer) {
+        (Some(webgl), Renderer::WebGl) => {
+            let geometry = GearGeometry::new(&gear.spec);
+            webgl.upload_gear(index, &geometry, center, &gear.style)
+        }
+        (_, Renderer::Svg) => {
+            let outline = tessellate_gear(gear, center);
+            layers
+                .svg
+                .update_gear(index, &outline, &gear.style, page_state.screen_ppi());
+            outline.point_count()
+        }
+        _ => {
+            let outline = tessellate_gear(gear, center);
+            let points = outline.point_count();
+            layer
+                .context
+                .translate(layers.width() as f64 / 2.0, layers.height() as f64 / 2.0)
+                .unwrap();
+            draw_gears(
+                &layer.context,
+                &[outline],
+                std::slice::from_ref(&gear.style),
+                &page_state.debug,
+                page_state.screen_ppi(),
+            );
+            points
+        }
+    };
+    perf::record_gear_points(index, points);
+}
+
+// shows whichever non-canvas renderer is active. webgl draws every uploaded
+// gear in one go, the svg scene covers the canvas layers while it's in use.
+fn present(layers: &CanvasLayers, page_state: &PageState) {
+    if let Some(webgl) = &layers.webgl {
+        match page_state.renderer {
+            Renderer::WebGl => webgl.draw(page_state.screen_ppi()),
+            _ => webgl.clear(),
+        }
+    }
+    layers.svg.set_visible(page_state.renderer == Renderer::Svg);
+}
+
+// annotations drawn over the gears, cheap enough to redraw in full
+fn draw_overlay(layers: &CanvasLayers, page_state: &mut PageState) {
+    page_state.overlay_changed = false;
+    let overlay = &layers.overlay;
+    overlay.clear();
+    let scale = page_state.screen_ppi() as f64;
+    overlay
+        .context
+        .translate(layers.width() as f64 / 2.0, layers.height() as f64 / 2.0)
+        .unwrap();
+    if let Some(outline) = &page_state.reference {
+        let placed = outline.placed(&page_state.reference_alignment);
+        reference::draw(&overlay.context, &placed, scale);
+    }
+    if let Some(picks) = &page_state.calibration_picks {
+        underlay::draw_picks(&overlay.context, picks, scale);
+    }
+    // the cam stands in for the gears and their annotations
+    if page_state.cam_mode {
+        if let Ok(cam) = page_state.cam() {
+            cam::draw(&overlay.context, &cam, Point { x: 0.0, y: 0.0 }, scale);
+        }
+        return;
+    }
+    let geometries: Vec<GearGeometry> = page_state
+        .gears
+        .iter()
+        .map(|gear| GearGeometry::new(&gear.spec))
+        .collect();
+    let centers = gear_centers(&page_state.gears);
+    geometries.iter().enumerate().for_each(|(index, geometry)| {
+        let center = Point {
+            x: centers[index].x * scale,
+            y: centers[index].y * scale,
+        };
+        let color = &page_state.gears[index].style.color;
+        if page_state.highlighted_gears.contains(&index) {
+            overlay::draw_highlight(&overlay.context, center, geometry.outer_radius * scale);
+        }
+        if page_state.show_rotation_arrows {
+            overlay::draw_rotation_arrow(
+                &overlay.context,
+                center,
+                geometry.root_radius * scale / 2.0,
+                gear_direction(page_state, index),
+                color,
+            );
+        }
+        if page_state.show_labels {
+            // gears on the same arbor stack their labels downwards
+            let stacked = page_state.gears[..=index]
+                .iter()
+                .rev()
+                .take_while(|gear| gear.same_arbor)
+                .count();
+            let position = Point {
+                x: center.x,
+                y: center.y + 16.0 * stacked as f64,
+            };
+            let teeth = format!("{}T", geometry.teeth);
+            overlay::draw_label(&overlay.context, &teeth, position, color);
+        }
+    });
 
-    // add text input for left gear teeth
-    let left_gear_input = document.create_element("input")?;
-    left_gear_input
-        .set_attribute("id", "left_gear_teeth")
-        .unwrap();
-    left_gear_input.set_attribute("type", "text").unwrap();
-    left_gear_input
-        .set_attribute("placeholder", "Enter left gear teeth")
-        .unwrap();
-    left_gear_input
-        .set_attribute("value", &state.borrow().left_gear_spec.teeth.to_string())
-        .unwrap();
-    left_gear_input
-        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
-        .unwrap();
-    sidebar.append_child(&left_gear_input)?;
+    for collision in blank_collisions(page_state) {
+        let index = collision.warning.gears[0];
+        overlay::draw_collision(&overlay.context, &collision.loops, centers[index], scale);
+    }
 
-    // add right gear subtitle
-    let right_gear_subtitle = document.create_element("h3")?;
-    right_gear_subtitle
-        .set_attribute("style", "text-align: center; width: 100%;")
-        .unwrap();
-    right_gear_subtitle.set_text_content(Some("Right Gear"));
-    sidebar.append_child(&right_gear_subtitle)?;
+    if page_state.show_tolerance_band {
+        let tolerance = page_state.profile_tolerance_mm / MM_PER_INCH;
+        geometries.iter().enumerate().for_each(|(index, geometry)| {
+            tolerance::draw(
+                &overlay.context,
+                &tolerance::outline_points(&geometry.outline()),
+                centers[index],
+                tolerance,
+                scale,
+                &page_state.gears[index].style.color,
+            );
+        });
+    }
 
-    // label for right gear teeth input
-    let right_gear_teeth_label = document.create_element("label")?;
-    right_gear_teeth_label
-        .set_attribute("for", "right_gear_teeth")
-        .unwrap();
-    right_gear_teeth_label.set_text_content(Some("Teeth:"));
-    right_gear_teeth_label
-        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
-        .unwrap();
-    sidebar.append_child(&right_gear_teeth_label)?;
+    // terminology only applies to involute teeth
+    if page_state.show_terminology && page_state.gears[0].spec.profile == ToothProfile::Involute {
+        let center = Point {
+            x: centers[0].x * scale,
+            y: centers[0].y * scale,
+        };
+        overlay::draw_terminology(&overlay.context, &geometries[0], center, scale);
+    }
 
-    // add right gear input
-    let right_gear_input = document.create_element("input")?;
-    right_gear_input
-        .set_attribute("id", "right_gear_teeth")
-        .unwrap();
-    right_gear_input.set_attribute("type", "text").unwrap();
-    right_gear_input
-        .set_attribute("placeholder", "Enter right gear teeth")
-        .unwrap();
-    right_gear_input
-        .set_attribute("value", &state.borrow().right_gear_spec.teeth.to_string())
-        .unwrap();
-    right_gear_input
-        .set_attribute("style", "width: 80%; margin-left: 10%; margin-right: 10%;")
-        .unwrap();
-    sidebar.append_child(&right_gear_input)?;
+    // mesh ratios just below where each pair meets, clear of the smaller gear
+    if page_state.show_labels {
+        geometries.windows(2).enumerate().for_each(|(index, pair)| {
+            if page_state.gears[index + 1].same_arbor {
+                return;
+            }
+            let ratio = overlay::ratio_label(pair[0].teeth as u32, pair[1].teeth as u32);
+            let below = f64::min(pair[0].outer_radius, pair[1].outer_radius) * scale + 14.0;
+            let pitch_point = Point {
+                x: (centers[index].x + pair[0].pitch_radius) * scale,
+                y: below,
+            };
+            overlay::draw_label(&overlay.context, &ratio, pitch_point, "black");
+        });
+    }
+}
 
-    // add button for print
-    let print_button = document.create_element("button")?;
-    print_button.set_attribute("id", "print_button").unwrap();
-    print_button.set_text_content(Some("Print"));
-    print_button
-        .set_attribute(
-            "style",
-            "width: 100px; position: fixed; bottom: 20px; left: 20px;",
-        )
-        .unwrap();
-    sidebar.append_child(&print_button)?;
+// the first gear drives, and every external mesh flips the direction. gears
+// on the same arbor turn together.
+fn gear_direction(page_state: &PageState, index: usize) -> RotationDirection {
+    let meshes = page_state.gears[1..=index]
+        .iter()
+        .filter(|gear| !gear.same_arbor)
+        .count();
+    if meshes.is_multiple_of(2) {
+        page_state.input_direction
+    } else {
+        page_state.input_direction.reversed()
+    }
+}
 
-    // update print button to create an alert with the current gear specs
-    print_button
-        .add_event_listener_with_callback("click", print_gears_closure.as_ref().unchecked_ref())?;
-    print_button
-        .add_event_listener_with_callback("click", redraw_closure.as_ref().unchecked_ref())?;
+// which renderer draws the gears on screen
+#[derive(Clone, Copy, PartialEq)]
+enum Renderer {
+    Canvas,
+    WebGl,
+    Svg,
+}
 
-    // Add all event listeners to update state when input changes
-    let closure = Closure::wrap(Box::new(move || {
-        // get left gear input
-        let value = left_gear_input
-            .dyn_ref::<HtmlInputElement>()
-            .unwrap()
-            .value();
-        if let Ok(teeth) = value.parse::<u32>() {
-            state.borrow_mut().left_gear_spec.teeth = teeth as f64; // Update the state
-        }
-        // gear diametric pitch input
-        let value = gear_diametric_pitch_input
-            .dyn_ref::<HtmlInputElement>()
-            .unwrap()
-            .value();
-        if let Ok(diametric_pitch) = value.parse::<f64>() {
-            state.borrow_mut().left_gear_spec.diametric_pitch = diametric_pitch;
-            state.borrow_mut().right_gear_spec.diametric_pitch = diametric_pitch;
+impl Renderer {
+    fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "canvas" => Some(Renderer::Canvas),
+            "webgl" => Some(Renderer::WebGl),
+            "svg" => Some(Renderer::Svg),
+            _ => None,
         }
+    }
 
-        // get right gear input
-        let value = right_gear_input
-            .dyn_ref::<HtmlInputElement>()
-            .unwrap()
-            .value();
-        if let Ok(teeth) = value.parse::<u32>() {
-            // Borrow the state mutably to update it
-            state.borrow_mut().right_gear_spec.teeth = teeth as f64; // Update the state
+    fn value(&self) -> &'static str {
+        match self {
+            Renderer::Canvas => "canvas",
+            Renderer::WebGl => "webgl",
+            Renderer::Svg => "svg",
         }
-    }) as Box<dyn Fn()>);
-
-    sidebar.add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())?;
-    closure.forget();
+    }
+}
 
-    // redraw after input is changed / parameters are updated
-    sidebar.add_event_listener_with_callback("input", redraw_closure.as_ref().unchecked_ref())?;
+// one gear of the train
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct GearState {
+    spec: GearSpecs,
+    style: GearStyle,
+    // turns on the previous gear's arbor instead of meshing with it
+    #[serde(default)]
+    same_arbor: bool,
+    // printed as a dimensioned side view under the gear
+    #[serde(default)]
+    hub: Hub,
+    #[serde(default)]
+    spokes: Spokes,
+    // an svg shape cut out of the blank
+    #[serde(default)]
+    svg_cutout: Option<cutouts::ImportedCutout>,
+    // circles, slots and rounded rectangles cut out of the blank
+    #[serde(default)]
+    shapes: Vec<cutouts::ShapeCutout>,
+}
 
-    Ok(sidebar)
+// the built in 50 / 10 tooth pair
+fn default_gears() -> Vec<GearState> {
+    [50.0, 10.0]
+        .iter()
+        .map(|&teeth| GearState {
+            spec: GearSpecs {
+                teeth,
+                diametric_pitch: 12.0,
+                tooth_angle: 20.0,
+                clearance_mult: 0.167,
+                backlash_mult: 0.05,
+                involute_steps: 100,
+                profile: ToothProfile::Involute,
+                helix_angle: 0.0,
+                thinning: Thinning::Half,
+            },
+            style: GearStyle::plain(),
+            same_arbor: false,
+            hub: Hub::default(),
+            spokes: Spokes::default(),
+            svg_cutout: None,
+            shapes: Vec::new(),
+        })
+        .collect()
 }
 
-fn full_redraw(
-    canvas: &web_sys::HtmlCanvasElement,
-    context: &web_sys::CanvasRenderingContext2d,
-    page_state: &PageState,
-) {
-    let width = calculate_window_width_pixels();
-    let height = calculate_window_height_pixels();
-    canvas
-        .set_attribute("style", "padding-left: 200px;")
-        .unwrap();
-    // 96 is a _reasonable_ default ppi, it's not exposed at all in browsers
-    redraw(canvas, context, width - 200, height, page_state, 96);
+// every change to the page state, applied by the store
+enum Action {
+    // the sidebar's controls as read on one input, applied in order
+    Batch(Vec<Action>),
+    // the design as the sidebar's inputs have it, redrawing whichever gears
+    // it changes or moves
+    EditDesign {
+        name: String,
+        linked: bool,
+        gears: Vec<GearState>,
+        flank_tolerance_mm: Option<f64>,
+    },
+    SetExportOptions(ExportOptions),
+    SetView(ViewOptions),
+    SetCam {
+        mode: bool,
+        program: String,
+        base_radius: f64,
+        follower: cam::Follower,
+    },
+    AlignReference(reference::Alignment),
+    PlaceUnderlay(underlay::Placement),
+    // highlights the gears, or clears the highlight if it's already them
+    ToggleHighlight(Vec<usize>),
+    SetDebug(DebugConfig),
+    // redraws every gear from its specs, as if they'd all just changed
+    Retessellate,
+    RemoveReference,
+    // drops the photo, its scale and any calibration picks in progress
+    RemoveUnderlay,
+    // swaps in a whole design, from storage, a tab, an assistant or an
+    // embedding page
+    LoadDesign(Design),
+    RenameDesign(String),
+    // inserts a copy of the gear right after it
+    DuplicateGear(usize),
+    // trades specs with the next gear, leaving the colors in place
+    SwapGears(usize),
+    ApplyRecommendedHub(usize),
+    RemoveCutout(usize),
+    ImportCutout {
+        gear: usize,
+        cutout: cutouts::ImportedCutout,
+    },
+    AddShape(usize),
+    RemoveShape {
+        gear: usize,
+        shape: usize,
+    },
+    LoadReference(reference::Reference),
+    // a new photo starts uncalibrated
+    LoadUnderlay(underlay::Underlay),
+    // the photo can be drawn once the browser has decoded it
+    UnderlayDecoded,
+    StartCalibration,
+    PickCalibrationPoint(Point),
+    // ends the picking, rescaling the photo if the points were usable
+    FinishCalibration(Option<underlay::Placement>),
+    // turns on actual size at the screen's measured pixels per inch
+    CalibrateScreen(f64),
+    // keeps the sheet editor's arrangement for the next pdf
+    ArrangeSheets(sheets::SheetLayout),
 }
 
-// enum left / right
-#[derive(PartialEq)]
-enum Gear {
-    Left,
-    Right,
+// settings that only change what exports come out as
+struct ExportOptions {
+    dxf_curve_mode: DxfCurveMode,
+    roughing: bool,
+    roughing_tool: roughing::Tool,
+    holding_tabs: bridges::Bridges,
+    oversize: paper::Oversize,
+    svg_units: svg::SvgUnits,
+    export_layout: paper::Layout,
+    exploded_gap: f64,
+    drill_marks: bool,
+    export_colors: bool,
+    mirror_exports: bool,
+    high_contrast: bool,
+    line_widths: style::LineWidths,
+    spacers: Spacers,
+    rack_tip_radius_mult: f64,
+    rack_protuberance_mult: f64,
+    kerf_test_size_mm: f64,
+    mold_blank: mold::BlankShape,
+    mold_wall: f64,
+    animation_format: animation::Format,
+    animation_turns: f64,
 }
 
-// struct for points
-#[derive(Clone, Copy)]
-struct Point {
-    x: f64,
-    y: f64,
+// how the workspace is drawn, none of which is saved with the design
+struct ViewOptions {
+    renderer: Renderer,
+    background: BackgroundStyle,
+    show_rulers: bool,
+    ruler_units: rulers::RulerUnits,
+    actual_size: bool,
+    calibrated_ppi: Option<f64>,
+    input_direction: RotationDirection,
+    show_rotation_arrows: bool,
+    show_labels: bool,
+    show_terminology: bool,
+    show_construction: bool,
+    show_hobbing: bool,
+    show_preview: bool,
+    show_tolerance_band: bool,
+    profile_tolerance_mm: f64,
 }
 
-impl std::ops::Add for Point {
-    type Output = Self;
+impl store::Reduce<Action> for PageState {
+    fn reduce(&mut self, action: &Action) {
+        match action {
+            Action::Batch(actions) => actions.iter().for_each(|action| self.reduce(action)),
+            Action::EditDesign {
+                name,
+                linked,
+                gears,
+                flank_tolerance_mm,
+            } => {
+                // gears edited, or moved along by an edit to a gear before them
+                let centers_before = gear_centers(&self.gears);
+                let centers = gear_centers(gears);
+                let changed: Vec<usize> = (0..gears.len())
+                    .filter(|&index| {
+                        self.gears.get(index) != Some(&gears[index])
+                            || centers_before.get(index) != Some(&centers[index])
+                    })
+                    .collect();
+                // highlights point at gears by index, which an edit can shuffle
+                if !changed.is_empty() && !self.highlighted_gears.is_empty() {
+                    self.highlighted_gears.clear();
+                    self.overlay_changed = true;
+                }
+                self.changed_gears.extend(changed);
+                self.name = name.clone();
+                self.linked = *linked;
+                self.gears = gears.clone();
+                self.flank_tolerance_mm = *flank_tolerance_mm;
+            }
+            Action::SetExportOptions(options) => {
+                let ExportOptions {
+                    dxf_curve_mode,
+                    roughing,
+                    roughing_tool,
+                    holding_tabs,
+                    oversize,
+                    svg_units,
+                    export_layout,
+                    exploded_gap,
+                    drill_marks,
+                    export_colors,
+                    mirror_exports,
+                    high_contrast,
+                    line_widths,
+                    spacers,
+                    rack_tip_radius_mult,
+                    rack_protuberance_mult,
+                    kerf_test_size_mm,
+                    mold_blank,
+                    mold_wall,
+                    animation_format,
+                    animation_turns,
+                } = options;
+                self.dxf_curve_mode = *dxf_curve_mode;
+                self.roughing = *roughing;
+                self.roughing_tool = *roughing_tool;
+                self.holding_tabs = *holding_tabs;
+                self.oversize = *oversize;
+                self.svg_units = *svg_units;
+                self.export_layout = *export_layout;
+                self.exploded_gap = *exploded_gap;
+                self.drill_marks = *drill_marks;
+                self.export_colors = *export_colors;
+                self.mirror_exports = *mirror_exports;
+                self.high_contrast = *high_contrast;
+                self.line_widths = *line_widths;
+                self.spacers = *spacers;
+                self.rack_tip_radius_mult = *rack_tip_radius_mult;
+                self.rack_protuberance_mult = *rack_protuberance_mult;
+                self.kerf_test_size_mm = *kerf_test_size_mm;
+                self.mold_blank = *mold_blank;
+                self.mold_wall = *mold_wall;
+                self.animation_format = *animation_format;
+                self.animation_turns = *animation_turns;
+            }
+            Action::SetView(view) => {
+                // switching renderers redraws every gear
+                self.set_screen_scale(view.actual_size, view.calibrated_ppi);
+                if self.renderer != view.renderer {
+                    self.changed_gears.extend(0..self.gears.len());
+                }
+                self.renderer = view.renderer;
+                // the background and rulers are drawn on their own layer
+                if (self.background, self.show_rulers, self.ruler_units)
+                    != (view.background, view.show_rulers, view.ruler_units)
+                {
+                    self.background = view.background;
+                    self.show_rulers = view.show_rulers;
+                    self.ruler_units = view.ruler_units;
+                    self.background_changed = true;
+                }
+                // and the rest only touch the overlay
+                let overlay = (
+                    self.input_direction,
+                    self.show_rotation_arrows,
+                    self.show_labels,
+                    self.show_terminology,
+                    self.show_preview,
+                    self.show_tolerance_band,
+                    self.profile_tolerance_mm,
+                );
+                self.input_direction = view.input_direction;
+                self.show_rotation_arrows = view.show_rotation_arrows;
+                self.show_labels = view.show_labels;
+                self.show_terminology = view.show_terminology;
+                self.show_preview = view.show_preview;
+                self.show_tolerance_band = view.show_tolerance_band;
+                self.profile_tolerance_mm = view.profile_tolerance_mm;
+                if overlay
+                    != (
+                        self.input_direction,
+                        self.show_rotation_arrows,
+                        self.show_labels,
+                        self.show_terminology,
+                        self.show_preview,
+                        self.show_tolerance_band,
+                        self.profile_tolerance_mm,
+                    )
+                {
+                    self.overlay_changed = true;
+                }
+                // the construction and hobbing animations draw on their own layer
+                self.show_construction = view.show_construction;
+                self.show_hobbing = view.show_hobbing;
+            }
+            Action::SetCam {
+                mode,
+                program,
+                base_radius,
+                follower,
+            } => {
+                if self.cam_mode != *mode {
+                    self.cam_mode = *mode;
+                    self.changed_gears.extend(0..self.gears.len());
+                }
+                if (&self.cam_program, self.cam_base_radius, self.cam_follower)
+                    != (program, *base_radius, *follower)
+                {
+                    self.cam_program = program.clone();
+                    self.cam_base_radius = *base_radius;
+                    self.cam_follower = *follower;
+                    self.overlay_changed = true;
+                }
+            }
+            // the reference outline only moves over the gears
+            Action::AlignReference(alignment) => {
+                if self.reference_alignment != *alignment {
+                    self.reference_alignment = *alignment;
+                    self.overlay_changed = true;
+                }
+            }
+            // the photo is part of the background, under everything else
+            Action::PlaceUnderlay(placement) => {
+                if self.underlay_placement != *placement {
+                    self.underlay_placement = *placement;
+                    self.background_changed = true;
+                }
+            }
+            Action::ToggleHighlight(gears) => {
+                if self.highlighted_gears == *gears {
+                    self.highlighted_gears.clear();
+                } else {
+                    self.highlighted_gears = gears.clone();
+                }
+                self.overlay_changed = true;
+            }
+            Action::SetDebug(config) => {
+                self.debug = *config;
+                self.changed_gears.extend(0..self.gears.len());
+            }
+            Action::Retessellate => {
+                self.changed_gears.extend(0..self.gears.len());
+                self.overlay_changed = true;
+            }
+            Action::RemoveReference => {
+                self.reference = None;
+                self.overlay_changed = true;
+            }
+            Action::RemoveUnderlay => {
+                self.underlay = None;
+                self.underlay_placement.pixels_per_inch =
+                    underlay::Placement::default().pixels_per_inch;
+                if self.calibration_picks.take().is_some() {
+                    self.overlay_changed = true;
+                }
+                self.background_changed = true;
+            }
+            Action::LoadDesign(design) => {
+                self.name = design.name.clone();
+                self.gears = design.gears.clone();
+                self.linked = design.linked;
+                // a set flank tolerance picks the loaded gears' points too
+                fit_flank_tolerance(&mut self.gears, self.flank_tolerance_mm);
+                self.highlighted_gears.clear();
+                self.gears_changed_from(0);
+            }
+            Action::RenameDesign(name) => self.name = name.clone(),
+            Action::DuplicateGear(index) => {
+                let gear = self.gears[*index].clone();
+                self.gears.insert(index + 1, gear);
+                self.gears_changed_from(*index);
+            }
+            Action::SwapGears(index) => {
+                if index + 1 < self.gears.len() {
+                    let (first, rest) = self.gears.split_at_mut(index + 1);
+                    std::mem::swap(&mut first[*index].spec, &mut rest[0].spec);
+                    self.gears_changed_from(*index);
+                }
+            }
+            Action::ApplyRecommendedHub(index) => {
+                let hub = &mut self.gears[*index].hub;
+                if let Some(recommended) = hub.recommendation() {
+                    hub.diameter = recommended.diameter;
+                    hub.length = recommended.length;
+                    self.gears_changed_from(*index);
+                }
+            }
+            Action::RemoveCutout(index) => {
+                self.gears[*index].svg_cutout = None;
+                self.gears_changed_from(*index);
+            }
+            Action::ImportCutout { gear, cutout } => {
+                if let Some(state) = self.gears.get_mut(*gear) {
+                    state.svg_cutout = Some(cutout.clone());
+                    self.changed_gears.push(*gear);
+                }
+            }
+            Action::AddShape(index) => {
+                self.gears[*index]
+                    .shapes
+                    .push(cutouts::ShapeCutout::default());
+                self.gears_changed_from(*index);
+            }
+            Action::RemoveShape { gear, shape } => {
+                if *shape < self.gears[*gear].shapes.len() {
+                    self.gears[*gear].shapes.remove(*shape);
+                    self.gears_changed_from(*gear);
+                }
+            }
+            Action::LoadReference(reference) => {
+                self.reference = Some(reference.clone());
+                self.overlay_changed = true;
+            }
+            Action::LoadUnderlay(underlay) => {
+                self.underlay = Some(underlay.clone());
+                self.underlay_placement.pixels_per_inch =
+                    underlay::Placement::default().pixels_per_inch;
+            }
+            Action::UnderlayDecoded => self.background_changed = true,
+            Action::StartCalibration => {
+                self.calibration_picks = Some(Vec::new());
+                self.overlay_changed = true;
+            }
+            Action::PickCalibrationPoint(pick) => {
+                if let Some(picks) = &mut self.calibration_picks {
+                    picks.push(*pick);
+                    self.overlay_changed = true;
+                }
+            }
+            Action::FinishCalibration(placement) => {
+                self.calibration_picks = None;
+                self.overlay_changed = true;
+                if let Some(placement) = placement {
+                    self.underlay_placement = *placement;
+                    self.background_changed = true;
+                }
+            }
+            Action::CalibrateScreen(ppi) => self.set_screen_scale(true, Some(*ppi)),
+            Action::ArrangeSheets(layout) => self.sheet_layout = Some(layout.clone()),
+        }
+    }
+}
 
-    fn add(self, other: Self) -> Self {
-        Point {
-            x: self.x + other.x,
-            y: self.y + other.y,
+// gives each involute gear the points per flank that keep it within
+// `tolerance_mm` of the true curve, if there's a tolerance
+fn fit_flank_tolerance(gears: &mut [GearState], tolerance_mm: Option<f64>) {
+    let Some(tolerance) = tolerance_mm else {
+        return;
+    };
+    for gear in gears.iter_mut() {
+        if gear.spec.profile == ToothProfile::Involute {
+            gear.spec.involute_steps =
+                geometry::involute_steps_within(&gear.spec, tolerance / MM_PER_INCH);
         }
     }
 }
 
 // struct for page state
 struct PageState {
-    left_gear_spec: GearSpecs,
-    right_gear_spec: GearSpecs,
+    // the design's name, empty until it's saved
+    name: String,
+    // gears in the order they mesh, left to right
+    gears: Vec<GearState>,
+    dxf_curve_mode: DxfCurveMode,
+    // pdf exports use the gear colors instead of plain black outlines
+    export_colors: bool,
+    // exports are flipped left to right, for templates glued to the back of
+    // the stock. the view is left alone.
+    mirror_exports: bool,
+    // what pdf exports do with layouts too big for a page
+    oversize: paper::Oversize,
+    // whether exports keep the gears in mesh or spread them out, and by how
+    // much, in inches between their tips
+    export_layout: paper::Layout,
+    exploded_gap: f64,
+    // center punch crosshairs and pilot circles on every hole
+    drill_marks: bool,
+    // what svg exports are measured in
+    svg_units: svg::SvgUnits,
+    // how thick exported lines are, in millimeters
+    line_widths: style::LineWidths,
+    // printed templates in thick black lines on plain white, for cutting
+    // by hand
+    high_contrast: bool,
+    // corner radius of the exported rack's teeth, in modules
+    rack_tip_radius_mult: f64,
+    // relief the exported rack's tips cut below the gear's working flanks,
+    // in modules
+    rack_protuberance_mult: f64,
+    // nominal width of the kerf test's slots and pins, usually the stock's
+    // thickness
+    kerf_test_size_mm: f64,
+    // the stock mold cavities are cut into, and how much of it is left
+    // beyond the gears' tips, in inches
+    mold_blank: mold::BlankShape,
+    mold_wall: f64,
+    // the pdf's sheets as they were last arranged by hand
+    sheet_layout: Option<sheets::SheetLayout>,
+    // recordings of the gears turning, and how many turns of the first gear
+    // they run for
+    animation_format: animation::Format,
+    animation_turns: f64,
+    // clearing passes for milling the gears, added to their dxf
+    roughing: bool,
+    roughing_tool: roughing::Tool,
+    // tabs left uncut around each outline in the dxf, none by default
+    holding_tabs: bridges::Bridges,
+    // how close the flanks have to follow the true involute, which sets
+    // each gear's points per flank instead of the shared count
+    flank_tolerance_mm: Option<f64>,
+    // rings cut with the gears to space them on their shafts
+    spacers: Spacers,
+    // shows and exports a cam built from the lift program instead of the gears
+    cam_mode: bool,
+    cam_program: String,
+    cam_base_radius: f64,
+    cam_follower: cam::Follower,
+    renderer: Renderer,
+    // which way the first gear turns
+    input_direction: RotationDirection,
+    show_rotation_arrows: bool,
+    // tooth counts and the mesh ratio
+    show_labels: bool,
+    // animates how the first gear's flank is drawn out from the base circle
+    show_construction: bool,
+    show_hobbing: bool,
+    // the gears extruded to their face widths in 3d, in place of the 2d view
+    show_preview: bool,
+    // every gear drawn at maximum and minimum material for the profile
+    // tolerance, to check the pair meshes across it
+    show_tolerance_band: bool,
+    profile_tolerance_mm: f64,
+    // names the parts of the first gear
+    show_terminology: bool,
+    // the developer panel's debug circles, only ever drawn on screen
+    debug: DebugConfig,
+    // gears picked out from the warnings panel
+    highlighted_gears: Vec<usize>,
+    // every gear shares pitch, pressure angle and clearance
+    linked: bool,
+    // indices of gears edited or moved since they were last drawn
+    changed_gears: Vec<usize>,
+    // overlay settings edited since it was last drawn
+    overlay_changed: bool,
+    // what the workspace is drawn on, and whether it needs drawing again
+    background: BackgroundStyle,
+    background_changed: bool,
+    // rulers along the workspace's edges, redrawn with the background
+    show_rulers: bool,
+    ruler_units: rulers::RulerUnits,
+    // draws the workspace at this screen's measured pixels per inch, so
+    // gears held up to it match their printed size
+    actual_size: bool,
+    calibrated_ppi: Option<f64>,
+    // an imported outline drawn over the gears, and where it sits
+    reference: Option<reference::Reference>,
+    reference_alignment: reference::Alignment,
+    // a photo drawn under the gears, and how big and where
+    underlay: Option<underlay::Underlay>,
+    underlay_placement: underlay::Placement,
+    // points picked on the photo while calibrating its scale, in inches
+    // from the view's center. `None` when not calibrating.
+    calibration_picks: Option<Vec<Point>>,
+}
+
+impl PageState {
+    // the parts of the state that are saved with a design
+    fn design(&self) -> Design {
+        Design {
+            name: self.name.clone(),
+            gears: self.gears.clone(),
+            linked: self.linked,
+        }
+    }
+
+    // pixels per inch the workspace is drawn at. the measured figure is
+    // rounded, which is within half a percent on any screen.
+    fn screen_ppi(&self) -> u32 {
+        match self.calibrated_ppi {
+            Some(ppi) if self.actual_size => ppi.round() as u32,
+            _ => SCREEN_PPI,
+        }
+    }
+
+    // the train from gear `index` on shifts along when a gear is added or
+    // moved, so all of it is redrawn
+    fn gears_changed_from(&mut self, index: usize) {
+        self.changed_gears.extend(index..self.gears.len());
+        self.overlay_changed = true;
+    }
+
+    // drawing at another scale redraws everything
+    fn set_screen_scale(&mut self, actual_size: bool, calibrated_ppi: Option<f64>) {
+        let scaled = self.screen_ppi();
+        self.actual_size = actual_size;
+        self.calibrated_ppi = calibrated_ppi;
+        if self.screen_ppi() != scaled {
+            self.changed_gears.extend(0..self.gears.len());
+            self.overlay_changed = true;
+            self.background_changed = true;
+        }
+    }
+
+    // the cam mode's cam, or why its lift program doesn't work
+    fn cam(&self) -> Result<cam::Cam, String> {
+        Ok(cam::Cam {
+            base_radius: self.cam_base_radius,
+            follower: self.cam_follower,
+            segments: cam::parse_program(&self.cam_program)?,
+        })
+    }
+}
+
+// sidebar inputs that are reset or rebuilt when a whole design is loaded
+struct DesignInputs {
+    name: web_sys::Element,
+    gear_sections: web_sys::Element,
+    gear_inputs: Rc<RefCell<Vec<GearInputs>>>,
+    linked: web_sys::Element,
+    diametric_pitch: web_sys::Element,
+    tooth_angle: web_sys::Element,
+    tooth_angle_presets: gear_inputs::PressureAnglePresets,
+    clearance: web_sys::Element,
+    backlash: web_sys::Element,
+    involute_steps: web_sys::Element,
+}
+
+impl DesignInputs {
+    // puts the state's design in the inputs, once it's been loaded
+    fn show(&self, state: &PageState) {
+        self.name
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .set_value(&state.name);
+
+        // the shared inputs start from the first gear
+        let spec = &state.gears[0].spec;
+        [
+            (
+                &self.diametric_pitch,
+                locale::format(spec.diametric_pitch),
+                !state.linked,
+            ),
+            (
+                &self.tooth_angle,
+                locale::format(spec.tooth_angle),
+                !state.linked,
+            ),
+            (
+                &self.clearance,
+                locale::format(spec.clearance_mult),
+                !state.linked,
+            ),
+            (&self.backlash, locale::format(spec.backlash_mult), false),
+            (
+                &self.involute_steps,
+                spec.involute_steps.to_string(),
+                state.flank_tolerance_mm.is_some(),
+            ),
+        ]
+        .iter()
+        .for_each(|(input, value, disabled)| {
+            let input = input.dyn_ref::<HtmlInputElement>().unwrap();
+            input.set_value(value);
+            input.set_disabled(*disabled);
+        });
+        self.tooth_angle_presets.describe(spec.tooth_angle);
+        self.linked
+            .dyn_ref::<HtmlInputElement>()
+            .unwrap()
+            .set_checked(state.linked);
+        self.rebuild_gear_sections(state);
+    }
+
+    fn rebuild_gear_sections(&self, state: &PageState) {
+        *self.gear_inputs.borrow_mut() =
+            build_gear_sections(&self.gear_sections, &state.gears, state.linked).unwrap();
+    }
 }
 
-// struct for gear specs
-struct GearSpecs {
-    teeth: f64,
-    diametric_pitch: f64,
-    tooth_angle: f64,
-    clearance_mult: f64,
-    backlash_mult: f64,
+// the developer panel, hidden until ctrl+alt+d. it turns the debug circles
+// on, redraws every gear from scratch, and shows the frame rate, each
+// gear's point count and the design's parameters, refreshed every second
+// while it's open.
+struct DebugPanel {
+    panel: web_sys::Element,
+    stats: web_sys::Element,
+    state: Rc<RefCell<PageState>>,
+    open: std::cell::Cell<bool>,
+    // when the current second of frames started, and how many there were
+    frames: std::cell::Cell<(f64, u32)>,
+}
+
+// the panel's debug circle toggles, by what they show
+const DEBUG_CIRCLES: [(&str, &str); 4] = [
+    ("base", "Base Circle"),
+    ("root", "Root Circle"),
+    ("outer", "Outer Circle"),
+    ("pitch", "Pitch Circle"),
+];
+
+impl DebugPanel {
+    fn create(
+        document: &web_sys::Document,
+        state: Rc<RefCell<PageState>>,
+        store: Rc<store::Store<PageState, Action>>,
+    ) -> Result<(), JsValue> {
+        let panel = document.create_element("div")?;
+        panel.set_attribute("style", "display: none; position: fixed; right: 10px; bottom: 10px; width: 320px; max-height: 60%; overflow: auto; padding: 8px; background-color: rgba(0, 0, 0, 0.8); color: white; font-size: 12px; z-index: 10;")?;
+        let title = document.create_element("h4")?;
+        title.set_attribute("style", "margin: 0 0 6px 0;")?;
+        title.set_text_content(Some("Developer (Ctrl+Alt+D)"));
+        panel.append_child(&title)?;
+        for (circle, text) in DEBUG_CIRCLES {
+            let label = document.create_element("label")?;
+            label.set_attribute("style", "display: block;")?;
+            let input = document.create_element("input")?;
+            input.set_attribute("type", "checkbox")?;
+            input.set_attribute("data-circle", circle)?;
+            label.append_child(&input)?;
+            label.append_with_str_1(text)?;
+            panel.append_child(&label)?;
+        }
+        // the svg and webgl renderers don't draw debug circles
+        let note = document.create_element("div")?;
+        note.set_attribute("style", "opacity: 0.7;")?;
+        note.set_text_content(Some("Circles show with the Canvas 2D renderer."));
+        panel.append_child(&note)?;
+        let retessellate = document.create_element("button")?;
+        retessellate.set_attribute("style", "margin: 6px 0;")?;
+        retessellate.set_text_content(Some("Re-tessellate All Gears"));
+        panel.append_child(&retessellate)?;
+        let stats = document.create_element("pre")?;
+        stats.set_attribute("style", "margin: 0; white-space: pre-wrap;")?;
+        panel.append_child(&stats)?;
+        document.body().unwrap().append_child(&panel)?;
+
+        let toggles = panel.clone();
+        let toggle_store = store.clone();
+        let toggle_closure = Closure::wrap(Box::new(move || {
+            let checked = |circle: &str| {
+                toggles
+                    .query_selector(&format!("[data-circle=\"{}\"]", circle))
+                    .ok()
+                    .flatten()
+                    .and_then(|input| input.dyn_into::<HtmlInputElement>().ok())
+                    .is_some_and(|input| input.checked())
+            };
+            toggle_store.dispatch(Action::SetDebug(DebugConfig {
+                show_base_circle: checked("base"),
+                show_inner_circle: checked("root"),
+                show_outer_circle: checked("outer"),
+                show_pitch_circle: checked("pitch"),
+            }));
+        }) as Box<dyn Fn()>);
+        panel
+            .add_event_listener_with_callback("change", toggle_closure.as_ref().unchecked_ref())?;
+        toggle_closure.forget();
+
+        let retessellate_closure = Closure::wrap(Box::new(move || {
+            store.dispatch(Action::Retessellate);
+        }) as Box<dyn Fn()>);
+        retessellate.add_event_listener_with_callback(
+            "click",
+            retessellate_closure.as_ref().unchecked_ref(),
+        )?;
+        retessellate_closure.forget();
+
+        let debug_panel = Rc::new(Self {
+            panel,
+            stats,
+            state,
+            open: std::cell::Cell::new(false),
+            frames: std::cell::Cell::new((0.0, 0)),
+        });
+        let key_closure = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+            if (event.ctrl_key() || event.meta_key()) && event.alt_key() && event.code() == "KeyD" {
+                event.prevent_default();
+                debug_panel.toggle();
+            }
+        }) as Box<dyn Fn(web_sys::KeyboardEvent)>);
+        web_sys::window()
+            .unwrap()
+            .add_event_listener_with_callback("keydown", key_closure.as_ref().unchecked_ref())?;
+        key_closure.forget();
+        Ok(())
+    }
+
+    fn toggle(self: &Rc<Self>) {
+        let open = !self.open.get();
+        self.open.set(open);
+        let display = if open { "block" } else { "none" };
+        self.panel
+            .dyn_ref::<web_sys::HtmlElement>()
+            .unwrap()
+            .style()
+            .set_property("display", display)
+            .unwrap();
+        if open {
+            self.frames.set((perf::now(), 0));
+            self.update_stats(None);
+            self.clone().tick();
+        }
+    }
+
+    // counts a frame and asks for the next one until the panel's closed
+    fn tick(self: Rc<Self>) {
+        if !self.open.get() {
+            return;
+        }
+        let (start, count) = self.frames.get();
+        let now = perf::now();
+        if now - start >= 1000.0 {
+            self.update_stats(Some(count as f64 * 1000.0 / (now - start)));
+            self.frames.set((now, 0));
+        } else {
+            self.frames.set((start, count + 1));
+        }
+        let next_frame = Closure::once_into_js(move || self.tick());
+        web_sys::window()
+            .unwrap()
+            .request_animation_frame(next_frame.unchecked_ref())
+            .unwrap();
+    }
+
+    fn update_stats(&self, fps: Option<f64>) {
+        // the page is mid-update, it'll be caught on the next second
+        let Ok(state) = self.state.try_borrow() else {
+            return;
+        };
+        let mut text = match fps {
+            Some(fps) => format!("fps: {:.0}\n", fps),
+            None => "fps: ...\n".to_string(),
+        };
+        let points = perf::gear_points();
+        text.push_str(&format!("points: {}\n", points.iter().sum::<usize>()));
+        for (index, count) in points.iter().enumerate() {
+            text.push_str(&format!("  gear {}: {}\n", index + 1, count));
+        }
+        text.push_str("parameters:\n");
+        text.push_str(&serde_json::to_string_pretty(&state.design()).unwrap_or_default());
+        self.stats.set_text_content(Some(&text));
+    }
 }
 
 // debug config struct
+#[derive(Clone, Copy, PartialEq)]
 struct DebugConfig {
     show_base_circle: bool,
     show_inner_circle: bool,
@@ -423,202 +6404,389 @@ impl DebugConfig {
     }
 }
 
-fn redraw(
-    canvas: &web_sys::HtmlCanvasElement,
+// clears the page and centers it on the part of the layout it shows, ready
+// to draw cut lines
+fn start_page(page: &Layer, width: u32, height: u32, ppi: u32, view: &paper::View, print: &Print) {
+    page.resize(width, height);
+    let context = &page.context;
+    // prints keep the half inch grid whatever the workspace shows, unless
+    // they're high contrast
+    let background = if print.grid {
+        BackgroundStyle::Grid
+    } else {
+        BackgroundStyle::White
+    };
+    background::draw(context, background, width, height, ppi);
+    context
+        .translate(width as f64 / 2.0, height as f64 / 2.0)
+        .unwrap(); // now 0,0 is the center of the canvas.
+                   // and then the part of the layout the page shows
+    context.scale(view.scale, view.scale).unwrap();
+    context
+        .translate(-view.center.x * ppi as f64, -view.center.y * ppi as f64)
+        .unwrap();
+    set_print_line_width(context, print.widths.cut, ppi, view);
+}
+
+// how printed pages are drawn
+struct Print {
+    widths: style::LineWidths,
+    grid: bool,
+}
+
+impl Print {
+    fn new(page_state: &PageState) -> Self {
+        if page_state.high_contrast {
+            Self {
+                widths: page_state.line_widths.high_contrast(),
+                grid: false,
+            }
+        } else {
+            Self {
+                widths: page_state.line_widths,
+                grid: true,
+            }
+        }
+    }
+}
+
+// lines `mm` thick on paper, however the page is scaled
+fn set_print_line_width(
     context: &web_sys::CanvasRenderingContext2d,
-    width: u32,
-    height: u32,
-    page_state: &PageState,
+    mm: f64,
     ppi: u32,
+    view: &paper::View,
 ) {
-    canvas.set_width(width);
-    canvas.set_height(height);
-    draw_background(context, width, height, ppi);
+    context.set_line_width(style::line_pixels(mm, ppi as f64) / view.scale);
+}
 
-    context
-        .translate(width as f64 / 2.0, height as f64 / 2.0)
-        .unwrap(); // now 0,0 is the center of the canvas.
+// draws the gears around the context's origin
+fn draw_gears(
+    context: &web_sys::CanvasRenderingContext2d,
+    outlines: &[GearOutline],
+    styles: &[GearStyle],
+    debug_config: &DebugConfig,
+    ppi: u32,
+) {
+    outlines
+        .iter()
+        .zip(styles)
+        .for_each(|(gear, style)| draw_gear(context, gear, style, debug_config, ppi));
+}
 
-    let debug_config = DebugConfig::default();
+// tessellate every gear at its meshing position
+fn tessellate(page_state: &PageState) -> Vec<GearOutline> {
+    page_state
+        .gears
+        .iter()
+        .zip(gear_centers(&page_state.gears))
+        .map(|(gear, center)| tessellate_gear(gear, center))
+        .collect()
+}
 
-    // Draw left gear (circle for now)
-    draw_gear(
-        context,
-        Gear::Left,
-        &page_state.left_gear_spec,
-        &debug_config,
-        ppi,
-    );
+// centers of the gears on exports, in mesh or spread out in a row
+fn export_centers(page_state: &PageState) -> Vec<Point> {
+    match page_state.export_layout {
+        paper::Layout::Meshed => gear_centers(&page_state.gears),
+        paper::Layout::Exploded => {
+            let radii: Vec<f64> = page_state
+                .gears
+                .iter()
+                .map(|gear| GearGeometry::new(&gear.spec).outer_radius)
+                .collect();
+            paper::exploded(&radii, page_state.exploded_gap)
+        }
+    }
+}
 
-    // Draw right gear (circle for now)
-    draw_gear(
-        context,
-        Gear::Right,
-        &page_state.right_gear_spec,
-        &debug_config,
-        ppi,
+// the gears as exported, laid out as asked and flipped if mirrored
+// exports were asked for
+fn tessellate_for_export(page_state: &PageState) -> Vec<GearOutline> {
+    let outlines: Vec<GearOutline> = page_state
+        .gears
+        .iter()
+        .zip(export_centers(page_state))
+        .map(|(gear, center)| tessellate_gear(gear, center))
+        .collect();
+    if page_state.mirror_exports {
+        outlines.into_iter().map(GearOutline::mirrored).collect()
+    } else {
+        outlines
+    }
+}
+
+// center punch marks for every hole, if they're wanted
+fn drill_marks(
+    page_state: &PageState,
+    outlines: &[GearOutline],
+    spacer_nest: Option<&spacers::Nest>,
+) -> Vec<drill::Mark> {
+    if page_state.drill_marks {
+        drill::marks(outlines, spacer_nest)
+    } else {
+        Vec::new()
+    }
+}
+
+// the box around everything printed with the gears, in inches
+fn layout_bounds(
+    outlines: &[GearOutline],
+    side_views: &[(Point, hub::SideView)],
+    spacer_nest: Option<&spacers::Nest>,
+) -> Option<paper::Bounds> {
+    let gears = outlines
+        .iter()
+        .map(|outline| paper::Bounds::around(outline.center, outline.geometry.outer_radius));
+    let views = side_views.iter().map(|(origin, view)| paper::Bounds {
+        min: Point {
+            x: origin.x - view.half_width,
+            y: origin.y - view.above,
+        },
+        max: Point {
+            x: origin.x + view.half_width,
+            y: origin.y + view.below,
+        },
+    });
+    let nest = spacer_nest.into_iter().flat_map(|nest| {
+        let label = &nest.label;
+        let half_width = paper::text_width(&label.text, label.height) / 2.0;
+        nest.rings
+            .iter()
+            .map(|ring| paper::Bounds::around(ring.center, ring.outer_radius))
+            .chain([paper::Bounds {
+                min: Point {
+                    x: label.position.x - half_width,
+                    y: label.position.y - label.height / 2.0,
+                },
+                max: Point {
+                    x: label.position.x + half_width,
+                    y: label.position.y + label.height / 2.0,
+                },
+            }])
+    });
+    paper::bounds(gears.chain(views).chain(nest))
+}
+
+// how far a side view sits below its gear, or the side view above it, in inches
+const SIDE_VIEW_GAP: f64 = 0.25;
+
+// side views of the gears with a hub or flange, each under its gear.
+// gears sharing an arbor stack their views under the widest of them.
+fn side_views(page_state: &PageState, outlines: &[GearOutline]) -> Vec<(Point, hub::SideView)> {
+    let mut views: Vec<(Point, hub::SideView)> = Vec::new();
+    // the bottom of what's already on each arbor
+    let mut arbor_bottom = 0.0;
+    for (index, (gear, outline)) in page_state.gears.iter().zip(outlines).enumerate() {
+        if index == 0 || !gear.same_arbor {
+            arbor_bottom = page_state.gears[index..]
+                .iter()
+                .zip(&outlines[index..])
+                .enumerate()
+                .take_while(|(offset, (gear, _))| *offset == 0 || gear.same_arbor)
+                .map(|(_, (_, outline))| outline.center.y + outline.geometry.outer_radius)
+                .fold(f64::MIN, f64::max);
+        }
+        let outer_diameter = 2.0 * outline.geometry.outer_radius;
+        if let Some(view) = hub::side_view(outer_diameter, &gear.hub) {
+            let origin = Point {
+                x: outline.center.x,
+                y: arbor_bottom + SIDE_VIEW_GAP + view.above,
+            };
+            arbor_bottom = origin.y + view.below;
+            views.push((origin, view));
+        }
+    }
+    views
+}
+
+// the spacer rings in a row under the gears and anything else down to
+// `below`, lined up with the leftmost gear
+fn nest_spacers(
+    page_state: &PageState,
+    outlines: &[GearOutline],
+    below: f64,
+) -> Option<spacers::Nest> {
+    let left = outlines
+        .iter()
+        .map(|outline| outline.center.x - outline.geometry.outer_radius)
+        .fold(f64::MAX, f64::min);
+    let top = outlines
+        .iter()
+        .map(|outline| outline.center.y + outline.geometry.outer_radius)
+        .fold(below, f64::max);
+    spacers::nest(&page_state.spacers, left, top)
+}
+
+// gear styles for exports, plain black unless colors were asked for
+fn export_styles(page_state: &PageState) -> Vec<GearStyle> {
+    page_state
+        .gears
+        .iter()
+        .map(|gear| {
+            if page_state.export_colors && !page_state.high_contrast {
+                gear.style.clone()
+            } else {
+                GearStyle::plain()
+            }
+        })
+        .collect()
+}
+
+fn tessellate_gear(gear: &GearState, center: Point) -> GearOutline {
+    let geometry = GearGeometry::new(&gear.spec);
+    let holes = blank_features(gear, &geometry)
+        .into_iter()
+        .flat_map(|feature| feature.loops)
+        .collect();
+    GearOutline {
+        center,
+        segments: geometry.outline(),
+        holes,
+        geometry,
+    }
+}
+
+// everything cut out of a gear's blank: its web, an imported shape and the
+// shapes drawn from numbers
+fn blank_features(gear: &GearState, geometry: &GearGeometry) -> Vec<warnings::Feature> {
+    let mut features = vec![warnings::Feature {
+        name: "web cutouts".to_string(),
+        loops: spokes::cutouts(&gear.spokes, geometry, gear.hub.diameter),
+    }];
+    if let Some(cutout) = &gear.svg_cutout {
+        features.push(warnings::Feature {
+            name: cutout.name.clone(),
+            loops: cutout.placed(),
+        });
+    }
+    features.extend(
+        gear.shapes
+            .iter()
+            .enumerate()
+            .map(|(index, shape)| warnings::Feature {
+                name: format!("cutout shape {}", index + 1),
+                loops: shape.loops(),
+            }),
     );
+    features
+}
+
+// cutouts running into each gear's teeth, hub or each other
+fn blank_collisions(page_state: &PageState) -> Vec<warnings::Collision> {
+    page_state
+        .gears
+        .iter()
+        .enumerate()
+        .flat_map(|(index, gear)| {
+            let geometry = GearGeometry::new(&gear.spec);
+            let features = blank_features(gear, &geometry);
+            warnings::check_blank(index, &geometry, gear.hub.diameter, &features)
+        })
+        .collect()
 }
 
 fn draw_gear(
     context: &web_sys::CanvasRenderingContext2d,
-    left_or_right: Gear,
-    gear_spec: &GearSpecs,
+    gear: &GearOutline,
+    style: &GearStyle,
     debug_config: &DebugConfig,
     ppi: u32,
 ) {
-    // Gear specifications
-    let teeth = gear_spec.teeth;
-    let module = (1.0 / gear_spec.diametric_pitch) * ppi as f64;
-    let tooth_angle = gear_spec.tooth_angle;
-    let pressure_angle_rads = tooth_angle * f64::consts::PI / 180.0;
-    let pitch_diameter = teeth * module;
-    let base_diameter = pitch_diameter * pressure_angle_rads.cos();
-    let addendum = module;
-    let clearance = gear_spec.clearance_mult * module;
-    let backlash_allowance = gear_spec.backlash_mult * module;
-    let dedendum = clearance + module;
-    let root_diameter = pitch_diameter - 2.0 * dedendum;
-    let outer_diameter = pitch_diameter + 2.0 * addendum;
-    let base_radius = base_diameter / 2.0;
-    let root_radius = root_diameter / 2.0;
-    let outer_radius = outer_diameter / 2.0;
-    let pitch_radius = pitch_diameter / 2.0;
+    let geometry = &gear.geometry;
+    let scale = ppi as f64;
+    let base_radius = geometry.base_radius * scale;
+    let root_radius = geometry.root_radius * scale;
+    let outer_radius = geometry.outer_radius * scale;
+    let pitch_radius = geometry.pitch_radius * scale;
 
     let offset = Point {
-        x: if left_or_right == Gear::Left {
-            -pitch_radius
-        } else {
-            pitch_radius
-        },
-        y: 0.0,
+        x: gear.center.x * scale,
+        y: gear.center.y * scale,
     };
 
     // maybe draw debug circles
     if debug_config.show_base_circle {
         context.set_stroke_style_str("lightblue");
-        draw_circle(context, offset.x, 0.0, base_radius);
+        draw_circle(context, offset.x, offset.y, base_radius);
     }
     if debug_config.show_inner_circle {
         context.set_stroke_style_str("purple");
-        draw_circle(context, offset.x, 0.0, root_radius);
+        draw_circle(context, offset.x, offset.y, root_radius);
     }
     if debug_config.show_outer_circle {
         context.set_stroke_style_str("lightgreen");
-        draw_circle(context, offset.x, 0.0, outer_radius);
+        draw_circle(context, offset.x, offset.y, outer_radius);
     }
     if debug_config.show_pitch_circle {
         context.set_stroke_style_str("red");
-        draw_circle(context, offset.x, 0.0, pitch_radius);
-    }
-
-    // Functions for the involute curve generation
-    fn involute(base_radius: f64, theta: f64) -> Point {
-        let x = base_radius * (theta.cos() + theta * theta.sin());
-        let y = base_radius * (theta.sin() - theta * theta.cos());
-        Point { x: x, y: y }
-    }
-
-    // Generate the involute gear profile
-    let tooth_angle = 2.0 * f64::consts::PI / teeth;
-    let involute_steps = 100; // Resolution for the involute curve
-    let theta_min = if root_radius > base_radius {
-        f64::sqrt((root_radius / base_radius).powi(2) - 1.0)
-    } else {
-        0.0
-    };
-    let theta_max = f64::sqrt((outer_radius / base_radius).powi(2) - 1.0); // Max theta for the involute
-    let theta: Vec<f64> = (0..involute_steps)
-        .map(|i| i as f64 * (theta_max - theta_min) / involute_steps as f64 + theta_min)
-        .collect();
-
-    let theta_pitch = f64::sqrt((pitch_radius / base_radius).powi(2) - 1.0); // Max theta for the involute
-    let mut pitch_correction = (involute(base_radius, theta_pitch).x / pitch_radius).acos();
-    let clearance_correction = ((backlash_allowance / 2.0) / pitch_radius).asin();
-    pitch_correction = pitch_correction - clearance_correction;
-
-    // generate involute points
-    let involute_points: Vec<Point> = theta
-        .iter()
-        .map(|theta| involute(base_radius, *theta))
-        .collect();
-    let involute_points_neg: Vec<Point> = theta
-        .iter()
-        .rev()
-        .map(|theta| involute(base_radius, -*theta))
-        .collect();
-
-    // draw involute points
-    fn rotate_point(point: &Point, angle: f64) -> Point {
-        let x_rot = point.x * angle.cos() - point.y * angle.sin();
-        let y_rot = point.x * angle.sin() + point.y * angle.cos();
-        Point { x: x_rot, y: y_rot }
+        draw_circle(context, offset.x, offset.y, pitch_radius);
     }
 
-    context.set_stroke_style_str("black");
+    context.set_stroke_style_str(&style.color);
     context
         .set_line_dash(&JsValue::from(Vec::<f64>::new()))
         .unwrap();
     context.begin_path();
 
-    // draw all teeth
-    (0..teeth as u32).for_each(|i| {
-        let angle_offset_rads = i as f64 * tooth_angle;
-
-        let start_point = offset
-            + (rotate_point(
-                &Point {
-                    x: root_radius,
-                    y: 0.0,
-                },
-                angle_offset_rads - pitch_correction,
-            ));
-        context.move_to(start_point.x, start_point.y);
-        (&involute_points)
-            .clone()
-            .into_iter()
-            .skip(1)
-            .for_each(|pt| {
-                let rotated_point = rotate_point(&pt, angle_offset_rads - pitch_correction);
-                context.line_to(offset.x + rotated_point.x, rotated_point.y);
-            });
-
-        let start_point_neg = offset
-            + (rotate_point(
-                &involute_points_neg[0],
-                angle_offset_rads + tooth_angle / 2.0 + pitch_correction,
-            ));
-        context.line_to(start_point_neg.x, start_point_neg.y);
-        (&involute_points_neg)
-            .clone()
-            .into_iter()
-            .skip(1)
-            .for_each(|pt| {
-                let rotated_point = rotate_point(
-                    &pt,
-                    angle_offset_rads + tooth_angle / 2.0 + pitch_correction,
-                );
-                context.line_to(offset.x + rotated_point.x, rotated_point.y);
-            });
-        let end_involute_point = rotate_point(
-            &Point {
-                x: root_radius,
-                y: 0.0,
-            },
-            angle_offset_rads + tooth_angle / 2.0 + pitch_correction,
-        );
-        context.line_to(offset.x + end_involute_point.x, end_involute_point.y);
+    // draw all teeth, segments share end points so only the first needs a move
+    let outline = &gear.segments;
+    if let Some(first) = outline.first().and_then(|segment| segment.points().first()) {
+        context.move_to(offset.x + first.x * scale, offset.y + first.y * scale);
+    }
+    outline.iter().for_each(|segment| {
+        segment.points().iter().skip(1).for_each(|pt| {
+            context.line_to(offset.x + pt.x * scale, offset.y + pt.y * scale);
+        });
+    });
+    // web cutouts are more subpaths, left unfilled by the even-odd rule
+    gear.holes.iter().for_each(|hole| {
+        hole.iter().enumerate().for_each(|(i, pt)| {
+            let (x, y) = (offset.x + pt.x * scale, offset.y + pt.y * scale);
+            if i == 0 {
+                context.move_to(x, y);
+            } else {
+                context.line_to(x, y);
+            }
+        });
+    });
+    if let Some(fill_color) = style.fill_color() {
+        context.set_fill_style_str(&fill_color);
+        context.fill_with_canvas_winding_rule(web_sys::CanvasWindingRule::Evenodd);
+    }
+    context.stroke();
+}
 
-        let end_point = rotate_point(
-            &Point {
-                x: root_radius,
+// centers of the gears in inches. each gear meshes with the one before it,
+// and the first pair meets at the origin, so editing a gear only moves the
+// gears after it.
+fn gear_centers(gears: &[GearState]) -> Vec<Point> {
+    let mut centers: Vec<Point> = Vec::with_capacity(gears.len());
+    let mut previous_radius = 0.0;
+    gears.iter().for_each(|gear| {
+        let radius = GearGeometry::new(&gear.spec).pitch_radius;
+        let center = match centers.last() {
+            Some(&previous) if gear.same_arbor => previous,
+            Some(previous) => Point {
+                x: previous.x + previous_radius + radius,
                 y: 0.0,
             },
-            angle_offset_rads + tooth_angle - pitch_correction,
-        );
-        context.line_to(offset.x + end_point.x, end_point.y);
+            None => Point { x: -radius, y: 0.0 },
+        };
+        centers.push(center);
+        previous_radius = radius;
     });
-    context.stroke();
+    centers
+}
+
+const SIDEBAR_WIDTH: u32 = 200;
+
+// room left of the canvas, none when embedded since the sidebar is hidden
+fn sidebar_width() -> u32 {
+    if embed::is_embedded() {
+        0
+    } else {
+        SIDEBAR_WIDTH
+    }
 }
 
 fn calculate_window_width_pixels() -> u32 {
@@ -640,51 +6808,14 @@ fn calculate_window_height_pixels() -> u32 {
 }
 
 fn draw_circle(context: &web_sys::CanvasRenderingContext2d, x: f64, y: f64, radius: f64) {
+    // a gear's root circle goes negative with few enough teeth, and canvas
+    // throws on a negative radius
+    if radius <= 0.0 {
+        return;
+    }
     context.begin_path();
     context
         .arc(x, y, radius, 0.0, f64::consts::PI * 2.0)
         .unwrap();
     context.stroke(); // Stroke the path after drawing
 }
-
-fn draw_background(context: &web_sys::CanvasRenderingContext2d, width: u32, height: u32, ppi: u32) {
-    context.clear_rect(0.0, 0.0, width as f64, height as f64);
-    context.set_fill_style_str("white");
-    context.fill_rect(0.0, 0.0, width as f64, height as f64);
-
-    // Draw the grid
-    context.set_stroke_style_str("lightblue");
-    context.set_line_width(1.0);
-
-    let grid_spacing = ppi as f64 / 2.0; // grid lines every half inch
-    context.save(); // Save the current context state
-
-    // Draw horizontal lines
-    let height_offset = ((height as f64) / 2.0) as u32 % grid_spacing as u32;
-    for i in (height_offset..=height + height_offset).step_by(grid_spacing as usize) {
-        context.move_to(0.0, (i) as f64);
-        context.line_to(width as f64, (i) as f64);
-        context.stroke();
-    }
-
-    // Draw vertical lines
-    let width_offset = ((width as f64) / 2.0) as u32 % grid_spacing as u32;
-    for i in (width_offset..=width + width_offset).step_by(grid_spacing as usize) {
-        context.move_to(i as f64, 0.0);
-        context.line_to(i as f64, height as f64);
-        context.stroke();
-    }
-
-    context.restore(); // Restore the context to its original state
-
-    // Draw tiny crosshair in the middle for debugging
-    context.set_stroke_style_str("red");
-    context.set_line_width(1.0);
-    context.begin_path();
-    let offset = 5.0;
-    context.move_to(width as f64 / 2.0, (height as f64 / 2.0) - offset);
-    context.line_to(width as f64 / 2.0, (height as f64 / 2.0) + offset);
-    context.move_to((width as f64 / 2.0) - offset, height as f64 / 2.0);
-    context.line_to((width as f64 / 2.0) + offset, height as f64 / 2.0);
-    context.stroke();
-}
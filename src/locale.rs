@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+thread_local! {
+    // the browser's decimal separator, looked up once
+    static DECIMAL_SEPARATOR: char = detect_decimal_separator();
+}
+
+fn detect_decimal_separator() -> char {
+    let formatted = String::from(js_sys::Number::from(1.5).to_locale_string("default"));
+    formatted
+        .chars()
+        .find(|c| !c.is_ascii_digit())
+        .unwrap_or('.')
+}
+
+// a number typed the way the user writes them. accepts either "1.5" or
+// "1,5", and thousands separators as long as there's a decimal point after
+// them, so "1.234,5" and "1,234.5" both work.
+pub fn parse<T: FromStr>(text: &str) -> Option<T> {
+    normalize(text).parse().ok()
+}
+
+// a number the way the user writes them, for filling in inputs
+pub fn format(value: f64) -> String {
+    let separator = DECIMAL_SEPARATOR.with(|separator| *separator);
+    value.to_string().replace('.', &separator.to_string())
+}
+
+// rewrites a typed number into the form rust parses. whichever of '.' and ','
+// comes last is the decimal separator, the other groups thousands.
+fn normalize(text: &str) -> String {
+    let text: String = text
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '\u{a0}' | '\u{202f}' | '\''))
+        .collect();
+    let decimal = text.rfind([',', '.']).map(|index| &text[index..index + 1]);
+    match decimal {
+        Some(",") => text.replace('.', "").replace(',', "."),
+        Some(_) => text.replace(',', ""),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commas_and_points_are_both_decimal_separators() {
+        assert_eq!(parse::<f64>("1,5"), Some(1.5));
+        assert_eq!(parse::<f64>("1.5"), Some(1.5));
+        assert_eq!(parse::<f64>(" 0,25 "), Some(0.25));
+    }
+
+    #[test]
+    fn the_last_separator_is_the_decimal_one() {
+        assert_eq!(parse::<f64>("1.234,5"), Some(1234.5));
+        assert_eq!(parse::<f64>("1,234.5"), Some(1234.5));
+        assert_eq!(parse::<f64>("1 234,5"), Some(1234.5));
+    }
+
+    #[test]
+    fn whole_numbers_still_parse_as_integers() {
+        assert_eq!(parse::<u32>("50"), Some(50));
+        assert_eq!(parse::<u32>("1,5"), None);
+        assert_eq!(parse::<f64>("abc"), None);
+    }
+}
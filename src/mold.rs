@@ -0,0 +1,108 @@
+use crate::geometry::Point;
+use crate::paper;
+
+// room between neighbouring blanks, in inches
+const BLANK_GAP: f64 = 0.25;
+// the stock left around a cavity's tips until the user asks for another
+pub const DEFAULT_WALL: f64 = 0.5;
+
+// the stock a mold cavity is cut into
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum BlankShape {
+    #[default]
+    Rectangle,
+    Circle,
+}
+
+impl BlankShape {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "rectangle" => Some(BlankShape::Rectangle),
+            "circle" => Some(BlankShape::Circle),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            BlankShape::Rectangle => "rectangle",
+            BlankShape::Circle => "circle",
+        }
+    }
+}
+
+// one gear's blank. the cavity is the gear's outline cut into it, and its
+// web cutouts stand up in the cavity as cores, so the blank, outline and
+// cutouts together read as the negative by the even-odd rule.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Blank {
+    pub shape: BlankShape,
+    pub center: Point,
+    // the circle's radius, or half the square's side
+    pub radius: f64,
+}
+
+impl Blank {
+    // the square's corners as a closed loop, `None` for a circle
+    pub fn corners(&self) -> Option<Vec<Point>> {
+        if self.shape == BlankShape::Circle {
+            return None;
+        }
+        let Point { x, y } = self.center;
+        let r = self.radius;
+        Some(vec![
+            Point { x: x - r, y: y - r },
+            Point { x: x + r, y: y - r },
+            Point { x: x + r, y: y + r },
+            Point { x: x - r, y: y + r },
+            Point { x: x - r, y: y - r },
+        ])
+    }
+
+    pub fn mirrored(self) -> Blank {
+        Blank {
+            center: Point {
+                x: -self.center.x,
+                y: self.center.y,
+            },
+            ..self
+        }
+    }
+}
+
+// a blank per gear of `outer_radii`, leaving `wall` inches of stock beyond
+// its tips, laid out in a row so neighbouring blanks don't overlap
+pub fn blanks(shape: BlankShape, outer_radii: &[f64], wall: f64) -> Vec<Blank> {
+    let radii: Vec<f64> = outer_radii.iter().map(|radius| radius + wall).collect();
+    paper::exploded(&radii, BLANK_GAP)
+        .into_iter()
+        .zip(radii)
+        .map(|(center, radius)| Blank {
+            shape,
+            center,
+            radius,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blanks_hold_their_gears_apart() {
+        let blanks = blanks(BlankShape::Rectangle, &[1.0, 0.5], 0.25);
+        assert_eq!(blanks[0].radius, 1.25);
+        assert_eq!(blanks[1].radius, 0.75);
+        // the squares' sides are a gap apart
+        let corners: Vec<Vec<Point>> = blanks.iter().map(|b| b.corners().unwrap()).collect();
+        assert!((corners[1][0].x - corners[0][1].x - BLANK_GAP).abs() < 1e-12);
+        assert_eq!(corners[0].first(), corners[0].last());
+
+        let circle = Blank {
+            shape: BlankShape::Circle,
+            ..blanks[0]
+        };
+        assert_eq!(circle.corners(), None);
+    }
+}
@@ -0,0 +1,42 @@
+use wasm_bindgen::prelude::*;
+
+// the app's bar color once it's installed, matching the sidebar
+const THEME_COLOR: &str = "#f0f0f0";
+
+// makes the app installable and usable with no connection, set up from
+// index.js with the urls webpack gave the service worker, manifest and icon.
+// everything runs in the browser, exports included, so once the worker has
+// cached the page's files there's nothing left to fetch.
+#[wasm_bindgen]
+pub fn install_offline(service_worker_url: &str, manifest_url: &str, icon_url: &str) {
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    if let Some(head) = document.head() {
+        for (tag, attributes) in [
+            ("link", [("rel", "manifest"), ("href", manifest_url)]),
+            ("link", [("rel", "icon"), ("href", icon_url)]),
+            ("meta", [("name", "theme-color"), ("content", THEME_COLOR)]),
+        ] {
+            let element = document.create_element(tag).unwrap();
+            for (name, value) in attributes {
+                element.set_attribute(name, value).unwrap();
+            }
+            head.append_child(&element).unwrap();
+        }
+    }
+
+    // only secure pages get service workers, plain http on the network
+    // just goes without
+    let navigator = window.navigator();
+    if !js_sys::Reflect::has(&navigator, &JsValue::from_str("serviceWorker")).unwrap_or(false) {
+        return;
+    }
+    let on_error = Closure::wrap(Box::new(|err: JsValue| {
+        web_sys::console::warn_2(&JsValue::from_str("Offline support unavailable:"), &err);
+    }) as Box<dyn FnMut(JsValue)>);
+    let _ = navigator
+        .service_worker()
+        .register(service_worker_url)
+        .catch(&on_error);
+    on_error.forget();
+}
@@ -0,0 +1,351 @@
+use crate::geometry::{involute, GearGeometry, Point, Rotation};
+use std::f64;
+
+// which way a gear turns, as seen on screen
+#[derive(Clone, Copy, PartialEq)]
+pub enum RotationDirection {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl RotationDirection {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "cw" => Some(RotationDirection::Clockwise),
+            "ccw" => Some(RotationDirection::CounterClockwise),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            RotationDirection::Clockwise => "cw",
+            RotationDirection::CounterClockwise => "ccw",
+        }
+    }
+
+    // direction of a gear driven by an external mesh with this one
+    pub fn reversed(&self) -> Self {
+        match self {
+            RotationDirection::Clockwise => RotationDirection::CounterClockwise,
+            RotationDirection::CounterClockwise => RotationDirection::Clockwise,
+        }
+    }
+}
+
+// half of the angle the arrow's arc sweeps over
+const ARROW_HALF_SWEEP: f64 = 0.6;
+
+// curved arrow over the top of a circle centered on `center`, in pixels
+pub fn draw_rotation_arrow(
+    context: &web_sys::CanvasRenderingContext2d,
+    center: Point,
+    radius: f64,
+    direction: RotationDirection,
+    color: &str,
+) {
+    // gears with too few teeth or a negative pitch have no room for one,
+    // and canvas throws on a negative radius
+    if radius <= 0.0 {
+        return;
+    }
+    // canvas y points down, so increasing angles run clockwise on screen
+    let top = -f64::consts::FRAC_PI_2;
+    let (start, end) = (top - ARROW_HALF_SWEEP, top + ARROW_HALF_SWEEP);
+
+    context.set_stroke_style_str(color);
+    context.set_line_width(2.0);
+    context.begin_path();
+    context.arc(center.x, center.y, radius, start, end).unwrap();
+    context.stroke();
+
+    // arrow head at whichever end the gear turns towards
+    let (tip_angle, heading) = match direction {
+        RotationDirection::Clockwise => (end, 1.0),
+        RotationDirection::CounterClockwise => (start, -1.0),
+    };
+    let tip = Point {
+        x: center.x + radius * tip_angle.cos(),
+        y: center.y + radius * tip_angle.sin(),
+    };
+    let tangent = Point {
+        x: -tip_angle.sin() * heading,
+        y: tip_angle.cos() * heading,
+    };
+    let head_size = f64::max(4.0, radius * 0.3);
+    context.begin_path();
+    [1.0, -1.0].iter().for_each(|side| {
+        // back along the arc, then out to either side of it
+        context.move_to(tip.x, tip.y);
+        context.line_to(
+            tip.x - head_size * (tangent.x - side * tangent.y * 0.6),
+            tip.y - head_size * (tangent.y + side * tangent.x * 0.6),
+        );
+    });
+    context.stroke();
+    context.set_line_width(1.0);
+}
+
+// text centered on `position`, in pixels. a white halo keeps it readable over
+// gear outlines and the grid.
+pub fn draw_label(
+    context: &web_sys::CanvasRenderingContext2d,
+    text: &str,
+    position: Point,
+    color: &str,
+) {
+    context.set_font("bold 14px sans-serif");
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context.set_line_width(4.0);
+    context.set_stroke_style_str("white");
+    context.stroke_text(text, position.x, position.y).unwrap();
+    context.set_fill_style_str(color);
+    context.fill_text(text, position.x, position.y).unwrap();
+    context.set_line_width(1.0);
+}
+
+// ring around a gear picked out from the warnings panel. `center` and
+// `radius` are in pixels.
+pub fn draw_highlight(context: &web_sys::CanvasRenderingContext2d, center: Point, radius: f64) {
+    context.set_stroke_style_str("rgba(220, 0, 0, 0.6)");
+    context.set_line_width(6.0);
+    context.begin_path();
+    context
+        .arc(
+            center.x,
+            center.y,
+            (radius + 8.0).max(0.0),
+            0.0,
+            2.0 * f64::consts::PI,
+        )
+        .unwrap();
+    context.stroke();
+    context.set_line_width(1.0);
+}
+
+// traces cutouts that run into something in red. `center` is the gear's, in
+// inches, and `scale` pixels per inch.
+pub fn draw_collision(
+    context: &web_sys::CanvasRenderingContext2d,
+    loops: &[Vec<Point>],
+    center: Point,
+    scale: f64,
+) {
+    context.set_stroke_style_str("rgba(220, 0, 0, 0.8)");
+    context.set_line_width(3.0);
+    context.begin_path();
+    for points in loops {
+        for (i, pt) in points.iter().enumerate() {
+            let (x, y) = ((center.x + pt.x) * scale, (center.y + pt.y) * scale);
+            if i == 0 {
+                context.move_to(x, y);
+            } else {
+                context.line_to(x, y);
+            }
+        }
+    }
+    context.stroke();
+    context.set_line_width(1.0);
+}
+
+// names the parts of an involute gear on the gear itself: pitch and base
+// circles, addendum and dedendum up and to the left, pressure angle and
+// circular pitch on a tooth down and to the left, away from the next gear.
+// `center` is in pixels, `scale` in pixels per inch.
+pub fn draw_terminology(
+    context: &web_sys::CanvasRenderingContext2d,
+    geometry: &GearGeometry,
+    center: Point,
+    scale: f64,
+) {
+    let polar = |radius: f64, angle: f64| Point {
+        x: center.x + radius * scale * angle.cos(),
+        y: center.y + radius * scale * angle.sin(),
+    };
+    let color = "rgb(0, 90, 200)";
+    let label_radius = geometry.outer_radius + 60.0 / scale;
+    let line = |from: Point, to: Point| {
+        context.begin_path();
+        context.move_to(from.x, from.y);
+        context.line_to(to.x, to.y);
+        context.stroke();
+    };
+
+    // pitch and base circles
+    context.set_stroke_style_str(color);
+    context.set_line_width(1.0);
+    context
+        .set_line_dash(&js_sys::Array::of2(&6.0.into(), &4.0.into()))
+        .unwrap();
+    [geometry.pitch_radius, geometry.base_radius]
+        .iter()
+        .filter(|radius| **radius > 0.0)
+        .for_each(|radius| {
+            context.begin_path();
+            context
+                .arc(
+                    center.x,
+                    center.y,
+                    radius * scale,
+                    0.0,
+                    2.0 * f64::consts::PI,
+                )
+                .unwrap();
+            context.stroke();
+        });
+    context.set_line_dash(&js_sys::Array::new()).unwrap();
+
+    // addendum and dedendum as radial spans, everything gets a leader out to
+    // its name past the tip
+    let addendum = geometry.outer_radius - geometry.pitch_radius;
+    let dedendum = geometry.pitch_radius - geometry.root_radius;
+    context.set_line_width(3.0);
+    let addendum_angle = 235f64.to_radians();
+    let dedendum_angle = 220f64.to_radians();
+    line(
+        polar(geometry.pitch_radius, addendum_angle),
+        polar(geometry.outer_radius, addendum_angle),
+    );
+    line(
+        polar(geometry.root_radius, dedendum_angle),
+        polar(geometry.pitch_radius, dedendum_angle),
+    );
+    context.set_line_width(1.0);
+    [
+        (
+            250f64,
+            geometry.pitch_radius,
+            format!("pitch circle {:.3} in dia", 2.0 * geometry.pitch_radius),
+        ),
+        (
+            235f64,
+            geometry.pitch_radius + addendum / 2.0,
+            format!("addendum {:.3} in", addendum),
+        ),
+        (
+            220f64,
+            geometry.root_radius + dedendum / 2.0,
+            format!("dedendum {:.3} in", dedendum),
+        ),
+        (
+            205f64,
+            geometry.base_radius,
+            format!("base circle {:.3} in dia", 2.0 * geometry.base_radius),
+        ),
+    ]
+    .iter()
+    .for_each(|(degrees, radius, text)| {
+        let angle = degrees.to_radians();
+        context.set_stroke_style_str(color);
+        line(polar(*radius, angle), polar(label_radius, angle));
+        draw_label(context, text, polar(label_radius, angle), color);
+    });
+
+    // a tooth around the lower left, where its flank crosses the pitch circle
+    let tooth_angle = geometry.tooth_pitch_angle();
+    let tooth = (geometry.teeth * 3.0 / 8.0).round();
+    let flank_rotation = Rotation::new(tooth * tooth_angle - geometry.pitch_correction());
+    let theta_pitch = f64::sqrt((geometry.pitch_radius / geometry.base_radius).powi(2) - 1.0);
+    let pitch_point = flank_rotation.apply(&involute(geometry.base_radius, theta_pitch));
+    let pitch_angle = pitch_point.y.atan2(pitch_point.x);
+
+    // pressure angle, between the line of action (tangent to the base
+    // circle) and the pitch circle's tangent at the pitch point
+    let tangent_point = flank_rotation.apply(&Point {
+        x: geometry.base_radius * theta_pitch.cos(),
+        y: geometry.base_radius * theta_pitch.sin(),
+    });
+    let action_angle = (pitch_point.y - tangent_point.y).atan2(pitch_point.x - tangent_point.x);
+    let pitch_tangent_angle = pitch_angle - f64::consts::FRAC_PI_2;
+    let pressure_angle = (action_angle - pitch_tangent_angle + f64::consts::PI)
+        .rem_euclid(2.0 * f64::consts::PI)
+        - f64::consts::PI;
+    let at_pitch_point = polar(geometry.pitch_radius, pitch_angle);
+    let reach = 40.0;
+    let towards = |angle: f64, length: f64| Point {
+        x: at_pitch_point.x + length * angle.cos(),
+        y: at_pitch_point.y + length * angle.sin(),
+    };
+    context.set_stroke_style_str("darkorange");
+    line(
+        Point {
+            x: center.x + tangent_point.x * scale,
+            y: center.y + tangent_point.y * scale,
+        },
+        towards(action_angle, reach),
+    );
+    line(
+        towards(pitch_tangent_angle, -reach),
+        towards(pitch_tangent_angle, reach),
+    );
+    let arc_end = pitch_tangent_angle + pressure_angle;
+    context.begin_path();
+    context
+        .arc(
+            at_pitch_point.x,
+            at_pitch_point.y,
+            reach * 0.6,
+            pitch_tangent_angle.min(arc_end),
+            pitch_tangent_angle.max(arc_end),
+        )
+        .unwrap();
+    context.stroke();
+    draw_label(
+        context,
+        &format!(
+            "pressure angle {:.1}\u{b0}",
+            pressure_angle.abs().to_degrees()
+        ),
+        polar(label_radius, pitch_angle),
+        "darkorange",
+    );
+
+    // circular pitch, one tooth pitch along the pitch circle
+    let next_pitch_angle = pitch_angle + tooth_angle;
+    context.set_stroke_style_str(color);
+    context.set_line_width(3.0);
+    if geometry.pitch_radius > 0.0 {
+        context.begin_path();
+        context
+            .arc(
+                center.x,
+                center.y,
+                geometry.pitch_radius * scale,
+                pitch_angle,
+                next_pitch_angle,
+            )
+            .unwrap();
+        context.stroke();
+    }
+    context.set_line_width(1.0);
+    let middle = pitch_angle + tooth_angle / 2.0;
+    let circular_pitch_label = label_radius + 30.0 / scale;
+    line(
+        polar(geometry.pitch_radius, middle),
+        polar(circular_pitch_label, middle),
+    );
+    draw_label(
+        context,
+        &format!(
+            "circular pitch {:.3} in",
+            geometry.pitch_radius * tooth_angle
+        ),
+        polar(circular_pitch_label, middle),
+        color,
+    );
+}
+
+// mesh ratio of a driving and driven gear in lowest terms, like "5:1"
+pub fn ratio_label(driver_teeth: u32, driven_teeth: u32) -> String {
+    let divisor = gcd(driver_teeth, driven_teeth).max(1);
+    format!("{}:{}", driver_teeth / divisor, driven_teeth / divisor)
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
@@ -0,0 +1,172 @@
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlElement;
+use web_sys::HtmlInputElement;
+
+// every action the palette offers, and the sidebar control it clicks. when
+// the selector matches several controls the last one is clicked, so gear
+// actions apply to the last gear.
+const COMMANDS: [(&str, &str); 26] = [
+    ("Print PDF", "#print_button"),
+    ("Export DXF", "#dxf_button"),
+    ("Export Rack DXF", "#rack_button"),
+    ("Export Kerf Test DXF", "#kerf_test_button"),
+    ("Export Mold Cavity DXF", "#mold_button"),
+    ("Print via Browser", "#browser_print_button"),
+    ("Print Inspection Sheets", "#inspection_button"),
+    ("Arrange PDF Sheets", "#sheets_button"),
+    ("Save View as PNG", "#save_view_button"),
+    ("Export Animation", "#animation_button"),
+    ("Calibrate Screen", "#calibrate_screen_button"),
+    ("Save Design", "#save_design_button"),
+    ("Reset to Defaults", "#reset_button"),
+    ("Generate Clock Train", "#clock_button"),
+    ("Generate Crossed Helical Pair", "#crossed_helical_button"),
+    ("Add Gear", "[data-action=duplicate]"),
+    ("Toggle Linked", "#linked"),
+    ("Toggle Rotation Arrows", "#rotation_arrows"),
+    ("Toggle Labels", "#labels"),
+    ("Toggle Terminology", "#terminology"),
+    ("Toggle Involute Construction", "#construction"),
+    ("Toggle Hobbing Simulation", "#hobbing"),
+    ("Toggle Cam Mode", "#cam_mode"),
+    ("Toggle Colors in PDF", "#export_colors"),
+    ("Toggle Mirror Exports", "#mirror_exports"),
+    ("Toggle Performance HUD", "#perf_hud"),
+];
+
+// a ctrl+k (cmd+k on macs) palette for running sidebar actions by name.
+// typing filters the list, enter runs the first match and escape closes it.
+pub fn create(document: &web_sys::Document) -> Result<(), JsValue> {
+    let palette = document.create_element("div")?;
+    palette.set_attribute(
+        "style",
+        "display: none; position: fixed; left: 50%; top: 15%; width: 360px; margin-left: -180px; \
+         padding: 8px; background-color: white; border: 1px solid #ccc; \
+         box-shadow: 0 4px 16px rgba(0, 0, 0, 0.25); z-index: 10;",
+    )?;
+    document.body().unwrap().append_child(&palette)?;
+
+    let filter = document
+        .create_element("input")?
+        .dyn_into::<HtmlInputElement>()?;
+    filter.set_attribute("type", "text")?;
+    filter.set_attribute("placeholder", "Type a command")?;
+    filter.set_attribute("style", "width: 100%; box-sizing: border-box;")?;
+    palette.append_child(&filter)?;
+
+    let list = document.create_element("div")?;
+    palette.append_child(&list)?;
+    build_list(&list, "")?;
+
+    // open with ctrl+k from anywhere on the page
+    let open_palette = palette.clone();
+    let open_filter = filter.clone();
+    let open_list = list.clone();
+    let open_closure = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+        if !(event.ctrl_key() || event.meta_key()) || event.key().to_lowercase() != "k" {
+            return;
+        }
+        event.prevent_default();
+        set_open(&open_palette, true);
+        open_filter.set_value("");
+        build_list(&open_list, "").unwrap();
+        open_filter.focus().unwrap();
+    }) as Box<dyn Fn(web_sys::KeyboardEvent)>);
+    web_sys::window()
+        .unwrap()
+        .add_event_listener_with_callback("keydown", open_closure.as_ref().unchecked_ref())?;
+    open_closure.forget();
+
+    let filter_list = list.clone();
+    let filter_input = filter.clone();
+    let filter_closure = Closure::wrap(Box::new(move || {
+        build_list(&filter_list, &filter_input.value()).unwrap();
+    }) as Box<dyn Fn()>);
+    filter.add_event_listener_with_callback("input", filter_closure.as_ref().unchecked_ref())?;
+    filter_closure.forget();
+
+    let key_palette = palette.clone();
+    let key_filter = filter.clone();
+    let key_closure = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+        let key = event.key();
+        if key == "Escape" {
+            set_open(&key_palette, false);
+        } else if key == "Enter" {
+            if let Some((_, selector)) = matching(&key_filter.value()).next() {
+                set_open(&key_palette, false);
+                run(selector);
+            }
+        }
+    }) as Box<dyn Fn(web_sys::KeyboardEvent)>);
+    filter.add_event_listener_with_callback("keydown", key_closure.as_ref().unchecked_ref())?;
+    key_closure.forget();
+
+    let click_palette = palette.clone();
+    let click_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let Some(index) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .and_then(|target| target.get_attribute("data-command"))
+            .and_then(|index| index.parse::<usize>().ok())
+        else {
+            return;
+        };
+        set_open(&click_palette, false);
+        run(COMMANDS[index].1);
+    }) as Box<dyn Fn(web_sys::Event)>);
+    list.add_event_listener_with_callback("click", click_closure.as_ref().unchecked_ref())?;
+    click_closure.forget();
+
+    Ok(())
+}
+
+// commands whose names contain every word of `filter`, ignoring case
+fn matching(filter: &str) -> impl Iterator<Item = (usize, &'static str)> {
+    let words: Vec<String> = filter
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect();
+    COMMANDS
+        .iter()
+        .enumerate()
+        .filter(move |(_, (label, _))| {
+            let label = label.to_lowercase();
+            words.iter().all(|word| label.contains(word.as_str()))
+        })
+        .map(|(index, (_, selector))| (index, *selector))
+}
+
+// a button per matching command, tagged with `data-command`
+fn build_list(list: &web_sys::Element, filter: &str) -> Result<(), JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    list.set_inner_html("");
+    for (index, _) in matching(filter) {
+        let button = document.create_element("button")?;
+        button.set_attribute("data-command", &index.to_string())?;
+        button.set_attribute(
+            "style",
+            "display: block; width: 100%; margin-top: 4px; text-align: left;",
+        )?;
+        button.set_text_content(Some(COMMANDS[index].0));
+        list.append_child(&button)?;
+    }
+    Ok(())
+}
+
+fn set_open(palette: &web_sys::Element, open: bool) {
+    let style = palette.dyn_ref::<HtmlElement>().unwrap().style();
+    let display = if open { "block" } else { "none" };
+    style.set_property("display", display).unwrap();
+}
+
+// clicks the control, which runs it through the sidebar's own handlers
+fn run(selector: &str) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let controls = document.query_selector_all(selector).unwrap();
+    if let Some(control) = controls
+        .item(controls.length().saturating_sub(1))
+        .and_then(|control| control.dyn_into::<HtmlElement>().ok())
+    {
+        control.click();
+    }
+}
@@ -0,0 +1,389 @@
+use crate::geometry::Point;
+
+// the printable area of a landscape letter page inside the export margins,
+// in inches
+pub const PRINTABLE_WIDTH: f64 = 10.75;
+pub const PRINTABLE_HEIGHT: f64 = 8.25;
+// tiles share this much with their neighbours, so they can be lined up by
+// laying one over the other
+pub const TILE_OVERLAP: f64 = 0.5;
+// layouts that would take more tiles than this are scaled onto one page
+// instead, since nobody tapes together hundreds of sheets and the export
+// would take minutes
+pub const MAX_TILES: u32 = 64;
+
+// what to do with a layout too big for one page
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum Oversize {
+    // full scale across as many pages as it takes
+    #[default]
+    Tile,
+    // shrunk onto one page
+    Scale,
+    // one full scale page, cutting off whatever doesn't fit
+    Clip,
+}
+
+impl Oversize {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "tile" => Some(Oversize::Tile),
+            "scale" => Some(Oversize::Scale),
+            "clip" => Some(Oversize::Clip),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            Oversize::Tile => "tile",
+            Oversize::Scale => "scale",
+            Oversize::Clip => "clip",
+        }
+    }
+}
+
+// where the gears sit on exports
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum Layout {
+    // in mesh as they're drawn, for assembly drawings
+    #[default]
+    Meshed,
+    // in a row with a gap between them, so cutting templates don't overlap
+    Exploded,
+}
+
+impl Layout {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "meshed" => Some(Layout::Meshed),
+            "exploded" => Some(Layout::Exploded),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            Layout::Meshed => "meshed",
+            Layout::Exploded => "exploded",
+        }
+    }
+}
+
+// centers for gears of `radii` in a row left to right, `gap` inches apart
+// at their tips, with the row centered on the page
+pub fn exploded(radii: &[f64], gap: f64) -> Vec<Point> {
+    let mut x = 0.0;
+    let mut centers: Vec<Point> = radii
+        .iter()
+        .map(|radius| {
+            let center = Point {
+                x: x + radius,
+                y: 0.0,
+            };
+            x += 2.0 * radius + gap;
+            center
+        })
+        .collect();
+    let width = (x - gap).max(0.0);
+    centers
+        .iter_mut()
+        .for_each(|center| center.x -= width / 2.0);
+    centers
+}
+
+// the box around everything drawn on the page, in inches from its center
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn around(center: Point, radius: f64) -> Bounds {
+        Bounds {
+            min: Point {
+                x: center.x - radius,
+                y: center.y - radius,
+            },
+            max: Point {
+                x: center.x + radius,
+                y: center.y + radius,
+            },
+        }
+    }
+
+    pub fn union(self, other: Bounds) -> Bounds {
+        Bounds {
+            min: Point {
+                x: self.min.x.min(other.min.x),
+                y: self.min.y.min(other.min.y),
+            },
+            max: Point {
+                x: self.max.x.max(other.max.x),
+                y: self.max.y.max(other.max.y),
+            },
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    fn center(&self) -> Point {
+        Point {
+            x: (self.min.x + self.max.x) / 2.0,
+            y: (self.min.y + self.max.y) / 2.0,
+        }
+    }
+
+    // whether it's small enough for one page
+    pub fn fits(&self) -> bool {
+        self.width() <= PRINTABLE_WIDTH && self.height() <= PRINTABLE_HEIGHT
+    }
+
+    // whether it's on the page as drawn, around the page's center
+    fn on_page(&self) -> bool {
+        let reach_x = self.min.x.abs().max(self.max.x.abs());
+        let reach_y = self.min.y.abs().max(self.max.y.abs());
+        reach_x <= PRINTABLE_WIDTH / 2.0 && reach_y <= PRINTABLE_HEIGHT / 2.0
+    }
+}
+
+// about how wide sans-serif `text` comes out at `height`, for keeping
+// labels inside the bounds without measuring them on a canvas
+pub fn text_width(text: &str, height: f64) -> f64 {
+    0.6 * height * text.chars().count() as f64
+}
+
+// the box around a set of boxes, `None` for none at all
+pub fn bounds(boxes: impl IntoIterator<Item = Bounds>) -> Option<Bounds> {
+    boxes.into_iter().reduce(Bounds::union)
+}
+
+// the part of the layout one page shows: the layout point at the page's
+// center, and how much it's shrunk. tiles also say where they sit, and
+// sheets laid out by hand which one they are.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct View {
+    pub center: Point,
+    pub scale: f64,
+    pub row: u32,
+    pub column: u32,
+    pub sheet: usize,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            center: Point { x: 0.0, y: 0.0 },
+            scale: 1.0,
+            row: 0,
+            column: 0,
+            sheet: 0,
+        }
+    }
+}
+
+// the pages to print `bounds` on. layouts already on the page print as
+// they are, and ones that would fit if they were centered, or that are
+// clipped, are centered on one page.
+pub fn views(bounds: Option<Bounds>, oversize: Oversize) -> Vec<View> {
+    let bounds = match bounds {
+        Some(bounds) if !bounds.on_page() => bounds,
+        _ => return vec![View::default()],
+    };
+    let centered = View {
+        center: bounds.center(),
+        ..View::default()
+    };
+    if bounds.fits() {
+        return vec![centered];
+    }
+    match fallback(&bounds, oversize) {
+        Oversize::Clip => vec![centered],
+        Oversize::Scale => vec![View {
+            scale: f64::min(
+                PRINTABLE_WIDTH / bounds.width(),
+                PRINTABLE_HEIGHT / bounds.height(),
+            ),
+            ..centered
+        }],
+        Oversize::Tile => {
+            let (columns, rows) = tile_grid(&bounds);
+            let center = bounds.center();
+            let step_x = PRINTABLE_WIDTH - TILE_OVERLAP;
+            let step_y = PRINTABLE_HEIGHT - TILE_OVERLAP;
+            (0..rows)
+                .flat_map(|row| {
+                    (0..columns).map(move |column| View {
+                        center: Point {
+                            x: center.x + (column as f64 - (columns - 1) as f64 / 2.0) * step_x,
+                            y: center.y + (row as f64 - (rows - 1) as f64 / 2.0) * step_y,
+                        },
+                        scale: 1.0,
+                        row,
+                        column,
+                        sheet: 0,
+                    })
+                })
+                .collect()
+        }
+    }
+}
+
+// a page for each of `count` sheets laid out by hand, each at full scale
+// around its own center
+pub fn sheets(count: usize) -> Vec<View> {
+    (0..count.max(1))
+        .map(|sheet| View {
+            sheet,
+            ..View::default()
+        })
+        .collect()
+}
+
+// tiling that takes too many pages falls back to scaling
+fn fallback(bounds: &Bounds, oversize: Oversize) -> Oversize {
+    match oversize {
+        Oversize::Tile if tile_count(bounds) > MAX_TILES => Oversize::Scale,
+        oversize => oversize,
+    }
+}
+
+fn tile_count(bounds: &Bounds) -> u32 {
+    let (columns, rows) = tile_grid(bounds);
+    columns.saturating_mul(rows)
+}
+
+// columns and rows of overlapping pages it takes to cover `bounds`
+fn tile_grid(bounds: &Bounds) -> (u32, u32) {
+    let count = |length: f64, printable: f64| {
+        ((length - TILE_OVERLAP) / (printable - TILE_OVERLAP))
+            .ceil()
+            .max(1.0) as u32
+    };
+    (
+        count(bounds.width(), PRINTABLE_WIDTH),
+        count(bounds.height(), PRINTABLE_HEIGHT),
+    )
+}
+
+// why `bounds` won't print on one page at full scale, and what will be done
+// about it, or `None` if it fits
+pub fn warning(bounds: &Bounds, oversize: Oversize) -> Option<String> {
+    if bounds.fits() {
+        return None;
+    }
+    let scaled = || {
+        format!(
+            "scaled to {:.0}%",
+            views(Some(*bounds), Oversize::Scale)[0].scale * 100.0
+        )
+    };
+    let outcome = match oversize {
+        Oversize::Tile if fallback(bounds, oversize) == Oversize::Scale => format!(
+            "{} instead, since tiling it would take more than {} pages",
+            scaled(),
+            MAX_TILES
+        ),
+        Oversize::Tile => format!("tiled across {} pages", tile_count(bounds)),
+        Oversize::Scale => scaled(),
+        Oversize::Clip => "clipped".to_string(),
+    };
+    Some(format!(
+        "Layout is {:.2} x {:.2} in, too big for the {} x {} in printable area of a letter page. PDFs will be {}.",
+        bounds.width(),
+        bounds.height(),
+        PRINTABLE_WIDTH,
+        PRINTABLE_HEIGHT,
+        outcome
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wide() -> Bounds {
+        Bounds {
+            min: Point { x: -3.0, y: -2.0 },
+            max: Point { x: 22.0, y: 6.0 },
+        }
+    }
+
+    #[test]
+    fn exploded_gears_are_a_gap_apart() {
+        let centers = exploded(&[1.0, 0.5, 0.5], 0.25);
+        assert_eq!(
+            centers.iter().map(|center| center.x).collect::<Vec<_>>(),
+            vec![-1.25, 0.5, 1.75]
+        );
+        assert_eq!(exploded(&[], 0.25), Vec::new());
+    }
+
+    #[test]
+    fn layouts_that_fit_print_as_they_are() {
+        let small = Bounds::around(Point { x: 1.0, y: 0.0 }, 3.0);
+        assert_eq!(views(Some(small), Oversize::Tile), vec![View::default()]);
+        assert_eq!(warning(&small, Oversize::Tile), None);
+        assert_eq!(views(None, Oversize::Scale), vec![View::default()]);
+
+        // off center, a box that would fit the page is moved onto it
+        let off_center = Bounds::around(Point { x: 3.0, y: 0.0 }, 3.0);
+        assert_eq!(warning(&off_center, Oversize::Clip), None);
+        for oversize in [Oversize::Tile, Oversize::Scale, Oversize::Clip] {
+            let views = views(Some(off_center), oversize);
+            assert_eq!(views.len(), 1);
+            assert_eq!((views[0].center.x, views[0].scale), (3.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn tiles_cover_the_layout_and_overlap() {
+        let bounds = wide();
+        let views = views(Some(bounds), Oversize::Tile);
+        assert_eq!(views.len(), 3);
+        let left = views.first().unwrap().center.x - PRINTABLE_WIDTH / 2.0;
+        let right = views.last().unwrap().center.x + PRINTABLE_WIDTH / 2.0;
+        assert!(left <= bounds.min.x && right >= bounds.max.x);
+        for view in &views {
+            assert!((view.center.y - 2.0).abs() < 1e-12);
+        }
+        for pair in views.windows(2) {
+            let shared = PRINTABLE_WIDTH - (pair[1].center.x - pair[0].center.x);
+            assert!((shared - TILE_OVERLAP).abs() < 1e-12);
+        }
+        assert_eq!(
+            warning(&bounds, Oversize::Tile).unwrap(),
+            "Layout is 25.00 x 8.00 in, too big for the 10.75 x 8.25 in printable area of a letter page. PDFs will be tiled across 3 pages."
+        );
+    }
+
+    #[test]
+    fn huge_layouts_scale_instead_of_tiling() {
+        let huge = Bounds::around(Point { x: 0.0, y: 0.0 }, 1e9);
+        assert_eq!(
+            views(Some(huge), Oversize::Tile),
+            views(Some(huge), Oversize::Scale)
+        );
+        assert!(warning(&huge, Oversize::Tile).unwrap().ends_with(
+            "PDFs will be scaled to 0% instead, since tiling it would take more than 64 pages."
+        ));
+    }
+
+    #[test]
+    fn scaling_shrinks_the_layout_onto_one_page() {
+        let bounds = wide();
+        let views = views(Some(bounds), Oversize::Scale);
+        assert_eq!(views.len(), 1);
+        assert!((views[0].scale - 10.75 / 25.0).abs() < 1e-12);
+        assert_eq!(views[0].center, Point { x: 9.5, y: 2.0 });
+        let clipped = super::views(Some(bounds), Oversize::Clip);
+        assert_eq!((clipped.len(), clipped[0].scale), (1, 1.0));
+    }
+}
@@ -0,0 +1,262 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+// called with the finished pdf of the export in flight, or why it couldn't
+// be built
+type PdfCallback = Box<dyn FnOnce(Result<Vec<u8>, String>)>;
+
+// how many times a worker that broke after it had been working is replaced
+// before pdfs are built on the main thread instead, so a worker that keeps
+// failing isn't restarted forever
+const MAX_PDF_WORKER_RESPAWNS: u32 = 3;
+
+thread_local! {
+    // creates the worker that assembles pdfs off the main thread, set from index.js
+    static PDF_WORKER_FACTORY: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+    static PDF_WORKER: RefCell<Option<web_sys::Worker>> = const { RefCell::new(None) };
+    static PDF_WORKER_RESPAWNS: Cell<u32> = const { Cell::new(0) };
+    static ON_PDF: RefCell<Option<(PdfJob, PdfCallback)>> = RefCell::new(None);
+}
+
+// everything `build_pdf` takes, kept until the worker answers in case it
+// turns out it can't and the pdf has to be built here
+struct PdfJob {
+    png_bytes: Vec<u8>,
+    png_lengths: Vec<u32>,
+    margin_inches: f64,
+    title: String,
+    keywords: Vec<String>,
+    created_at: f64,
+}
+
+impl PdfJob {
+    fn build(self) -> Vec<u8> {
+        build_pdf(
+            &self.png_bytes,
+            &self.png_lengths,
+            self.margin_inches,
+            &self.title,
+            self.keywords,
+            self.created_at,
+        )
+    }
+}
+
+// registers how to create the pdf worker, and starts one up
+#[wasm_bindgen]
+pub fn set_pdf_worker_factory(factory: js_sys::Function) {
+    PDF_WORKER_FACTORY.with(|pdf_worker_factory| *pdf_worker_factory.borrow_mut() = Some(factory));
+    spawn_pdf_worker();
+}
+
+fn spawn_pdf_worker() {
+    let factory = PDF_WORKER_FACTORY.with(|pdf_worker_factory| pdf_worker_factory.borrow().clone());
+    let Some(factory) = factory else {
+        return;
+    };
+    let worker: web_sys::Worker = factory.call0(&JsValue::NULL).unwrap().unchecked_into();
+    // a worker that has answered once has loaded its script
+    let answered = Rc::new(Cell::new(false));
+
+    // finished pdfs come back as messages, failures as an object saying
+    // what went wrong
+    let message_answered = answered.clone();
+    let onmessage = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+        message_answered.set(true);
+        let data = event.data();
+        let result = if data.is_instance_of::<js_sys::Uint8Array>() {
+            Ok(js_sys::Uint8Array::new(&data).to_vec())
+        } else {
+            let error = js_sys::Reflect::get(&data, &"error".into())
+                .ok()
+                .and_then(|error| error.as_string())
+                .unwrap_or_else(|| "the worker sent back something unexpected".to_string());
+            // a panic leaves the worker's module unusable
+            recover_pdf_worker();
+            Err(error)
+        };
+        if let Some((_, on_pdf)) = ON_PDF.with(|on_pdf| on_pdf.borrow_mut().take()) {
+            on_pdf(result);
+        }
+    }) as Box<dyn Fn(web_sys::MessageEvent)>);
+    worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+
+    // a worker that throws outside its handler never answers, so the export
+    // in flight is failed here instead. one that never answered at all
+    // couldn't load its script, and another would fail the same way, so
+    // it's dropped and pdfs are built here from then on, starting with the
+    // one in flight.
+    let onerror = Closure::wrap(Box::new(move |event: web_sys::ErrorEvent| {
+        event.prevent_default();
+        let pending = ON_PDF.with(|on_pdf| on_pdf.borrow_mut().take());
+        if answered.get() {
+            recover_pdf_worker();
+            if let Some((_, on_pdf)) = pending {
+                on_pdf(Err(event.message()));
+            }
+        } else {
+            drop_pdf_worker();
+            if let Some((job, on_pdf)) = pending {
+                on_pdf(Ok(job.build()));
+            }
+        }
+    }) as Box<dyn Fn(web_sys::ErrorEvent)>);
+    worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+    onerror.forget();
+
+    PDF_WORKER.with(|pdf_worker| *pdf_worker.borrow_mut() = Some(worker));
+}
+
+// what the pdf's document properties say about it, so exported files can
+// be told apart and found by searching later
+pub struct PdfInfo {
+    pub title: String,
+    pub keywords: Vec<String>,
+}
+
+// hands the page pngs to the worker if there is one, otherwise builds the
+// pdf right here and blocks until it's done. each png is a page.
+pub fn export_pdf(
+    pages: &[Vec<u8>],
+    margin_inches: f64,
+    info: &PdfInfo,
+    on_pdf: impl FnOnce(Result<Vec<u8>, String>) + 'static,
+) -> Result<(), JsValue> {
+    let job = PdfJob {
+        // the pages cross to the worker as one buffer, split up by their lengths
+        png_bytes: pages.concat(),
+        png_lengths: pages.iter().map(|page| page.len() as u32).collect(),
+        margin_inches,
+        title: info.title.clone(),
+        keywords: info.keywords.clone(),
+        // workers have a clock too, but the export is stamped with when it
+        // was asked for
+        created_at: js_sys::Date::now(),
+    };
+    let worker = PDF_WORKER.with(|pdf_worker| pdf_worker.borrow().clone());
+    match worker {
+        Some(worker) => {
+            let png = js_sys::Uint8Array::from(job.png_bytes.as_slice());
+            let message = js_sys::Object::new();
+            js_sys::Reflect::set(&message, &"png".into(), &png)?;
+            js_sys::Reflect::set(
+                &message,
+                &"pngLengths".into(),
+                &js_sys::Uint32Array::from(job.png_lengths.as_slice()),
+            )?;
+            js_sys::Reflect::set(&message, &"marginInches".into(), &margin_inches.into())?;
+            js_sys::Reflect::set(&message, &"title".into(), &info.title.as_str().into())?;
+            let keywords: js_sys::Array = info
+                .keywords
+                .iter()
+                .map(|keyword| JsValue::from_str(keyword))
+                .collect();
+            js_sys::Reflect::set(&message, &"keywords".into(), &keywords)?;
+            js_sys::Reflect::set(&message, &"createdAt".into(), &job.created_at.into())?;
+            ON_PDF.with(|pending| *pending.borrow_mut() = Some((job, Box::new(on_pdf))));
+            worker.post_message_with_transfer(&message, &js_sys::Array::of1(&png.buffer()))
+        }
+        None => {
+            on_pdf(Ok(job.build()));
+            Ok(())
+        }
+    }
+}
+
+// abandons the pdf in flight. the worker can't be interrupted mid-build,
+// so it's thrown away and replaced with a fresh one.
+pub fn cancel_pdf() {
+    ON_PDF.with(|on_pdf| on_pdf.borrow_mut().take());
+    replace_pdf_worker();
+}
+
+// replaces a worker that broke after it had been working, until it's
+// broken too many times
+fn recover_pdf_worker() {
+    let respawns = PDF_WORKER_RESPAWNS.with(Cell::get);
+    if respawns < MAX_PDF_WORKER_RESPAWNS {
+        PDF_WORKER_RESPAWNS.with(|count| count.set(respawns + 1));
+        replace_pdf_worker();
+    } else {
+        drop_pdf_worker();
+    }
+}
+
+fn replace_pdf_worker() {
+    if drop_pdf_worker() {
+        spawn_pdf_worker();
+    }
+}
+
+// stops the worker, if there is one, leaving pdfs to be built here
+fn drop_pdf_worker() -> bool {
+    let worker = PDF_WORKER.with(|pdf_worker| pdf_worker.borrow_mut().take());
+    if let Some(worker) = &worker {
+        worker.terminate();
+    }
+    worker.is_some()
+}
+
+// wraps rendered landscape letter pages into a portrait pdf, a page each.
+// `png_bytes` holds the pngs one after another, `png_lengths` long.
+// `created_at` is in milliseconds since the epoch.
+#[wasm_bindgen]
+pub fn build_pdf(
+    png_bytes: &[u8],
+    png_lengths: &[u32],
+    margin_inches: f64,
+    title: &str,
+    keywords: Vec<String>,
+    created_at: f64,
+) -> Vec<u8> {
+    let mut doc = printpdf::PdfDocument::new(title);
+    let doc_info = &mut doc.metadata.info;
+    doc_info.document_title = title.to_string();
+    doc_info.author = "GearGen".to_string();
+    doc_info.creator = "GearGen".to_string();
+    doc_info.keywords = keywords;
+    // the pdf library can't read the clock in wasm, so it's passed in
+    if let Ok(created) = printpdf::OffsetDateTime::from_unix_timestamp((created_at / 1000.0) as i64)
+    {
+        doc_info.creation_date = created;
+        doc_info.modification_date = created;
+        doc_info.metadata_date = created;
+    }
+    let mut offset = 0;
+    let pages = png_lengths
+        .iter()
+        .map(|&length| {
+            let png = &png_bytes[offset..offset + length as usize];
+            offset += length as usize;
+            let image = printpdf::RawImage::decode_from_bytes(png).unwrap();
+
+            // In the PDF, an image is an `XObject`, identified by a unique `ImageId`
+            let image_xobject_id = doc.add_image(&image);
+
+            let transform = printpdf::XObjectTransform {
+                rotate: Some(printpdf::XObjectRotation {
+                    angle_ccw_degrees: 90.0,
+                    rotation_center_x: printpdf::Px(0),
+                    rotation_center_y: printpdf::Px(0),
+                }),
+                translate_x: Some(printpdf::Pt(72.0 * (8.5 - margin_inches as f32 / 2.0))),
+                translate_y: Some(printpdf::Pt(72.0 * (margin_inches as f32 / 2.0))),
+                ..Default::default()
+            };
+            let contents = vec![printpdf::Op::UseXObject {
+                id: image_xobject_id,
+                transform,
+            }];
+
+            printpdf::PdfPage::new(
+                printpdf::Mm(25.4 * 8.5),
+                printpdf::Mm(25.4 * 11.0),
+                contents,
+            )
+        })
+        .collect();
+    doc.with_pages(pages)
+        .save(&printpdf::PdfSaveOptions::default())
+}
@@ -0,0 +1,97 @@
+use std::cell::RefCell;
+
+// timings collected for the performance hud
+#[derive(Default)]
+struct PerfStats {
+    hud: Option<web_sys::Element>,
+    redraw_ms: f64,
+    // points drawn for each gear, indexed like the gear layers
+    gear_points: Vec<usize>,
+    export_stages: Vec<(&'static str, f64)>,
+}
+
+thread_local! {
+    static PERF_STATS: RefCell<PerfStats> = RefCell::new(PerfStats::default());
+}
+
+// milliseconds from the page's high resolution clock
+pub fn now() -> f64 {
+    web_sys::window().unwrap().performance().unwrap().now()
+}
+
+pub fn record_redraw(ms: f64) {
+    PERF_STATS.with(|stats| stats.borrow_mut().redraw_ms = ms);
+    update_hud();
+}
+
+pub fn record_gear_points(index: usize, points: usize) {
+    PERF_STATS.with(|stats| {
+        let mut stats = stats.borrow_mut();
+        if stats.gear_points.len() <= index {
+            stats.gear_points.resize(index + 1, 0);
+        }
+        stats.gear_points[index] = points;
+    });
+}
+
+// the points drawn for each gear on the last redraw
+pub fn gear_points() -> Vec<usize> {
+    PERF_STATS.with(|stats| stats.borrow().gear_points.clone())
+}
+
+// forgets the point counts of gears that were removed
+pub fn retain_gears(count: usize) {
+    PERF_STATS.with(|stats| stats.borrow_mut().gear_points.truncate(count));
+}
+
+// clears the previous export's timings
+pub fn start_export() {
+    PERF_STATS.with(|stats| stats.borrow_mut().export_stages.clear());
+    update_hud();
+}
+
+pub fn record_export_stage(stage: &'static str, ms: f64) {
+    PERF_STATS.with(|stats| stats.borrow_mut().export_stages.push((stage, ms)));
+    update_hud();
+}
+
+// shows or hides the hud in the top right corner of the page
+pub fn set_hud_visible(visible: bool) {
+    PERF_STATS.with(|stats| {
+        let mut stats = stats.borrow_mut();
+        match (visible, stats.hud.take()) {
+            (true, None) => {
+                let document = web_sys::window().unwrap().document().unwrap();
+                let hud = document.create_element("pre").unwrap();
+                hud.set_attribute("style", "position: fixed; right: 10px; top: 10px; margin: 0; padding: 8px; background-color: rgba(0, 0, 0, 0.7); color: white; font-size: 12px; pointer-events: none;").unwrap();
+                document.body().unwrap().append_child(&hud).unwrap();
+                stats.hud = Some(hud);
+            }
+            (true, Some(hud)) => stats.hud = Some(hud),
+            (false, Some(hud)) => hud.remove(),
+            (false, None) => {}
+        }
+    });
+    update_hud();
+}
+
+fn update_hud() {
+    PERF_STATS.with(|stats| {
+        let stats = stats.borrow();
+        let Some(hud) = &stats.hud else {
+            return;
+        };
+        let mut text = format!(
+            "redraw: {:.1} ms\npoints: {}",
+            stats.redraw_ms,
+            stats.gear_points.iter().sum::<usize>()
+        );
+        if !stats.export_stages.is_empty() {
+            text.push_str("\nlast export:");
+            stats.export_stages.iter().for_each(|(stage, ms)| {
+                text.push_str(&format!("\n  {}: {:.1} ms", stage, ms));
+            });
+        }
+        hud.set_text_content(Some(&text));
+    });
+}
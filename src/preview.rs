@@ -0,0 +1,533 @@
+use crate::geometry::Point;
+use crate::hub::Hub;
+use std::cell::{Cell, RefCell};
+use std::f64;
+use wasm_bindgen::prelude::*;
+use web_sys::WebGl2RenderingContext as Gl;
+
+// gears with no flange to stand their teeth on are shown this thick, in
+// inches
+pub const DEFAULT_FACE_WIDTH: f64 = 0.25;
+// slices a helical gear's teeth twist through
+const HELIX_SLICES: usize = 12;
+// points round a hub
+const HUB_STEPS: usize = 48;
+// the camera stops just short of looking straight along the axes, where its
+// up direction would flip
+const MAX_PITCH: f64 = 1.5;
+// radians of orbit per pixel dragged
+const ORBIT_SPEED: f64 = 0.01;
+// vertical field of view, in radians
+const FIELD_OF_VIEW: f64 = 0.8;
+
+const VERTEX_SHADER: &str = r#"#version 300 es
+in vec3 a_position;
+in vec3 a_normal;
+in vec3 a_color;
+uniform mat4 u_matrix;
+out vec3 v_normal;
+out vec3 v_color;
+void main() {
+    v_normal = a_normal;
+    v_color = a_color;
+    gl_Position = u_matrix * vec4(a_position, 1.0);
+}
+"#;
+
+// lit from the camera, both sides of a face alike so the winding of the
+// outlines doesn't matter
+const FRAGMENT_SHADER: &str = r#"#version 300 es
+precision mediump float;
+uniform vec3 u_light;
+in vec3 v_normal;
+in vec3 v_color;
+out vec4 color;
+void main() {
+    float shade = 0.35 + 0.65 * abs(dot(normalize(v_normal), u_light));
+    color = vec4(v_color * shade, 1.0);
+}
+"#;
+
+// triangles with a flat normal and a color at every corner, in inches. z
+// is up out of the page and y is flipped from the page's, so the gears seen
+// from above look as they do in the 2d view.
+#[derive(Default)]
+pub struct Mesh {
+    pub positions: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub colors: Vec<f32>,
+}
+
+impl Mesh {
+    pub fn triangles(&self) -> usize {
+        self.positions.len() / 9
+    }
+
+    fn triangle(&mut self, corners: [[f64; 3]; 3], color: [f32; 3]) {
+        let [a, b, c] = corners;
+        let (u, v) = (sub(b, a), sub(c, a));
+        let normal = [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ];
+        let length = dot(normal, normal).sqrt();
+        // slivers from repeated points have nothing to show
+        if length < 1e-12 {
+            return;
+        }
+        for corner in corners {
+            self.positions.extend(corner.map(|value| value as f32));
+            self.normals
+                .extend(normal.map(|value| (value / length) as f32));
+            self.colors.extend(color);
+        }
+    }
+
+    fn quad(&mut self, corners: [[f64; 3]; 4], color: [f32; 3]) {
+        let [a, b, c, d] = corners;
+        self.triangle([a, b, c], color);
+        self.triangle([a, c, d], color);
+    }
+}
+
+// where a gear stands in the preview
+pub struct Placement {
+    pub center: Point,
+    // the height of its lower face, in inches
+    pub base: f64,
+    // turned this far, in radians, so it meshes with its neighbours
+    pub angle: f64,
+    pub face_width: f64,
+    // radians the teeth turn from the lower face to the upper one
+    pub twist: f64,
+}
+
+// the twist of a gear's teeth across its face, right handed for a positive
+// helix angle
+pub fn twist(helix_angle: f64, face_width: f64, pitch_radius: f64) -> f64 {
+    if pitch_radius <= 0.0 {
+        return 0.0;
+    }
+    face_width * helix_angle.to_radians().tan() / pitch_radius
+}
+
+// `outline`, a closed loop round the gear's center, extruded through its
+// face width with the hub standing up out of its upper face. the faces are
+// fanned from the center, which suits a gear's outline but covers over any
+// cutouts in the web.
+pub fn add_gear(
+    mesh: &mut Mesh,
+    outline: &[Point],
+    placement: &Placement,
+    hub: &Hub,
+    color: [f32; 3],
+) {
+    let slices = if placement.twist == 0.0 {
+        1
+    } else {
+        HELIX_SLICES
+    };
+    let at = |pt: &Point, slice: usize| {
+        let share = slice as f64 / slices as f64;
+        let (sin, cos) = (placement.angle + placement.twist * share).sin_cos();
+        [
+            placement.center.x + pt.x * cos - pt.y * sin,
+            -(placement.center.y + pt.x * sin + pt.y * cos),
+            placement.base + placement.face_width * share,
+        ]
+    };
+    for slice in 0..slices {
+        for pair in outline.windows(2) {
+            mesh.quad(
+                [
+                    at(&pair[0], slice),
+                    at(&pair[1], slice),
+                    at(&pair[1], slice + 1),
+                    at(&pair[0], slice + 1),
+                ],
+                color,
+            );
+        }
+    }
+    let center = Point { x: 0.0, y: 0.0 };
+    for slice in [0, slices] {
+        for pair in outline.windows(2) {
+            mesh.triangle(
+                [at(&center, slice), at(&pair[0], slice), at(&pair[1], slice)],
+                color,
+            );
+        }
+    }
+
+    // the hub's length is overall, so it only shows past the face
+    let radius = hub.diameter / 2.0;
+    let top = placement.base + hub.length;
+    let face = placement.base + placement.face_width;
+    if radius <= 0.0 || top <= face {
+        return;
+    }
+    let around = |step: usize, z: f64| {
+        let (sin, cos) = (2.0 * f64::consts::PI * step as f64 / HUB_STEPS as f64).sin_cos();
+        [
+            placement.center.x + radius * cos,
+            -(placement.center.y + radius * sin),
+            z,
+        ]
+    };
+    let axis = [placement.center.x, -placement.center.y, top];
+    for step in 0..HUB_STEPS {
+        mesh.quad(
+            [
+                around(step, face),
+                around(step + 1, face),
+                around(step + 1, top),
+                around(step, top),
+            ],
+            color,
+        );
+        mesh.triangle([axis, around(step, top), around(step + 1, top)], color);
+    }
+}
+
+// orbits a point, at a distance, `yaw` round the vertical and `pitch` up
+// from the plane of the gears
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub yaw: f64,
+    pub pitch: f64,
+    pub distance: f64,
+    pub target: [f64; 3],
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            yaw: -f64::consts::FRAC_PI_2,
+            pitch: 0.7,
+            distance: 10.0,
+            target: [0.0; 3],
+        }
+    }
+}
+
+impl Camera {
+    // looks at `target` from far enough away to see `radius` round it
+    pub fn frame(&mut self, target: [f64; 3], radius: f64) {
+        self.target = target;
+        self.distance = radius.max(0.1) / (FIELD_OF_VIEW / 2.0).sin();
+    }
+
+    // turns round the target for a drag of `dx` and `dy` pixels
+    pub fn orbit(&mut self, dx: f64, dy: f64) {
+        self.yaw -= dx * ORBIT_SPEED;
+        self.pitch = (self.pitch + dy * ORBIT_SPEED).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    // moves in for a negative wheel delta and out for a positive one
+    pub fn zoom(&mut self, delta: f64) {
+        self.distance = (self.distance * (delta * 0.001).exp()).clamp(0.1, 1000.0);
+    }
+
+    pub fn eye(&self) -> [f64; 3] {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        [
+            self.target[0] + self.distance * cos_pitch * cos_yaw,
+            self.target[1] + self.distance * cos_pitch * sin_yaw,
+            self.target[2] + self.distance * sin_pitch,
+        ]
+    }
+
+    // inches to clip space, column major for webgl
+    pub fn matrix(&self, aspect: f64) -> [f32; 16] {
+        let eye = self.eye();
+        let forward = normalize(sub(self.target, eye));
+        let side = normalize(cross(forward, [0.0, 0.0, 1.0]));
+        let up = cross(side, forward);
+        let view = [
+            side[0],
+            up[0],
+            -forward[0],
+            0.0,
+            side[1],
+            up[1],
+            -forward[1],
+            0.0,
+            side[2],
+            up[2],
+            -forward[2],
+            0.0,
+            -dot(side, eye),
+            -dot(up, eye),
+            dot(forward, eye),
+            1.0,
+        ];
+        let (near, far) = (self.distance / 100.0, self.distance * 10.0);
+        let f = 1.0 / (FIELD_OF_VIEW / 2.0).tan();
+        let mut projection = [0.0; 16];
+        projection[0] = f / aspect;
+        projection[5] = f;
+        projection[10] = (far + near) / (near - far);
+        projection[11] = -1.0;
+        projection[14] = 2.0 * far * near / (near - far);
+        let mut matrix = [0.0f32; 16];
+        for column in 0..4 {
+            for row in 0..4 {
+                matrix[column * 4 + row] = (0..4)
+                    .map(|k| projection[k * 4 + row] * view[column * 4 + k])
+                    .sum::<f64>() as f32;
+            }
+        }
+        matrix
+    }
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    let length = dot(a, a).sqrt();
+    a.map(|value| value / length)
+}
+
+// the uploaded mesh's buffers and how many vertices it has
+struct GpuMesh {
+    vao: web_sys::WebGlVertexArrayObject,
+    buffers: [web_sys::WebGlBuffer; 3],
+    vertices: i32,
+}
+
+// a webgl2 canvas over the workspace showing the gears as solids, turned
+// round by dragging and zoomed with the wheel
+pub struct Preview {
+    pub canvas: web_sys::HtmlCanvasElement,
+    gl: Gl,
+    program: web_sys::WebGlProgram,
+    mesh: RefCell<Option<GpuMesh>>,
+    pub camera: Cell<Camera>,
+}
+
+impl Preview {
+    // returns `None` when the browser has no webgl2 support
+    pub fn new(container: &web_sys::Element) -> Result<Option<Self>, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let canvas = document
+            .create_element("canvas")?
+            .dyn_into::<web_sys::HtmlCanvasElement>()?;
+        let Some(gl) = canvas.get_context("webgl2")? else {
+            return Ok(None);
+        };
+        let gl = gl.dyn_into::<Gl>()?;
+        gl.enable(Gl::DEPTH_TEST);
+        canvas.set_attribute(
+            "style",
+            "position: absolute; left: 0; top: 0; display: none; background: white; cursor: grab;",
+        )?;
+        container.append_child(&canvas)?;
+
+        let program = gl.create_program().ok_or("unable to create program")?;
+        for (shader_type, source) in [
+            (Gl::VERTEX_SHADER, VERTEX_SHADER),
+            (Gl::FRAGMENT_SHADER, FRAGMENT_SHADER),
+        ] {
+            let shader = gl
+                .create_shader(shader_type)
+                .ok_or("unable to create shader")?;
+            gl.shader_source(&shader, source);
+            gl.compile_shader(&shader);
+            if !gl
+                .get_shader_parameter(&shader, Gl::COMPILE_STATUS)
+                .as_bool()
+                .unwrap_or(false)
+            {
+                return Err(gl.get_shader_info_log(&shader).unwrap_or_default().into());
+            }
+            gl.attach_shader(&program, &shader);
+        }
+        gl.link_program(&program);
+        if !gl
+            .get_program_parameter(&program, Gl::LINK_STATUS)
+            .as_bool()
+            .unwrap_or(false)
+        {
+            return Err(gl.get_program_info_log(&program).unwrap_or_default().into());
+        }
+
+        Ok(Some(Self {
+            canvas,
+            gl,
+            program,
+            mesh: RefCell::new(None),
+            camera: Cell::new(Camera::default()),
+        }))
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        self.canvas.set_width(width);
+        self.canvas.set_height(height);
+        self.gl.viewport(0, 0, width as i32, height as i32);
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.canvas.style().get_property_value("display").unwrap() != "none"
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        self.canvas
+            .style()
+            .set_property("display", if visible { "block" } else { "none" })
+            .unwrap();
+    }
+
+    // replaces the solids on the gpu
+    pub fn upload(&self, mesh: &Mesh) {
+        let gl = &self.gl;
+        let vao = gl.create_vertex_array().unwrap();
+        gl.bind_vertex_array(Some(&vao));
+        let buffers = [
+            ("a_position", &mesh.positions),
+            ("a_normal", &mesh.normals),
+            ("a_color", &mesh.colors),
+        ]
+        .map(|(name, values)| {
+            let buffer = gl.create_buffer().unwrap();
+            let location = gl.get_attrib_location(&self.program, name) as u32;
+            gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&buffer));
+            gl.buffer_data_with_array_buffer_view(
+                Gl::ARRAY_BUFFER,
+                &js_sys::Float32Array::from(&values[..]),
+                Gl::STATIC_DRAW,
+            );
+            gl.enable_vertex_attrib_array(location);
+            gl.vertex_attrib_pointer_with_i32(location, 3, Gl::FLOAT, false, 0, 0);
+            buffer
+        });
+        gl.bind_vertex_array(None);
+        if let Some(old) = self.mesh.borrow_mut().replace(GpuMesh {
+            vao,
+            buffers,
+            vertices: (mesh.triangles() * 3) as i32,
+        }) {
+            gl.delete_vertex_array(Some(&old.vao));
+            old.buffers
+                .iter()
+                .for_each(|buffer| gl.delete_buffer(Some(buffer)));
+        }
+    }
+
+    pub fn draw(&self) {
+        let gl = &self.gl;
+        gl.clear_color(1.0, 1.0, 1.0, 1.0);
+        gl.clear(Gl::COLOR_BUFFER_BIT | Gl::DEPTH_BUFFER_BIT);
+        let mesh = self.mesh.borrow();
+        let Some(mesh) = mesh.as_ref() else {
+            return;
+        };
+        gl.use_program(Some(&self.program));
+        let camera = self.camera.get();
+        let aspect = self.canvas.width() as f64 / self.canvas.height().max(1) as f64;
+        gl.uniform_matrix4fv_with_f32_array(
+            gl.get_uniform_location(&self.program, "u_matrix").as_ref(),
+            false,
+            &camera.matrix(aspect),
+        );
+        let light = normalize(sub(camera.eye(), camera.target)).map(|value| value as f32);
+        gl.uniform3f(
+            gl.get_uniform_location(&self.program, "u_light").as_ref(),
+            light[0],
+            light[1],
+            light[2],
+        );
+        gl.bind_vertex_array(Some(&mesh.vao));
+        gl.draw_arrays(Gl::TRIANGLES, 0, mesh.vertices);
+        gl.bind_vertex_array(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outlines_extrude_into_closed_solids() {
+        let square = [
+            Point { x: -1.0, y: -1.0 },
+            Point { x: 1.0, y: -1.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: -1.0, y: 1.0 },
+            Point { x: -1.0, y: -1.0 },
+        ];
+        let placement = Placement {
+            center: Point { x: 2.0, y: 0.0 },
+            base: 0.5,
+            angle: 0.0,
+            face_width: 0.25,
+            twist: 0.0,
+        };
+        let mut mesh = Mesh::default();
+        add_gear(&mut mesh, &square, &placement, &Hub::default(), [1.0; 3]);
+        // four sides of two triangles, and two faces fanned in four
+        assert_eq!(mesh.triangles(), 16);
+        let heights: Vec<f32> = mesh.positions.iter().skip(2).step_by(3).copied().collect();
+        assert!(heights.iter().all(|z| *z == 0.5 || *z == 0.75));
+        assert_eq!(mesh.normals.len(), mesh.positions.len());
+
+        // twisted teeth are sliced up the face, and the hub stands above it
+        let hub = Hub {
+            diameter: 1.0,
+            length: 1.0,
+            flange_thickness: 0.25,
+            ..Hub::default()
+        };
+        let mut twisted = Mesh::default();
+        let placement = Placement {
+            twist: 0.5,
+            ..placement
+        };
+        add_gear(&mut twisted, &square, &placement, &hub, [1.0; 3]);
+        assert_eq!(twisted.triangles(), 8 * HELIX_SLICES + 8 + 3 * HUB_STEPS);
+        let top = twisted
+            .positions
+            .iter()
+            .skip(2)
+            .step_by(3)
+            .fold(0.0f32, |a, b| a.max(*b));
+        assert_eq!(top, 1.5);
+        assert!((twist(45.0, 0.5, 1.0) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn the_camera_keeps_its_target_in_the_middle() {
+        let mut camera = Camera::default();
+        camera.frame([1.0, -2.0, 0.5], 3.0);
+        camera.orbit(40.0, 1000.0);
+        assert_eq!(camera.pitch, MAX_PITCH);
+        let matrix = camera.matrix(1.5);
+        let clip: Vec<f64> = (0..4)
+            .map(|row| {
+                (0..3)
+                    .map(|k| matrix[k * 4 + row] as f64 * camera.target[k])
+                    .sum::<f64>()
+                    + matrix[12 + row] as f64
+            })
+            .collect();
+        assert!((clip[0] / clip[3]).abs() < 1e-5 && (clip[1] / clip[3]).abs() < 1e-5);
+        assert!((clip[3] - camera.distance).abs() < 1e-4);
+        camera.zoom(-1e9);
+        assert_eq!(camera.distance, 0.1);
+    }
+}
@@ -0,0 +1,79 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+// stages an export moves through, in order
+const EXPORT_STAGES: [&str; 3] = ["Tessellating gears", "Rasterizing page", "Assembling PDF"];
+
+// modal progress bar shown while an export is running
+pub struct ExportProgress {
+    overlay: web_sys::Element,
+    stage_label: web_sys::Element,
+    bar: web_sys::Element,
+    cancelled: Rc<Cell<bool>>,
+}
+
+impl ExportProgress {
+    pub fn show(title: &str, on_cancel: impl FnOnce() + 'static) -> Result<Self, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let overlay = document.create_element("div")?;
+        overlay.set_attribute("style", "position: fixed; left: 0; top: 0; width: 100%; height: 100%; background-color: rgba(0, 0, 0, 0.3);")?;
+
+        let dialog = document.create_element("div")?;
+        dialog.set_attribute("style", "position: absolute; left: 50%; top: 40%; transform: translate(-50%, -50%); width: 300px; padding: 20px; background-color: #f0f0f0; text-align: center;")?;
+        overlay.append_child(&dialog)?;
+
+        // add title
+        let title_element = document.create_element("h3")?;
+        title_element.set_text_content(Some(title));
+        dialog.append_child(&title_element)?;
+
+        // current stage + progress bar
+        let stage_label = document.create_element("div")?;
+        dialog.append_child(&stage_label)?;
+        let bar = document.create_element("progress")?;
+        bar.set_attribute("max", &EXPORT_STAGES.len().to_string())?;
+        bar.set_attribute(
+            "style",
+            "width: 100%; margin-top: 10px; margin-bottom: 10px;",
+        )?;
+        dialog.append_child(&bar)?;
+
+        // add cancel button
+        let cancelled = Rc::new(Cell::new(false));
+        let cancel_button = document.create_element("button")?;
+        cancel_button.set_text_content(Some("Cancel"));
+        dialog.append_child(&cancel_button)?;
+        let cancelled_clone = cancelled.clone();
+        let overlay_clone = overlay.clone();
+        let cancel_closure = Closure::once_into_js(move || {
+            cancelled_clone.set(true);
+            overlay_clone.remove();
+            on_cancel();
+        });
+        cancel_button.add_event_listener_with_callback("click", cancel_closure.unchecked_ref())?;
+
+        document.body().unwrap().append_child(&overlay)?;
+
+        Ok(Self {
+            overlay,
+            stage_label,
+            bar,
+            cancelled,
+        })
+    }
+
+    pub fn set_stage(&self, stage: usize) {
+        self.stage_label
+            .set_text_content(Some(&format!("{}...", EXPORT_STAGES[stage])));
+        self.bar.set_attribute("value", &stage.to_string()).unwrap();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+
+    pub fn finish(&self) {
+        self.overlay.remove();
+    }
+}
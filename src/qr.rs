@@ -0,0 +1,494 @@
+// a qr code encoder, just enough to print links: byte mode at the low error
+// correction level, in the smallest version that fits. follows iso 18004.
+
+// error correction codewords per block and number of blocks for versions 1
+// to 40 at the low level, indexed by version
+const ECC_PER_BLOCK: [usize; 41] = [
+    0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30,
+    30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+];
+const BLOCKS: [usize; 41] = [
+    0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14,
+    15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+];
+// the low level's bits in the format information
+const LOW_FORMAT_BITS: u32 = 1;
+
+pub struct QrCode {
+    size: usize,
+    // row major, true is dark
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    // `None` if `data` is too long for even the largest version
+    pub fn encode(data: &[u8]) -> Option<Self> {
+        let version = (1..=40).find(|&version| {
+            let count_bits = if version < 10 { 8 } else { 16 };
+            4 + count_bits + data.len() * 8 <= data_codewords(version) * 8
+        })?;
+        let codewords = add_error_correction(&data_bits(data, version), version);
+
+        let mut code = Builder::new(version);
+        code.draw_function_patterns();
+        code.draw_codewords(&codewords);
+        // every mask is valid, the one with the fewest scanner unfriendly
+        // features is kept
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                code.apply_mask(mask);
+                code.draw_format_bits(mask);
+                let penalty = code.penalty();
+                code.apply_mask(mask);
+                penalty
+            })
+            .unwrap();
+        code.apply_mask(mask);
+        code.draw_format_bits(mask);
+        Some(Self {
+            size: code.size,
+            modules: code.modules,
+        })
+    }
+
+    // modules along each side, not counting the quiet zone
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+}
+
+// draws `code` with its top left corner at `x`, `y` and `module` pixels to a
+// module, on a white square that includes the four module quiet zone
+pub fn draw(
+    context: &web_sys::CanvasRenderingContext2d,
+    code: &QrCode,
+    x: f64,
+    y: f64,
+    module: f64,
+) {
+    let quiet = 4.0 * module;
+    let side = code.size() as f64 * module + 2.0 * quiet;
+    context.set_fill_style_str("white");
+    context.fill_rect(x, y, side, side);
+    context.set_fill_style_str("black");
+    for row in 0..code.size() {
+        for column in 0..code.size() {
+            if code.is_dark(column, row) {
+                context.fill_rect(
+                    x + quiet + column as f64 * module,
+                    y + quiet + row as f64 * module,
+                    module,
+                    module,
+                );
+            }
+        }
+    }
+}
+
+// modules in the symbol that aren't taken by function patterns
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8 - ECC_PER_BLOCK[version] * BLOCKS[version]
+}
+
+// the mode, length and data, then a terminator and padding out to the
+// version's data capacity
+fn data_bits(data: &[u8], version: usize) -> Vec<u8> {
+    let mut bits: Vec<bool> = Vec::new();
+    let mut push = |value: u32, count: usize| {
+        bits.extend((0..count).rev().map(|i| (value >> i) & 1 == 1));
+    };
+    push(0b0100, 4);
+    push(data.len() as u32, if version < 10 { 8 } else { 16 });
+    for &byte in data {
+        push(byte as u32, 8);
+    }
+
+    let capacity = data_codewords(version) * 8;
+    let terminator = (capacity - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    bits.extend(std::iter::repeat_n(false, (8 - bits.len() % 8) % 8));
+
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |value, &bit| (value << 1) | bit as u8))
+        .collect();
+    for pad in [0xec, 0x11].iter().cycle() {
+        if codewords.len() >= data_codewords(version) {
+            break;
+        }
+        codewords.push(*pad);
+    }
+    codewords
+}
+
+// splits the data into blocks, adds each block's error correction and
+// interleaves them all
+fn add_error_correction(data: &[u8], version: usize) -> Vec<u8> {
+    let blocks = BLOCKS[version];
+    let ecc_length = ECC_PER_BLOCK[version];
+    let raw_codewords = raw_data_modules(version) / 8;
+    // the first blocks are a codeword shorter than the rest
+    let short_blocks = blocks - raw_codewords % blocks;
+    let short_length = raw_codewords / blocks - ecc_length;
+
+    let divisor = reed_solomon_divisor(ecc_length);
+    let mut start = 0;
+    let split: Vec<(&[u8], Vec<u8>)> = (0..blocks)
+        .map(|block| {
+            let length = short_length + usize::from(block >= short_blocks);
+            let data = &data[start..start + length];
+            start += length;
+            (data, reed_solomon_remainder(data, &divisor))
+        })
+        .collect();
+
+    let mut codewords = Vec::with_capacity(raw_codewords);
+    for i in 0..=short_length {
+        codewords.extend(split.iter().filter_map(|(data, _)| data.get(i)));
+    }
+    for i in 0..ecc_length {
+        codewords.extend(split.iter().map(|(_, ecc)| ecc[i]));
+    }
+    codewords
+}
+
+// multiplication in the field the error correction works over
+fn field_multiply(x: u8, y: u8) -> u8 {
+    let mut product: u8 = 0;
+    for i in (0..8).rev() {
+        product = (product << 1) ^ ((product >> 7) * 0x1d);
+        product ^= ((y >> i) & 1) * x;
+    }
+    product
+}
+
+// the generator polynomial's coefficients, highest power first, without the
+// leading one
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0; degree];
+    divisor[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = field_multiply(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = field_multiply(root, 2);
+    }
+    divisor
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (coefficient, &term) in remainder.iter_mut().zip(divisor) {
+            *coefficient ^= field_multiply(term, factor);
+        }
+    }
+    remainder
+}
+
+// the 15 format bits for the low level and `mask`, error corrected
+fn format_bits(mask: u32) -> u32 {
+    let data = (LOW_FORMAT_BITS << 3) | mask;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    ((data << 10) | remainder) ^ 0x5412
+}
+
+struct Builder {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+    // modules the data mustn't go in
+    function: Vec<bool>,
+}
+
+impl Builder {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            version,
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        }
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        // finders in three corners, with their white separators
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4..=4_i32 {
+                for dx in -4..=4_i32 {
+                    let (module_x, module_y) = (x as i32 + dx, y as i32 + dy);
+                    if (0..size as i32).contains(&module_x) && (0..size as i32).contains(&module_y)
+                    {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(
+                            module_x as usize,
+                            module_y as usize,
+                            distance != 2 && distance != 4,
+                        );
+                    }
+                }
+            }
+        }
+
+        // alignment patterns everywhere on the grid but over the finders
+        let positions = self.alignment_positions();
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                if (i, j) == (0, 0) || (i, j) == (0, last) || (i, j) == (last, 0) {
+                    continue;
+                }
+                for dy in -2..=2_i32 {
+                    for dx in -2..=2_i32 {
+                        self.set_function(
+                            (x as i32 + dx) as usize,
+                            (y as i32 + dy) as usize,
+                            dx.abs().max(dy.abs()) != 1,
+                        );
+                    }
+                }
+            }
+        }
+
+        // reserve the format bits until the mask is chosen
+        self.draw_format_bits(0);
+
+        if self.version >= 7 {
+            let mut remainder = self.version as u32;
+            for _ in 0..12 {
+                remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+            }
+            let bits = (self.version as u32) << 12 | remainder;
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 == 1;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    // centers of the alignment patterns along each axis
+    fn alignment_positions(&self) -> Vec<usize> {
+        if self.version == 1 {
+            return Vec::new();
+        }
+        let count = self.version / 7 + 2;
+        let step = (self.version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+        let mut positions: Vec<usize> = (0..count - 1).map(|i| self.size - 7 - i * step).collect();
+        positions.push(6);
+        positions.reverse();
+        positions
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let bits = format_bits(mask);
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+
+        // around the top left finder
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        // split between the other two
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    // fills the data modules in two column wide zigzags from the bottom right
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        while right >= 1 {
+            // the vertical timing pattern takes a whole column
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vertical in 0..size {
+                let y = if upward {
+                    size - 1 - vertical
+                } else {
+                    vertical
+                };
+                for x in [right, right - 1] {
+                    if !self.function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[i / 8] >> (7 - i % 8)) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    // flips the data modules in `mask`'s pattern. applying it twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let flip = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if flip && !self.function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    // the standard's penalty for long runs, blocks, finder lookalikes and
+    // an uneven balance of dark and light
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let dark = |x: usize, y: usize| self.modules[y * size + x];
+        let mut penalty = 0;
+
+        let lines = (0..size).flat_map(|i| {
+            [
+                (0..size).map(|j| dark(j, i)).collect::<Vec<_>>(),
+                (0..size).map(|j| dark(i, j)).collect::<Vec<_>>(),
+            ]
+        });
+        let finder_like = [
+            true, false, true, true, true, false, true, false, false, false, false,
+        ];
+        for line in lines {
+            let mut run = 1;
+            for j in 1..=size {
+                if j < size && line[j] == line[j - 1] {
+                    run += 1;
+                    continue;
+                }
+                if run >= 5 {
+                    penalty += run - 2;
+                }
+                run = 1;
+            }
+            for window in line.windows(finder_like.len()) {
+                if window.iter().eq(finder_like.iter())
+                    || window.iter().eq(finder_like.iter().rev())
+                {
+                    penalty += 40;
+                }
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = dark(x, y);
+                if dark(x + 1, y) == color && dark(x, y + 1) == color && dark(x + 1, y + 1) == color
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        let dark_count = self.modules.iter().filter(|&&dark| dark).count();
+        let percent = dark_count * 100 / (size * size);
+        penalty + percent.abs_diff(50) / 5 * 10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_correction_matches_the_standard_example() {
+        // "HELLO WORLD" at 1-M, from the worked example in the standard
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            reed_solomon_remainder(&data, &reed_solomon_divisor(10)),
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+    }
+
+    #[test]
+    fn format_bits_match_the_standard_table() {
+        assert_eq!(format_bits(0), 0b111011111000100);
+        assert_eq!(format_bits(6), 0b110110001000001);
+        assert_eq!(format_bits(7), 0b110100101110110);
+    }
+
+    #[test]
+    fn data_fills_every_data_module() {
+        for version in [1, 2, 7, 10, 21, 40] {
+            let codewords = add_error_correction(&data_bits(b"", version), version);
+            assert_eq!(codewords.len() * 8, raw_data_modules(version) / 8 * 8);
+            let mut code = Builder::new(version);
+            code.draw_function_patterns();
+            let free = code.function.iter().filter(|&&function| !function).count();
+            assert_eq!(free, raw_data_modules(version));
+        }
+    }
+
+    #[test]
+    fn smallest_version_that_fits_is_used() {
+        assert_eq!(QrCode::encode(&[0; 17]).unwrap().size(), 21);
+        assert_eq!(QrCode::encode(&[0; 18]).unwrap().size(), 25);
+        assert!(QrCode::encode(&[0; 3000]).is_none());
+    }
+}
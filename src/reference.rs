@@ -0,0 +1,669 @@
+use crate::geometry::Point;
+use std::f64;
+
+// points sampled round a full circle, and along each curve
+const CIRCLE_STEPS: u32 = 72;
+const CURVE_STEPS: u32 = 8;
+// css pixels, what svg user units are without any other units given
+const SVG_UNITS_PER_INCH: f64 = 96.0;
+const MM_PER_INCH: f64 = 25.4;
+
+// an imported outline of an existing gear, drawn over the generated one to
+// compare them. the paths are open polylines in inches, as they were in the
+// file, with closed ones repeating their first point at the end.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference {
+    pub name: String,
+    pub paths: Vec<Vec<Point>>,
+}
+
+// where the reference sits over the gears: turned `rotation` degrees about
+// its own center, then moved by `offset` inches
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Alignment {
+    pub offset: Point,
+    pub rotation: f64,
+}
+
+impl Default for Alignment {
+    fn default() -> Self {
+        Self {
+            offset: Point { x: 0.0, y: 0.0 },
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Reference {
+    // reads a dxf or an svg, going by the file's name
+    pub fn load(name: &str, text: &str) -> Result<Reference, String> {
+        let paths = if name.to_lowercase().ends_with(".svg") {
+            parse_svg(text)?
+        } else {
+            parse_dxf(text)?
+        };
+        if paths.is_empty() {
+            return Err(format!("{} has no outlines in it", name));
+        }
+        Ok(Reference {
+            name: name.to_string(),
+            paths,
+        })
+    }
+
+    // the middle of the box around the paths, which it turns about
+    pub fn center(&self) -> Point {
+        let points = self.paths.iter().flatten();
+        let (min, max) = points.fold(
+            (
+                Point {
+                    x: f64::MAX,
+                    y: f64::MAX,
+                },
+                Point {
+                    x: f64::MIN,
+                    y: f64::MIN,
+                },
+            ),
+            |(min, max), pt| {
+                (
+                    Point {
+                        x: min.x.min(pt.x),
+                        y: min.y.min(pt.y),
+                    },
+                    Point {
+                        x: max.x.max(pt.x),
+                        y: max.y.max(pt.y),
+                    },
+                )
+            },
+        );
+        Point {
+            x: (min.x + max.x) / 2.0,
+            y: (min.y + max.y) / 2.0,
+        }
+    }
+
+    // the paths where they're drawn, in inches from the page's center
+    pub fn placed(&self, alignment: &Alignment) -> Vec<Vec<Point>> {
+        let center = self.center();
+        let (sin, cos) = alignment.rotation.to_radians().sin_cos();
+        self.paths
+            .iter()
+            .map(|path| {
+                path.iter()
+                    .map(|pt| {
+                        let (x, y) = (pt.x - center.x, pt.y - center.y);
+                        Point {
+                            x: center.x + x * cos - y * sin + alignment.offset.x,
+                            y: center.y + x * sin + y * cos + alignment.offset.y,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // the offset that puts its center on `target`, at the same rotation
+    pub fn offset_to(&self, target: Point) -> Point {
+        let center = self.center();
+        Point {
+            x: target.x - center.x,
+            y: target.y - center.y,
+        }
+    }
+}
+
+// points round an arc from `start` to `end` radians, counterclockwise
+fn arc(center: Point, radius: f64, start: f64, end: f64, steps: u32) -> Vec<Point> {
+    let sweep = if end > start {
+        end - start
+    } else {
+        end - start + 2.0 * f64::consts::PI
+    };
+    (0..=steps)
+        .map(|i| {
+            let angle = start + sweep * i as f64 / steps as f64;
+            Point {
+                x: center.x + radius * angle.cos(),
+                y: center.y + radius * angle.sin(),
+            }
+        })
+        .collect()
+}
+
+// lines, polylines, circles, arcs and splines from a dxf's entities, in
+// inches. splines are followed through their fit points, or their control
+// points without any. bulges in polylines are read as straight.
+pub fn parse_dxf(text: &str) -> Result<Vec<Vec<Point>>, String> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let pairs: Vec<(i32, &str)> = lines
+        .chunks(2)
+        .filter(|pair| pair.len() == 2)
+        .map(|pair| {
+            pair[0]
+                .parse::<i32>()
+                .map(|code| (code, pair[1]))
+                .map_err(|_| format!("\"{}\" isn't a dxf group code", pair[0]))
+        })
+        .collect::<Result<_, _>>()?;
+
+    // the drawing's units, inches unless it says otherwise
+    let scale = pairs
+        .windows(2)
+        .find(|pair| pair[0] == (9, "$INSUNITS"))
+        .and_then(|pair| pair[1].1.parse::<u32>().ok())
+        .map(|units| match units {
+            4 => 1.0 / MM_PER_INCH,
+            5 => 10.0 / MM_PER_INCH,
+            6 => 1000.0 / MM_PER_INCH,
+            2 => 12.0,
+            _ => 1.0,
+        })
+        .unwrap_or(1.0);
+
+    // entities start at each 0 group, with their values until the next
+    let mut entities: Vec<(&str, Vec<(i32, f64)>)> = Vec::new();
+    for (code, value) in &pairs {
+        if *code == 0 {
+            entities.push((value, Vec::new()));
+        } else if let (Some(entity), Ok(number)) = (entities.last_mut(), value.parse::<f64>()) {
+            entity.1.push((*code, number));
+        }
+    }
+    let first = |values: &[(i32, f64)], code: i32| {
+        values
+            .iter()
+            .find(|(group, _)| *group == code)
+            .map(|(_, value)| *value)
+    };
+    // the x of each point is followed by its y
+    let points = |values: &[(i32, f64)], x_code: i32| -> Vec<Point> {
+        values
+            .windows(2)
+            .filter(|pair| pair[0].0 == x_code && pair[1].0 == x_code + 10)
+            .map(|pair| Point {
+                x: pair[0].1 * scale,
+                y: pair[1].1 * scale,
+            })
+            .collect()
+    };
+    let center = |values: &[(i32, f64)]| points(values, 10).first().copied();
+    let radius = |values: &[(i32, f64)]| first(values, 40).map(|radius| radius * scale);
+
+    let mut paths: Vec<Vec<Point>> = Vec::new();
+    // old style polylines come as a run of vertex entities
+    let mut polyline: Option<(Vec<Point>, bool)> = None;
+    for (kind, values) in &entities {
+        match *kind {
+            "LWPOLYLINE" => {
+                let mut path = points(values, 10);
+                let closed = first(values, 70).is_some_and(|flags| flags as u32 & 1 == 1);
+                if closed && !path.is_empty() {
+                    path.push(path[0]);
+                }
+                paths.push(path);
+            }
+            "LINE" => paths.push(
+                points(values, 10)
+                    .into_iter()
+                    .chain(points(values, 11))
+                    .collect(),
+            ),
+            "CIRCLE" => {
+                if let (Some(center), Some(radius)) = (center(values), radius(values)) {
+                    paths.push(arc(
+                        center,
+                        radius,
+                        0.0,
+                        2.0 * f64::consts::PI,
+                        CIRCLE_STEPS,
+                    ));
+                }
+            }
+            "ARC" => {
+                // angles are in degrees
+                if let (Some(center), Some(radius), Some(start), Some(end)) = (
+                    center(values),
+                    radius(values),
+                    first(values, 50),
+                    first(values, 51),
+                ) {
+                    paths.push(arc(
+                        center,
+                        radius,
+                        start.to_radians(),
+                        end.to_radians(),
+                        CIRCLE_STEPS / 4,
+                    ));
+                }
+            }
+            "SPLINE" => {
+                let fit = points(values, 11);
+                paths.push(if fit.is_empty() {
+                    points(values, 10)
+                } else {
+                    fit
+                });
+            }
+            "POLYLINE" => {
+                let closed = first(values, 70).is_some_and(|flags| flags as u32 & 1 == 1);
+                polyline = Some((Vec::new(), closed));
+            }
+            "VERTEX" => {
+                if let Some((path, _)) = polyline.as_mut() {
+                    path.extend(points(values, 10));
+                }
+            }
+            "SEQEND" => {
+                if let Some((mut path, closed)) = polyline.take() {
+                    if closed && !path.is_empty() {
+                        path.push(path[0]);
+                    }
+                    paths.push(path);
+                }
+            }
+            _ => {}
+        }
+    }
+    paths.retain(|path| path.len() >= 2);
+    Ok(paths)
+}
+
+// the value of `name` in the attributes of a tag, like `d` in `<path d="...">`
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(found) = rest.find(name) {
+        let before = rest[..found].chars().last();
+        let after = rest[found + name.len()..].trim_start();
+        rest = &rest[found + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(after) = after.strip_prefix('=') else {
+            continue;
+        };
+        let after = after.trim_start();
+        let quote = after.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &after[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+// the numbers in a list like "1,2 3 -4.5e1", in order
+fn numbers(text: &str) -> Vec<f64> {
+    let mut numbers = Vec::new();
+    let mut current = String::new();
+    let mut flush = |current: &mut String| {
+        if let Ok(number) = current.parse::<f64>() {
+            numbers.push(number);
+        }
+        current.clear();
+    };
+    let mut previous = ' ';
+    for c in text.chars() {
+        let starts_new = (c == '-' || c == '+') && !matches!(previous, 'e' | 'E')
+            || c == '.' && current.contains('.') && !current.contains(['e', 'E']);
+        if c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E') {
+            if starts_new {
+                flush(&mut current);
+            }
+            current.push(c);
+        } else {
+            flush(&mut current);
+        }
+        previous = c;
+    }
+    flush(&mut current);
+    numbers
+}
+
+// a point on the bezier through `points` at `t`
+fn bezier(points: &[Point], t: f64) -> Point {
+    if points.len() == 1 {
+        return points[0];
+    }
+    let between: Vec<Point> = points
+        .windows(2)
+        .map(|pair| Point {
+            x: pair[0].x + (pair[1].x - pair[0].x) * t,
+            y: pair[0].y + (pair[1].y - pair[0].y) * t,
+        })
+        .collect();
+    bezier(&between, t)
+}
+
+// the subpaths in an svg path's data, in user units. curves are sampled
+// and elliptical arcs are taken straight to their end points.
+fn path_data(data: &str) -> Vec<Vec<Point>> {
+    let mut paths: Vec<Vec<Point>> = Vec::new();
+    let mut path: Vec<Point> = Vec::new();
+    let mut current = Point { x: 0.0, y: 0.0 };
+    let mut start = current;
+    // the last curve's second control point, reflected by smooth curves
+    let mut last_control: Option<Point> = None;
+
+    // each command and the numbers after it
+    let mut commands: Vec<(char, Vec<f64>)> = Vec::new();
+    let mut rest = data;
+    while let Some(index) = rest.find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E') {
+        let command = rest[index..].chars().next().unwrap();
+        let after = &rest[index + 1..];
+        let end = after
+            .find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')
+            .unwrap_or(after.len());
+        commands.push((command, numbers(&after[..end])));
+        rest = &after[end..];
+    }
+
+    for (command, args) in commands {
+        let relative = command.is_ascii_lowercase();
+        let at = |x: f64, y: f64, from: Point| {
+            if relative {
+                Point {
+                    x: from.x + x,
+                    y: from.y + y,
+                }
+            } else {
+                Point { x, y }
+            }
+        };
+        let arity = match command.to_ascii_uppercase() {
+            'M' | 'L' | 'T' => 2,
+            'H' | 'V' => 1,
+            'C' => 6,
+            'S' | 'Q' => 4,
+            'A' => 7,
+            _ => 0,
+        };
+        if arity == 0 {
+            // close path
+            if !path.is_empty() {
+                path.push(start);
+                paths.push(std::mem::take(&mut path));
+            }
+            current = start;
+            continue;
+        }
+        for (i, group) in args
+            .chunks(arity)
+            .filter(|group| group.len() == arity)
+            .enumerate()
+        {
+            let kind = command.to_ascii_uppercase();
+            // a move starts a new subpath, the pairs after it are lines
+            if kind == 'M' && i == 0 {
+                if path.len() > 1 {
+                    paths.push(std::mem::take(&mut path));
+                }
+                current = at(group[0], group[1], current);
+                start = current;
+                path = vec![current];
+                last_control = None;
+                continue;
+            }
+            if path.is_empty() {
+                path.push(current);
+            }
+            let mut control = None;
+            let next = match kind {
+                'M' | 'L' => at(group[0], group[1], current),
+                'H' => Point {
+                    x: if relative {
+                        current.x + group[0]
+                    } else {
+                        group[0]
+                    },
+                    y: current.y,
+                },
+                'V' => Point {
+                    x: current.x,
+                    y: if relative {
+                        current.y + group[0]
+                    } else {
+                        group[0]
+                    },
+                },
+                'A' => at(group[5], group[6], current),
+                _ => {
+                    let previous = last_control.unwrap_or(current);
+                    let reflected = Point {
+                        x: 2.0 * current.x - previous.x,
+                        y: 2.0 * current.y - previous.y,
+                    };
+                    let controls: Vec<Point> = match kind {
+                        'C' => vec![
+                            at(group[0], group[1], current),
+                            at(group[2], group[3], current),
+                            at(group[4], group[5], current),
+                        ],
+                        'S' => vec![
+                            reflected,
+                            at(group[0], group[1], current),
+                            at(group[2], group[3], current),
+                        ],
+                        'Q' => vec![
+                            at(group[0], group[1], current),
+                            at(group[2], group[3], current),
+                        ],
+                        _ => vec![reflected, at(group[0], group[1], current)],
+                    };
+                    let mut curve = vec![current];
+                    curve.extend(&controls);
+                    path.extend(
+                        (1..CURVE_STEPS)
+                            .map(|step| bezier(&curve, step as f64 / CURVE_STEPS as f64)),
+                    );
+                    control = Some(controls[controls.len() - 2]);
+                    controls[controls.len() - 1]
+                }
+            };
+            path.push(next);
+            current = next;
+            last_control = control;
+        }
+    }
+    if path.len() > 1 {
+        paths.push(path);
+    }
+    paths
+}
+
+// paths, polylines, polygons, lines and circles from an svg, in inches.
+// user units are css pixels unless the svg's width is in inches or
+// millimetres with a view box to scale. transforms aren't applied, the
+// alignment controls are there to put the outline where it should be.
+pub fn parse_svg(text: &str) -> Result<Vec<Vec<Point>>, String> {
+    if !text.contains("<svg") {
+        return Err("that isn't an svg".to_string());
+    }
+    let tags: Vec<&str> = text
+        .split('<')
+        .skip(1)
+        .filter_map(|tag| tag.split_once('>').map(|(tag, _)| tag))
+        .collect();
+    let name = |tag: &str| tag.split_whitespace().next().unwrap_or("").to_string();
+
+    // inches per user unit
+    let root = tags
+        .iter()
+        .find(|tag| name(tag) == "svg")
+        .copied()
+        .unwrap_or("");
+    let view_width = attribute(root, "viewBox")
+        .map(numbers)
+        .filter(|view_box| view_box.len() == 4)
+        .map(|view_box| view_box[2]);
+    let width = attribute(root, "width").unwrap_or("");
+    let scale = match (view_width, numbers(width).first()) {
+        (Some(view_width), Some(&width_value)) if width.ends_with("in") => width_value / view_width,
+        (Some(view_width), Some(&width_value)) if width.ends_with("mm") => {
+            width_value / MM_PER_INCH / view_width
+        }
+        _ => 1.0 / SVG_UNITS_PER_INCH,
+    };
+
+    let mut paths: Vec<Vec<Point>> = Vec::new();
+    for tag in tags {
+        let number =
+            |attr: &str| attribute(tag, attr).and_then(|value| numbers(value).first().copied());
+        match name(tag).as_str() {
+            "path" => paths.extend(attribute(tag, "d").map(path_data).unwrap_or_default()),
+            kind @ ("polyline" | "polygon") => {
+                let mut path: Vec<Point> = attribute(tag, "points")
+                    .map(numbers)
+                    .unwrap_or_default()
+                    .chunks(2)
+                    .filter(|pair| pair.len() == 2)
+                    .map(|pair| Point {
+                        x: pair[0],
+                        y: pair[1],
+                    })
+                    .collect();
+                if kind == "polygon" && !path.is_empty() {
+                    path.push(path[0]);
+                }
+                paths.push(path);
+            }
+            "line" => paths.push(vec![
+                Point {
+                    x: number("x1").unwrap_or(0.0),
+                    y: number("y1").unwrap_or(0.0),
+                },
+                Point {
+                    x: number("x2").unwrap_or(0.0),
+                    y: number("y2").unwrap_or(0.0),
+                },
+            ]),
+            "circle" => {
+                if let Some(radius) = number("r") {
+                    let center = Point {
+                        x: number("cx").unwrap_or(0.0),
+                        y: number("cy").unwrap_or(0.0),
+                    };
+                    paths.push(arc(
+                        center,
+                        radius,
+                        0.0,
+                        2.0 * f64::consts::PI,
+                        CIRCLE_STEPS,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(paths
+        .into_iter()
+        .filter(|path| path.len() >= 2)
+        .map(|path| {
+            path.into_iter()
+                .map(|pt| Point {
+                    x: pt.x * scale,
+                    y: pt.y * scale,
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// strokes the placed reference over the gears, `scale` pixels per inch on
+// a context centered on the view
+pub fn draw(context: &web_sys::CanvasRenderingContext2d, paths: &[Vec<Point>], scale: f64) {
+    context.set_stroke_style_str("rgba(200, 0, 120, 0.8)");
+    context.set_line_width(1.5);
+    context.set_line_dash(&js_sys::Array::new()).unwrap();
+    context.begin_path();
+    for path in paths {
+        for (i, pt) in path.iter().enumerate() {
+            if i == 0 {
+                context.move_to(pt.x * scale, pt.y * scale);
+            } else {
+                context.line_to(pt.x * scale, pt.y * scale);
+            }
+        }
+    }
+    context.stroke();
+    context.set_line_width(1.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dxf;
+    use crate::geometry::OutlineSegment;
+
+    fn close(a: Point, b: Point) -> bool {
+        (a.x - b.x).abs() < 1e-6 && (a.y - b.y).abs() < 1e-6
+    }
+
+    #[test]
+    fn exported_dxfs_read_back_in() {
+        let square = vec![
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 1.0, y: 0.0 },
+            Point { x: 1.0, y: 2.0 },
+            Point { x: 0.0, y: 0.0 },
+        ];
+        let text = dxf::export_outline(&[OutlineSegment::Line(square.clone())]);
+        let reference = Reference::load("square.dxf", &text).unwrap();
+        assert_eq!(reference.paths.len(), 1);
+        assert!(reference.paths[0]
+            .iter()
+            .zip(&square)
+            .all(|(a, b)| close(*a, *b)));
+
+        // millimetre drawings come in as inches
+        let metric = "0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n4\n0\nENDSEC\n0\nSECTION\n2\nENTITIES\n0\nCIRCLE\n8\n0\n10\n25.4\n20\n0\n40\n12.7\n0\nENDSEC\n0\nEOF\n";
+        let circle = &parse_dxf(metric).unwrap()[0];
+        assert!(circle
+            .iter()
+            .all(|pt| (((pt.x - 1.0).powi(2) + pt.y.powi(2)).sqrt() - 0.5).abs() < 1e-9));
+        assert!(Reference::load("empty.dxf", "0\nEOF\n").is_err());
+    }
+
+    #[test]
+    fn svg_paths_and_shapes_come_in_as_inches() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="2in" height="2in" viewBox="0 0 200 200">
+            <path d="M10 10 h50 v50 L10,60 z m100 0 c0 50 50 50 50 0"/>
+            <polygon points="0,0 100,0 100,100"/>
+            <circle cx="100" cy="100" r="50"/>
+        </svg>"#;
+        let paths = parse_svg(svg).unwrap();
+        assert_eq!(paths.len(), 4);
+        let square = &paths[0];
+        assert_eq!(square.len(), 5);
+        assert!(close(square[0], Point { x: 0.1, y: 0.1 }));
+        assert!(close(square[2], Point { x: 0.6, y: 0.6 }));
+        assert!(close(square[4], square[0]));
+        // the relative move starts from where the square closed
+        let curve = &paths[1];
+        assert!(close(curve[0], Point { x: 1.1, y: 0.1 }));
+        assert!(close(*curve.last().unwrap(), Point { x: 1.6, y: 0.1 }));
+        assert!(curve.iter().any(|pt| pt.y > 0.4));
+        assert!(close(*paths[2].last().unwrap(), Point { x: 0.0, y: 0.0 }));
+        assert!(paths[3]
+            .iter()
+            .all(|pt| (((pt.x - 1.0).powi(2) + (pt.y - 1.0).powi(2)).sqrt() - 0.5).abs() < 1e-9));
+    }
+
+    #[test]
+    fn alignment_turns_about_the_center_then_moves() {
+        let reference = Reference {
+            name: "bar".to_string(),
+            paths: vec![vec![Point { x: 1.0, y: 1.0 }, Point { x: 3.0, y: 1.0 }]],
+        };
+        let placed = reference.placed(&Alignment {
+            offset: Point { x: 0.5, y: 0.0 },
+            rotation: 90.0,
+        });
+        assert!(close(placed[0][0], Point { x: 2.5, y: 0.0 }));
+        assert!(close(placed[0][1], Point { x: 2.5, y: 2.0 }));
+        let offset = reference.offset_to(Point { x: -1.0, y: 0.0 });
+        assert!(close(offset, Point { x: -3.0, y: -1.0 }));
+    }
+}
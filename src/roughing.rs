@@ -0,0 +1,198 @@
+use crate::geometry::{distance_to_segment, inside, GearOutline, Point};
+use crate::tolerance;
+use std::collections::HashMap;
+
+// how far inside its offset a path point can fall and still count, as a
+// share of the offset. miters at sharp corners land a little short.
+const SLACK: f64 = 1e-3;
+// passes a single region gets at most, in case a stepover is tiny
+const MAX_PASSES: usize = 200;
+
+// the cutter and how far apart its passes are, in inches
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tool {
+    pub diameter: f64,
+    pub stepover: f64,
+}
+
+impl Default for Tool {
+    fn default() -> Self {
+        Self {
+            diameter: 0.125,
+            stepover: 0.05,
+        }
+    }
+}
+
+// tool center paths for clearing a gear from a disk of its outside diameter,
+// in inches on the page. a path ending where it starts is closed.
+#[derive(Default)]
+pub struct Paths {
+    // outward from the teeth, between them and the disk's edge
+    pub spaces: Vec<Vec<Point>>,
+    // inward from each web cutout's edge
+    pub holes: Vec<Vec<Point>>,
+}
+
+// clearing contours for every gear, stepping away from the finished edge one
+// stepover at a time until there's nothing left to clear. the finishing pass
+// around the edge itself is left to the cam package.
+pub fn paths(gears: &[GearOutline], tool: Tool) -> Paths {
+    let mut paths = Paths::default();
+    if tool.diameter <= 0.0 || tool.stepover <= 0.0 {
+        return paths;
+    }
+    let tool_radius = tool.diameter / 2.0;
+    for gear in gears {
+        let outline: Vec<Point> = tolerance::outline_points(&gear.segments)
+            .iter()
+            .map(|pt| *pt + gear.center)
+            .collect();
+        let stock = gear.geometry.outer_radius;
+        paths
+            .spaces
+            .extend(contours(&outline, tool_radius, tool.stepover, |pt| {
+                (pt.x - gear.center.x).hypot(pt.y - gear.center.y) <= stock
+            }));
+        for hole in &gear.holes {
+            let hole: Vec<Point> = hole.iter().map(|pt| *pt + gear.center).collect();
+            paths
+                .holes
+                .extend(contours(&hole, -tool_radius, -tool.stepover, |pt| {
+                    inside(pt, &hole)
+                }));
+        }
+    }
+    paths
+}
+
+// offsets of `edge` every `stepover` from `first`, outward for positive
+// distances and inward for negative ones. only the stretches that clear the
+// edge by the full offset and that `keep` allows are kept, so a contour
+// squeezed out of a narrow space breaks into pieces or disappears.
+fn contours(
+    edge: &[Point],
+    first: f64,
+    stepover: f64,
+    keep: impl Fn(&Point) -> bool,
+) -> Vec<Vec<Point>> {
+    let mut contours = Vec::new();
+    for pass in 0..MAX_PASSES {
+        let distance = first + pass as f64 * stepover;
+        let offset = tolerance::offset(edge, distance);
+        let near = Near::new(edge, distance.abs());
+        let clear = |pt: &Point| keep(pt) && near.clears(pt, distance.abs() * (1.0 - SLACK));
+        let runs = runs(&offset, clear);
+        if runs.is_empty() {
+            break;
+        }
+        // a pocket closing up to a point needs no pass of its own
+        contours.extend(runs.into_iter().filter(|run| {
+            run.windows(2)
+                .any(|pair| (pair[1].x - pair[0].x).hypot(pair[1].y - pair[0].y) > 1e-9)
+        }));
+    }
+    contours
+}
+
+// the stretches of a closed loop where `clear` holds, the whole loop if it
+// holds everywhere
+fn runs(points: &[Point], clear: impl Fn(&Point) -> bool) -> Vec<Vec<Point>> {
+    let corners = &points[..points.len().saturating_sub(1)];
+    let flags: Vec<bool> = corners.iter().map(&clear).collect();
+    let Some(start) = flags.iter().position(|flag| !flag) else {
+        return vec![points.to_vec()];
+    };
+    let mut runs = Vec::new();
+    let mut run: Vec<Point> = Vec::new();
+    for i in 0..corners.len() {
+        let index = (start + i) % corners.len();
+        if flags[index] {
+            run.push(corners[index]);
+        } else if !run.is_empty() {
+            runs.push(std::mem::take(&mut run));
+        }
+    }
+    if !run.is_empty() {
+        runs.push(run);
+    }
+    runs.retain(|run| run.len() > 1);
+    runs
+}
+
+// the edge's segments bucketed into square cells, so checking a point only
+// looks at the segments around it. gear outlines run to thousands of points.
+struct Near<'a> {
+    edge: &'a [Point],
+    cell: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl<'a> Near<'a> {
+    // cells at least `reach` across, the furthest `clears` is asked about
+    fn new(edge: &'a [Point], reach: f64) -> Self {
+        let cell = reach.max(1e-6);
+        let key = |value: f64| (value / cell).floor() as i64;
+        let mut cells: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (index, pair) in edge.windows(2).enumerate() {
+            let x_range = key(pair[0].x.min(pair[1].x))..=key(pair[0].x.max(pair[1].x));
+            let y_range = key(pair[0].y.min(pair[1].y))..=key(pair[0].y.max(pair[1].y));
+            for x in x_range {
+                for y in y_range.clone() {
+                    cells.entry((x, y)).or_default().push(index);
+                }
+            }
+        }
+        Self { edge, cell, cells }
+    }
+
+    // whether every segment is at least `distance` from `point`, for a
+    // distance up to the cell size
+    fn clears(&self, point: &Point, distance: f64) -> bool {
+        let x = (point.x / self.cell).floor() as i64;
+        let y = (point.y / self.cell).floor() as i64;
+        (x - 1..=x + 1)
+            .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+            .filter_map(|key| self.cells.get(&key))
+            .flatten()
+            .all(|&index| {
+                distance_to_segment(point, &self.edge[index], &self.edge[index + 1]) >= distance
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(half: f64) -> Vec<Point> {
+        vec![
+            Point { x: -half, y: -half },
+            Point { x: half, y: -half },
+            Point { x: half, y: half },
+            Point { x: -half, y: half },
+            Point { x: -half, y: -half },
+        ]
+    }
+
+    #[test]
+    fn pockets_step_in_until_they_close() {
+        let hole = square(1.0);
+        let passes = contours(&hole, -0.25, -0.25, |pt| inside(pt, &hole));
+        // a tool 0.5 across clears a 2 inch pocket in passes 0.25, 0.5 and
+        // 0.75 in from the edge, the last a square 0.5 across
+        assert_eq!(passes.len(), 3);
+        assert!(passes.iter().all(|pass| pass.first() == pass.last()));
+        assert!((passes[2][0].x + 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn outward_passes_stop_at_the_stock() {
+        let edge = square(1.0);
+        // only the parts of each pass left of x = 1.3 are kept
+        let passes = contours(&edge, 0.1, 0.1, |pt| pt.x <= 1.3);
+        assert!(!passes.is_empty());
+        assert!(passes.iter().flatten().all(|pt| pt.x <= 1.3));
+        assert!(passes.iter().all(|pass| pass.len() > 1));
+    }
+}
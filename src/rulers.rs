@@ -0,0 +1,221 @@
+use crate::locale;
+
+// how thick the rulers along the top and left of the workspace are, in pixels
+pub const RULER_SIZE: f64 = 20.0;
+const MM_PER_INCH: f64 = 25.4;
+const BAND_COLOR: &str = "#f4f4f4";
+const TICK_COLOR: &str = "#555";
+const CURSOR_COLOR: &str = "red";
+
+// what the rulers and cursor readout measure in
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum RulerUnits {
+    #[default]
+    Inches,
+    Millimeters,
+}
+
+impl RulerUnits {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "in" => Some(RulerUnits::Inches),
+            "mm" => Some(RulerUnits::Millimeters),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            RulerUnits::Inches => "in",
+            RulerUnits::Millimeters => "mm",
+        }
+    }
+
+    fn per_inch(&self) -> f64 {
+        match self {
+            RulerUnits::Inches => 1.0,
+            RulerUnits::Millimeters => MM_PER_INCH,
+        }
+    }
+
+    // the smallest tick, and how many of them make a medium and a labelled
+    // major tick
+    fn divisions(&self) -> (f64, u32, u32) {
+        match self {
+            RulerUnits::Inches => (0.125, 4, 8),
+            RulerUnits::Millimeters => (1.0, 5, 10),
+        }
+    }
+
+    // a position in these units, like "1.25 in"
+    pub fn format(&self, value: f64) -> String {
+        let decimals = match self {
+            RulerUnits::Inches => 3,
+            RulerUnits::Millimeters => 1,
+        };
+        let rounded = (value * 10f64.powi(decimals)).round() / 10f64.powi(decimals);
+        format!("{} {}", locale::format(rounded + 0.0), self.value())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tick {
+    // pixels along the ruler
+    pub position: f64,
+    // a fraction of the ruler's thickness
+    pub length: f64,
+    pub label: Option<String>,
+}
+
+// ticks along a ruler `length` pixels long with the layout's origin at
+// `origin` pixels, `ppi` pixels to the inch
+pub fn ticks(length: f64, origin: f64, ppi: f64, units: RulerUnits) -> Vec<Tick> {
+    let (step, medium, major) = units.divisions();
+    let spacing = step * ppi / units.per_inch();
+    let first = (-origin / spacing).ceil() as i64;
+    let last = ((length - origin) / spacing).floor() as i64;
+    (first..=last)
+        .map(|i| {
+            let position = origin + i as f64 * spacing;
+            if i % major as i64 == 0 {
+                let value = i as f64 * step;
+                Tick {
+                    position,
+                    length: 0.8,
+                    // major ticks fall on whole units
+                    label: Some(format!("{}", value.round() + 0.0)),
+                }
+            } else if i % medium as i64 == 0 {
+                Tick {
+                    position,
+                    length: 0.5,
+                    label: None,
+                }
+            } else {
+                Tick {
+                    position,
+                    length: 0.25,
+                    label: None,
+                }
+            }
+        })
+        .collect()
+}
+
+// draws both rulers across a `width` by `height` pixel workspace, with the
+// cursor marked on them and read out in the corner when it's over the
+// workspace
+pub fn draw(
+    context: &web_sys::CanvasRenderingContext2d,
+    width: f64,
+    height: f64,
+    ppi: f64,
+    units: RulerUnits,
+    cursor: Option<(f64, f64)>,
+) {
+    context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap();
+    context.clear_rect(0.0, 0.0, width, height);
+    context.set_fill_style_str(BAND_COLOR);
+    context.fill_rect(0.0, 0.0, width, RULER_SIZE);
+    context.fill_rect(0.0, 0.0, RULER_SIZE, height);
+
+    let (center_x, center_y) = (width / 2.0, height / 2.0);
+    context.set_stroke_style_str(TICK_COLOR);
+    context.set_fill_style_str(TICK_COLOR);
+    context.set_line_width(1.0);
+    context.set_font("9px sans-serif");
+    context.begin_path();
+    context.move_to(RULER_SIZE, RULER_SIZE + 0.5);
+    context.line_to(width, RULER_SIZE + 0.5);
+    context.move_to(RULER_SIZE + 0.5, RULER_SIZE);
+    context.line_to(RULER_SIZE + 0.5, height);
+    for tick in ticks(width, center_x, ppi, units) {
+        if tick.position < RULER_SIZE {
+            continue;
+        }
+        let x = tick.position.round() + 0.5;
+        context.move_to(x, RULER_SIZE * (1.0 - tick.length));
+        context.line_to(x, RULER_SIZE);
+        if let Some(label) = &tick.label {
+            context.fill_text(label, x + 2.0, 9.0).unwrap();
+        }
+    }
+    for tick in ticks(height, center_y, ppi, units) {
+        if tick.position < RULER_SIZE {
+            continue;
+        }
+        let y = tick.position.round() + 0.5;
+        context.move_to(RULER_SIZE * (1.0 - tick.length), y);
+        context.line_to(RULER_SIZE, y);
+        if let Some(label) = &tick.label {
+            // the left ruler's labels run up it
+            context.save();
+            context.translate(9.0, y - 2.0).unwrap();
+            context.rotate(-std::f64::consts::FRAC_PI_2).unwrap();
+            context.fill_text(label, 0.0, 0.0).unwrap();
+            context.restore();
+        }
+    }
+    context.stroke();
+
+    let Some((x, y)) = cursor else {
+        return;
+    };
+    context.set_stroke_style_str(CURSOR_COLOR);
+    context.begin_path();
+    context.move_to(x, 0.0);
+    context.line_to(x, RULER_SIZE);
+    context.move_to(0.0, y);
+    context.line_to(RULER_SIZE, y);
+    context.stroke();
+
+    // the readout sits just inside the corner, where the rulers meet
+    let readout = format!(
+        "X {}  Y {}",
+        units.format((x - center_x) / ppi * units.per_inch()),
+        units.format((y - center_y) / ppi * units.per_inch())
+    );
+    context.set_font("12px sans-serif");
+    let readout_width = context.measure_text(&readout).unwrap().width();
+    context.set_fill_style_str("rgba(255, 255, 255, 0.9)");
+    context.fill_rect(
+        RULER_SIZE + 2.0,
+        RULER_SIZE + 2.0,
+        readout_width + 8.0,
+        18.0,
+    );
+    context.set_fill_style_str("black");
+    context
+        .fill_text(&readout, RULER_SIZE + 6.0, RULER_SIZE + 15.0)
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_are_labelled_from_the_origin() {
+        let inches = ticks(200.0, 100.0, 96.0, RulerUnits::Inches);
+        let origin = inches.iter().find(|tick| tick.position == 100.0).unwrap();
+        assert_eq!(origin.label.as_deref(), Some("0"));
+        assert_eq!((inches[1].position - inches[0].position), 12.0);
+        let one_inch = inches.iter().find(|tick| tick.position == 196.0).unwrap();
+        assert_eq!(one_inch.label.as_deref(), Some("1"));
+        assert_eq!(
+            inches
+                .iter()
+                .filter(|tick| tick.position == 52.0)
+                .map(|tick| tick.length)
+                .collect::<Vec<_>>(),
+            vec![0.5]
+        );
+
+        let millimeters = ticks(200.0, 100.0, 96.0, RulerUnits::Millimeters);
+        let labels: Vec<&str> = millimeters
+            .iter()
+            .filter_map(|tick| tick.label.as_deref())
+            .collect();
+        assert_eq!(labels, vec!["-20", "-10", "0", "10", "20"]);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::storage::{self, Design};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use web_sys::{IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode};
+
+// the session lives in indexeddb rather than local storage, so it doesn't
+// compete with saved designs for local storage's few megabytes
+const DB_NAME: &str = "geargen";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "session";
+const SESSION_KEY: &str = "current";
+
+// how often the open designs are snapshotted while they have unsaved edits
+pub const SNAPSHOT_INTERVAL_MS: i32 = 30 * 1000;
+
+// every design open in the session, kept until the edits in it are saved or
+// discarded. a page that crashes or is navigated away from leaves it behind.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Session {
+    // milliseconds since the epoch
+    pub saved_at: f64,
+    pub designs: Vec<Design>,
+    pub active: usize,
+}
+
+// whether any design has edits that aren't in its saved copy. an untouched
+// default design has nothing worth recovering.
+pub fn has_unsaved_edits(designs: &[Design]) -> bool {
+    designs.iter().any(|design| {
+        *design != Design::default() && storage::named_design(&design.name).as_ref() != Some(design)
+    })
+}
+
+pub fn save(session: &Session) {
+    let Ok(json) = serde_json::to_string(session) else {
+        return;
+    };
+    with_store(IdbTransactionMode::Readwrite, move |store| {
+        let _ = store.put_with_key(&JsValue::from_str(&json), &SESSION_KEY.into());
+    });
+}
+
+pub fn clear() {
+    with_store(IdbTransactionMode::Readwrite, |store| {
+        let _ = store.delete(&SESSION_KEY.into());
+    });
+}
+
+// calls `on_loaded` with the session left behind last time, if there is one
+pub fn load(on_loaded: impl FnOnce(Session) + 'static) {
+    with_store(IdbTransactionMode::Readonly, move |store| {
+        let Ok(request) = store.get(&SESSION_KEY.into()) else {
+            return;
+        };
+        let success_request = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let session = success_request
+                .result()
+                .ok()
+                .and_then(|value| value.as_string())
+                .and_then(|json| serde_json::from_str::<Session>(&json).ok())
+                .filter(|session| {
+                    !session.designs.is_empty() && session.designs.iter().all(Design::is_usable)
+                });
+            if let Some(session) = session {
+                on_loaded(session);
+            }
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+    });
+}
+
+// opens the database, creating the store the first time, and runs `f` on
+// the store in a new transaction. indexeddb can be unavailable (private
+// windows in some browsers), in which case nothing happens.
+fn with_store(mode: IdbTransactionMode, f: impl FnOnce(web_sys::IdbObjectStore) + 'static) {
+    let Some(factory) = web_sys::window().and_then(|window| window.indexed_db().ok().flatten())
+    else {
+        return;
+    };
+    let Ok(request) = factory.open_with_u32(DB_NAME, DB_VERSION) else {
+        return;
+    };
+
+    let upgrade_request = request.clone();
+    let on_upgrade = Closure::once_into_js(move || {
+        if let Some(db) = database(&upgrade_request) {
+            let _ = db.create_object_store(STORE_NAME);
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    let success_request = request.clone();
+    let on_success = Closure::once_into_js(move || {
+        let store = database(&success_request)
+            .and_then(|db| db.transaction_with_str_and_mode(STORE_NAME, mode).ok())
+            .and_then(|transaction| transaction.object_store(STORE_NAME).ok());
+        if let Some(store) = store {
+            f(store);
+        }
+    });
+    request.set_onsuccess(Some(on_success.unchecked_ref()));
+}
+
+fn database(request: &IdbOpenDbRequest) -> Option<IdbDatabase> {
+    request
+        .dyn_ref::<IdbRequest>()?
+        .result()
+        .ok()?
+        .dyn_into::<IdbDatabase>()
+        .ok()
+}
@@ -0,0 +1,63 @@
+use crate::storage::Design;
+use base64::Engine;
+
+// designs travel in the url's fragment, so they're never sent to the server
+const DESIGN_PARAM: &str = "design=";
+
+// a link that opens the page with `design` loaded
+pub fn url(design: &Design) -> String {
+    let location = web_sys::window().unwrap().location();
+    format!(
+        "{}{}#{}",
+        location.origin().unwrap_or_default(),
+        location.pathname().unwrap_or_default(),
+        fragment(design)
+    )
+}
+
+fn fragment(design: &Design) -> String {
+    let json = serde_json::to_string(design).unwrap();
+    format!(
+        "{}{}",
+        DESIGN_PARAM,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json)
+    )
+}
+
+fn from_fragment(fragment: &str) -> Option<Design> {
+    let encoded = fragment
+        .trim_start_matches('#')
+        .strip_prefix(DESIGN_PARAM)?;
+    let json = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded)
+        .ok()?;
+    serde_json::from_slice::<Design>(&json)
+        .ok()
+        .filter(Design::is_usable)
+}
+
+// the design in the link the page was opened with, if there is one. the
+// fragment is cleared so that reloading keeps later edits instead of
+// starting over from the link.
+pub fn take_design_from_url() -> Option<Design> {
+    let location = web_sys::window()?.location();
+    let design = from_fragment(&location.hash().ok()?)?;
+    location.set_hash("").ok()?;
+    Some(design)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn designs_survive_the_fragment() {
+        let design = Design {
+            name: "pair".to_string(),
+            ..Design::default()
+        };
+        assert!(from_fragment(&format!("#{}", fragment(&design))) == Some(design));
+        assert!(from_fragment("#design=not json").is_none());
+        assert!(from_fragment("#other").is_none());
+    }
+}
@@ -0,0 +1,181 @@
+use crate::geometry::Point;
+use crate::paper::{PRINTABLE_HEIGHT, PRINTABLE_WIDTH};
+
+// room left between parts when they're first laid out, in inches
+const GAP: f64 = 0.25;
+
+// one copy of a gear on a sheet, `center` inches from the sheet's center and
+// turned `rotation` degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Part {
+    pub gear: usize,
+    pub sheet: usize,
+    pub center: Point,
+    pub rotation: f64,
+}
+
+// parts arranged by hand across the sheets of a pdf. `gears` is how many
+// gears the train had when it was laid out, so a layout left over from a
+// different train isn't reused.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SheetLayout {
+    pub gears: usize,
+    pub sheets: usize,
+    pub parts: Vec<Part>,
+}
+
+impl SheetLayout {
+    // every gear of `radii` once, in rows across the printable area, going
+    // on to a new sheet when one fills up. a gear too big for a sheet gets
+    // one to itself and hangs off its edges.
+    pub fn new(radii: &[f64]) -> Self {
+        let mut layout = Self {
+            gears: radii.len(),
+            sheets: 1,
+            parts: Vec::new(),
+        };
+        let (left, top) = (-PRINTABLE_WIDTH / 2.0, -PRINTABLE_HEIGHT / 2.0);
+        let (mut x, mut y, mut row_height) = (left, top, 0.0f64);
+        for (gear, &radius) in radii.iter().enumerate() {
+            let size = 2.0 * radius;
+            if x > left && x + size > -left {
+                (x, y) = (left, y + row_height + GAP);
+                row_height = 0.0;
+            }
+            if y > top && y + size > -top {
+                (x, y) = (left, top);
+                layout.sheets += 1;
+            }
+            layout.parts.push(Part {
+                gear,
+                sheet: layout.sheets - 1,
+                center: Point {
+                    x: x + radius,
+                    y: y + radius,
+                },
+                rotation: 0.0,
+            });
+            x += size + GAP;
+            row_height = row_height.max(size);
+        }
+        layout
+    }
+
+    // the part under `point` on `sheet`, the one drawn last where they overlap
+    pub fn part_at(&self, sheet: usize, point: Point, radii: &[f64]) -> Option<usize> {
+        self.parts.iter().rposition(|part| {
+            part.sheet == sheet
+                && (point.x - part.center.x).hypot(point.y - part.center.y) <= radii[part.gear]
+        })
+    }
+
+    // a copy of the part beside it, returning the copy's index
+    pub fn duplicate(&mut self, index: usize, radii: &[f64]) -> usize {
+        let part = self.parts[index];
+        let radius = radii[part.gear];
+        self.parts.push(Part {
+            center: Point {
+                x: part.center.x + 2.0 * radius + GAP,
+                y: part.center.y,
+            },
+            ..part
+        });
+        self.parts.len() - 1
+    }
+
+    pub fn rotate(&mut self, index: usize, degrees: f64) {
+        let part = &mut self.parts[index];
+        part.rotation = (part.rotation + degrees).rem_euclid(360.0);
+    }
+
+    // drops a part, and its sheet too if that leaves it empty
+    pub fn remove(&mut self, index: usize) {
+        self.parts.remove(index);
+        self.drop_empty_sheets();
+    }
+
+    pub fn add_sheet(&mut self) {
+        self.sheets += 1;
+    }
+
+    // takes out sheets with nothing on them, moving the ones after them
+    // down. there's always at least one sheet, even with nothing on it.
+    pub fn drop_empty_sheets(&mut self) {
+        let mut sheet = 0;
+        while sheet < self.sheets && self.sheets > 1 {
+            if self.parts.iter().any(|part| part.sheet == sheet) {
+                sheet += 1;
+                continue;
+            }
+            self.sheets -= 1;
+            self.parts
+                .iter_mut()
+                .filter(|part| part.sheet > sheet)
+                .for_each(|part| part.sheet -= 1);
+        }
+    }
+
+    // parts hanging over the edge of their sheet's printable area
+    pub fn overhanging(&self, radii: &[f64]) -> Vec<usize> {
+        self.parts
+            .iter()
+            .enumerate()
+            .filter(|(_, part)| {
+                let radius = radii[part.gear];
+                part.center.x.abs() + radius > PRINTABLE_WIDTH / 2.0
+                    || part.center.y.abs() + radius > PRINTABLE_HEIGHT / 2.0
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts_fill_rows_then_sheets() {
+        // three 3 inch gears to a row, two rows to a sheet
+        let radii = [1.5; 7];
+        let layout = SheetLayout::new(&radii);
+        assert_eq!(layout.sheets, 2);
+        assert_eq!(layout.parts[2].center.y, layout.parts[0].center.y);
+        assert!(layout.parts[3].center.y > layout.parts[0].center.y);
+        assert_eq!(layout.parts[6].sheet, 1);
+        assert!(layout.overhanging(&radii).is_empty());
+
+        // too big for a sheet, so on one of its own
+        let layout = SheetLayout::new(&[0.5, 5.0]);
+        assert_eq!(layout.sheets, 2);
+        assert_eq!(layout.overhanging(&[0.5, 5.0]), vec![1]);
+    }
+
+    #[test]
+    fn parts_move_between_sheets() {
+        let radii = [1.0, 1.0];
+        let mut layout = SheetLayout::new(&radii);
+        let copy = layout.duplicate(0, &radii);
+        assert_eq!(
+            layout.part_at(0, layout.parts[copy].center, &radii),
+            Some(copy)
+        );
+        layout.rotate(copy, -90.0);
+        assert_eq!(layout.parts[copy].rotation, 270.0);
+
+        // parts moved off a sheet leave it empty until it's tidied away,
+        // and the sheets after it move down
+        layout.add_sheet();
+        layout.add_sheet();
+        layout.parts[copy].sheet = 2;
+        layout.drop_empty_sheets();
+        assert_eq!(layout.sheets, 2);
+        assert_eq!(layout.parts[copy].sheet, 1);
+        layout.remove(copy);
+        assert_eq!(layout.sheets, 1);
+        layout.remove(0);
+        layout.remove(0);
+        assert_eq!(layout.sheets, 1);
+        assert_eq!(layout.part_at(0, Point { x: 5.0, y: 5.0 }, &radii), None);
+    }
+}
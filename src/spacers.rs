@@ -0,0 +1,150 @@
+use crate::geometry::Point;
+use crate::kerf_test::Label;
+use std::f64;
+
+// between neighbouring rings, and between the gears and the row, in inches
+const GAP: f64 = 0.25;
+const LABEL_HEIGHT: f64 = 0.1;
+
+// spacer rings or thrust washers cut alongside the gears, in inches. the
+// thickness is the stock to cut them from, so it's only printed.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Spacers {
+    pub count: u32,
+    pub inner_diameter: f64,
+    pub outer_diameter: f64,
+    pub thickness: f64,
+}
+
+impl Default for Spacers {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            inner_diameter: 0.25,
+            outer_diameter: 0.5,
+            thickness: 0.125,
+        }
+    }
+}
+
+pub struct Ring {
+    pub center: Point,
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+}
+
+// the rings laid out in a row, and a label saying what they are
+pub struct Nest {
+    pub rings: Vec<Ring>,
+    pub label: Label,
+}
+
+// a row of rings starting at `left`, below `top`. y runs down the page like
+// the gears' outlines. `None` without any rings, or if they'd have no wall.
+pub fn nest(spacers: &Spacers, left: f64, top: f64) -> Option<Nest> {
+    if spacers.count == 0 || spacers.outer_diameter <= spacers.inner_diameter {
+        return None;
+    }
+    let outer_radius = spacers.outer_diameter / 2.0;
+    let inner_radius = spacers.inner_diameter.max(0.0) / 2.0;
+    let rings: Vec<Ring> = (0..spacers.count)
+        .map(|i| Ring {
+            center: Point {
+                x: left + outer_radius + i as f64 * (spacers.outer_diameter + GAP),
+                y: top + GAP + outer_radius,
+            },
+            inner_radius,
+            outer_radius,
+        })
+        .collect();
+    let width = spacers.count as f64 * (spacers.outer_diameter + GAP) - GAP;
+    let label = Label {
+        position: Point {
+            x: left + width / 2.0,
+            y: top + GAP + spacers.outer_diameter + GAP / 2.0 + LABEL_HEIGHT / 2.0,
+        },
+        height: LABEL_HEIGHT,
+        text: format!(
+            "{} x spacer {:.3} ID x {:.3} OD x {:.3} thick",
+            spacers.count, spacers.inner_diameter, spacers.outer_diameter, spacers.thickness
+        ),
+    };
+    Some(Nest { rings, label })
+}
+
+// draws the nest in black, on a context already centered on the page
+pub fn draw(context: &web_sys::CanvasRenderingContext2d, nest: &Nest, ppi: f64) {
+    context.set_stroke_style_str("black");
+    context
+        .set_line_dash(&wasm_bindgen::JsValue::from(Vec::<f64>::new()))
+        .unwrap();
+    for ring in &nest.rings {
+        for radius in [ring.outer_radius, ring.inner_radius] {
+            if radius <= 0.0 {
+                continue;
+            }
+            context.begin_path();
+            context
+                .arc(
+                    ring.center.x * ppi,
+                    ring.center.y * ppi,
+                    radius * ppi,
+                    0.0,
+                    2.0 * f64::consts::PI,
+                )
+                .unwrap();
+            context.stroke();
+        }
+    }
+    context.set_fill_style_str("black");
+    context.set_font(&format!("{}px sans-serif", nest.label.height * ppi));
+    context.set_text_align("center");
+    context.set_text_baseline("middle");
+    context
+        .fill_text(
+            &nest.label.text,
+            nest.label.position.x * ppi,
+            nest.label.position.y * ppi,
+        )
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rings_line_up_below_the_gears_without_touching() {
+        let spacers = Spacers {
+            count: 3,
+            inner_diameter: 0.25,
+            outer_diameter: 0.75,
+            thickness: 0.125,
+        };
+        let nest = nest(&spacers, -2.0, 1.0).unwrap();
+        assert_eq!(nest.rings.len(), 3);
+        assert!((nest.rings[0].center.x - (-2.0 + 0.375)).abs() < 1e-12);
+        for ring in &nest.rings {
+            assert!(ring.center.y - ring.outer_radius >= 1.0);
+        }
+        for pair in nest.rings.windows(2) {
+            let between = pair[1].center.x - pair[0].center.x;
+            assert!(between > pair[0].outer_radius + pair[1].outer_radius);
+        }
+        assert_eq!(
+            nest.label.text,
+            "3 x spacer 0.250 ID x 0.750 OD x 0.125 thick"
+        );
+
+        let none = Spacers {
+            count: 0,
+            ..spacers
+        };
+        assert!(super::nest(&none, 0.0, 0.0).is_none());
+        let no_wall = Spacers {
+            inner_diameter: 0.75,
+            ..spacers
+        };
+        assert!(super::nest(&no_wall, 0.0, 0.0).is_none());
+    }
+}
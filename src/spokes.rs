@@ -0,0 +1,453 @@
+use crate::geometry::{GearGeometry, Point};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{PI, TAU};
+
+// solid material left under the roots, in modules
+const RIM_MODULES: f64 = 2.0;
+// without a hub, the web is solid this far out, as a share of the root radius
+const DEFAULT_HUB_SHARE: f64 = 0.25;
+// points sampled along each curved edge and round each arc
+const EDGE_STEPS: u32 = 12;
+const ARC_STEPS: u32 = 24;
+const CIRCLE_STEPS: u32 = 48;
+const FILLET_STEPS: u32 = 8;
+// halvings when finding where a fillet leaves a spoke's side
+const FILLET_SEARCH_STEPS: u32 = 50;
+
+// how the web between the hub and the rim is cut away
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SpokeStyle {
+    #[default]
+    SolidWeb,
+    Straight,
+    // each spoke sweeps half the spacing between spokes on its way out
+    Curved,
+    // a ring of round holes instead of spokes
+    Circles,
+}
+
+impl SpokeStyle {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "solid" => Some(SpokeStyle::SolidWeb),
+            "straight" => Some(SpokeStyle::Straight),
+            "curved" => Some(SpokeStyle::Curved),
+            "circles" => Some(SpokeStyle::Circles),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            SpokeStyle::SolidWeb => "solid",
+            SpokeStyle::Straight => "straight",
+            SpokeStyle::Curved => "curved",
+            SpokeStyle::Circles => "circles",
+        }
+    }
+}
+
+// `count` spokes or holes, and `width` the material between neighbouring
+// cutouts in inches, measured around the circle. `fillet` rounds the corners
+// where spokes meet the hub and rim, zero leaving them sharp.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spokes {
+    pub style: SpokeStyle,
+    pub count: u32,
+    pub width: f64,
+    #[serde(default)]
+    pub fillet: f64,
+}
+
+impl Default for Spokes {
+    fn default() -> Self {
+        Self {
+            style: SpokeStyle::SolidWeb,
+            count: 5,
+            width: 0.25,
+            fillet: 0.0625,
+        }
+    }
+}
+
+fn polar(radius: f64, angle: f64) -> Point {
+    Point {
+        x: radius * angle.cos(),
+        y: radius * angle.sin(),
+    }
+}
+
+// the points strictly between the ends of an arc round `center`
+fn arc(
+    center: Point,
+    radius: f64,
+    from: f64,
+    sweep: f64,
+    steps: u32,
+) -> impl Iterator<Item = Point> {
+    (1..steps).map(move |i| {
+        let pt = polar(radius, from + sweep * i as f64 / steps as f64);
+        Point {
+            x: center.x + pt.x,
+            y: center.y + pt.y,
+        }
+    })
+}
+
+fn angle_of(pt: Point, center: Point) -> f64 {
+    (pt.y - center.y).atan2(pt.x - center.x)
+}
+
+// the ring between the hub and the rim the spokes cross, and the spokes'
+// shape
+struct Web {
+    inner: f64,
+    outer: f64,
+    half_width: f64,
+    // how far round each spoke sweeps on its way out
+    bend: f64,
+    // points along each side, one segment for straight spokes
+    edge_steps: u32,
+}
+
+// where a spoke's side meets the hub or rim, rounded or not
+struct Corner {
+    // the radius it leaves the side at
+    radius: f64,
+    center: Point,
+    on_circle: Point,
+}
+
+impl Web {
+    // the side of the spoke at `start`, `side` 1 toward the next spoke
+    fn edge(&self, start: f64, side: f64, radius: f64) -> Point {
+        let along = (radius - self.inner) / (self.outer - self.inner);
+        polar(
+            radius,
+            start + self.bend * along + side * (self.half_width / radius).asin(),
+        )
+    }
+
+    // the unit normal to a side, pointing away from its spoke
+    fn normal(&self, start: f64, side: f64, radius: f64) -> Point {
+        let step = (self.outer - self.inner) * 1e-6;
+        let from = self.edge(start, side, radius - step);
+        let to = self.edge(start, side, radius + step);
+        let length = (to.x - from.x).hypot(to.y - from.y);
+        Point {
+            x: -side * (to.y - from.y) / length,
+            y: side * (to.x - from.x) / length,
+        }
+    }
+
+    // the corner between a side and the circle of `circle` radius, the hub
+    // or the rim. a fillet's center is `fillet` from both, outside the hub
+    // and inside the rim, found by halving the stretch of side it could
+    // leave from. `None` if it doesn't fit against this side.
+    fn corner(&self, start: f64, side: f64, circle: f64, fillet: f64) -> Option<Corner> {
+        let hub = circle == self.inner;
+        if fillet <= 0.0 {
+            let pt = self.edge(start, side, circle);
+            return Some(Corner {
+                radius: circle,
+                center: pt,
+                on_circle: pt,
+            });
+        }
+        let target = if hub {
+            circle + fillet
+        } else {
+            circle - fillet
+        };
+        let center = |radius: f64| {
+            let (pt, normal) = (
+                self.edge(start, side, radius),
+                self.normal(start, side, radius),
+            );
+            Point {
+                x: pt.x + fillet * normal.x,
+                y: pt.y + fillet * normal.y,
+            }
+        };
+        let beyond = |radius: f64| {
+            let pt = center(radius);
+            pt.x.hypot(pt.y) > target
+        };
+        let (mut low, mut high) = (self.inner, self.outer);
+        if beyond(low) || !beyond(high) {
+            return None;
+        }
+        for _ in 0..FILLET_SEARCH_STEPS {
+            let middle = (low + high) / 2.0;
+            if beyond(middle) {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+        let center = center(low);
+        Some(Corner {
+            radius: low,
+            center,
+            on_circle: polar(circle, angle_of(center, Point { x: 0.0, y: 0.0 })),
+        })
+    }
+
+    // the window between the spoke at `start` and the next one, out along
+    // this spoke, round the rim, in along the next and back round the hub,
+    // the last point repeating the first. `None` if the fillets don't fit.
+    fn window(&self, start: f64, spacing: f64, fillet: f64) -> Option<Vec<Point>> {
+        let end = start + spacing;
+        let origin = Point { x: 0.0, y: 0.0 };
+        let hub_start = self.corner(start, 1.0, self.inner, fillet)?;
+        let rim_start = self.corner(start, 1.0, self.outer, fillet)?;
+        let rim_end = self.corner(end, -1.0, self.outer, fillet)?;
+        let hub_end = self.corner(end, -1.0, self.inner, fillet)?;
+        if hub_start.radius >= rim_start.radius || hub_end.radius >= rim_end.radius {
+            return None;
+        }
+        let rim_from = angle_of(rim_start.on_circle, origin);
+        let rim_sweep = (angle_of(rim_end.on_circle, origin) - rim_from).rem_euclid(TAU);
+        let hub_from = angle_of(hub_end.on_circle, origin);
+        let hub_sweep = (hub_from - angle_of(hub_start.on_circle, origin)).rem_euclid(TAU);
+        // fillets running past each other turn the arcs between them inside
+        // out
+        if rim_sweep >= spacing || hub_sweep >= spacing {
+            return None;
+        }
+        // the short way round a fillet, from one point on it to another
+        let round = |corner: &Corner, from: Point, to: Point| {
+            let from = angle_of(from, corner.center);
+            let sweep = (angle_of(to, corner.center) - from + PI).rem_euclid(TAU) - PI;
+            arc(corner.center, fillet, from, sweep, FILLET_STEPS)
+        };
+        let side = |start: f64, side: f64, from: f64, to: f64| {
+            (0..=self.edge_steps).map(move |i| {
+                self.edge(
+                    start,
+                    side,
+                    from + (to - from) * i as f64 / self.edge_steps as f64,
+                )
+            })
+        };
+
+        let mut window: Vec<Point> = side(start, 1.0, hub_start.radius, rim_start.radius).collect();
+        if fillet > 0.0 {
+            window.extend(round(
+                &rim_start,
+                *window.last().unwrap(),
+                rim_start.on_circle,
+            ));
+            window.push(rim_start.on_circle);
+        }
+        window.extend(arc(origin, self.outer, rim_from, rim_sweep, ARC_STEPS));
+        if fillet > 0.0 {
+            window.push(rim_end.on_circle);
+            let edge = self.edge(end, -1.0, rim_end.radius);
+            window.extend(round(&rim_end, rim_end.on_circle, edge));
+        }
+        window.extend(side(end, -1.0, rim_end.radius, hub_end.radius));
+        if fillet > 0.0 {
+            window.extend(round(&hub_end, *window.last().unwrap(), hub_end.on_circle));
+            window.push(hub_end.on_circle);
+        }
+        window.extend(arc(origin, self.inner, hub_from, -hub_sweep, ARC_STEPS));
+        if fillet > 0.0 {
+            window.push(hub_start.on_circle);
+            window.extend(round(&hub_start, hub_start.on_circle, window[0]));
+        }
+        window.push(window[0]);
+        Some(window)
+    }
+}
+
+// closed outlines cut out of the web, the last point repeating the first,
+// centered on the gear. the web runs from the hub, or a quarter of the way
+// out without one, to a rim two modules under the roots. spokes too wide or
+// many to leave any gap between them leave the web solid.
+pub fn cutouts(spokes: &Spokes, geometry: &GearGeometry, hub_diameter: f64) -> Vec<Vec<Point>> {
+    let module = geometry.outer_radius - geometry.pitch_radius;
+    let outer = geometry.root_radius - RIM_MODULES * module;
+    let inner = if hub_diameter > 0.0 {
+        hub_diameter / 2.0
+    } else {
+        geometry.root_radius * DEFAULT_HUB_SHARE
+    };
+    if spokes.count == 0 || inner <= 0.0 || outer <= inner || spokes.width < 0.0 {
+        return Vec::new();
+    }
+    let spacing = TAU / spokes.count as f64;
+
+    match spokes.style {
+        SpokeStyle::SolidWeb => Vec::new(),
+        SpokeStyle::Straight | SpokeStyle::Curved => {
+            // the gap is narrowest at the hub
+            let half_width = spokes.width / 2.0;
+            if half_width >= inner || 2.0 * (half_width / inner).asin() >= spacing {
+                return Vec::new();
+            }
+            let bend = if spokes.style == SpokeStyle::Curved {
+                spacing / 2.0
+            } else {
+                0.0
+            };
+            let web = Web {
+                inner,
+                outer,
+                half_width,
+                bend,
+                edge_steps: if bend == 0.0 { 1 } else { EDGE_STEPS },
+            };
+            // fillets too big for the windows are left off
+            (0..spokes.count)
+                .map(|spoke| {
+                    let start = spoke as f64 * spacing;
+                    web.window(start, spacing, spokes.fillet.max(0.0))
+                        .or_else(|| web.window(start, spacing, 0.0))
+                        .unwrap()
+                })
+                .collect()
+        }
+        SpokeStyle::Circles => {
+            let middle = (inner + outer) / 2.0;
+            let radius =
+                ((outer - inner) / 2.0).min(middle * (spacing / 2.0).sin() - spokes.width / 2.0);
+            if radius <= 0.0 {
+                return Vec::new();
+            }
+            (0..spokes.count)
+                .map(|hole| {
+                    let center = polar(middle, hole as f64 * spacing);
+                    (0..=CIRCLE_STEPS)
+                        .map(|i| {
+                            let angle = TAU * (i % CIRCLE_STEPS) as f64 / CIRCLE_STEPS as f64;
+                            Point {
+                                x: center.x + radius * angle.cos(),
+                                y: center.y + radius * angle.sin(),
+                            }
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{GearSpecs, Thinning, ToothProfile};
+
+    fn geometry() -> GearGeometry {
+        GearGeometry::new(&GearSpecs {
+            teeth: 60.0,
+            diametric_pitch: 12.0,
+            tooth_angle: 20.0,
+            clearance_mult: 0.167,
+            backlash_mult: 0.05,
+            involute_steps: 20,
+            profile: ToothProfile::Involute,
+            helix_angle: 0.0,
+            thinning: Thinning::Half,
+        })
+    }
+
+    #[test]
+    fn cutouts_stay_between_the_hub_and_rim() {
+        let geometry = geometry();
+        let module = 1.0 / 12.0;
+        let rim = geometry.root_radius - RIM_MODULES * module;
+        for style in [
+            SpokeStyle::Straight,
+            SpokeStyle::Curved,
+            SpokeStyle::Circles,
+        ] {
+            let spokes = Spokes {
+                style,
+                count: 6,
+                width: 0.3,
+                fillet: 0.1,
+            };
+            let cutouts = cutouts(&spokes, &geometry, 0.75);
+            assert_eq!(cutouts.len(), 6);
+            for cutout in &cutouts {
+                let (first, last) = (cutout[0], cutout[cutout.len() - 1]);
+                assert!((first.x - last.x).abs() < 1e-9 && (first.y - last.y).abs() < 1e-9);
+                for pt in cutout {
+                    let radius = (pt.x * pt.x + pt.y * pt.y).sqrt();
+                    assert!(radius >= 0.375 - 1e-9 && radius <= rim + 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn straight_spokes_keep_their_width() {
+        let spokes = Spokes {
+            style: SpokeStyle::Straight,
+            count: 4,
+            width: 0.3,
+            fillet: 0.1,
+        };
+        // the first window starts on the side of the spoke along the x axis
+        let cutouts = cutouts(&spokes, &geometry(), 0.75);
+        assert!(cutouts[0].iter().all(|pt| pt.y >= 0.15 - 1e-9));
+        assert!(cutouts[0].iter().any(|pt| (pt.y - 0.15).abs() < 1e-9));
+
+        // too wide to leave a gap at the hub
+        let solid = Spokes {
+            width: 0.6,
+            ..spokes
+        };
+        assert!(super::cutouts(&solid, &geometry(), 0.75).is_empty());
+        let web = Spokes {
+            style: SpokeStyle::SolidWeb,
+            ..spokes
+        };
+        assert!(super::cutouts(&web, &geometry(), 0.75).is_empty());
+    }
+
+    // the sharpest turn along a closed outline, in degrees
+    fn sharpest_turn(outline: &[Point]) -> f64 {
+        let points = &outline[..outline.len() - 1];
+        (0..points.len())
+            .map(|i| {
+                let (prev, pt, next) = (
+                    points[(i + points.len() - 1) % points.len()],
+                    points[i],
+                    points[(i + 1) % points.len()],
+                );
+                let turn = angle_of(next, pt) - angle_of(pt, prev);
+                ((turn + PI).rem_euclid(TAU) - PI).abs().to_degrees()
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn fillets_round_the_corners() {
+        for style in [SpokeStyle::Straight, SpokeStyle::Curved] {
+            let sharp = Spokes {
+                style,
+                count: 4,
+                width: 0.3,
+                fillet: 0.0,
+            };
+            let rounded = Spokes {
+                fillet: 0.1,
+                ..sharp
+            };
+            assert!(sharpest_turn(&cutouts(&sharp, &geometry(), 0.75)[0]) > 60.0);
+            assert!(sharpest_turn(&cutouts(&rounded, &geometry(), 0.75)[0]) < 20.0);
+
+            // too big to fit between the hub and rim leaves them sharp
+            let huge = Spokes {
+                fillet: 10.0,
+                ..sharp
+            };
+            assert_eq!(
+                cutouts(&huge, &geometry(), 0.75),
+                cutouts(&sharp, &geometry(), 0.75)
+            );
+        }
+    }
+}
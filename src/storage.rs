@@ -0,0 +1,184 @@
+use crate::watermark::Watermark;
+use crate::{default_gears, GearState};
+use serde::{Deserialize, Serialize};
+
+const AUTOSAVE_KEY: &str = "geargen.autosave";
+const RECENT_KEY: &str = "geargen.recent";
+const NAMED_KEY: &str = "geargen.designs";
+const WATERMARK_KEY: &str = "geargen.watermark";
+const SCREEN_PPI_KEY: &str = "geargen.screen_ppi";
+
+// how many recent designs are kept
+const RECENT_LIMIT: usize = 10;
+// edits closer together than this update the newest recent design instead of
+// adding another, so the list covers the last few hours rather than keystrokes
+const SNAPSHOT_INTERVAL_MS: f64 = 5.0 * 60.0 * 1000.0;
+
+// everything needed to put a design back on the page
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Design {
+    // empty until the user names it
+    #[serde(default)]
+    pub name: String,
+    pub gears: Vec<GearState>,
+    pub linked: bool,
+}
+
+impl Design {
+    // whether the design can go on the page. stored designs may be from an
+    // older version or edited by hand, so they're checked like any other.
+    pub fn is_usable(&self) -> bool {
+        !self.gears.is_empty() && self.gears.iter().all(|gear| gear.spec.is_valid())
+    }
+}
+
+// the built in pair, unnamed
+impl Default for Design {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            gears: default_gears(),
+            linked: true,
+        }
+    }
+}
+
+// a design as it was at `saved_at`, in milliseconds since the epoch.
+// `started_at` is when the entry was added, later edits update it in place
+// until it's older than the snapshot interval.
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub started_at: f64,
+    pub saved_at: f64,
+    pub design: Design,
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+// reads and parses a stored value, `None` if it's missing or doesn't parse
+fn read<T: for<'de> Deserialize<'de>>(key: &str) -> Option<T> {
+    let value = local_storage()?.get_item(key).ok()??;
+    serde_json::from_str(&value).ok()
+}
+
+// storage can be full or turned off, neither is worth interrupting the user for
+fn write<T: Serialize>(key: &str, value: &T) {
+    if let (Some(storage), Ok(value)) = (local_storage(), serde_json::to_string(value)) {
+        let _ = storage.set_item(key, &value);
+    }
+}
+
+// the design the page was left with, if there's a usable one
+pub fn load_autosave() -> Option<Design> {
+    read::<Design>(AUTOSAVE_KEY).filter(Design::is_usable)
+}
+
+// saves the design to come back to on reload, and records it in the recent
+// designs. `new_snapshot` starts a new recent entry even if the newest one is
+// still fresh, so loading an old design doesn't overwrite the one before it.
+pub fn autosave(design: &Design, new_snapshot: bool) {
+    write(AUTOSAVE_KEY, design);
+
+    let mut recent = recent_designs();
+    if recent.first().map(|snapshot| &snapshot.design) == Some(design) {
+        return;
+    }
+    let now = js_sys::Date::now();
+    match recent.first_mut() {
+        Some(newest) if !new_snapshot && now - newest.started_at < SNAPSHOT_INTERVAL_MS => {
+            newest.saved_at = now;
+            newest.design = design.clone();
+        }
+        _ => recent.insert(
+            0,
+            Snapshot {
+                started_at: now,
+                saved_at: now,
+                design: design.clone(),
+            },
+        ),
+    }
+    recent.truncate(RECENT_LIMIT);
+    write(RECENT_KEY, &recent);
+}
+
+// recent designs, newest first
+pub fn recent_designs() -> Vec<Snapshot> {
+    read::<Vec<Snapshot>>(RECENT_KEY)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|snapshot| snapshot.design.is_usable())
+        .collect()
+}
+
+// designs the user has saved by name, sorted by name
+pub fn named_designs() -> Vec<Design> {
+    let mut designs: Vec<Design> = read::<Vec<Design>>(NAMED_KEY)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(Design::is_usable)
+        .collect();
+    designs.sort_by(|a, b| a.name.cmp(&b.name));
+    designs
+}
+
+pub fn named_design(name: &str) -> Option<Design> {
+    named_designs()
+        .into_iter()
+        .find(|design| design.name == name)
+}
+
+// saves the design under its name, replacing any design already called that
+pub fn save_named(design: &Design) {
+    let mut designs = named_designs();
+    designs.retain(|named| named.name != design.name);
+    designs.push(design.clone());
+    write(NAMED_KEY, &designs);
+}
+
+// false if another design already has the new name
+pub fn rename_named(name: &str, new_name: &str) -> bool {
+    let mut designs = named_designs();
+    if designs.iter().any(|design| design.name == new_name) {
+        return false;
+    }
+    designs
+        .iter_mut()
+        .filter(|design| design.name == name)
+        .for_each(|design| design.name = new_name.to_string());
+    write(NAMED_KEY, &designs);
+    true
+}
+
+pub fn delete_named(name: &str) {
+    let mut designs = named_designs();
+    designs.retain(|design| design.name != name);
+    write(NAMED_KEY, &designs);
+}
+
+pub fn load_watermark() -> Watermark {
+    read(WATERMARK_KEY).unwrap_or_default()
+}
+
+pub fn save_watermark(watermark: &Watermark) {
+    write(WATERMARK_KEY, watermark);
+}
+
+// this screen's measured pixels per inch, which belongs to the device
+// rather than any design
+pub fn load_screen_ppi() -> Option<f64> {
+    read(SCREEN_PPI_KEY)
+}
+
+pub fn save_screen_ppi(ppi: Option<f64>) {
+    match ppi {
+        Some(ppi) => write(SCREEN_PPI_KEY, &ppi),
+        None => {
+            if let Some(storage) = local_storage() {
+                let _ = storage.remove_item(SCREEN_PPI_KEY);
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+// how a state takes an action. all its changes go through here, so
+// everything that reacts to them can hear about them in one place.
+pub trait Reduce<A> {
+    fn reduce(&mut self, action: &A);
+}
+
+// called with each action once it's been applied
+type Subscriber<A> = Rc<dyn Fn(&A)>;
+
+// the state, the one place actions change it and whoever wants to hear
+// about it. neither the state nor the subscribers are borrowed while
+// subscribers run, so they're free to borrow the state however they like
+// and to subscribe more, and actions dispatched from inside one wait until
+// the action in flight is done.
+pub struct Store<S, A> {
+    state: Rc<RefCell<S>>,
+    subscribers: RefCell<Vec<Subscriber<A>>>,
+    queue: RefCell<VecDeque<A>>,
+    dispatching: Cell<bool>,
+}
+
+impl<S: Reduce<A>, A> Store<S, A> {
+    // a store around state the rest of the page still reads directly, but
+    // only changes by dispatching
+    pub fn new(state: Rc<RefCell<S>>) -> Self {
+        Self {
+            state,
+            subscribers: RefCell::new(Vec::new()),
+            queue: RefCell::new(VecDeque::new()),
+            dispatching: Cell::new(false),
+        }
+    }
+
+    pub fn subscribe(&self, subscriber: impl Fn(&A) + 'static) {
+        self.subscribers.borrow_mut().push(Rc::new(subscriber));
+    }
+
+    // applies the action and tells every subscriber, then does the same for
+    // anything they dispatched in turn, in the order it came in
+    pub fn dispatch(&self, action: A) {
+        self.queue.borrow_mut().push_back(action);
+        if self.dispatching.replace(true) {
+            return;
+        }
+        loop {
+            let Some(action) = self.queue.borrow_mut().pop_front() else {
+                break;
+            };
+            self.state.borrow_mut().reduce(&action);
+            // subscribers added along the way hear from the next action on
+            let subscribers = self.subscribers.borrow().clone();
+            for subscriber in subscribers {
+                subscriber(&action);
+            }
+        }
+        self.dispatching.set(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        total: i32,
+        seen: Vec<i32>,
+    }
+
+    impl Reduce<i32> for Counter {
+        fn reduce(&mut self, action: &i32) {
+            self.total += action;
+            self.seen.push(*action);
+        }
+    }
+
+    #[test]
+    fn subscribers_dispatch_after_the_action_in_flight() {
+        let state = Rc::new(RefCell::new(Counter::default()));
+        let store = Rc::new(Store::new(state.clone()));
+        let totals = Rc::new(RefCell::new(Vec::new()));
+
+        // a subscriber that answers the first action with another before
+        // the other subscriber has heard about it
+        let follow_up = Rc::downgrade(&store);
+        store.subscribe(move |action: &i32| {
+            if *action == 1 {
+                follow_up.upgrade().unwrap().dispatch(10);
+            }
+        });
+        // and one that borrows the state for itself, mutably even
+        let seen_state = state.clone();
+        let seen_totals = totals.clone();
+        store.subscribe(move |_: &i32| {
+            let counter = seen_state.borrow_mut();
+            seen_totals.borrow_mut().push(counter.total);
+        });
+
+        store.dispatch(1);
+        store.dispatch(100);
+        assert_eq!(state.borrow().seen, vec![1, 10, 100]);
+        // every subscriber hears about each action, in order
+        assert_eq!(*totals.borrow(), vec![1, 11, 111]);
+    }
+
+    #[test]
+    fn subscribers_can_subscribe() {
+        let store = Rc::new(Store::new(Rc::new(RefCell::new(Counter::default()))));
+        let heard = Rc::new(RefCell::new(Vec::new()));
+
+        let inner_store = Rc::downgrade(&store);
+        let inner_heard = heard.clone();
+        store.subscribe(move |action: &i32| {
+            if *action == 1 {
+                let heard = inner_heard.clone();
+                inner_store
+                    .upgrade()
+                    .unwrap()
+                    .subscribe(move |action: &i32| heard.borrow_mut().push(*action));
+            }
+        });
+
+        store.dispatch(1);
+        store.dispatch(2);
+        assert_eq!(*heard.borrow(), vec![2]);
+    }
+}
@@ -0,0 +1,102 @@
+// opacity of a gear's fill, light enough that overlapping gears show through
+pub const FILL_ALPHA: f64 = 0.25;
+
+use serde::{Deserialize, Serialize};
+
+// how a gear is drawn
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct GearStyle {
+    // css hex color, as given by a color input
+    pub color: String,
+    pub fill: bool,
+}
+
+impl GearStyle {
+    // black outline with no fill, used for exports without colors
+    pub fn plain() -> Self {
+        Self {
+            color: "#000000".to_string(),
+            fill: false,
+        }
+    }
+
+    // red, green and blue parsed from the hex color, black if it doesn't parse
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        let channel = |i: usize| {
+            self.color
+                .get(i..i + 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        };
+        match (channel(1), channel(3), channel(5)) {
+            (Some(r), Some(g), Some(b)) if self.color.starts_with('#') => (r, g, b),
+            _ => (0, 0, 0),
+        }
+    }
+
+    // translucent css color for the fill, `None` when the gear isn't filled
+    pub fn fill_color(&self) -> Option<String> {
+        self.fill.then(|| {
+            let (r, g, b) = self.rgb();
+            format!("rgba({}, {}, {}, {})", r, g, b, FILL_ALPHA)
+        })
+    }
+}
+
+// the thinnest cut lines a high contrast print draws, in millimeters, thick
+// enough to follow with a scroll saw through glue and sawdust. engraved and
+// construction lines get half as much.
+const HIGH_CONTRAST_MM: f64 = 0.8;
+
+// how thick exported lines are for each kind of line, in millimeters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineWidths {
+    pub cut: f64,
+    pub engrave: f64,
+    pub construction: f64,
+}
+
+impl Default for LineWidths {
+    // thin enough for laser software to take as hairlines
+    fn default() -> Self {
+        Self {
+            cut: 0.1,
+            engrave: 0.1,
+            construction: 0.1,
+        }
+    }
+}
+
+impl LineWidths {
+    // no thinner than a high contrast print needs, thicker lines are kept
+    pub fn high_contrast(&self) -> Self {
+        Self {
+            cut: self.cut.max(HIGH_CONTRAST_MM),
+            engrave: self.engrave.max(HIGH_CONTRAST_MM / 2.0),
+            construction: self.construction.max(HIGH_CONTRAST_MM / 2.0),
+        }
+    }
+}
+
+// pixels across for a line `mm` millimeters thick
+pub fn line_pixels(mm: f64, ppi: f64) -> f64 {
+    mm / 25.4 * ppi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_contrast_only_thickens() {
+        let widths = LineWidths {
+            cut: 1.5,
+            engrave: 0.1,
+            construction: 0.2,
+        }
+        .high_contrast();
+        assert_eq!(widths.cut, 1.5);
+        assert_eq!(widths.engrave, 0.4);
+        assert_eq!(widths.construction, 0.4);
+        assert_eq!(line_pixels(25.4, 300.0), 300.0);
+    }
+}
@@ -0,0 +1,670 @@
+use crate::background::{self, BackgroundStyle};
+use crate::drill::Mark;
+use crate::geometry::{GearOutline, Point};
+use crate::paper::Bounds;
+use crate::spacers::Nest;
+use crate::style::{GearStyle, LineWidths};
+use crate::underlay::{Placement, Underlay};
+use crate::watermark;
+use std::cell::RefCell;
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const INKSCAPE_NAMESPACE: &str = "http://www.inkscape.org/namespaces/inkscape";
+// the tallest a gear's label gets, in inches
+const LABEL_HEIGHT: f64 = 0.15;
+
+// what exported svgs are measured in. the viewbox is in the same units as
+// the width and height, so one user unit is one of them and the file
+// imports at full size without rescaling.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum SvgUnits {
+    #[default]
+    Millimeters,
+    Inches,
+}
+
+impl SvgUnits {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "mm" => Some(SvgUnits::Millimeters),
+            "in" => Some(SvgUnits::Inches),
+            _ => None,
+        }
+    }
+
+    // also the suffix on the svg's width and height
+    pub fn value(&self) -> &'static str {
+        match self {
+            SvgUnits::Millimeters => "mm",
+            SvgUnits::Inches => "in",
+        }
+    }
+
+    fn per_inch(&self) -> f64 {
+        match self {
+            SvgUnits::Millimeters => 25.4,
+            SvgUnits::Inches => 1.0,
+        }
+    }
+}
+
+// the exported svg's layers, with the colors laser software usually takes
+// as cut, vector engrave and raster engrave. they're inkscape layers, so
+// they show up named in its layers panel and in anything that imports them.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Layer {
+    Cut,
+    Engrave,
+    Construction,
+    Annotations,
+}
+
+impl Layer {
+    fn id(&self) -> &'static str {
+        match self {
+            Layer::Cut => "cut",
+            Layer::Engrave => "engrave",
+            Layer::Construction => "construction",
+            Layer::Annotations => "annotations",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Layer::Cut => "Cut",
+            Layer::Engrave => "Engrave",
+            Layer::Construction => "Construction",
+            Layer::Annotations => "Annotations",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Layer::Cut => "#ff0000",
+            Layer::Engrave => "#0000ff",
+            Layer::Construction => "#00a000",
+            Layer::Annotations => "#000000",
+        }
+    }
+
+    // construction lines are only for lining things up, so that layer
+    // starts out hidden and laser software skips it
+    fn open(&self, out: &mut String, widths: &LineWidths, scale: f64) {
+        let display = if *self == Layer::Construction {
+            " style=\"display:none\""
+        } else {
+            ""
+        };
+        let width = match self {
+            Layer::Cut => widths.cut,
+            Layer::Engrave => widths.engrave,
+            Layer::Construction => widths.construction,
+            Layer::Annotations => 0.0,
+        };
+        let paint = if *self == Layer::Annotations {
+            format!("fill=\"{}\" stroke=\"none\"", self.color())
+        } else {
+            format!(
+                "fill=\"none\" stroke=\"{}\" stroke-width=\"{:.4}\"",
+                self.color(),
+                width / 25.4 * scale
+            )
+        };
+        writeln!(
+            out,
+            "<g id=\"{}\" inkscape:groupmode=\"layer\" inkscape:label=\"{}\"{} {} fill-rule=\"evenodd\">",
+            self.id(),
+            self.label(),
+            display,
+            paint
+        )
+        .unwrap();
+    }
+}
+
+// renders the scene as svg elements instead of canvas pixels, so it stays
+// crisp at any zoom and right-click -> save gives real vector output
+pub struct SvgRenderer {
+    svg: web_sys::Element,
+    background: web_sys::Element,
+    grid: web_sys::Element,
+    underlay: web_sys::Element,
+    gears: web_sys::Element,
+    gear_paths: RefCell<Vec<web_sys::Element>>,
+}
+
+impl SvgRenderer {
+    pub fn new(container: &web_sys::Element, before: &web_sys::Element) -> Result<Self, JsValue> {
+        let svg = create_svg_element("svg")?;
+        svg.set_attribute("xmlns", SVG_NAMESPACE)?;
+        svg.set_attribute(
+            "style",
+            "position: absolute; left: 0; top: 0; display: none;",
+        )?;
+        container.insert_before(&svg, Some(before))?;
+
+        let background = create_svg_element("rect")?;
+        background.set_attribute("width", "100%")?;
+        background.set_attribute("height", "100%")?;
+        background.set_attribute("fill", "white")?;
+        svg.append_child(&background)?;
+
+        // a path per color of the background's lines
+        let grid = create_svg_element("g")?;
+        grid.set_attribute("stroke-width", "1")?;
+        svg.append_child(&grid)?;
+
+        // a photo under the gears, hidden without one
+        let underlay = create_svg_element("image")?;
+        underlay.set_attribute("display", "none")?;
+        underlay.set_attribute("preserveAspectRatio", "none")?;
+        svg.append_child(&underlay)?;
+
+        // gears are drawn around the center of the view
+        let gears = create_svg_element("g")?;
+        svg.append_child(&gears)?;
+
+        Ok(Self {
+            svg,
+            background,
+            grid,
+            underlay,
+            gears,
+            gear_paths: RefCell::new(Vec::new()),
+        })
+    }
+
+    pub fn resize(&self, width: u32, height: u32, ppi: u32, style: BackgroundStyle) {
+        self.svg.set_attribute("width", &width.to_string()).unwrap();
+        self.svg
+            .set_attribute("height", &height.to_string())
+            .unwrap();
+        self.svg
+            .set_attribute("viewBox", &format!("0 0 {} {}", width, height))
+            .unwrap();
+        self.gears
+            .set_attribute(
+                "transform",
+                &format!("translate({} {})", width as f64 / 2.0, height as f64 / 2.0),
+            )
+            .unwrap();
+
+        // same background as the canvas, lined up on the center
+        self.background
+            .set_attribute("fill", style.fill().unwrap_or("none"))
+            .unwrap();
+        self.grid.set_inner_html("");
+        for rule in background::rules(style, width, height, ppi) {
+            let mut data = String::new();
+            for (from, to) in &rule.lines {
+                write!(
+                    data,
+                    "M{:.2} {:.2}L{:.2} {:.2} ",
+                    from.x, from.y, to.x, to.y
+                )
+                .unwrap();
+            }
+            let path = create_svg_element("path").unwrap();
+            path.set_attribute("stroke", rule.color).unwrap();
+            path.set_attribute("d", &data).unwrap();
+            self.grid.append_child(&path).unwrap();
+        }
+    }
+
+    // shows the photo where the canvas would draw it, or hides it
+    pub fn set_underlay(
+        &self,
+        underlay: Option<(&Underlay, &Placement)>,
+        width: u32,
+        height: u32,
+        ppi: u32,
+    ) {
+        let Some((underlay, placement, (photo_width, photo_height))) = underlay
+            .and_then(|(underlay, placement)| Some((underlay, placement, underlay.size()?)))
+        else {
+            self.underlay.set_attribute("display", "none").unwrap();
+            return;
+        };
+        let (left, top, photo_width, photo_height) =
+            placement.rect(photo_width, photo_height, ppi as f64);
+        [
+            ("href", underlay.source.clone()),
+            ("x", format!("{:.2}", width as f64 / 2.0 + left)),
+            ("y", format!("{:.2}", height as f64 / 2.0 + top)),
+            ("width", format!("{:.2}", photo_width)),
+            ("height", format!("{:.2}", photo_height)),
+            ("opacity", placement.opacity.clamp(0.0, 1.0).to_string()),
+            ("display", "inline".to_string()),
+        ]
+        .iter()
+        .for_each(|(name, value)| self.underlay.set_attribute(name, value).unwrap());
+    }
+
+    // replaces the path for one gear, leaving the others untouched
+    pub fn update_gear(&self, index: usize, gear: &GearOutline, style: &GearStyle, ppi: u32) {
+        let mut gear_paths = self.gear_paths.borrow_mut();
+        while gear_paths.len() <= index {
+            let path = create_svg_element("path").unwrap();
+            path.set_attribute("stroke-width", "1").unwrap();
+            // web cutouts are left unfilled
+            path.set_attribute("fill-rule", "evenodd").unwrap();
+            self.gears.append_child(&path).unwrap();
+            gear_paths.push(path);
+        }
+        let path = &gear_paths[index];
+        path.set_attribute("d", &gear_path_data(gear, ppi as f64, 3))
+            .unwrap();
+        path.set_attribute("stroke", &style.color).unwrap();
+        path.set_attribute("fill", &style.fill_color().unwrap_or("none".to_string()))
+            .unwrap();
+    }
+
+    // drops the paths past `count`, once those gears have been removed
+    pub fn truncate(&self, count: usize) {
+        let mut gear_paths = self.gear_paths.borrow_mut();
+        if gear_paths.len() > count {
+            gear_paths.drain(count..).for_each(|path| path.remove());
+        }
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        let display = if visible { "block" } else { "none" };
+        self.svg
+            .set_attribute(
+                "style",
+                &format!("position: absolute; left: 0; top: 0; display: {};", display),
+            )
+            .unwrap();
+    }
+}
+
+// closed svg path for a gear outline, scaled from inches and placed at its
+// center, with `decimals` places on each coordinate
+pub fn gear_path_data(gear: &GearOutline, scale: f64, decimals: usize) -> String {
+    let mut data = String::new();
+    let mut first = true;
+    gear.segments.iter().for_each(|segment| {
+        // segments share end points, so only the very first point starts the path
+        let skip = if first { 0 } else { 1 };
+        segment.points().iter().skip(skip).for_each(|pt| {
+            let command = if first { "M" } else { "L" };
+            first = false;
+            write!(
+                data,
+                "{}{:.*} {:.*}",
+                command,
+                decimals,
+                (gear.center.x + pt.x) * scale,
+                decimals,
+                (gear.center.y + pt.y) * scale
+            )
+            .unwrap();
+        });
+    });
+    data.push('Z');
+    gear.holes.iter().for_each(|hole| {
+        hole.iter().enumerate().for_each(|(i, pt)| {
+            let command = if i == 0 { "M" } else { "L" };
+            write!(
+                data,
+                "{}{:.*} {:.*}",
+                command,
+                decimals,
+                (gear.center.x + pt.x) * scale,
+                decimals,
+                (gear.center.y + pt.y) * scale
+            )
+            .unwrap();
+        });
+        data.push('Z');
+    });
+    data
+}
+
+// a standalone svg of the gears at full size, with any spacers and drill
+// marks, cropped to `bounds` in inches. cut lines, marking, pitch circles
+// and labels each get a layer of their own, like the dxf's layers.
+pub fn export_gears(
+    gears: &[GearOutline],
+    spacers: Option<&Nest>,
+    marks: &[Mark],
+    bounds: Bounds,
+    units: SvgUnits,
+    widths: LineWidths,
+    watermark: &watermark::Saved,
+) -> String {
+    let scale = units.per_inch();
+    let mut out = start_document(bounds, units);
+    Layer::Cut.open(&mut out, &widths, scale);
+    for gear in gears {
+        writeln!(out, "<path d=\"{}\"/>", gear_path_data(gear, scale, 4)).unwrap();
+    }
+    if let Some(spacers) = spacers {
+        for ring in &spacers.rings {
+            write_circle(&mut out, ring.center, ring.outer_radius, scale);
+            if ring.inner_radius > 0.0 {
+                write_circle(&mut out, ring.center, ring.inner_radius, scale);
+            }
+        }
+    }
+    out.push_str("</g>\n");
+
+    Layer::Engrave.open(&mut out, &widths, scale);
+    for mark in marks {
+        for (from, to) in mark.cross() {
+            write_line(&mut out, from, to, scale);
+        }
+        write_circle(&mut out, mark.center, mark.pilot_radius(), scale);
+    }
+    out.push_str("</g>\n");
+
+    // each gear's pitch circle and a cross on its center
+    Layer::Construction.open(&mut out, &widths, scale);
+    for gear in gears {
+        write_circle(&mut out, gear.center, gear.geometry.pitch_radius, scale);
+        let arm = gear.geometry.root_radius / 4.0;
+        for (dx, dy) in [(arm, 0.0), (0.0, arm)] {
+            write_line(
+                &mut out,
+                Point {
+                    x: gear.center.x - dx,
+                    y: gear.center.y - dy,
+                },
+                Point {
+                    x: gear.center.x + dx,
+                    y: gear.center.y + dy,
+                },
+                scale,
+            );
+        }
+    }
+    out.push_str("</g>\n");
+
+    // gears are numbered in the order they're listed in the sidebar, the
+    // label halfway between the center and the root circle
+    Layer::Annotations.open(&mut out, &widths, scale);
+    for (index, gear) in gears.iter().enumerate() {
+        let root = gear.geometry.root_radius;
+        write_text(
+            &mut out,
+            &format!("{}: {}T", index + 1, gear.geometry.teeth),
+            Point {
+                x: gear.center.x,
+                y: gear.center.y + root / 2.0,
+            },
+            (root / 4.0).min(LABEL_HEIGHT),
+            scale,
+        );
+    }
+    if let Some(spacers) = spacers {
+        let label = &spacers.label;
+        write_text(&mut out, &label.text, label.position, label.height, scale);
+    }
+    write_watermark(&mut out, watermark, bounds, scale);
+    out.push_str("</g>\n");
+    out.push_str("</svg>\n");
+    out
+}
+
+// the watermark in its corner of the document, placed as on printed pages
+fn write_watermark(out: &mut String, watermark: &watermark::Saved, bounds: Bounds, scale: f64) {
+    let page = (bounds.width() * scale, bounds.height() * scale);
+    let Some(layout) = watermark.layout(page, scale) else {
+        return;
+    };
+    let (left, top) = (bounds.min.x * scale, bounds.min.y * scale);
+    if let (Some(logo), Some(((x, y), (width, height)))) = (&watermark.watermark.logo, layout.logo)
+    {
+        writeln!(
+            out,
+            "<image x=\"{:.4}\" y=\"{:.4}\" width=\"{:.4}\" height=\"{:.4}\" href=\"{}\"/>",
+            left + x,
+            top + y,
+            width,
+            height,
+            escape(logo).replace('"', "&quot;")
+        )
+        .unwrap();
+    }
+    if let Some((x, y)) = layout.text {
+        writeln!(
+            out,
+            "<text x=\"{:.4}\" y=\"{:.4}\" font-size=\"{:.4}\" font-family=\"sans-serif\" fill=\"#808080\" text-anchor=\"{}\" dominant-baseline=\"middle\">{}</text>",
+            left + x,
+            top + y,
+            layout.text_height,
+            if layout.text_end { "end" } else { "start" },
+            escape(watermark.watermark.text.trim())
+        )
+        .unwrap();
+    }
+}
+
+// a standalone svg of one closed outline in inches, like a cam's profile
+pub fn export_outline(
+    outline: &[Point],
+    bounds: Bounds,
+    units: SvgUnits,
+    widths: LineWidths,
+) -> String {
+    let scale = units.per_inch();
+    let mut out = start_document(bounds, units);
+    let mut data = String::new();
+    for (i, pt) in outline.iter().enumerate() {
+        let command = if i == 0 { "M" } else { "L" };
+        write!(data, "{}{:.4} {:.4}", command, pt.x * scale, pt.y * scale).unwrap();
+    }
+    data.push('Z');
+    Layer::Cut.open(&mut out, &widths, scale);
+    writeln!(out, "<path d=\"{}\"/>\n</g>\n</svg>", data).unwrap();
+    out
+}
+
+// the svg element, sized in real units with a viewbox to match
+fn start_document(bounds: Bounds, units: SvgUnits) -> String {
+    let scale = units.per_inch();
+    let (width, height) = (bounds.width() * scale, bounds.height() * scale);
+    let mut out = String::new();
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+    writeln!(
+        out,
+        "<svg xmlns=\"{}\" xmlns:inkscape=\"{}\" width=\"{:.4}{}\" height=\"{:.4}{}\" viewBox=\"{:.4} {:.4} {:.4} {:.4}\">",
+        SVG_NAMESPACE,
+        INKSCAPE_NAMESPACE,
+        width,
+        units.value(),
+        height,
+        units.value(),
+        bounds.min.x * scale,
+        bounds.min.y * scale,
+        width,
+        height
+    )
+    .unwrap();
+    out
+}
+
+fn write_circle(out: &mut String, center: Point, radius: f64, scale: f64) {
+    writeln!(
+        out,
+        "<circle cx=\"{:.4}\" cy=\"{:.4}\" r=\"{:.4}\"/>",
+        center.x * scale,
+        center.y * scale,
+        radius * scale
+    )
+    .unwrap();
+}
+
+fn write_line(out: &mut String, from: Point, to: Point, scale: f64) {
+    writeln!(
+        out,
+        "<line x1=\"{:.4}\" y1=\"{:.4}\" x2=\"{:.4}\" y2=\"{:.4}\"/>",
+        from.x * scale,
+        from.y * scale,
+        to.x * scale,
+        to.y * scale
+    )
+    .unwrap();
+}
+
+// text centered on `position`, `height` inches tall
+fn write_text(out: &mut String, text: &str, position: Point, height: f64, scale: f64) {
+    writeln!(
+        out,
+        "<text x=\"{:.4}\" y=\"{:.4}\" font-size=\"{:.4}\" font-family=\"sans-serif\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>",
+        position.x * scale,
+        position.y * scale,
+        height * scale,
+        escape(text)
+    )
+    .unwrap();
+}
+
+// text safe to put between xml tags
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn create_svg_element(name: &str) -> Result<web_sys::Element, JsValue> {
+    web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element_ns(Some(SVG_NAMESPACE), name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{GearGeometry, GearSpecs, OutlineSegment, Thinning, ToothProfile};
+
+    fn gear() -> GearOutline {
+        GearOutline {
+            center: Point { x: 0.0, y: 0.0 },
+            segments: vec![OutlineSegment::Line(vec![
+                Point { x: -1.0, y: 0.0 },
+                Point { x: 1.0, y: 0.0 },
+                Point { x: 0.0, y: 1.0 },
+            ])],
+            holes: Vec::new(),
+            geometry: GearGeometry::new(&GearSpecs {
+                teeth: 30.0,
+                diametric_pitch: 12.0,
+                tooth_angle: 20.0,
+                clearance_mult: 0.167,
+                backlash_mult: 0.05,
+                involute_steps: 10,
+                profile: ToothProfile::Involute,
+                helix_angle: 0.0,
+                thinning: Thinning::Half,
+            }),
+        }
+    }
+
+    fn no_watermark() -> watermark::Saved {
+        watermark::Saved {
+            watermark: watermark::Watermark::default(),
+            logo_size: None,
+        }
+    }
+
+    #[test]
+    fn exports_are_sized_in_real_units() {
+        let gear = gear();
+        let bounds = Bounds {
+            min: Point { x: -1.0, y: -0.5 },
+            max: Point { x: 1.0, y: 1.0 },
+        };
+        let widths = LineWidths {
+            engrave: 0.25,
+            ..LineWidths::default()
+        };
+        let svg = export_gears(
+            &[gear],
+            None,
+            &[],
+            bounds,
+            SvgUnits::Millimeters,
+            widths,
+            &no_watermark(),
+        );
+        assert!(svg.contains(
+            "width=\"50.8000mm\" height=\"38.1000mm\" viewBox=\"-25.4000 -12.7000 50.8000 38.1000\""
+        ));
+        assert!(svg.contains("M-25.4000 0.0000L25.4000 0.0000L0.0000 25.4000Z"));
+        // every layer is there, named for inkscape, even with nothing on it
+        for layer in [
+            Layer::Cut,
+            Layer::Engrave,
+            Layer::Construction,
+            Layer::Annotations,
+        ] {
+            assert!(svg.contains(&format!(
+                "id=\"{}\" inkscape:groupmode=\"layer\" inkscape:label=\"{}\"",
+                layer.id(),
+                layer.label()
+            )));
+        }
+        assert!(
+            svg.contains("stroke=\"#0000ff\" stroke-width=\"0.2500\" fill-rule=\"evenodd\">\n</g>")
+        );
+        assert!(svg.contains(">1: 30T</text>"));
+
+        let svg = export_outline(
+            &[Point { x: 1.0, y: 0.0 }],
+            bounds,
+            SvgUnits::Inches,
+            widths,
+        );
+        assert!(svg.contains(
+            "width=\"2.0000in\" height=\"1.5000in\" viewBox=\"-1.0000 -0.5000 2.0000 1.5000\""
+        ));
+    }
+
+    #[test]
+    fn the_watermark_goes_in_its_corner() {
+        let bounds = Bounds {
+            min: Point { x: -1.0, y: -0.5 },
+            max: Point { x: 3.0, y: 2.5 },
+        };
+        let watermark = watermark::Saved {
+            watermark: watermark::Watermark {
+                text: "Shop & Co".to_string(),
+                logo: Some("data:image/png;base64,AAAA".to_string()),
+                corner: watermark::Corner::BottomRight,
+            },
+            logo_size: Some((200.0, 100.0)),
+        };
+        let svg = export_gears(
+            &[gear()],
+            None,
+            &[],
+            bounds,
+            SvgUnits::Inches,
+            LineWidths::default(),
+            &watermark,
+        );
+        // the logo fills its widest, a tenth of an inch in from the bottom
+        // right, and the text ends a tenth of an inch before it
+        assert!(svg.contains(
+            "<image x=\"1.4000\" y=\"1.6500\" width=\"1.5000\" height=\"0.7500\" href=\"data:image/png;base64,AAAA\"/>"
+        ));
+        assert!(svg.contains(
+            "<text x=\"1.3000\" y=\"2.0250\" font-size=\"0.1389\" font-family=\"sans-serif\" fill=\"#808080\" text-anchor=\"end\" dominant-baseline=\"middle\">Shop &amp; Co</text>"
+        ));
+        assert!(!export_gears(
+            &[gear()],
+            None,
+            &[],
+            bounds,
+            SvgUnits::Inches,
+            LineWidths::default(),
+            &no_watermark(),
+        )
+        .contains("<image"));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::storage::Design;
+
+// designs open side by side in the session. the page state holds the active
+// tab's design while it's being edited, so the methods that leave it take
+// the current design to put back in its slot.
+pub struct DesignTabs {
+    designs: Vec<Design>,
+    active: usize,
+}
+
+impl DesignTabs {
+    pub fn new(design: Design) -> Self {
+        Self {
+            designs: vec![design],
+            active: 0,
+        }
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    // what each tab is called, its design's name or its position if unnamed.
+    // `current` stands in for the active tab.
+    pub fn labels(&self, current: &Design) -> Vec<String> {
+        self.designs
+            .iter()
+            .enumerate()
+            .map(|(index, design)| {
+                let design = if index == self.active {
+                    current
+                } else {
+                    design
+                };
+                if design.name.is_empty() {
+                    format!("Design {}", index + 1)
+                } else {
+                    design.name.clone()
+                }
+            })
+            .collect()
+    }
+
+    // every tab's design, `current` standing in for the active tab
+    pub fn designs(&self, current: &Design) -> Vec<Design> {
+        let mut designs = self.designs.clone();
+        designs[self.active] = current.clone();
+        designs
+    }
+
+    // swaps in a whole new set of tabs, returning the active one's design to
+    // load. `None` leaves the tabs alone if there are no designs.
+    pub fn replace(&mut self, designs: Vec<Design>, active: usize) -> Option<Design> {
+        if designs.is_empty() {
+            return None;
+        }
+        self.active = active.min(designs.len() - 1);
+        self.designs = designs;
+        Some(self.designs[self.active].clone())
+    }
+
+    // moves to tab `index`, returning its design to load
+    pub fn switch(&mut self, current: Design, index: usize) -> Option<Design> {
+        if index >= self.designs.len() || index == self.active {
+            return None;
+        }
+        self.designs[self.active] = current;
+        self.active = index;
+        Some(self.designs[index].clone())
+    }
+
+    // opens a copy of the current design in a new tab after the active one
+    // and moves to it. the copy is already loaded, so there's nothing to
+    // return.
+    pub fn duplicate(&mut self, current: Design) {
+        self.designs[self.active] = current.clone();
+        self.active += 1;
+        self.designs.insert(self.active, current);
+    }
+
+    // closes tab `index`, returning the design to load if the active tab
+    // was the one closed. the last tab can't be closed.
+    pub fn close(&mut self, current: Design, index: usize) -> Option<Design> {
+        if self.designs.len() < 2 || index >= self.designs.len() {
+            return None;
+        }
+        self.designs[self.active] = current;
+        self.designs.remove(index);
+        if index < self.active {
+            self.active -= 1;
+            None
+        } else if index == self.active {
+            self.active = self.active.min(self.designs.len() - 1);
+            Some(self.designs[self.active].clone())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> Design {
+        Design {
+            name: name.to_string(),
+            ..Design::default()
+        }
+    }
+
+    #[test]
+    fn switching_keeps_edits_to_the_tab_being_left() {
+        let mut tabs = DesignTabs::new(named("a"));
+        tabs.duplicate(named("a"));
+        assert_eq!(tabs.active(), 1);
+        assert!(tabs.switch(named("edited"), 0) == Some(named("a")));
+        assert!(tabs.switch(named("a"), 1) == Some(named("edited")));
+        assert!(tabs.switch(named("edited"), 1).is_none());
+    }
+
+    #[test]
+    fn closing_the_active_tab_loads_its_neighbour() {
+        let mut tabs = DesignTabs::new(named("a"));
+        tabs.duplicate(named("a"));
+        tabs.duplicate(named("b"));
+        assert!(tabs.close(named("c"), 2) == Some(named("b")));
+        assert_eq!(tabs.active(), 1);
+        assert!(tabs.close(named("b"), 0).is_none());
+        assert_eq!(tabs.active(), 0);
+        assert!(tabs.close(named("b"), 0).is_none());
+        assert_eq!(tabs.labels(&named("")), vec!["Design 1"]);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::geometry::{OutlineSegment, Point};
+use wasm_bindgen::JsValue;
+
+// how far a corner can be pushed along its bisector, in offset distances,
+// so sharp tips don't throw out long spikes
+const MITER_LIMIT: f64 = 4.0;
+
+// the least and most backlash a mesh can have once both gears are cut
+// anywhere in their tolerance band, in inches along the pitch circle. a
+// negative `tightest` means the gears bind at maximum material.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Band {
+    pub tightest: f64,
+    pub loosest: f64,
+}
+
+// the band around a mesh with `backlash` inches of backlash as drawn, when
+// every flank may be cut up to `tolerance` inches over or under size.
+// `pressure_angle` is in degrees, in the plane of the outline.
+pub fn mesh_band(backlash: f64, tolerance: f64, pressure_angle: f64) -> Band {
+    // each gear's flank moves square to itself, along the line of action,
+    // which is leaning over from the pitch circle by the pressure angle
+    let change = 2.0 * tolerance / pressure_angle.to_radians().cos();
+    Band {
+        tightest: backlash - change,
+        loosest: backlash + change,
+    }
+}
+
+// a gear's segments as one closed loop, ending where it starts
+pub fn outline_points(segments: &[OutlineSegment]) -> Vec<Point> {
+    let mut points: Vec<Point> = segments
+        .first()
+        .and_then(|segment| segment.points().first())
+        .copied()
+        .into_iter()
+        .collect();
+    for segment in segments {
+        points.extend(segment.points().iter().skip(1));
+    }
+    points
+}
+
+// a closed loop of points grown by `distance`, or shrunk by a negative one,
+// moving each corner out along its bisector. good for distances small next
+// to the loop's features, like a machining tolerance around a tooth.
+pub fn offset(points: &[Point], distance: f64) -> Vec<Point> {
+    let mut corners: Vec<Point> = Vec::with_capacity(points.len());
+    for point in points {
+        if corners.last() != Some(point) {
+            corners.push(*point);
+        }
+    }
+    if corners.len() > 1 && corners.first() == corners.last() {
+        corners.pop();
+    }
+    let count = corners.len();
+    if count < 3 {
+        return points.to_vec();
+    }
+
+    // the outward side of each edge depends on which way the loop winds
+    let area: f64 = (0..count)
+        .map(|i| {
+            let (a, b) = (corners[i], corners[(i + 1) % count]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+    let outward = if area > 0.0 { 1.0 } else { -1.0 };
+    let normal = |i: usize| {
+        let (a, b) = (corners[i], corners[(i + 1) % count]);
+        let length = (b.x - a.x).hypot(b.y - a.y);
+        Point {
+            x: outward * (b.y - a.y) / length,
+            y: -outward * (b.x - a.x) / length,
+        }
+    };
+
+    let mut offset: Vec<Point> = (0..count)
+        .map(|i| {
+            let (before, after) = (normal((i + count - 1) % count), normal(i));
+            let (x, y) = (before.x + after.x, before.y + after.y);
+            let length = x.hypot(y);
+            if length < 1e-12 {
+                // the loop doubles straight back on itself here
+                return Point {
+                    x: corners[i].x + before.x * distance,
+                    y: corners[i].y + before.y * distance,
+                };
+            }
+            let (x, y) = (x / length, y / length);
+            // a corner moves further than the edges beside it to keep them
+            // `distance` out
+            let cos = x * after.x + y * after.y;
+            let reach = distance / cos.max(1.0 / MITER_LIMIT);
+            Point {
+                x: corners[i].x + x * reach,
+                y: corners[i].y + y * reach,
+            }
+        })
+        .collect();
+    offset.push(offset[0]);
+    offset
+}
+
+// a gear's maximum and minimum material outlines, dashed in its color, on a
+// context already centered on the view
+pub fn draw(
+    context: &web_sys::CanvasRenderingContext2d,
+    outline: &[Point],
+    center: Point,
+    tolerance: f64,
+    scale: f64,
+    color: &str,
+) {
+    context.set_stroke_style_str(color);
+    context.set_line_width(1.0);
+    context
+        .set_line_dash(&JsValue::from(vec![4.0, 3.0]))
+        .unwrap();
+    context.begin_path();
+    for distance in [tolerance, -tolerance] {
+        for (i, pt) in offset(outline, distance).iter().enumerate() {
+            let (x, y) = ((center.x + pt.x) * scale, (center.y + pt.y) * scale);
+            if i == 0 {
+                context.move_to(x, y);
+            } else {
+                context.line_to(x, y);
+            }
+        }
+    }
+    context.stroke();
+    context
+        .set_line_dash(&JsValue::from(Vec::<f64>::new()))
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_grow_and_shrink_either_winding() {
+        let square = vec![
+            Point { x: -1.0, y: -1.0 },
+            Point { x: 1.0, y: -1.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: -1.0, y: 1.0 },
+            Point { x: -1.0, y: -1.0 },
+        ];
+        let reversed: Vec<Point> = square.iter().rev().copied().collect();
+        for loop_points in [&square, &reversed] {
+            let grown = offset(loop_points, 0.5);
+            assert_eq!(grown.len(), 5);
+            assert_eq!(grown.first(), grown.last());
+            for pt in &grown {
+                assert!((pt.x.abs() - 1.5).abs() < 1e-12 && (pt.y.abs() - 1.5).abs() < 1e-12);
+            }
+            for pt in offset(loop_points, -0.25) {
+                assert!((pt.x.abs() - 0.75).abs() < 1e-12 && (pt.y.abs() - 0.75).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn tolerance_eats_into_the_backlash() {
+        let band = mesh_band(0.01, 0.002, 0.0);
+        assert!((band.tightest - 0.006).abs() < 1e-12);
+        assert!((band.loosest - 0.014).abs() < 1e-12);
+        // the flanks lean over, so they close more of the pitch circle gap
+        assert!(mesh_band(0.01, 0.002, 20.0).tightest < band.tightest);
+    }
+}
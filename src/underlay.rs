@@ -0,0 +1,162 @@
+use crate::geometry::Point;
+use web_sys::HtmlImageElement;
+
+// photo pixels per inch until it's calibrated, the same as the screen's so
+// it starts at its own size
+const UNCALIBRATED_PIXELS_PER_INCH: f64 = 96.0;
+// half the width of the crosshairs marking picked points, in screen pixels
+const PICK_SIZE: f64 = 6.0;
+const PICK_COLOR: &str = "#e07000";
+
+// a photo of a gear shown under the generated one, to check a replacement
+// against it before cutting
+#[derive(Clone)]
+pub struct Underlay {
+    pub image: HtmlImageElement,
+    // the photo as a data url, for the svg renderer
+    pub source: String,
+}
+
+impl Underlay {
+    // the photo's size in its own pixels, `None` until the browser has
+    // decoded it
+    pub fn size(&self) -> Option<(f64, f64)> {
+        (self.image.complete() && self.image.natural_width() > 0).then(|| {
+            (
+                self.image.natural_width() as f64,
+                self.image.natural_height() as f64,
+            )
+        })
+    }
+}
+
+// how big the photo is drawn and where: `pixels_per_inch` of the photo
+// make an inch, and its center sits `offset` inches from the view's
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    pub pixels_per_inch: f64,
+    pub offset: Point,
+    pub opacity: f64,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            pixels_per_inch: UNCALIBRATED_PIXELS_PER_INCH,
+            offset: Point { x: 0.0, y: 0.0 },
+            opacity: 0.5,
+        }
+    }
+}
+
+impl Placement {
+    // the photo's left, top, width and height in screen pixels from the
+    // view's center, for a `width` by `height` pixel photo
+    pub fn rect(&self, width: f64, height: f64, ppi: f64) -> (f64, f64, f64, f64) {
+        let scale = ppi / self.pixels_per_inch;
+        let (width, height) = (width * scale, height * scale);
+        (
+            self.offset.x * ppi - width / 2.0,
+            self.offset.y * ppi - height / 2.0,
+            width,
+            height,
+        )
+    }
+
+    // rescales the photo so the points `first` and `second`, picked on the
+    // workspace in inches, come out `distance` inches apart. the photo
+    // grows or shrinks about `first`, so it stays under the pointer.
+    pub fn calibrated(&self, first: Point, second: Point, distance: f64) -> Result<Self, String> {
+        let picked = ((second.x - first.x).powi(2) + (second.y - first.y).powi(2)).sqrt();
+        if distance <= 0.0 {
+            return Err("the known distance must be more than zero".to_string());
+        }
+        if picked == 0.0 {
+            return Err("the two points are the same".to_string());
+        }
+        let grow = distance / picked;
+        Ok(Self {
+            pixels_per_inch: self.pixels_per_inch / grow,
+            offset: Point {
+                x: first.x + (self.offset.x - first.x) * grow,
+                y: first.y + (self.offset.y - first.y) * grow,
+            },
+            ..*self
+        })
+    }
+}
+
+// draws the photo on a context already centered on the view, see-through
+// by the placement's opacity
+pub fn draw(
+    context: &web_sys::CanvasRenderingContext2d,
+    underlay: &Underlay,
+    placement: &Placement,
+    ppi: f64,
+) {
+    let Some((width, height)) = underlay.size() else {
+        return;
+    };
+    let (left, top, width, height) = placement.rect(width, height, ppi);
+    context.save();
+    context.set_global_alpha(placement.opacity.clamp(0.0, 1.0));
+    context
+        .draw_image_with_html_image_element_and_dw_and_dh(&underlay.image, left, top, width, height)
+        .unwrap();
+    context.restore();
+}
+
+// marks the points picked so far while calibrating, on a context already
+// centered on the view
+pub fn draw_picks(context: &web_sys::CanvasRenderingContext2d, picks: &[Point], ppi: f64) {
+    context.set_stroke_style_str(PICK_COLOR);
+    context.set_line_width(1.5);
+    context.begin_path();
+    for pick in picks {
+        let (x, y) = (pick.x * ppi, pick.y * ppi);
+        context.move_to(x - PICK_SIZE, y);
+        context.line_to(x + PICK_SIZE, y);
+        context.move_to(x, y - PICK_SIZE);
+        context.line_to(x, y + PICK_SIZE);
+    }
+    context.stroke();
+    if let [first, second] = picks {
+        context.begin_path();
+        context.move_to(first.x * ppi, first.y * ppi);
+        context.line_to(second.x * ppi, second.y * ppi);
+        context.stroke();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibrating_keeps_the_first_point_still() {
+        let placement = Placement {
+            offset: Point { x: 1.0, y: -0.5 },
+            ..Placement::default()
+        };
+        let first = Point { x: -1.0, y: 0.5 };
+        let second = Point { x: 1.0, y: 0.5 };
+        // the points are two inches apart on screen but really one
+        let calibrated = placement.calibrated(first, second, 1.0).unwrap();
+        assert_eq!(calibrated.pixels_per_inch, 192.0);
+        assert_eq!(calibrated.offset, Point { x: 0.0, y: 0.0 });
+
+        // the same photo pixel is under the first point before and after
+        let photo_pixel = |placement: &Placement| {
+            let (left, top, _, _) = placement.rect(400.0, 300.0, 96.0);
+            let scale = placement.pixels_per_inch / 96.0;
+            (
+                (first.x * 96.0 - left) * scale,
+                (first.y * 96.0 - top) * scale,
+            )
+        };
+        assert_eq!(photo_pixel(&placement), photo_pixel(&calibrated));
+
+        assert!(placement.calibrated(first, first, 1.0).is_err());
+        assert!(placement.calibrated(first, second, 0.0).is_err());
+    }
+}
@@ -0,0 +1,363 @@
+use crate::geometry::{inside, GearGeometry, GearSpecs, Point, ToothProfile};
+use std::f64;
+
+// the narrowest top land before a tooth counts as pointed, in modules
+const MIN_TIP_LAND_MULT: f64 = 0.2;
+// how far a cutout can stray onto an edge it was meant to stop at, like
+// spoke windows drawn right to the hub, in inches
+const TOUCHING: f64 = 1e-6;
+
+// something wrong with the train, and the gears it's about, indexed from 0
+#[derive(Debug, PartialEq)]
+pub struct Warning {
+    pub gears: Vec<usize>,
+    pub message: String,
+}
+
+// every problem with the train. `gears` pairs each gear's specs with whether
+// it shares the previous gear's arbor instead of meshing with it.
+pub fn check_train(gears: &[(&GearSpecs, bool)]) -> Vec<Warning> {
+    let geometries: Vec<GearGeometry> = gears
+        .iter()
+        .map(|(spec, _)| GearGeometry::new(spec))
+        .collect();
+    let mut warnings = Vec::new();
+
+    gears.iter().enumerate().for_each(|(index, (spec, _))| {
+        if let ToothProfile::Custom { formula } = &spec.profile {
+            let geometry = &geometries[index];
+            // rounding lets a formula like `pitch + module` land just past the tip
+            let slack = 1e-9 * geometry.outer_radius;
+            let fits = geometry.custom_radii(formula).is_some_and(|radii| {
+                radii.iter().all(|&(_, radius)| {
+                    radius >= geometry.root_radius - slack
+                        && radius <= geometry.outer_radius + slack
+                })
+            });
+            if !fits {
+                warnings.push(Warning {
+                    gears: vec![index],
+                    message: format!(
+                        "Gear {}'s formula leaves its root and tip circles",
+                        index + 1
+                    ),
+                });
+            }
+        }
+        if spec.profile != ToothProfile::Involute {
+            return;
+        }
+        let min_teeth = min_teeth_without_undercut(spec.tooth_angle);
+        if spec.teeth < min_teeth {
+            warnings.push(Warning {
+                gears: vec![index],
+                message: format!(
+                    "Gear {} is undercut, {} teeth needs at least {} at {}\u{b0}",
+                    index + 1,
+                    spec.teeth,
+                    min_teeth.ceil(),
+                    spec.tooth_angle
+                ),
+            });
+        }
+        let geometry = &geometries[index];
+        let module = geometry.outer_radius - geometry.pitch_radius;
+        if tip_land(geometry) < MIN_TIP_LAND_MULT * module {
+            warnings.push(Warning {
+                gears: vec![index],
+                message: format!("Gear {} has pointed teeth", index + 1),
+            });
+        }
+    });
+
+    (1..gears.len())
+        .filter(|&index| !gears[index].1)
+        .for_each(|index| {
+            let (a, b) = (gears[index - 1].0, gears[index].0);
+            let gear_pair = vec![index - 1, index];
+            if a.diametric_pitch != b.diametric_pitch || a.tooth_angle != b.tooth_angle {
+                warnings.push(Warning {
+                    gears: gear_pair,
+                    message: format!(
+                        "Gears {} and {} have mismatched pitch or pressure angle",
+                        index,
+                        index + 1
+                    ),
+                });
+                return;
+            }
+            if a.profile != ToothProfile::Involute || b.profile != ToothProfile::Involute {
+                return;
+            }
+            let (geometry_a, geometry_b) = (&geometries[index - 1], &geometries[index]);
+            if interferes(geometry_a, geometry_b) || interferes(geometry_b, geometry_a) {
+                warnings.push(Warning {
+                    gears: gear_pair,
+                    message: format!(
+                        "Gears {} and {} interfere, a tip digs into the other's flank",
+                        index,
+                        index + 1
+                    ),
+                });
+            }
+        });
+
+    warnings
+}
+
+// something cut out of a gear's blank, the loops it's made of centered on
+// the gear. the loops of one feature can sit inside each other, like the
+// counter of a letter in a logo.
+pub struct Feature {
+    pub name: String,
+    pub loops: Vec<Vec<Point>>,
+}
+
+// a warning about gear features running into each other, with the loops to
+// point out on the gear
+pub struct Collision {
+    pub warning: Warning,
+    pub loops: Vec<Vec<Point>>,
+}
+
+// everything in gear `index`'s blank that runs into its teeth, its hub or
+// another feature. a `hub_diameter` of zero is no hub.
+pub fn check_blank(
+    index: usize,
+    geometry: &GearGeometry,
+    hub_diameter: f64,
+    features: &[Feature],
+) -> Vec<Collision> {
+    let mut collisions = Vec::new();
+    let mut warn = |message: String, loops: Vec<Vec<Point>>| {
+        collisions.push(Collision {
+            warning: Warning {
+                gears: vec![index],
+                message: format!("Gear {}'s {}", index + 1, message),
+            },
+            loops,
+        })
+    };
+    let hub_radius = hub_diameter / 2.0;
+    if hub_radius >= geometry.root_radius {
+        warn("hub is wider than its root circle".to_string(), Vec::new());
+    }
+    let radius = |pt: &Point| pt.x.hypot(pt.y);
+    for feature in features {
+        let points = || feature.loops.iter().flatten();
+        if points().any(|pt| radius(pt) > geometry.root_radius + TOUCHING) {
+            warn(
+                format!("{} cuts into its teeth", feature.name),
+                feature.loops.clone(),
+            );
+        }
+        if hub_radius > 0.0 && points().any(|pt| radius(pt) < hub_radius - TOUCHING) {
+            warn(
+                format!("{} cuts into its hub", feature.name),
+                feature.loops.clone(),
+            );
+        }
+    }
+    for (first, a) in features.iter().enumerate() {
+        for b in &features[first + 1..] {
+            let overlapping = a
+                .loops
+                .iter()
+                .any(|loop_a| b.loops.iter().any(|loop_b| loops_overlap(loop_a, loop_b)));
+            if overlapping {
+                warn(
+                    format!("{} and {} overlap", a.name, b.name),
+                    a.loops.iter().chain(&b.loops).cloned().collect(),
+                );
+            }
+        }
+    }
+    collisions
+}
+
+// whether two closed loops cross, or one sits inside the other
+fn loops_overlap(a: &[Point], b: &[Point]) -> bool {
+    let (Some(box_a), Some(box_b)) = (bounding_box(a), bounding_box(b)) else {
+        return false;
+    };
+    if box_a.1.x < box_b.0.x
+        || box_b.1.x < box_a.0.x
+        || box_a.1.y < box_b.0.y
+        || box_b.1.y < box_a.0.y
+    {
+        return false;
+    }
+    let crosses = a.windows(2).any(|edge_a| {
+        b.windows(2)
+            .any(|edge_b| segments_cross(edge_a[0], edge_a[1], edge_b[0], edge_b[1]))
+    });
+    crosses || inside(&a[0], b) || inside(&b[0], a)
+}
+
+fn bounding_box(points: &[Point]) -> Option<(Point, Point)> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), pt| {
+        (
+            Point {
+                x: min.x.min(pt.x),
+                y: min.y.min(pt.y),
+            },
+            Point {
+                x: max.x.max(pt.x),
+                y: max.y.max(pt.y),
+            },
+        )
+    }))
+}
+
+// whether segments `a`-`b` and `c`-`d` cross each other, strictly
+fn segments_cross(a: Point, b: Point, c: Point, d: Point) -> bool {
+    let side = |from: Point, to: Point, pt: Point| {
+        (to.x - from.x) * (pt.y - from.y) - (to.y - from.y) * (pt.x - from.x)
+    };
+    let (c_side, d_side) = (side(a, b, c), side(a, b, d));
+    let (a_side, b_side) = (side(c, d, a), side(c, d, b));
+    c_side * d_side < 0.0 && a_side * b_side < 0.0
+}
+
+// fewest teeth a full depth involute gear can have before the generating
+// rack cuts away the bottom of its flanks
+fn min_teeth_without_undercut(pressure_angle: f64) -> f64 {
+    2.0 / pressure_angle.to_radians().sin().powi(2)
+}
+
+// width of the flat across each tooth tip, negative once the flanks cross
+// below the tip circle
+fn tip_land(geometry: &GearGeometry) -> f64 {
+    let involute_function = |radius: f64| {
+        let angle = (geometry.base_radius / radius).acos();
+        angle.tan() - angle
+    };
+    let pitch_thickness =
+        f64::consts::PI * geometry.pitch_radius / geometry.teeth - geometry.backlash_allowance;
+    let half_angle = pitch_thickness / (2.0 * geometry.pitch_radius)
+        + involute_function(geometry.pitch_radius)
+        - involute_function(geometry.outer_radius);
+    2.0 * geometry.outer_radius * half_angle
+}
+
+// whether `driver`'s tips reach past where the line of action leaves
+// `driven`'s base circle, into flank that isn't involute
+fn interferes(driver: &GearGeometry, driven: &GearGeometry) -> bool {
+    let center_distance = driver.pitch_radius + driven.pitch_radius;
+    let pressure_angle = (driver.base_radius / driver.pitch_radius).acos();
+    let line_of_action = center_distance * pressure_angle.sin();
+    let max_tip_radius = f64::sqrt(driver.base_radius.powi(2) + line_of_action.powi(2));
+    driver.outer_radius > max_tip_radius
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Thinning;
+
+    fn spec(teeth: f64) -> GearSpecs {
+        GearSpecs {
+            teeth,
+            diametric_pitch: 12.0,
+            tooth_angle: 20.0,
+            clearance_mult: 0.167,
+            backlash_mult: 0.05,
+            involute_steps: 100,
+            profile: ToothProfile::Involute,
+            helix_angle: 0.0,
+            thinning: Thinning::Half,
+        }
+    }
+
+    #[test]
+    fn the_default_pair_only_warns_about_the_pinion() {
+        let (wheel, pinion) = (spec(50.0), spec(10.0));
+        let warnings = check_train(&[(&wheel, false), (&pinion, false)]);
+        assert!(!warnings.is_empty());
+        assert!(warnings.iter().all(|warning| warning.gears.contains(&1)));
+    }
+
+    #[test]
+    fn large_gears_mesh_cleanly() {
+        let (a, b) = (spec(40.0), spec(60.0));
+        assert_eq!(check_train(&[(&a, false), (&b, false)]), vec![]);
+    }
+
+    #[test]
+    fn the_default_custom_formula_stays_inside_the_gear() {
+        let custom = GearSpecs {
+            profile: ToothProfile::Custom {
+                formula: crate::geometry::DEFAULT_CUSTOM_FORMULA.to_string(),
+            },
+            ..spec(30.0)
+        };
+        assert_eq!(check_train(&[(&custom, false)]), vec![]);
+        let too_deep = GearSpecs {
+            profile: ToothProfile::Custom {
+                formula: "pitch + 2 * module * cos(teeth * theta)".to_string(),
+            },
+            ..spec(30.0)
+        };
+        assert_eq!(check_train(&[(&too_deep, false)]).len(), 1);
+    }
+
+    #[test]
+    fn mismatched_pitch_is_only_checked_across_a_mesh() {
+        let a = spec(40.0);
+        let b = GearSpecs {
+            diametric_pitch: 24.0,
+            ..spec(40.0)
+        };
+        assert_eq!(check_train(&[(&a, false), (&b, false)]).len(), 1);
+        assert_eq!(check_train(&[(&a, false), (&b, true)]), vec![]);
+    }
+
+    #[test]
+    fn cutouts_are_checked_against_the_teeth_hub_and_each_other() {
+        let geometry = GearGeometry::new(&spec(40.0));
+        let square = |x: f64, half: f64| Feature {
+            name: format!("square at {}", x),
+            loops: vec![vec![
+                Point {
+                    x: x - half,
+                    y: -half,
+                },
+                Point {
+                    x: x + half,
+                    y: -half,
+                },
+                Point {
+                    x: x + half,
+                    y: half,
+                },
+                Point {
+                    x: x - half,
+                    y: half,
+                },
+                Point {
+                    x: x - half,
+                    y: -half,
+                },
+            ]],
+        };
+        // root radius is about 1.55 inches
+        assert!(check_blank(0, &geometry, 0.5, &[square(1.0, 0.2)]).is_empty());
+        let teeth = check_blank(0, &geometry, 0.0, &[square(1.5, 0.2)]);
+        assert_eq!(teeth.len(), 1);
+        assert_eq!(
+            teeth[0].warning.message,
+            "Gear 1's square at 1.5 cuts into its teeth"
+        );
+        assert_eq!(check_blank(0, &geometry, 1.0, &[square(0.6, 0.2)]).len(), 1);
+        // one inside the other counts as well as crossing edges
+        let overlaps = check_blank(1, &geometry, 0.0, &[square(1.0, 0.3), square(1.0, 0.1)]);
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].loops.len(), 2);
+        assert_eq!(
+            check_blank(0, &geometry, 0.0, &[square(0.9, 0.2), square(1.2, 0.2)]).len(),
+            1
+        );
+        assert_eq!(check_blank(0, &geometry, 4.0, &[]).len(), 1);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use web_sys::{HtmlImageElement, HtmlInputElement, HtmlSelectElement};
+
+// the largest a logo is printed, in inches. it's scaled down to fit both.
+const LOGO_MAX_WIDTH: f64 = 1.5;
+const LOGO_MAX_HEIGHT: f64 = 0.75;
+// text is printed at 10pt
+const TEXT_SIZE: f64 = 10.0 / 72.0;
+// how far the watermark sits in from the edge of the printed area, in inches
+const INSET: f64 = 0.1;
+
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "top_left" => Some(Corner::TopLeft),
+            "top_right" => Some(Corner::TopRight),
+            "bottom_left" => Some(Corner::BottomLeft),
+            "bottom_right" => Some(Corner::BottomRight),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            Corner::TopLeft => "top_left",
+            Corner::TopRight => "top_right",
+            Corner::BottomLeft => "bottom_left",
+            Corner::BottomRight => "bottom_right",
+        }
+    }
+
+    // top left of a `size` box in this corner of a `page` sized area, `inset`
+    // in from its edges
+    fn place(&self, page: (f64, f64), size: (f64, f64), inset: f64) -> (f64, f64) {
+        let left = inset;
+        let right = page.0 - inset - size.0;
+        let top = inset;
+        let bottom = page.1 - inset - size.1;
+        match self {
+            Corner::TopLeft => (left, top),
+            Corner::TopRight => (right, top),
+            Corner::BottomLeft => (left, bottom),
+            Corner::BottomRight => (right, bottom),
+        }
+    }
+}
+
+// branding in a corner of pdf and svg exports. it belongs to whoever is
+// printing rather than to any one design, so it's kept apart from designs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Watermark {
+    pub text: String,
+    // the uploaded logo as a data url
+    pub logo: Option<String>,
+    pub corner: Corner,
+}
+
+struct LoadedWatermark {
+    watermark: Watermark,
+    // the logo decoded and ready to draw, once the browser has loaded it
+    logo_image: Option<HtmlImageElement>,
+}
+
+thread_local! {
+    static WATERMARK: RefCell<LoadedWatermark> = RefCell::new(load());
+}
+
+fn load() -> LoadedWatermark {
+    let watermark = storage::load_watermark();
+    let logo_image = watermark.logo.as_deref().and_then(decode_logo);
+    LoadedWatermark {
+        watermark,
+        logo_image,
+    }
+}
+
+fn decode_logo(data_url: &str) -> Option<HtmlImageElement> {
+    let image = HtmlImageElement::new().ok()?;
+    image.set_src(data_url);
+    Some(image)
+}
+
+fn update(f: impl FnOnce(&mut Watermark)) {
+    WATERMARK.with(|loaded| {
+        let mut loaded = loaded.borrow_mut();
+        f(&mut loaded.watermark);
+        loaded.logo_image = loaded.watermark.logo.as_deref().and_then(decode_logo);
+        storage::save_watermark(&loaded.watermark);
+    });
+}
+
+// the watermark as saved, and its logo's size in pixels once the browser
+// has decoded it, for exports that aren't drawn on a canvas
+pub struct Saved {
+    pub watermark: Watermark,
+    pub logo_size: Option<(f64, f64)>,
+}
+
+pub fn saved() -> Saved {
+    WATERMARK.with(|loaded| {
+        let loaded = loaded.borrow();
+        Saved {
+            watermark: loaded.watermark.clone(),
+            logo_size: loaded.logo_image.as_ref().and_then(logo_size),
+        }
+    })
+}
+
+impl Saved {
+    pub fn layout(&self, page: (f64, f64), inch: f64) -> Option<Layout> {
+        layout(&self.watermark, self.logo_size, page, inch)
+    }
+}
+
+// where the watermark goes on a page, in the page's units
+pub struct Layout {
+    // the logo's top left corner and its size
+    pub logo: Option<((f64, f64), (f64, f64))>,
+    // the middle of the text's edge toward the corner. on the right of the
+    // page the text ends there instead of starting there.
+    pub text: Option<(f64, f64)>,
+    pub text_end: bool,
+    pub text_height: f64,
+}
+
+fn logo_size(image: &HtmlImageElement) -> Option<(f64, f64)> {
+    (image.complete() && image.natural_width() > 0)
+        .then(|| (image.natural_width() as f64, image.natural_height() as f64))
+}
+
+// the logo sits in the corner with the text beside it, toward the middle of
+// the page. `page` is measured in units `inch` to the inch, and `logo_size`
+// is the logo's own size, which it's scaled down from.
+fn layout(
+    watermark: &Watermark,
+    logo_size: Option<(f64, f64)>,
+    page: (f64, f64),
+    inch: f64,
+) -> Option<Layout> {
+    let logo = logo_size
+        .filter(|(width, height)| *width > 0.0 && *height > 0.0)
+        .map(|(width, height)| {
+            let scale = (LOGO_MAX_WIDTH * inch / width).min(LOGO_MAX_HEIGHT * inch / height);
+            (width * scale, height * scale)
+        });
+    let text = !watermark.text.trim().is_empty();
+    if logo.is_none() && !text {
+        return None;
+    }
+    let text_height = TEXT_SIZE * inch;
+    let logo_width = logo.map_or(0.0, |logo| logo.0);
+    let height = logo
+        .map_or(0.0, |logo| logo.1)
+        .max(if text { text_height } else { 0.0 });
+    let gap = if logo.is_some() && text {
+        INSET * inch
+    } else {
+        0.0
+    };
+    // only the logo's width is needed to place it, the text runs off it
+    let (x, y) = watermark
+        .corner
+        .place(page, (logo_width, height), INSET * inch);
+    let text_end = matches!(watermark.corner, Corner::TopRight | Corner::BottomRight);
+    let text_x = if text_end {
+        x - gap
+    } else {
+        x + logo_width + gap
+    };
+    Some(Layout {
+        logo: logo.map(|size| ((x, y + (height - size.1) / 2.0), size)),
+        text: text.then_some((text_x, y + height / 2.0)),
+        text_end,
+        text_height,
+    })
+}
+
+// draws the watermark in its corner of a printed page `width` by `height`
+// pixels. the context's transform is reset first.
+pub fn draw(context: &web_sys::CanvasRenderingContext2d, width: u32, height: u32, dpi: u32) {
+    WATERMARK.with(|loaded| {
+        let loaded = loaded.borrow();
+        let logo_image = loaded
+            .logo_image
+            .as_ref()
+            .filter(|image| logo_size(image).is_some());
+        let Some(layout) = layout(
+            &loaded.watermark,
+            logo_image.and_then(logo_size),
+            (width as f64, height as f64),
+            dpi as f64,
+        ) else {
+            return;
+        };
+        context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap();
+        if let (Some(image), Some(((x, y), (logo_width, logo_height)))) = (logo_image, layout.logo)
+        {
+            context
+                .draw_image_with_html_image_element_and_dw_and_dh(
+                    image,
+                    x,
+                    y,
+                    logo_width,
+                    logo_height,
+                )
+                .unwrap();
+        }
+        if let Some((x, y)) = layout.text {
+            context.set_font(&format!("{}px sans-serif", layout.text_height));
+            context.set_fill_style_str("#808080");
+            context.set_text_align(if layout.text_end { "right" } else { "left" });
+            context.set_text_baseline("middle");
+            context
+                .fill_text(loaded.watermark.text.trim(), x, y)
+                .unwrap();
+        }
+    });
+}
+
+// builds the watermark controls in `container`: the text, a logo upload and
+// the corner. changes are saved straight away and don't touch the design.
+pub fn attach(container: &web_sys::Element) -> Result<(), JsValue> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let watermark = WATERMARK.with(|loaded| loaded.borrow().watermark.clone());
+    let style = "width: 80%; margin-left: 10%; margin-right: 10%;";
+
+    let text_input = crate::gear_inputs::append_text_input(
+        container,
+        "watermark_text",
+        "Text:",
+        "Shop or company name",
+        &watermark.text,
+    )?
+    .dyn_into::<HtmlInputElement>()?;
+
+    let logo_label = document.create_element("label")?;
+    logo_label.set_attribute("for", "watermark_logo")?;
+    logo_label.set_text_content(Some("Logo:"));
+    logo_label.set_attribute("style", style)?;
+    container.append_child(&logo_label)?;
+    let logo_input = document
+        .create_element("input")?
+        .dyn_into::<HtmlInputElement>()?;
+    logo_input.set_attribute("id", "watermark_logo")?;
+    logo_input.set_attribute("type", "file")?;
+    logo_input.set_attribute("accept", "image/*")?;
+    logo_input.set_attribute("style", style)?;
+    container.append_child(&logo_input)?;
+
+    let clear_logo_button = document.create_element("button")?;
+    clear_logo_button.set_text_content(Some("Remove Logo"));
+    clear_logo_button.set_attribute("style", "width: 80%; margin: 2px 10%;")?;
+    container.append_child(&clear_logo_button)?;
+
+    let corner_label = document.create_element("label")?;
+    corner_label.set_attribute("for", "watermark_corner")?;
+    corner_label.set_text_content(Some("Corner:"));
+    corner_label.set_attribute("style", style)?;
+    container.append_child(&corner_label)?;
+    let corner_select = document
+        .create_element("select")?
+        .dyn_into::<HtmlSelectElement>()?;
+    corner_select.set_attribute("id", "watermark_corner")?;
+    corner_select.set_attribute("style", style)?;
+    for (corner, text) in [
+        (Corner::TopLeft, "Top Left"),
+        (Corner::TopRight, "Top Right"),
+        (Corner::BottomLeft, "Bottom Left"),
+        (Corner::BottomRight, "Bottom Right"),
+    ] {
+        let option = document.create_element("option")?;
+        option.set_attribute("value", corner.value())?;
+        option.set_text_content(Some(text));
+        if watermark.corner == corner {
+            option.set_attribute("selected", "")?;
+        }
+        corner_select.append_child(&option)?;
+    }
+    container.append_child(&corner_select)?;
+
+    // nothing here changes the drawing, so input stops short of the sidebar
+    // and its redraw
+    let input_text = text_input.clone();
+    let input_corner = corner_select.clone();
+    let input_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        event.stop_propagation();
+        let text = input_text.value();
+        let corner = Corner::from_value(&input_corner.value());
+        update(|watermark| {
+            watermark.text = text;
+            if let Some(corner) = corner {
+                watermark.corner = corner;
+            }
+        });
+    }) as Box<dyn Fn(web_sys::Event)>);
+    text_input.add_event_listener_with_callback("input", input_closure.as_ref().unchecked_ref())?;
+    corner_select
+        .add_event_listener_with_callback("input", input_closure.as_ref().unchecked_ref())?;
+    input_closure.forget();
+
+    // the logo is read into a data url so it survives reloads with the rest
+    let upload_input = logo_input.clone();
+    let upload_closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        event.stop_propagation();
+        let Some(file) = upload_input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        let reader = web_sys::FileReader::new().unwrap();
+        let loaded_reader = reader.clone();
+        let on_load = Closure::once_into_js(move || {
+            if let Some(data_url) = loaded_reader
+                .result()
+                .ok()
+                .and_then(|result| result.as_string())
+            {
+                update(|watermark| watermark.logo = Some(data_url));
+            }
+        });
+        reader.set_onload(Some(on_load.unchecked_ref()));
+        reader.read_as_data_url(&file).unwrap();
+    }) as Box<dyn Fn(web_sys::Event)>);
+    logo_input
+        .add_event_listener_with_callback("input", upload_closure.as_ref().unchecked_ref())?;
+    upload_closure.forget();
+
+    let clear_input = logo_input.clone();
+    let clear_closure = Closure::wrap(Box::new(move || {
+        clear_input.set_value("");
+        update(|watermark| watermark.logo = None);
+    }) as Box<dyn Fn()>);
+    clear_logo_button
+        .add_event_listener_with_callback("click", clear_closure.as_ref().unchecked_ref())?;
+    clear_closure.forget();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corners_keep_the_watermark_inset_on_the_page() {
+        let page = (100.0, 50.0);
+        let size = (20.0, 10.0);
+        assert_eq!(Corner::TopLeft.place(page, size, 2.0), (2.0, 2.0));
+        assert_eq!(Corner::TopRight.place(page, size, 2.0), (78.0, 2.0));
+        assert_eq!(Corner::BottomLeft.place(page, size, 2.0), (2.0, 38.0));
+        assert_eq!(Corner::BottomRight.place(page, size, 2.0), (78.0, 38.0));
+    }
+
+    #[test]
+    fn text_runs_from_the_logo_toward_the_middle() {
+        let watermark = Watermark {
+            text: "Shop".to_string(),
+            logo: None,
+            corner: Corner::BottomRight,
+        };
+        // a logo twice as wide as it's tall fills the widest a logo goes
+        let page = (850.0, 1100.0);
+        let placed = layout(&watermark, Some((300.0, 150.0)), page, 100.0).unwrap();
+        assert_eq!(placed.logo, Some(((690.0, 1015.0), (150.0, 75.0))));
+        assert_eq!(placed.text, Some((680.0, 1052.5)));
+        assert!(placed.text_end);
+
+        let watermark = Watermark {
+            corner: Corner::TopLeft,
+            ..watermark
+        };
+        let placed = layout(&watermark, None, page, 100.0).unwrap();
+        assert_eq!(placed.logo, None);
+        assert_eq!(placed.text.unwrap().0, 10.0);
+        assert!(!placed.text_end);
+        assert!(layout(&Watermark::default(), None, page, 100.0).is_none());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::geometry::{GearGeometry, Point};
+use crate::style::{GearStyle, FILL_ALPHA};
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use web_sys::WebGl2RenderingContext as Gl;
+
+const VERTEX_SHADER: &str = r#"#version 300 es
+in vec2 a_position;
+in float a_angle;
+uniform vec2 u_center;
+uniform vec2 u_scale;
+void main() {
+    float c = cos(a_angle);
+    float s = sin(a_angle);
+    vec2 p = vec2(a_position.x * c - a_position.y * s, a_position.x * s + a_position.y * c);
+    gl_Position = vec4((p + u_center) * u_scale, 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"#version 300 es
+precision mediump float;
+uniform vec4 u_color;
+out vec4 color;
+void main() {
+    color = u_color;
+}
+"#;
+
+// a gear's single tooth profile on the gpu, drawn once per tooth. the
+// profile starts with the gear's center point, so the same buffer strokes the
+// outline as a line strip and fills the tooth as a fan around the center.
+struct GpuGear {
+    vao: web_sys::WebGlVertexArrayObject,
+    buffers: [web_sys::WebGlBuffer; 2],
+    center: Point,
+    profile_points: i32,
+    teeth: i32,
+    color: [f32; 3],
+    fill: bool,
+}
+
+// webgl2 gear renderer. each gear's tooth profile is uploaded once when its
+// spec changes, then every tooth is an instanced, rotated copy of it, which
+// keeps large trains interactive.
+pub struct WebGlRenderer {
+    canvas: web_sys::HtmlCanvasElement,
+    gl: Gl,
+    program: web_sys::WebGlProgram,
+    gears: RefCell<Vec<Option<GpuGear>>>,
+}
+
+impl WebGlRenderer {
+    // returns `None` when the browser has no webgl2 support
+    pub fn new(
+        container: &web_sys::Element,
+        before: &web_sys::Element,
+    ) -> Result<Option<Self>, JsValue> {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let canvas = document
+            .create_element("canvas")?
+            .dyn_into::<web_sys::HtmlCanvasElement>()?;
+        let Some(gl) = canvas.get_context("webgl2")? else {
+            return Ok(None);
+        };
+        let gl = gl.dyn_into::<Gl>()?;
+        // fills are translucent, blended into the canvas' premultiplied alpha
+        gl.enable(Gl::BLEND);
+        gl.blend_func_separate(
+            Gl::SRC_ALPHA,
+            Gl::ONE_MINUS_SRC_ALPHA,
+            Gl::ONE,
+            Gl::ONE_MINUS_SRC_ALPHA,
+        );
+        canvas.set_attribute("style", "position: absolute; left: 0; top: 0;")?;
+        container.insert_before(&canvas, Some(before))?;
+
+        let vertex_shader = compile_shader(&gl, Gl::VERTEX_SHADER, VERTEX_SHADER)?;
+        let fragment_shader = compile_shader(&gl, Gl::FRAGMENT_SHADER, FRAGMENT_SHADER)?;
+        let program = gl.create_program().ok_or("unable to create program")?;
+        gl.attach_shader(&program, &vertex_shader);
+        gl.attach_shader(&program, &fragment_shader);
+        gl.link_program(&program);
+        if !gl
+            .get_program_parameter(&program, Gl::LINK_STATUS)
+            .as_bool()
+            .unwrap_or(false)
+        {
+            return Err(gl.get_program_info_log(&program).unwrap_or_default().into());
+        }
+
+        Ok(Some(Self {
+            canvas,
+            gl,
+            program,
+            gears: RefCell::new(Vec::new()),
+        }))
+    }
+
+    pub fn resize(&self, width: u32, height: u32) {
+        self.canvas.set_width(width);
+        self.canvas.set_height(height);
+        self.gl.viewport(0, 0, width as i32, height as i32);
+    }
+
+    // uploads one tooth of the gear plus the rotation of every tooth, returns
+    // how many points get drawn for it
+    pub fn upload_gear(
+        &self,
+        index: usize,
+        geometry: &GearGeometry,
+        center: Point,
+        style: &GearStyle,
+    ) -> usize {
+        let gl = &self.gl;
+        let tooth = geometry.tooth();
+        let profile: Vec<f32> = std::iter::once(Point { x: 0.0, y: 0.0 })
+            .chain(tooth.iter().enumerate().flat_map(|(i, segment)| {
+                // segments share end points, only keep them once
+                let skip = if i == 0 { 0 } else { 1 };
+                segment.points()[skip..].iter().copied()
+            }))
+            .flat_map(|pt| [pt.x as f32, pt.y as f32])
+            .collect();
+        let tooth_angle = geometry.tooth_pitch_angle();
+        let angles: Vec<f32> = (0..geometry.teeth as u32)
+            .map(|i| (i as f64 * tooth_angle) as f32)
+            .collect();
+
+        let vao = gl.create_vertex_array().unwrap();
+        gl.bind_vertex_array(Some(&vao));
+
+        let profile_buffer = gl.create_buffer().unwrap();
+        let position = gl.get_attrib_location(&self.program, "a_position") as u32;
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&profile_buffer));
+        gl.buffer_data_with_array_buffer_view(
+            Gl::ARRAY_BUFFER,
+            &js_sys::Float32Array::from(&profile[..]),
+            Gl::STATIC_DRAW,
+        );
+        gl.enable_vertex_attrib_array(position);
+        gl.vertex_attrib_pointer_with_i32(position, 2, Gl::FLOAT, false, 0, 0);
+
+        let angle_buffer = gl.create_buffer().unwrap();
+        let angle = gl.get_attrib_location(&self.program, "a_angle") as u32;
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&angle_buffer));
+        gl.buffer_data_with_array_buffer_view(
+            Gl::ARRAY_BUFFER,
+            &js_sys::Float32Array::from(&angles[..]),
+            Gl::STATIC_DRAW,
+        );
+        gl.enable_vertex_attrib_array(angle);
+        gl.vertex_attrib_pointer_with_i32(angle, 1, Gl::FLOAT, false, 0, 0);
+        gl.vertex_attrib_divisor(angle, 1);
+
+        gl.bind_vertex_array(None);
+
+        let (r, g, b) = style.rgb();
+        let profile_points = profile.len() / 2 - 1;
+        let mut gears = self.gears.borrow_mut();
+        if gears.len() <= index {
+            gears.resize_with(index + 1, || None);
+        }
+        if let Some(old) = gears[index].replace(GpuGear {
+            vao,
+            buffers: [profile_buffer, angle_buffer],
+            center,
+            profile_points: profile_points as i32,
+            teeth: angles.len() as i32,
+            color: [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0],
+            fill: style.fill,
+        }) {
+            gl.delete_vertex_array(Some(&old.vao));
+            old.buffers
+                .iter()
+                .for_each(|buffer| gl.delete_buffer(Some(buffer)));
+        }
+        profile_points * angles.len()
+    }
+
+    // frees the gears past `count`, once they've been removed from the train
+    pub fn truncate(&self, count: usize) {
+        let mut gears = self.gears.borrow_mut();
+        if gears.len() <= count {
+            return;
+        }
+        gears.drain(count..).flatten().for_each(|gear| {
+            self.gl.delete_vertex_array(Some(&gear.vao));
+            gear.buffers
+                .iter()
+                .for_each(|buffer| self.gl.delete_buffer(Some(buffer)));
+        });
+    }
+
+    pub fn draw(&self, ppi: u32) {
+        let gl = &self.gl;
+        self.clear();
+        gl.use_program(Some(&self.program));
+
+        // inches to clip space, flipping y to match the 2d canvas
+        let scale_x = 2.0 * ppi as f32 / self.canvas.width() as f32;
+        let scale_y = -2.0 * ppi as f32 / self.canvas.height() as f32;
+        gl.uniform2f(
+            gl.get_uniform_location(&self.program, "u_scale").as_ref(),
+            scale_x,
+            scale_y,
+        );
+
+        let center_location = gl.get_uniform_location(&self.program, "u_center");
+        let color_location = gl.get_uniform_location(&self.program, "u_color");
+        self.gears.borrow().iter().flatten().for_each(|gear| {
+            gl.uniform2f(
+                center_location.as_ref(),
+                gear.center.x as f32,
+                gear.center.y as f32,
+            );
+            gl.bind_vertex_array(Some(&gear.vao));
+            let [r, g, b] = gear.color;
+            if gear.fill {
+                gl.uniform4f(color_location.as_ref(), r, g, b, FILL_ALPHA as f32);
+                gl.draw_arrays_instanced(Gl::TRIANGLE_FAN, 0, gear.profile_points + 1, gear.teeth);
+            }
+            gl.uniform4f(color_location.as_ref(), r, g, b, 1.0);
+            gl.draw_arrays_instanced(Gl::LINE_STRIP, 1, gear.profile_points, gear.teeth);
+        });
+        gl.bind_vertex_array(None);
+    }
+
+    pub fn clear(&self) {
+        self.gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        self.gl.clear(Gl::COLOR_BUFFER_BIT);
+    }
+}
+
+fn compile_shader(
+    gl: &Gl,
+    shader_type: u32,
+    source: &str,
+) -> Result<web_sys::WebGlShader, JsValue> {
+    let shader = gl
+        .create_shader(shader_type)
+        .ok_or("unable to create shader")?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+    if gl
+        .get_shader_parameter(&shader, Gl::COMPILE_STATUS)
+        .as_bool()
+        .unwrap_or(false)
+    {
+        Ok(shader)
+    } else {
+        Err(gl.get_shader_info_log(&shader).unwrap_or_default().into())
+    }
+}
@@ -2,17 +2,20 @@ const path = require('path');
 const HtmlWebpackPlugin = require('html-webpack-plugin');
 const webpack = require('webpack');
 const WasmPackPlugin = require("@wasm-tool/wasm-pack-plugin");
+const PrecachePlugin = require('./precache_plugin');
 
 module.exports = {
     entry: './index.js',
     output: {
         path: path.resolve(__dirname, 'dist'),
         filename: 'index.js',
+        assetModuleFilename: '[name][ext]',
     },
     plugins: [
         new HtmlWebpackPlugin({
             template: 'index.html'
         }),
+        new PrecachePlugin(),
         new WasmPackPlugin({
             crateDirectory: path.resolve(__dirname, ".")
         }),